@@ -0,0 +1,33 @@
+use log::{info, LevelFilter};
+use solana_sniper_core::trading::{
+    risk::backtest::{run_backtest, ReplayFeed},
+    RiskConfig,
+};
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::builder().filter_level(LevelFilter::Info).init();
+
+    // Путь к записи (CSV, JSON или журнал монитора .jsonl) — первым аргументом
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "tests/fixtures/replay/launch.csv".to_string());
+    let feed = ReplayFeed::from_file(Path::new(&path))?;
+    info!("Бэктест по {} ({} точек)...", path, feed.len());
+
+    let config = RiskConfig {
+        take_profit_tiers: vec![(2.0, 0.3)],
+        ..Default::default()
+    };
+    let summary = run_backtest(config, feed, 1.0, 500).await?;
+    info!(
+        "Итог: {:?}, PnL {:+.4} SOL ({:+.1}%), пик {:.2}x, {} сек",
+        summary.exit_reason,
+        summary.realized_pnl_sol,
+        summary.realized_pnl_pct,
+        summary.peak_multiple,
+        summary.hold_duration.as_secs()
+    );
+    Ok(())
+}
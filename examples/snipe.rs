@@ -0,0 +1,80 @@
+use log::{info, LevelFilter};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sniper_core::{
+    config::Config,
+    scanner::{FixtureScanner, PumpFunScanner, TokenScanner},
+    trading::{PaperBuyer, PositionManager, PumpArbTrader, SnipeEngine},
+};
+use std::{path::Path, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::builder().filter_level(LevelFilter::Info).init();
+
+    // Конфиг — JSON первым аргументом; без него — настройки по умолчанию.
+    // Пример всегда работает в dry-run: покупки и продажи без транзакций.
+    let mut config: Config = match std::env::args().nth(1) {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+        None => serde_json::from_value(serde_json::json!({
+            "rpc_url": std::env::var("RPC_URL")
+                .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
+            "wallets": [],
+            "buy_amount_sol": 10.0,
+            "jito_region": "",
+            "dry_run": true,
+        }))?,
+    };
+    config.dry_run = true;
+
+    // SCANNER_FIXTURE=tests/fixtures — без сети, по записанным ответам
+    let scanner: Arc<dyn TokenScanner> = match std::env::var("SCANNER_FIXTURE") {
+        Ok(path) => Arc::new(FixtureScanner::from_fixture(Path::new(&path))?),
+        Err(_) => Arc::new(PumpFunScanner::new()),
+    };
+
+    let client = Arc::new(RpcClient::new(config.rpc_url.clone()));
+    let wallet = Arc::new(Keypair::new());
+    let paper = PaperBuyer::new(wallet.pubkey(), config.slippage.buy_bps);
+    let trader = PumpArbTrader::from_config(client, wallet, &config)?;
+    let positions = Arc::new(Mutex::new(PositionManager::new(
+        trader,
+        config.positions.clone(),
+    )));
+    let capital = std::env::var("CAPITAL_SOL")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(10.0);
+    let engine =
+        SnipeEngine::new(config, scanner, Arc::new(paper), positions.clone()).with_capital(capital);
+
+    // SNIPE_SECS — сколько работать; по умолчанию до Ctrl-C
+    let cancel = CancellationToken::new();
+    tokio::spawn({
+        let cancel = cancel.clone();
+        let secs = std::env::var("SNIPE_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok());
+        async move {
+            match secs {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => {
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+            cancel.cancel();
+        }
+    });
+    engine.run(cancel).await?;
+
+    let mut positions = positions.lock().await;
+    let closed = positions.close_all().await;
+    info!(
+        "Итог: закрыто позиций {}, PnL {:+.4} SOL",
+        closed.len(),
+        positions.realized_pnl_sol()
+    );
+    Ok(())
+}
@@ -1,17 +1,24 @@
 use log::{info, warn, LevelFilter};
-use solana_sniper_core::scanner::PumpFunScanner;
+use solana_sniper_core::scanner::{FixtureScanner, PumpFunScanner, TokenScanner};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::builder()
-        .filter_level(LevelFilter::Info)
-        .init();
+    env_logger::builder().filter_level(LevelFilter::Info).init();
 
-    info!("Запуск тестового сканера Pump.fun...");
+    // SCANNER_FIXTURE=tests/fixtures — без сети, по записанным ответам
+    let scanner: Box<dyn TokenScanner> = match std::env::var("SCANNER_FIXTURE") {
+        Ok(path) => {
+            info!("Запуск тестового сканера на фикстурах {}...", path);
+            Box::new(FixtureScanner::from_fixture(Path::new(&path))?)
+        }
+        Err(_) => {
+            info!("Запуск тестового сканера Pump.fun...");
+            Box::new(PumpFunScanner::new())
+        }
+    };
 
-    let scanner = PumpFunScanner::new();
-    
-    match scanner.get_eligible_tokens().await {
+    match scanner.eligible_tokens().await {
         Ok(tokens) => {
             info!("Найдено подходящих токенов: {}", tokens.len());
             for t in tokens {
@@ -31,4 +38,4 @@ async fn main() -> anyhow::Result<()> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -6,14 +6,28 @@ use axum::{
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::signature::Keypair;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
 
-use solana_sniper_core::scanner::PumpFunScanner;
+use solana_sniper_core::pricing::SolPriceFeed;
+use solana_sniper_core::scanner::{
+    pump_fun::unix_now, PumpFunScanner, PumpToken, ScannerStats, TokenScanner,
+};
+use solana_sniper_core::trading::{
+    BreakerState, ExecutionMode, ExitSummary, PositionLimits, PositionManager, PositionStatus,
+    PumpArbTrader,
+};
 
 #[derive(Clone)]
 struct AppState {
-    scanner: Arc<Mutex<PumpFunScanner>>,
+    scanner: Arc<dyn TokenScanner>,
+    /// Тот же сканер pump.fun — для счётчиков
+    pump: PumpFunScanner,
+    positions: Arc<Mutex<PositionManager>>,
 }
 
 #[derive(Deserialize)]
@@ -25,7 +39,7 @@ struct WebhookPayload {
 struct ApiResponse {
     status: String,
     message: String,
-    tokens: Vec<String>,
+    tokens: Vec<PumpToken>,
 }
 
 async fn health() -> &'static str {
@@ -35,16 +49,12 @@ async fn health() -> &'static str {
 async fn scan_tokens(
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse>, (StatusCode, String)> {
-    let scanner = state.scanner.lock().await;
-    match scanner.get_eligible_tokens().await {
-        Ok(tokens) => {
-            let mints: Vec<String> = tokens.into_iter().map(|t| t.mint).collect();
-            Ok(Json(ApiResponse {
-                status: "success".to_string(),
-                message: format!("Found {} tokens", mints.len()),
-                tokens: mints,
-            }))
-        }
+    match state.scanner.eligible_tokens().await {
+        Ok(tokens) => Ok(Json(ApiResponse {
+            status: "success".to_string(),
+            message: format!("Found {} tokens", tokens.len()),
+            tokens,
+        })),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Scan failed: {}", e),
@@ -52,28 +62,181 @@ async fn scan_tokens(
     }
 }
 
+async fn stats(State(state): State<AppState>) -> Json<ScannerStats> {
+    Json(state.pump.stats())
+}
+
+async fn positions(State(state): State<AppState>) -> Json<Vec<PositionStatus>> {
+    Json(state.positions.lock().await.list())
+}
+
+async fn breaker(State(state): State<AppState>) -> Json<BreakerState> {
+    Json(state.positions.lock().await.breaker_status())
+}
+
+/// Аварийная остановка: закрыть всё и не открывать новое до `/breaker/reset`
+async fn kill(State(state): State<AppState>) -> Json<Vec<ExitSummary>> {
+    log::warn!("🛑 Kill switch via HTTP");
+    Json(state.positions.lock().await.kill_switch().await)
+}
+
+async fn reset_breaker(State(state): State<AppState>) -> Json<BreakerState> {
+    let mut positions = state.positions.lock().await;
+    positions.reset_breaker();
+    Json(positions.breaker_status())
+}
+
+/// Команды `/kill` и `/reset` из чата Telegram (`TELEGRAM_BOT_TOKEN`, `TELEGRAM_CHAT_ID`)
+#[cfg(feature = "telegram")]
+fn spawn_telegram_commands(positions: Arc<Mutex<PositionManager>>) {
+    use solana_sniper_core::{
+        config::TelegramConfig,
+        notify::{spawn_commands, BotCommand},
+    };
+    let (Ok(bot_token), Ok(chat_id)) = (
+        std::env::var("TELEGRAM_BOT_TOKEN"),
+        std::env::var("TELEGRAM_CHAT_ID"),
+    ) else {
+        return;
+    };
+    let (mut commands, _) = spawn_commands(TelegramConfig {
+        bot_token,
+        chat_id,
+        min_interval_ms: 1500,
+        max_batch: 20,
+    });
+    tokio::spawn(async move {
+        while let Some(command) = commands.recv().await {
+            let mut positions = positions.lock().await;
+            match command {
+                BotCommand::Kill => {
+                    positions.kill_switch().await;
+                }
+                BotCommand::Reset => positions.reset_breaker(),
+            }
+        }
+    });
+}
+
 async fn webhook_handler(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(payload): Json<WebhookPayload>,
 ) -> impl IntoResponse {
     println!("🔥 Webhook received: {}", payload.mint);
-    // Здесь будет логика входа в сделку
+
+    let mut token = match state.pump.get_token_by_mint(&payload.mint).await {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            log::warn!("Webhook: unknown mint {}", payload.mint);
+            return StatusCode::NOT_FOUND;
+        }
+        Err(e) => {
+            log::error!("Webhook: failed to fetch {}: {}", payload.mint, e);
+            return StatusCode::BAD_GATEWAY;
+        }
+    };
+
+    let filter = state.pump.filter();
+    if filter.require_socials {
+        state.pump.enrich_metadata(&mut token).await;
+    }
+    let now = unix_now();
+    if filter.matches(&token, now) {
+        log::info!(
+            "✅ Webhook token eligible: {} ({})",
+            token.symbol,
+            token.mint
+        );
+        // Здесь будет логика входа в сделку
+    } else {
+        let reason = filter.rejection_reason(&token, now).unwrap_or("no_socials");
+        log::info!("❌ Webhook token rejected: {} ({})", token.mint, reason);
+    }
     StatusCode::OK
 }
 
+/// Ctrl+C или SIGTERM (Railway при редеплое шлёт SIGTERM)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        if let Ok(mut sig) =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        {
+            sig.recv().await;
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 #[tokio::main]
 async fn main() {
     env_logger::init();
     log::info!("🚀 Starting Pump.fun Scanner on Railway...");
 
     let scanner = PumpFunScanner::new();
+
+    // Новые токены в реальном времени через сокет pump.fun
+    let feed = scanner.subscribe_new_tokens();
+    tokio::spawn(async move {
+        tokio::pin!(feed);
+        while let Some(token) = feed.next().await {
+            log::info!("🆕 New token: {} ({})", token.symbol, token.mint);
+        }
+    });
+
+    let cancel = CancellationToken::new();
+    let monitor = {
+        let scanner = scanner.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            scanner
+                .monitor_eligible_tokens(cancel, |tokens| {
+                    for t in tokens {
+                        log::info!("✅ Eligible: {} ({})", t.symbol, t.mint);
+                    }
+                })
+                .await
+        })
+    };
+
+    // Позиции в бумажном режиме: продажи без транзакций
+    let rpc_url = std::env::var("RPC_URL")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    let rpc = Arc::new(RpcClient::new(rpc_url));
+    let trader = PumpArbTrader::new(rpc.clone(), Arc::new(Keypair::new()))
+        .with_execution_mode(ExecutionMode::Paper)
+        .with_sol_price(Arc::new(SolPriceFeed::on_chain(rpc)));
+
     let app_state = AppState {
-        scanner: Arc::new(Mutex::new(scanner)),
+        scanner: Arc::new(scanner.clone()),
+        pump: scanner,
+        positions: Arc::new(Mutex::new(PositionManager::new(
+            trader,
+            PositionLimits::default(),
+        ))),
     };
+    #[cfg(feature = "telegram")]
+    spawn_telegram_commands(app_state.positions.clone());
 
     let app = Router::new()
         .route("/health", get(health))
         .route("/scan", get(scan_tokens))
+        .route("/stats", get(stats))
+        .route("/positions", get(positions))
+        .route("/breaker", get(breaker))
+        .route("/breaker/reset", post(reset_breaker))
+        .route("/kill", post(kill))
         .route("/webhook", post(webhook_handler))
         .with_state(app_state);
 
@@ -85,8 +248,23 @@ async fn main() {
     let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
     log::info!("Listening on http://{}", addr);
 
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            shutdown_signal().await;
+            log::info!("Shutdown signal received");
+            cancel.cancel();
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cancel.clone().cancelled_owned())
         .await
         .unwrap();
-}
\ No newline at end of file
+
+    if let Err(e) = monitor.await.unwrap() {
+        log::error!("Monitor stopped with error: {}", e);
+    }
+    log::info!("Bye");
+}
@@ -1,10 +1,98 @@
+use anyhow::Result;
 use serde::Deserialize;
 
+use crate::trading::{
+    ExecutionMode, JitoClient, PositionLimits, RiskConfig, SlippageConfig, WalletSplitConfig,
+};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub rpc_url: String,
-    pub wallets: Vec<String>,
-    pub buy_amount_sol: f64,    // % от капитала (10.0 = 10%)
+    #[serde(default)]
+    pub rpc_fallback_urls: Vec<String>, // запасные RPC: переключение при сбоях, срочные продажи — во все
+    pub wallets: Vec<String>, // секретные ключи: base58 или JSON-массив; первый — основной
+    #[serde(default)]
+    pub wallet_split: WalletSplitConfig, // деление покупки между кошельками
+    pub buy_amount_sol: f64,  // % от капитала (10.0 = 10%)
     pub jito_region: String,
+    #[serde(default)]
+    pub jito_buy_tip_lamports: u64, // чаевые Jito за покупку; 0 — покупка обычным RPC
+    #[serde(default = "default_jito_fallback_slots")]
+    pub jito_fallback_slots: u64, // бандл не сел за столько слотов — отправка через RPC (0 — ждать)
     pub dry_run: bool,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub lists_path: Option<String>, // JSON с чёрными/белыми списками сканера
+    #[serde(default)]
+    pub helius_api_key: Option<String>, // запасной источник метаданных; пусто — выключен
+    #[serde(default)]
+    pub risk: RiskConfig, // пороги выхода из позиции
+    #[serde(default)]
+    pub slippage: SlippageConfig, // допустимое проскальзывание покупок и продаж
+    #[serde(default)]
+    pub positions: PositionLimits, // лимиты одновременно открытых позиций
+    #[serde(default)]
+    pub telegram: Option<TelegramConfig>, // уведомления; нужна фича `telegram`
+}
+
+/// Бот и чат для уведомлений о позициях
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+    /// Не чаще одного сообщения за столько мс
+    #[serde(default = "default_telegram_interval_ms")]
+    pub min_interval_ms: u64,
+    /// Сколько событий склеивать в одно сообщение
+    #[serde(default = "default_telegram_batch")]
+    pub max_batch: usize,
+}
+
+fn default_jito_fallback_slots() -> u64 {
+    crate::trading::jito::DEFAULT_FALLBACK_SLOTS
+}
+
+fn default_telegram_interval_ms() -> u64 {
+    1500
+}
+
+fn default_telegram_batch() -> usize {
+    20
+}
+
+impl Config {
+    /// Пороги выхода и проскальзывание (предел `MAX_SLIPPAGE_BPS` без `allow_above_cap`)
+    pub fn validate(&self) -> Result<()> {
+        self.risk.validate()?;
+        self.slippage.validate(&self.risk.sell_slippage_ladder_bps)
+    }
+
+    /// Режим исполнения продаж: `dry_run` — без транзакций
+    pub fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::from_dry_run(self.dry_run)
+    }
+
+    /// Клиент Jito региона `jito_region`, если чаевые заданы для покупки или продаж
+    pub fn jito_client(&self) -> Result<Option<JitoClient>> {
+        let risk = &self.risk;
+        if self.jito_buy_tip_lamports == 0
+            && risk.jito_tip_lamports == 0
+            && risk.jito_emergency_tip_lamports == 0
+        {
+            return Ok(None);
+        }
+        Ok(Some(
+            JitoClient::new(&self.jito_region)?.with_fallback_slots(self.jito_fallback_slots),
+        ))
+    }
+
+    /// `rpc_url`, затем запасные, без повторов
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        let mut urls = vec![self.rpc_url.clone()];
+        for url in &self.rpc_fallback_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+}
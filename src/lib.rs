@@ -1,4 +1,7 @@
 pub mod scanner;
 pub mod trading;    // ← добавлено
 pub mod config;     // ← если ещё не сделано
+pub mod pricing;
+#[cfg(feature = "telegram")]
+pub mod notify;
 // остальное по желанию
\ No newline at end of file
@@ -0,0 +1,417 @@
+use anyhow::Result;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::{collections::VecDeque, time::Duration};
+use tokio::{sync::mpsc, task::JoinHandle, time};
+
+use crate::config::TelegramConfig;
+use crate::trading::{slippage, PositionEvent, RiskEvent, WhaleReaction};
+
+/// Лимит длины сообщения Telegram
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Сколько строк держать в очереди, пока Telegram недоступен
+const MAX_PENDING_LINES: usize = 500;
+
+/// Уведомления о позициях в Telegram.
+/// События копятся и уходят пачками не чаще `min_interval_ms`;
+/// неотправленное остаётся в очереди до следующей попытки.
+#[derive(Debug)]
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    config: TelegramConfig,
+    pending: VecDeque<String>,
+}
+
+impl TelegramNotifier {
+    pub fn new(config: TelegramConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Фоновая задача: читает события до закрытия канала, затем досылает очередь
+    pub fn spawn(self, events: mpsc::Receiver<PositionEvent>) -> JoinHandle<()> {
+        tokio::spawn(self.run(events))
+    }
+
+    async fn run(mut self, mut events: mpsc::Receiver<PositionEvent>) {
+        let mut interval = time::interval(Duration::from_millis(self.config.min_interval_ms));
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    // Срочное — в начало очереди и сразу, не дожидаясь интервала
+                    Some(event) if is_urgent(&event.event) => {
+                        self.pending.push_front(format_event(&event));
+                        self.flush().await;
+                    }
+                    Some(event) => self.enqueue(format_event(&event)),
+                    None => break,
+                },
+                _ = interval.tick() => self.flush().await,
+            }
+        }
+        // Последняя попытка: не больше нескольких пачек, чтобы не висеть на выходе
+        for _ in 0..3 {
+            if self.pending.is_empty() {
+                break;
+            }
+            interval.tick().await;
+            self.flush().await;
+        }
+    }
+
+    fn enqueue(&mut self, line: String) {
+        if self.pending.len() >= MAX_PENDING_LINES {
+            self.pending.pop_front();
+            log::warn!("Очередь Telegram переполнена, старое уведомление выброшено");
+        }
+        self.pending.push_back(line);
+    }
+
+    /// Отправляет одну пачку; при ошибке строки возвращаются в начало очереди
+    async fn flush(&mut self) {
+        let mut batch = Vec::new();
+        let mut len = 0;
+        while let Some(line) = self.pending.front() {
+            if batch.len() >= self.config.max_batch
+                || (!batch.is_empty() && len + line.len() + 1 > MAX_MESSAGE_CHARS)
+            {
+                break;
+            }
+            len += line.len() + 1;
+            batch.push(self.pending.pop_front().unwrap());
+        }
+        if batch.is_empty() {
+            return;
+        }
+        if let Err(e) = self.send(&batch.join("\n")).await {
+            log::warn!("Telegram: сообщение не отправлено ({}), повтор позже", e);
+            for line in batch.into_iter().rev() {
+                self.pending.push_front(line);
+            }
+        }
+    }
+
+    async fn send(&self, text: &str) -> Result<()> {
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            self.config.bot_token
+        );
+        self.client
+            .post(url)
+            .timeout(Duration::from_secs(10))
+            .json(&serde_json::json!({
+                "chat_id": self.config.chat_id,
+                "text": text,
+                "parse_mode": "HTML",
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Команды из чата уведомлений
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotCommand {
+    /// `/kill` — закрыть все позиции и остановить торговлю (`PositionManager::kill_switch`)
+    Kill,
+    /// `/reset` — снова разрешить торговлю (`PositionManager::reset_breaker`)
+    Reset,
+}
+
+impl BotCommand {
+    /// Команда по тексту сообщения (`/kill`, `/kill@имя_бота`)
+    pub fn parse(text: &str) -> Option<Self> {
+        let word = text.split_whitespace().next()?;
+        match word.split('@').next()? {
+            "/kill" => Some(Self::Kill),
+            "/reset" => Some(Self::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// Сколько секунд Telegram держит запрос `getUpdates` без новых сообщений
+const POLL_TIMEOUT_SECS: u64 = 25;
+
+/// Фоновая задача: читает команды бота через long polling `getUpdates`.
+/// Принимаются только сообщения из `chat_id` конфига; задача завершается,
+/// когда получатель закрыт.
+pub fn spawn_commands(config: TelegramConfig) -> (mpsc::Receiver<BotCommand>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel(16);
+    let handle = tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let url = format!(
+            "https://api.telegram.org/bot{}/getUpdates",
+            config.bot_token
+        );
+        let mut offset = 0i64;
+        while !tx.is_closed() {
+            let updates = match poll_updates(&client, &url, offset).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    log::warn!("Telegram: команды не получены ({}), повтор позже", e);
+                    time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            for update in updates {
+                offset = offset.max(update["update_id"].as_i64().unwrap_or(0) + 1);
+                let message = &update["message"];
+                let chat = message["chat"]["id"].as_i64().map(|id| id.to_string());
+                if chat.as_deref() != Some(config.chat_id.as_str()) {
+                    continue;
+                }
+                if let Some(command) = message["text"].as_str().and_then(BotCommand::parse) {
+                    log::info!("Telegram: команда {:?}", command);
+                    if tx.send(command).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    (rx, handle)
+}
+
+async fn poll_updates(
+    client: &reqwest::Client,
+    url: &str,
+    offset: i64,
+) -> Result<Vec<serde_json::Value>> {
+    let response: serde_json::Value = client
+        .get(url)
+        .query(&[
+            ("offset", offset.to_string()),
+            ("timeout", POLL_TIMEOUT_SECS.to_string()),
+        ])
+        .timeout(Duration::from_secs(POLL_TIMEOUT_SECS + 10))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response["result"].as_array().cloned().unwrap_or_default())
+}
+
+/// События, которые уходят вне очереди
+fn is_urgent(event: &RiskEvent) -> bool {
+    matches!(
+        event,
+        RiskEvent::Frozen { .. }
+            | RiskEvent::ZeroBalance { .. }
+            | RiskEvent::CreatorDump { .. }
+            | RiskEvent::SupplyInflated { .. }
+            | RiskEvent::MintAuthority { .. }
+            | RiskEvent::Degraded { .. }
+            | RiskEvent::CircuitBreaker { .. }
+    )
+}
+
+/// Текст уведомления (HTML-разметка Telegram)
+pub fn format_event(event: &PositionEvent) -> String {
+    let mint = short_mint(&event.mint.to_string());
+    match &event.event {
+        RiskEvent::Opened {
+            stake_sol,
+            entry_price,
+        } => format!(
+            "📂 <b>{}</b> вход {} SOL по {:.3e}",
+            mint, stake_sol, entry_price
+        ),
+        RiskEvent::FreezeAuthority => format!("🧊 <b>{}</b> freeze authority не отозван", mint),
+        RiskEvent::RugPull {
+            drop_pct, drain, ..
+        } => match drain {
+            Some(d) => format!(
+                "🚨 <b>{}</b> RUG: резерв утекает {:.2} SOL/сек",
+                mint, d.sol_per_sec
+            ),
+            None => format!("🚨 <b>{}</b> RUG: резерв −{:.1}%", mint, drop_pct),
+        },
+        RiskEvent::LiquidityFloor { reserve, floor } => format!(
+            "🚨 <b>{}</b> в пуле {:.2} SOL — ниже порога {:.2} SOL → выходим",
+            mint,
+            *reserve as f64 / LAMPORTS_PER_SOL as f64,
+            *floor as f64 / LAMPORTS_PER_SOL as f64
+        ),
+        RiskEvent::PanicSell { drawdown_pct, .. } => {
+            format!(
+                "🔥 <b>{}</b> panic sell: −{:.1}% от входа",
+                mint, drawdown_pct
+            )
+        }
+        RiskEvent::BreakevenArmed { stop_price, .. } => {
+            format!("🛡️ <b>{}</b> стоп в безубытке: {:.3e}", mint, stop_price)
+        }
+        RiskEvent::BreakevenStop { price, .. } => {
+            format!(
+                "🛡️ <b>{}</b> стоп в безубытке сработал по {:.3e}",
+                mint, price
+            )
+        }
+        RiskEvent::TimeoutPartial {
+            fraction,
+            elapsed_secs,
+        } => format!(
+            "⏳ <b>{}</b> нет роста {} сек → продаём {:.0}%",
+            mint,
+            elapsed_secs,
+            fraction * 100.0
+        ),
+        RiskEvent::TrailingArmed { multiple, .. } => {
+            format!(
+                "🎚️ <b>{}</b> trailing stop включён на {:.2}x",
+                mint, multiple
+            )
+        }
+        RiskEvent::TrailingStop { drawdown_pct, .. } => {
+            format!(
+                "📉 <b>{}</b> trailing stop: −{:.1}% от пика",
+                mint, drawdown_pct
+            )
+        }
+        RiskEvent::WhaleDump {
+            reserve_drop_pct,
+            reaction,
+        } => match reaction {
+            WhaleReaction::Exit { fraction } => format!(
+                "🐋 <b>{}</b> крупная продажа −{:.1}% резерва → продаём {:.0}%",
+                mint,
+                reserve_drop_pct,
+                fraction * 100.0
+            ),
+            WhaleReaction::Tighten {
+                trailing_stop_pct,
+                secs,
+            } => format!(
+                "🐋 <b>{}</b> крупная продажа −{:.1}% резерва → trailing {}% на {} сек",
+                mint, reserve_drop_pct, trailing_stop_pct, secs
+            ),
+        },
+        RiskEvent::CreatorDump { amount_pct } => format!(
+            "🚨 <b>{}</b> создатель продал {:.0}% своих токенов → выходим",
+            mint, amount_pct
+        ),
+        RiskEvent::SupplyInflated { old, new } => format!(
+            "🚨 <b>{}</b> supply вырос {} → {} (+{:.2}%) → выходим",
+            mint,
+            old,
+            new,
+            (*new as f64 / (*old).max(1) as f64 - 1.0) * 100.0
+        ),
+        RiskEvent::MintAuthority { authority } => format!(
+            "🚨 <b>{}</b> mint authority не отозван ({}) → выходим",
+            mint,
+            short_mint(&authority.to_string())
+        ),
+        RiskEvent::MaxHoldExit { elapsed_secs } => format!(
+            "⌛ <b>{}</b> держим {} мин — лимит, продаём всё",
+            mint,
+            elapsed_secs / 60
+        ),
+        RiskEvent::MoonExit { multiple, .. } => {
+            format!("🌕 <b>{}</b> moon: {:.1}x → лунная доля", mint, multiple)
+        }
+        RiskEvent::TierHit {
+            multiple, fraction, ..
+        } => format!(
+            "🎯 <b>{}</b> take-profit {:.1}x → продаём {:.0}%",
+            mint,
+            multiple,
+            fraction * 100.0
+        ),
+        RiskEvent::SellExecuted(receipt) => {
+            let sol = receipt.sol_received as f64 / LAMPORTS_PER_SOL as f64;
+            if receipt.simulated {
+                format!("💰 <b>{}</b> продано за {:.4} SOL (симуляция)", mint, sol)
+            } else {
+                format!(
+                    "💰 <b>{}</b> продано за {:.4} SOL{}: <a href=\"https://solscan.io/tx/{}\">tx</a>",
+                    mint,
+                    sol,
+                    slippage::describe(receipt.realized_slippage_bps),
+                    receipt.signature
+                )
+            }
+        }
+        RiskEvent::SellRetry {
+            attempt,
+            slippage_bps,
+            error,
+            ..
+        } => format!(
+            "🔁 <b>{}</b> попытка {} ({} б.п.) не прошла: {}",
+            mint,
+            attempt,
+            slippage_bps,
+            escape_html(error)
+        ),
+        RiskEvent::Degraded { consecutive_errors } => format!(
+            "🆘 <b>{}</b> мониторинг сбоит {} раз подряд → защитный выход",
+            mint, consecutive_errors
+        ),
+        RiskEvent::Frozen { .. } => format!(
+            "🧊🚨 <b>{}</b> наш счёт ЗАМОРОЖЕН — продать нельзя, позиция списана",
+            mint
+        ),
+        RiskEvent::DustSkipped {
+            proceeds_sol,
+            fees_sol,
+            ..
+        } => format!(
+            "🧹 <b>{}</b> остаток ~{:.6} SOL не окупает комиссии ~{:.6} SOL — списан, счёт закроем",
+            mint, proceeds_sol, fees_sol
+        ),
+        RiskEvent::ZeroBalance { reason } => format!(
+            "🚩 <b>{}</b> продажа ({:?}): токенов на кошельке нет, позиция закрыта",
+            mint, reason
+        ),
+        RiskEvent::SellFailed { reason, error } => format!(
+            "⚠️ <b>{}</b> продажа ({:?}) не прошла: {}",
+            mint,
+            reason,
+            escape_html(error)
+        ),
+        RiskEvent::CircuitBreaker { reason } => format!(
+            "🛑 <b>Торговля остановлена</b>: {} — позиции закрываются, новые входы запрещены",
+            escape_html(&reason.to_string())
+        ),
+        RiskEvent::Closed(summary) => format!(
+            "{} <b>{}</b> закрыта ({:?}): PnL {:+.4} SOL ({:+.1}%){}, пик {:.1}x, {} мин",
+            if summary.realized_pnl_sol >= 0.0 {
+                "✅"
+            } else {
+                "❌"
+            },
+            mint,
+            summary.exit_reason,
+            summary.realized_pnl_sol,
+            summary.realized_pnl_pct,
+            summary
+                .realized_pnl_usd
+                .map_or(String::new(), |usd| format!(" ≈ {:+.2} $", usd)),
+            summary.peak_multiple,
+            summary.hold_duration.as_secs() / 60
+        ),
+    }
+}
+
+fn short_mint(mint: &str) -> String {
+    if mint.len() <= 10 {
+        return mint.to_string();
+    }
+    format!("{}…{}", &mint[..4], &mint[mint.len() - 4..])
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
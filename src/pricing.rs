@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Сколько держать полученную цену SOL по умолчанию
+pub const SOL_PRICE_TTL: Duration = Duration::from_secs(60);
+
+/// Feed SOL/USD в Pyth
+const PYTH_SOL_USD_FEED: &str = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+/// Аккаунт `PriceUpdateV2` с SOL/USD (спонсируемый Pyth, shard 0)
+pub const PYTH_SOL_USD_ACCOUNT: Pubkey = pubkey!("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE");
+
+/// Смещение `verification_level` в `PriceUpdateV2`: дискриминатор (8) + write_authority (32)
+const PRICE_UPDATE_VERIFICATION_OFFSET: usize = 40;
+
+/// Откуда брать цену SOL в USD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// Аккаунт Pyth on-chain через RPC (`SolPriceFeed::with_rpc`)
+    PythOnChain,
+    /// Pyth Hermes
+    Pyth,
+    CoinGecko,
+}
+
+/// Цена SOL/USD из данных аккаунта Pyth `PriceUpdateV2`
+pub fn parse_pyth_price_update(data: &[u8]) -> Result<f64> {
+    let offset = PRICE_UPDATE_VERIFICATION_OFFSET;
+    // VerificationLevel: 0 — Partial { num_signatures: u8 }, 1 — Full
+    let message = match data.get(offset) {
+        Some(0) => offset + 2,
+        Some(1) => offset + 1,
+        Some(level) => anyhow::bail!("неизвестный verification_level Pyth: {}", level),
+        None => anyhow::bail!("аккаунт Pyth слишком короткий: {} байт", data.len()),
+    };
+    // PriceFeedMessage: feed_id [32], price i64, conf u64, exponent i32
+    let field = |at: usize, len: usize| {
+        data.get(message + at..message + at + len)
+            .context("аккаунт Pyth слишком короткий")
+    };
+    let feed_id: String = field(0, 32)?.iter().map(|b| format!("{:02x}", b)).collect();
+    anyhow::ensure!(
+        feed_id == PYTH_SOL_USD_FEED,
+        "аккаунт Pyth не SOL/USD: feed {}",
+        feed_id
+    );
+    let price = i64::from_le_bytes(field(32, 8)?.try_into()?);
+    let expo = i32::from_le_bytes(field(48, 4)?.try_into()?);
+    Ok(price as f64 * 10f64.powi(expo))
+}
+
+impl PriceSource {
+    async fn fetch(self, client: &reqwest::Client, rpc: Option<&RpcClient>) -> Result<f64> {
+        match self {
+            Self::PythOnChain => {
+                let rpc = rpc.context("для цены Pyth on-chain нужен RPC")?;
+                let account = rpc.get_account(&PYTH_SOL_USD_ACCOUNT).await?;
+                parse_pyth_price_update(&account.data)
+            }
+            Self::Pyth => {
+                let url = format!(
+                    "https://hermes.pyth.network/v2/updates/price/latest?ids[]={}",
+                    PYTH_SOL_USD_FEED
+                );
+                let v: serde_json::Value = client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                let price = &v["parsed"][0]["price"];
+                let mantissa: f64 = price["price"]
+                    .as_str()
+                    .context("в ответе Pyth нет цены")?
+                    .parse()?;
+                let expo = price["expo"].as_i64().context("в ответе Pyth нет expo")?;
+                Ok(mantissa * 10f64.powi(expo as i32))
+            }
+            Self::CoinGecko => {
+                let v: serde_json::Value = client
+                    .get("https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                v["solana"]["usd"]
+                    .as_f64()
+                    .context("в ответе CoinGecko нет цены")
+            }
+        }
+    }
+}
+
+/// Последняя полученная цена SOL
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SolPrice {
+    pub usd: f64,
+    /// Сколько мс назад получена
+    pub age_ms: u64,
+}
+
+/// Цена SOL в USD с кэшем на `ttl` (по умолчанию `SOL_PRICE_TTL`);
+/// источники пробуются по порядку
+pub struct SolPriceFeed {
+    client: reqwest::Client,
+    rpc: Option<Arc<RpcClient>>,
+    sources: Vec<PriceSource>,
+    ttl: Duration,
+    /// Одновременные запросы ждут один поход в сеть
+    fetch_lock: tokio::sync::Mutex<()>,
+    /// Последняя цена; читается без ожидания сети
+    last: Mutex<Option<(f64, Instant)>>,
+}
+
+impl std::fmt::Debug for SolPriceFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolPriceFeed")
+            .field("sources", &self.sources)
+            .field("ttl", &self.ttl)
+            .field("last", &self.cached())
+            .finish()
+    }
+}
+
+impl Default for SolPriceFeed {
+    fn default() -> Self {
+        Self::new(vec![PriceSource::Pyth, PriceSource::CoinGecko])
+    }
+}
+
+impl SolPriceFeed {
+    pub fn new(sources: Vec<PriceSource>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("Failed to build HTTP client"),
+            rpc: None,
+            sources,
+            ttl: SOL_PRICE_TTL,
+            fetch_lock: tokio::sync::Mutex::new(()),
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Pyth on-chain через `rpc` первым источником, HTTP — запасными
+    pub fn on_chain(rpc: Arc<RpcClient>) -> Self {
+        Self::new(vec![
+            PriceSource::PythOnChain,
+            PriceSource::Pyth,
+            PriceSource::CoinGecko,
+        ])
+        .with_rpc(rpc)
+    }
+
+    /// RPC для `PriceSource::PythOnChain`
+    pub fn with_rpc(mut self, rpc: Arc<RpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Сколько держать полученную цену
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Последняя полученная цена и её возраст, без запросов в сеть; `None` — ещё не было
+    pub fn cached(&self) -> Option<SolPrice> {
+        self.last.lock().unwrap().map(|(usd, at)| SolPrice {
+            usd,
+            age_ms: at.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Текущая цена SOL в USD
+    pub async fn sol_usd(&self) -> Result<f64> {
+        let _fetch = self.fetch_lock.lock().await;
+        if let Some(price) = self.cached() {
+            if Duration::from_millis(price.age_ms) < self.ttl {
+                return Ok(price.usd);
+            }
+        }
+
+        let mut last_err = None;
+        for source in &self.sources {
+            match source.fetch(&self.client, self.rpc.as_deref()).await {
+                Ok(price) if price > 0.0 => {
+                    *self.last.lock().unwrap() = Some((price, Instant::now()));
+                    return Ok(price);
+                }
+                Ok(price) => last_err = Some(anyhow::anyhow!("{:?}: цена {}", source, price)),
+                Err(e) => {
+                    log::debug!("Цена SOL из {:?} недоступна: {}", source, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("не задано ни одного источника цены")))
+    }
+}
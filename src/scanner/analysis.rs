@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::trades::PumpTrade;
+
+/// Меньше сделок — оценка 0: по паре сделок накрутку не отличить
+const MIN_TRADES: usize = 10;
+
+/// Веса составляющих `wash_trading_score`; итог делится на их сумму
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WashWeights {
+    /// Мало кошельков на много сделок: `1 - кошельков / сделок`
+    pub wallet_concentration: f64,
+    /// Доля сделок, входящих в пары покупка/продажа одного кошелька
+    pub round_trips: f64,
+    /// Доля сделок с размером, встречающимся в ленте 3+ раза
+    pub repeated_sizes: f64,
+}
+
+impl Default for WashWeights {
+    fn default() -> Self {
+        Self {
+            wallet_concentration: 0.4,
+            round_trips: 0.35,
+            repeated_sizes: 0.25,
+        }
+    }
+}
+
+/// Оценка накрутки объёма 0..1 с весами по умолчанию
+pub fn wash_trading_score(trades: &[PumpTrade]) -> f64 {
+    wash_trading_score_with(trades, &WashWeights::default())
+}
+
+/// Оценка накрутки объёма 0..1: взвешенное среднее трёх признаков из `WashWeights`
+pub fn wash_trading_score_with(trades: &[PumpTrade], weights: &WashWeights) -> f64 {
+    let total = weights.wallet_concentration + weights.round_trips + weights.repeated_sizes;
+    if trades.len() < MIN_TRADES || total <= 0.0 {
+        return 0.0;
+    }
+    let n = trades.len() as f64;
+
+    let mut by_wallet: HashMap<&str, (usize, usize)> = HashMap::new();
+    for t in trades {
+        let (buys, sells) = by_wallet.entry(t.user.as_str()).or_default();
+        if t.is_buy {
+            *buys += 1;
+        } else {
+            *sells += 1;
+        }
+    }
+    let concentration = 1.0 - by_wallet.len() as f64 / n;
+    let paired: usize = by_wallet.values().map(|(b, s)| 2 * b.min(s)).sum();
+    let round_trips = paired as f64 / n;
+
+    // Округлённые суммы (0.1, 1 SOL) повторяются и у живых людей,
+    // поэтому считаем только размеры, встречающиеся 3+ раза
+    let mut by_size: HashMap<u64, usize> = HashMap::new();
+    for t in trades {
+        *by_size.entry(t.sol_amount).or_default() += 1;
+    }
+    let repeated: usize = by_size.values().filter(|&&c| c >= 3).sum();
+    let repeated_sizes = repeated as f64 / n;
+
+    let score = weights.wallet_concentration * concentration
+        + weights.round_trips * round_trips
+        + weights.repeated_sizes * repeated_sizes;
+    (score / total).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Порог, с которого ленту стоит считать накрученной
+    const THRESHOLD: f64 = 0.5;
+    const SOL: u64 = 1_000_000_000;
+
+    fn trade(user: &str, sol_amount: u64, is_buy: bool) -> PumpTrade {
+        PumpTrade {
+            user: user.to_string(),
+            sol_amount,
+            is_buy,
+            ..Default::default()
+        }
+    }
+
+    /// Два кошелька гоняют одну и ту же сумму туда-обратно
+    fn ping_pong(n: usize) -> Vec<PumpTrade> {
+        (0..n)
+            .map(|i| trade(["a", "b"][i / 2 % 2], SOL / 2, i % 2 == 0))
+            .collect()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn ping_pong_tape_scores_high() {
+        // 1 − 2/20 = 0.9 концентрации, все сделки в парах, все одного размера
+        let score = wash_trading_score(&ping_pong(20));
+        assert!(close(score, 0.4 * 0.9 + 0.35 + 0.25));
+        assert!(score > THRESHOLD);
+    }
+
+    #[test]
+    fn organic_tape_scores_low() {
+        // 18 кошельков: двое вышли, трое купили на ровный 1 SOL
+        let mut trades: Vec<PumpTrade> = (0..15)
+            .map(|i| trade(&format!("w{}", i), SOL / 10 + i as u64 * 7_777_777, true))
+            .collect();
+        for user in ["w0", "w1"] {
+            let bought = trades.iter().find(|t| t.user == user).unwrap().sol_amount;
+            trades.push(trade(user, bought + 3_333_333, false));
+        }
+        for user in ["x", "y", "z"] {
+            trades.push(trade(user, SOL, true));
+        }
+        assert_eq!(trades.len(), 20);
+        // 1 − 18/20, 4/20 в парах, 3/20 повторов
+        let score = wash_trading_score(&trades);
+        assert!(close(score, 0.4 * 0.1 + 0.35 * 0.2 + 0.25 * 0.15));
+        assert!(score < THRESHOLD);
+    }
+
+    #[test]
+    fn too_few_trades_are_neutral() {
+        assert_eq!(wash_trading_score(&ping_pong(MIN_TRADES - 1)), 0.0);
+        assert_eq!(wash_trading_score(&[]), 0.0);
+        assert!(wash_trading_score(&ping_pong(MIN_TRADES)) > THRESHOLD);
+    }
+
+    #[test]
+    fn weights_per_signal() {
+        // Только повторы: десять разных кошельков покупают по 0.5 SOL
+        let repeated: Vec<PumpTrade> = (0..10)
+            .map(|i| trade(&format!("w{}", i), SOL / 2, true))
+            .collect();
+        assert!(close(wash_trading_score(&repeated), 0.25));
+        // Только концентрация: один кошелёк, разные суммы, без продаж
+        let single: Vec<PumpTrade> = (0..10).map(|i| trade("a", SOL + i as u64, true)).collect();
+        assert!(close(wash_trading_score(&single), 0.4 * 0.9));
+
+        // Свои веса нормируются на сумму
+        let only_sizes = WashWeights {
+            wallet_concentration: 0.0,
+            round_trips: 0.0,
+            repeated_sizes: 2.0,
+        };
+        assert!(close(wash_trading_score_with(&repeated, &only_sizes), 1.0));
+        assert!(close(wash_trading_score_with(&single, &only_sizes), 0.0));
+        let zero = WashWeights {
+            repeated_sizes: 0.0,
+            ..only_sizes
+        };
+        assert_eq!(wash_trading_score_with(&repeated, &zero), 0.0);
+    }
+}
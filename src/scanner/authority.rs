@@ -0,0 +1,143 @@
+use anyhow::Result;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Сколько mint-ов проверять параллельно
+pub const AUTHORITY_CONCURRENCY: usize = 8;
+
+/// Размер SPL Mint (у Token-2022 дальше идут расширения, начало то же)
+const MINT_LEN: usize = 82;
+
+/// Состояние полномочий mint-а по данным on-chain
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthorityStatus {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+}
+
+impl AuthorityStatus {
+    /// Разбор данных SPL Mint аккаунта
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        if data.len() < MINT_LEN {
+            anyhow::bail!("слишком короткие данные mint: {} байт", data.len());
+        }
+        if data[45] != 1 {
+            anyhow::bail!("mint не инициализирован");
+        }
+        Ok(Self {
+            mint_authority: unpack_coption_key(&data[0..36])?,
+            supply: u64::from_le_bytes(data[36..44].try_into()?),
+            decimals: data[44],
+            freeze_authority: unpack_coption_key(&data[46..82])?,
+        })
+    }
+
+    pub fn mint_revoked(&self) -> bool {
+        self.mint_authority.is_none()
+    }
+
+    /// Живой freeze authority позволяет заморозить наш счёт — продать будет нельзя
+    pub fn freeze_revoked(&self) -> bool {
+        self.freeze_authority.is_none()
+    }
+}
+
+/// COption<Pubkey>: u32 тег (0 — None, 1 — Some) + 32 байта ключа
+fn unpack_coption_key(src: &[u8]) -> Result<Option<Pubkey>> {
+    let tag = u32::from_le_bytes(src[0..4].try_into()?);
+    match tag {
+        0 => Ok(None),
+        1 => Ok(Some(Pubkey::new_from_array(src[4..36].try_into()?))),
+        _ => anyhow::bail!("неверный тег COption: {}", tag),
+    }
+}
+
+/// Читает mint аккаунт и возвращает состояние mint/freeze authority
+pub async fn verify_authorities(client: &RpcClient, mint: &Pubkey) -> Result<AuthorityStatus> {
+    let account = client.get_account(mint).await?;
+    AuthorityStatus::unpack(&account.data)
+}
+
+/// Проверка перед покупкой: держатель freeze authority может заморозить
+/// наш счёт после входа (honeypot)
+pub async fn is_freezable(client: &RpcClient, mint: &Pubkey) -> Result<bool> {
+    Ok(!verify_authorities(client, mint).await?.freeze_revoked())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Данные SPL Mint: COption mint authority, supply, decimals, is_initialized,
+    /// COption freeze authority
+    fn mint_data(mint: Option<Pubkey>, freeze: Option<Pubkey>, supply: u64) -> Vec<u8> {
+        let coption = |key: Option<Pubkey>| {
+            let mut bytes = vec![0u8; 36];
+            if let Some(key) = key {
+                bytes[0] = 1;
+                bytes[4..].copy_from_slice(key.as_ref());
+            }
+            bytes
+        };
+        let mut data = coption(mint);
+        data.extend_from_slice(&supply.to_le_bytes());
+        data.push(6);
+        data.push(1);
+        data.extend(coption(freeze));
+        assert_eq!(data.len(), MINT_LEN);
+        data
+    }
+
+    #[test]
+    fn unpack_with_authorities() {
+        let (mint, freeze) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let status = AuthorityStatus::unpack(&mint_data(Some(mint), Some(freeze), 1_000)).unwrap();
+        assert_eq!(
+            status,
+            AuthorityStatus {
+                mint_authority: Some(mint),
+                freeze_authority: Some(freeze),
+                supply: 1_000,
+                decimals: 6,
+            }
+        );
+        assert!(!status.mint_revoked());
+        assert!(!status.freeze_revoked());
+    }
+
+    #[test]
+    fn unpack_revoked() {
+        let supply = 1_000_000_000_000_000;
+        let status = AuthorityStatus::unpack(&mint_data(None, None, supply)).unwrap();
+        assert!(status.mint_revoked());
+        assert!(status.freeze_revoked());
+        assert_eq!(status.supply, supply);
+    }
+
+    #[test]
+    fn unpack_token_2022_extensions() {
+        let freeze = Pubkey::new_unique();
+        let mut data = mint_data(None, Some(freeze), 5);
+        data.extend_from_slice(&[0xAB; 83]);
+        let status = AuthorityStatus::unpack(&data).unwrap();
+        assert!(status.mint_revoked());
+        assert_eq!(status.freeze_authority, Some(freeze));
+    }
+
+    #[test]
+    fn unpack_rejects_bad_data() {
+        let data = mint_data(None, None, 0);
+        assert!(AuthorityStatus::unpack(&data[..MINT_LEN - 1]).is_err());
+
+        let mut uninitialized = data.clone();
+        uninitialized[45] = 0;
+        assert!(AuthorityStatus::unpack(&uninitialized).is_err());
+
+        let mut bad_tag = data;
+        bad_tag[0] = 2;
+        assert!(AuthorityStatus::unpack(&bad_tag).is_err());
+    }
+}
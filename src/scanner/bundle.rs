@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_request::RpcRequest,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use super::trades::PumpTrade;
+
+/// Сколько покупателей из слота создания проверять на связь с создателем
+const MAX_FUNDING_CHECKS: usize = 5;
+
+/// Столько покупок в слоте создания считаются полным бандлом
+const FULL_BUNDLE_BUYERS: usize = 5;
+
+/// Покупки в слоте создания токена и их связь с создателем
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BundleReport {
+    pub creation_slot: u64,
+    /// Сторонние кошельки, купившие в слоте создания
+    pub same_slot_buyers: Vec<String>,
+    /// Из них — получившие первые средства от создателя
+    pub funded_by_creator: Vec<String>,
+}
+
+impl BundleReport {
+    /// 0..1: половина — сколько кошельков купило в слоте создания
+    /// (`FULL_BUNDLE_BUYERS` и больше = 0.5), половина — доля проверенных
+    /// из них, профинансированных создателем
+    pub fn score(&self) -> f64 {
+        let n = self.same_slot_buyers.len();
+        if n == 0 {
+            return 0.0;
+        }
+        let crowd = (n as f64 / FULL_BUNDLE_BUYERS as f64).min(1.0);
+        let checked = n.min(MAX_FUNDING_CHECKS);
+        let funded = self.funded_by_creator.len() as f64 / checked as f64;
+        0.5 * crowd + 0.5 * funded
+    }
+}
+
+/// Ищет покупки в слоте создания (по ленте сделок) и проверяет на один шаг,
+/// не создатель ли пополнил кошельки покупателей первой транзакцией.
+pub async fn detect_bundle(
+    rpc: &RpcClient,
+    trades: &[PumpTrade],
+    creator: &str,
+) -> Result<BundleReport> {
+    // Первая покупка создателя идёт в транзакции создания
+    let creation_slot = trades
+        .iter()
+        .map(|t| t.slot)
+        .filter(|s| *s > 0)
+        .min()
+        .context("в сделках нет слотов")?;
+
+    let mut same_slot_buyers: Vec<String> = Vec::new();
+    for t in trades {
+        if t.is_buy
+            && t.slot == creation_slot
+            && t.user != creator
+            && !same_slot_buyers.contains(&t.user)
+        {
+            same_slot_buyers.push(t.user.clone());
+        }
+    }
+
+    let mut funded_by_creator = Vec::new();
+    for buyer in same_slot_buyers.iter().take(MAX_FUNDING_CHECKS) {
+        match funded_by(rpc, buyer, creator).await {
+            Ok(true) => funded_by_creator.push(buyer.clone()),
+            Ok(false) => {}
+            Err(e) => log::debug!("Финансирование {} не проверено: {}", buyer, e),
+        }
+    }
+
+    Ok(BundleReport {
+        creation_slot,
+        same_slot_buyers,
+        funded_by_creator,
+    })
+}
+
+/// Участвовал ли `creator` в самой первой транзакции кошелька `wallet`.
+/// У кошелька с длинной историей первую транзакцию не ищем — это не свежий бандл.
+async fn funded_by(rpc: &RpcClient, wallet: &str, creator: &str) -> Result<bool> {
+    let page = rpc
+        .get_signatures_for_address_with_config(
+            &Pubkey::from_str(wallet)?,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(1000),
+                ..Default::default()
+            },
+        )
+        .await?;
+    if page.len() >= 1000 {
+        return Ok(false);
+    }
+    let Some(first) = page.last() else {
+        return Ok(false);
+    };
+
+    let tx: serde_json::Value = rpc
+        .send(
+            RpcRequest::GetTransaction,
+            serde_json::json!([first.signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )
+        .await?;
+    Ok(tx["transaction"]["message"]["accountKeys"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|k| k.as_str() == Some(creator)))
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::http::{read_text, HttpPool};
+
+/// Интервал свечи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandleTimeframe {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+}
+
+impl CandleTimeframe {
+    pub fn secs(self) -> u64 {
+        match self {
+            Self::OneMinute => 60,
+            Self::FiveMinutes => 5 * 60,
+            Self::FifteenMinutes => 15 * 60,
+            Self::OneHour => 60 * 60,
+        }
+    }
+}
+
+/// Свеча по цене в SOL за токен
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Candle {
+    #[serde(default)]
+    pub open: f64,
+    #[serde(default)]
+    pub high: f64,
+    #[serde(default)]
+    pub low: f64,
+    #[serde(default)]
+    pub close: f64,
+    #[serde(default)]
+    pub volume: f64,
+    /// Начало свечи, unix, сек
+    #[serde(default)]
+    pub timestamp: u64,
+}
+
+/// Последние `limit` свечей от старых к новым.
+/// У свежего токена свечей может быть меньше — отдаётся сколько есть.
+pub async fn fetch_candles(
+    http: &HttpPool,
+    mint: &str,
+    timeframe: CandleTimeframe,
+    limit: usize,
+) -> Result<Vec<Candle>> {
+    let path = format!(
+        "/candlesticks/{}?offset=0&limit={}&timeframe={}",
+        mint,
+        limit,
+        timeframe.secs()
+    );
+    let res = http.send_api(&path, Default::default()).await?;
+    let mut candles: Vec<Candle> = serde_json::from_str(&read_text(res).await?)?;
+    candles.sort_by_key(|c| c.timestamp);
+    if candles.len() > limit {
+        candles.drain(..candles.len() - limit);
+    }
+    if candles.len() < limit {
+        log::debug!("{}: получено {} свечей из {}", mint, candles.len(), limit);
+    }
+    Ok(candles)
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Deserializer};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Больше этого — уже миллисекунды (10^11 сек — это 5138 год)
+const MS_THRESHOLD: u64 = 100_000_000_000;
+
+/// Насколько время создания может опережать наши часы без предупреждения, сек
+const SKEW_WARN_SECS: i64 = 5;
+
+static SKEW_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Unix-время в секундах: миллисекунды определяются по величине и делятся на 1000
+pub fn normalize_timestamp(ts: u64) -> u64 {
+    if ts >= MS_THRESHOLD {
+        ts / 1000
+    } else {
+        ts
+    }
+}
+
+/// Возраст в секундах на момент `now`. Время из будущего (расхождение часов)
+/// даёт 0; расхождение больше `SKEW_WARN_SECS` пишется в лог один раз.
+pub fn age_secs(created: u64, now: u64) -> u64 {
+    let age = normalize_timestamp(now) as i64 - normalize_timestamp(created) as i64;
+    if age < -SKEW_WARN_SECS && !SKEW_WARNED.swap(true, Ordering::Relaxed) {
+        log::warn!(
+            "Время создания токена на {} сек впереди локальных часов — проверьте синхронизацию",
+            -age
+        );
+    }
+    age.max(0) as u64
+}
+
+/// Как `timestamp_secs`, но null и 0 остаются `None`
+pub(crate) fn opt_timestamp_secs<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?
+        .filter(|ts| *ts > 0)
+        .map(normalize_timestamp))
+}
+
+/// Для `#[serde(deserialize_with)]`: null → 0, миллисекунды → секунды
+pub(crate) fn timestamp_secs<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?
+        .map(normalize_timestamp)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_760_500_000;
+
+    #[test]
+    fn normalize_timestamp_threshold() {
+        assert_eq!(normalize_timestamp(NOW), NOW);
+        assert_eq!(normalize_timestamp(NOW * 1000), NOW);
+        assert_eq!(normalize_timestamp(NOW * 1000 + 999), NOW);
+        assert_eq!(normalize_timestamp(MS_THRESHOLD - 1), MS_THRESHOLD - 1);
+        assert_eq!(normalize_timestamp(MS_THRESHOLD), MS_THRESHOLD / 1000);
+        assert_eq!(normalize_timestamp(0), 0);
+    }
+
+    #[test]
+    fn age_secs_mixes_units() {
+        assert_eq!(age_secs(NOW - 60, NOW), 60);
+        assert_eq!(age_secs((NOW - 60) * 1000, NOW), 60);
+        assert_eq!(age_secs(NOW - 60, NOW * 1000), 60);
+        assert_eq!(age_secs((NOW - 60) * 1000, NOW * 1000 + 500), 60);
+    }
+
+    #[test]
+    fn age_secs_clamps_future() {
+        assert_eq!(age_secs(NOW, NOW), 0);
+        assert_eq!(age_secs(NOW + 3, NOW), 0);
+        assert_eq!(age_secs((NOW + 3_600) * 1000, NOW), 0);
+    }
+
+    #[derive(Deserialize)]
+    struct Row {
+        #[serde(default, deserialize_with = "timestamp_secs")]
+        created: u64,
+        #[serde(default, deserialize_with = "opt_timestamp_secs")]
+        last_trade: Option<u64>,
+    }
+
+    #[test]
+    fn serde_helpers() {
+        let row: Row = serde_json::from_str(&format!(
+            r#"{{"created": {}, "last_trade": 0}}"#,
+            NOW * 1000
+        ))
+        .unwrap();
+        assert_eq!((row.created, row.last_trade), (NOW, None));
+        let row: Row =
+            serde_json::from_str(&format!(r#"{{"created": null, "last_trade": {}}}"#, NOW))
+                .unwrap();
+        assert_eq!((row.created, row.last_trade), (0, Some(NOW)));
+    }
+}
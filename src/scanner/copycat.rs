@@ -0,0 +1,211 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use super::pump_fun::PumpToken;
+
+/// Окно, в котором ищем оригинал
+pub const DEFAULT_COPYCAT_WINDOW: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Слова, которые копии дописывают к имени оригинала
+const COPY_SUFFIXES: &[&str] = &["classic", "official", "real", "new"];
+
+/// Имя/символ для сравнения: только буквы и цифры в нижнем регистре,
+/// похожие кириллические, греческие и полноширинные буквы сведены к латинским,
+/// хвосты вроде " 2.0", "V2", " Classic" отрезаны
+/// ("$PEPE", "PEPE ", "pepe 🐸", "РЕРЕ", "Pepe2" → "pepe")
+pub fn normalize(s: &str) -> String {
+    let folded: String = s
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_homoglyph)
+        .collect();
+    let mut words: Vec<&str> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    while words.len() > 1 && is_copy_suffix(words[words.len() - 1]) {
+        words.pop();
+    }
+    let joined = words.concat();
+    let trimmed = joined.trim_end_matches(|c: char| c.is_ascii_digit());
+    // "404" и "V2" не схлопываем
+    if trimmed.chars().count() < 2 {
+        joined
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn is_copy_suffix(word: &str) -> bool {
+    let digits = word.strip_prefix('v').unwrap_or(word);
+    (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        || COPY_SUFFIXES.contains(&word)
+}
+
+/// Строчная буква, похожая на латинскую, → латинская
+fn fold_homoglyph(c: char) -> char {
+    match c {
+        // Полноширинные ASCII
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        'а' | 'α' => 'a',
+        'в' | 'β' => 'b',
+        'с' | 'ϲ' => 'c',
+        'е' | 'ё' | 'ε' => 'e',
+        'н' => 'h',
+        'і' | 'ι' => 'i',
+        'ј' => 'j',
+        'к' | 'κ' => 'k',
+        'м' => 'm',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ѕ' => 's',
+        'т' | 'τ' => 't',
+        'υ' => 'u',
+        'ν' => 'v',
+        'у' => 'y',
+        'х' | 'χ' => 'x',
+        _ => c,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    at: Instant,
+    name: String,
+    symbol: String,
+    image_uri: String,
+    is_copycat: bool,
+}
+
+/// Скользящее окно недавно виденных токенов.
+/// Токен — копия, если до него в окне был другой mint с тем же
+/// нормализованным именем, символом или той же картинкой.
+#[derive(Debug, Clone)]
+pub struct CopycatDetector {
+    window: Duration,
+    entries: HashMap<String, Entry>,
+}
+
+impl Default for CopycatDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_COPYCAT_WINDOW)
+    }
+}
+
+impl CopycatDetector {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Запоминает токен и возвращает, копия ли он.
+    /// Повторный вызов для того же mint отдаёт прежний ответ.
+    pub fn observe(&mut self, t: &PumpToken) -> bool {
+        self.observe_at(t, Instant::now())
+    }
+
+    pub fn observe_at(&mut self, t: &PumpToken, now: Instant) -> bool {
+        let window = self.window;
+        self.entries
+            .retain(|_, e| now.saturating_duration_since(e.at) < window);
+
+        if let Some(e) = self.entries.get(&t.mint) {
+            return e.is_copycat;
+        }
+
+        let name = normalize(&t.name);
+        let symbol = normalize(&t.symbol);
+        let is_copycat = self.entries.values().any(|e| {
+            (!name.is_empty() && e.name == name)
+                || (!symbol.is_empty() && e.symbol == symbol)
+                || (!t.image_uri.is_empty() && e.image_uri == t.image_uri)
+        });
+        self.entries.insert(
+            t.mint.clone(),
+            Entry {
+                at: now,
+                name,
+                symbol,
+                image_uri: t.image_uri.clone(),
+                is_copycat,
+            },
+        );
+        is_copycat
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_cases() {
+        let cases = [
+            // Регистр, пробелы, знаки, эмодзи
+            ("PEPE", "pepe"),
+            ("$PEPE", "pepe"),
+            ("PEPE ", "pepe"),
+            ("  p e p e ", "pepe"),
+            ("pepe 🐸", "pepe"),
+            ("Moon Cat", "mooncat"),
+            // Похожие буквы: кириллица, греческий, полноширинные
+            ("РЕРЕ", "pepe"),
+            ("рeрe", "pepe"),
+            ("ΡΕΡΕ", "pepe"),
+            ("ＰＥＰＥ", "pepe"),
+            ("Мооn Сат", "mooncat"),
+            // Хвосты копий
+            ("PEPE 2.0", "pepe"),
+            ("Pepe2", "pepe"),
+            ("PEPE V2", "pepe"),
+            ("Pepe Classic", "pepe"),
+            ("Official Pepe Official", "officialpepe"),
+            // Не схлопываются в пустое
+            ("404", "404"),
+            ("Classic", "classic"),
+            ("V2", "v2"),
+            ("🐸", ""),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(input), expected, "{:?}", input);
+        }
+    }
+
+    fn token(mint: &str, name: &str, symbol: &str) -> PumpToken {
+        PumpToken {
+            mint: mint.to_string(),
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detector_flags_later_copies_within_window() {
+        let start = Instant::now();
+        let window = Duration::from_secs(60);
+        let mut detector = CopycatDetector::new(window);
+        assert!(!detector.observe_at(&token("a", "Pepe", "PEPE"), start));
+        assert!(detector.observe_at(&token("b", "РЕРЕ 2.0", "$pepe"), start));
+        assert!(!detector.observe_at(&token("c", "Moon Cat", "MCAT"), start));
+        // Тот же mint — прежний ответ
+        assert!(!detector.observe_at(&token("a", "Pepe", "PEPE"), start));
+        assert_eq!(detector.len(), 3);
+
+        // Оригинал выпал из окна
+        assert!(!detector.observe_at(&token("d", "pepe", "PEPE"), start + window));
+        assert_eq!(detector.len(), 1);
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use super::{
+    clock::{age_secs, timestamp_secs},
+    http::HttpPool,
+    pump_fun::unix_now,
+};
+
+/// Сколько страниц подписей (по 1000) листать в поисках первой транзакции
+const MAX_SIGNATURE_PAGES: usize = 10;
+
+/// Токен считается «зарагганным», если капитализация упала более чем на 90% от ATH
+const RUG_DRAWDOWN: f64 = 0.9;
+
+/// Репутация кошелька-создателя
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatorReport {
+    pub address: String,
+    /// Сколько токенов запущено с этого кошелька
+    pub tokens_launched: usize,
+    /// Сколько из них потеряли > 90% капитализации от пика
+    pub rugged: usize,
+    /// Возраст кошелька в днях (нижняя оценка); `None`, если определить не удалось
+    pub account_age_days: Option<f64>,
+}
+
+/// Монета из `coins/user-created-coins`; нужны только поля для оценки
+#[derive(Debug, Deserialize)]
+struct CreatedCoin {
+    #[serde(default, deserialize_with = "timestamp_secs")]
+    created_timestamp: u64,
+    #[serde(default)]
+    market_cap: f64,
+    #[serde(default)]
+    ath_market_cap: Option<f64>,
+}
+
+/// Проверка создателей токенов через pump.fun API и (опционально) историю on-chain.
+/// Результаты кэшируются на всё время жизни анализатора.
+#[derive(Clone)]
+pub struct CreatorAnalyzer {
+    http: HttpPool,
+    rpc: Option<Arc<RpcClient>>,
+    cache: Arc<Mutex<HashMap<String, CreatorReport>>>,
+}
+
+impl fmt::Debug for CreatorAnalyzer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreatorAnalyzer")
+            .field("rpc", &self.rpc.as_ref().map(|r| r.url()))
+            .field("cached", &self.cache.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl CreatorAnalyzer {
+    pub fn new(http: HttpPool) -> Self {
+        Self {
+            http,
+            rpc: None,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Точный возраст кошелька по первой транзакции
+    pub fn with_rpc(mut self, rpc: Arc<RpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    pub async fn analyze_creator(&self, address: &str) -> Result<CreatorReport> {
+        if let Some(report) = self.cache.lock().unwrap().get(address) {
+            return Ok(report.clone());
+        }
+
+        let path = format!(
+            "/coins/user-created-coins/{}?offset=0&limit=200&includeNsfw=true",
+            address
+        );
+        let res = self.http.send_api(&path, Default::default()).await?;
+        let status = res.status();
+        if !status.is_success() {
+            anyhow::bail!("HTTP {} для создателя {}", status, address);
+        }
+        let coins: Vec<CreatedCoin> = res.json().await?;
+
+        let rugged = coins
+            .iter()
+            .filter(|c| {
+                c.ath_market_cap
+                    .is_some_and(|ath| ath > 0.0 && c.market_cap < ath * (1.0 - RUG_DRAWDOWN))
+            })
+            .count();
+
+        let account_age_days = match &self.rpc {
+            Some(rpc) => match first_activity(rpc, address).await {
+                Ok(age) => age,
+                Err(e) => {
+                    log::debug!("Не удалось получить историю {}: {}", address, e);
+                    None
+                }
+            },
+            None => None,
+        }
+        // Без RPC — по самой ранней запущенной монете
+        .or_else(|| {
+            coins
+                .iter()
+                .map(|c| c.created_timestamp)
+                .filter(|ts| *ts > 0)
+                .min()
+                .map(|ts| age_secs(ts, unix_now()) as f64 / 86_400.0)
+        });
+
+        let report = CreatorReport {
+            address: address.to_string(),
+            tokens_launched: coins.len(),
+            rugged,
+            account_age_days,
+        };
+        log::debug!("Создатель {:?}", report);
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(address.to_string(), report.clone());
+        Ok(report)
+    }
+}
+
+/// Возраст кошелька в днях по самой ранней найденной подписи
+async fn first_activity(rpc: &RpcClient, address: &str) -> Result<Option<f64>> {
+    let pubkey = Pubkey::from_str(address)?;
+    let mut before: Option<Signature> = None;
+    let mut oldest: Option<i64> = None;
+
+    for _ in 0..MAX_SIGNATURE_PAGES {
+        let page = rpc
+            .get_signatures_for_address_with_config(
+                &pubkey,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(1000),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        oldest = page.iter().filter_map(|s| s.block_time).min().or(oldest);
+        before = Some(Signature::from_str(&last.signature)?);
+        if page.len() < 1000 {
+            break;
+        }
+    }
+
+    Ok(oldest.map(|t| unix_now().saturating_sub(t.max(0) as u64) as f64 / 86_400.0))
+}
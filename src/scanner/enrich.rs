@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+use super::{
+    creator::CreatorReport, holders::HolderStats, metadata::TokenMetadata, pump_fun::PumpToken,
+};
+
+/// Сколько токенов обогащать одновременно по умолчанию
+pub const DEFAULT_ENRICH_CONCURRENCY: usize = 8;
+
+/// Токен с результатами дополнительных проверок.
+/// Неудавшаяся проверка даёт `None`, токен при этом не отбрасывается.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnrichedToken {
+    pub token: PumpToken,
+    pub metadata: Option<TokenMetadata>,
+    /// Нужен RPC в builder
+    pub holders: Option<HolderStats>,
+    pub creator: Option<CreatorReport>,
+    /// Доля supply у создателя, % (нужен RPC)
+    pub creator_holding_pct: Option<f64>,
+    /// Оценка бандла при запуске, 0..1 (только с `bundle_check` в builder)
+    pub bundled_buy_score: Option<f64>,
+    /// `image_uri` отвечает (только с `image_check` в builder или фильтром по картинке)
+    pub image_ok: Option<bool>,
+    /// Та же картинка была у другого токена за последние 24ч
+    pub image_reused: Option<bool>,
+}
@@ -0,0 +1,61 @@
+use std::{fmt, time::Duration};
+
+use super::pump_fun::PumpToken;
+
+/// События сканера для потребителей через канал
+#[derive(Debug, Clone)]
+pub enum ScannerEvent {
+    /// Токен прошёл фильтры впервые
+    NewToken(PumpToken),
+    /// У отслеживаемого токена заметно изменились ликвидность или цена;
+    /// `old` — снимок на момент прошлого события по нему
+    TokenUpdated { old: Box<PumpToken>, new: PumpToken },
+    /// Кривая отслеживаемого токена завершена — торговать через Raydium
+    Graduated(String),
+    /// Ошибка цикла сканирования (цикл продолжается)
+    ScanError(String),
+    /// API вернул 429
+    RateLimited { retry_after: Duration },
+    /// Потребитель не успевал: столько старых событий выброшено (`run_with_channel`)
+    Dropped(u64),
+}
+
+/// Ошибка HTTP 429 от pump.fun; `retry_after` — из заголовка `Retry-After`
+#[derive(Debug, Clone)]
+pub struct RateLimitedError {
+    pub retry_after: Option<Duration>,
+}
+
+impl fmt::Display for RateLimitedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.retry_after {
+            Some(d) => write!(f, "HTTP 429: rate limited, retry after {:?}", d),
+            None => write!(f, "HTTP 429: rate limited"),
+        }
+    }
+}
+
+impl std::error::Error for RateLimitedError {}
+
+/// Изменилась ли ликвидность больше чем на `liquidity_pct` процентов
+/// или цена больше чем на `price_pct`
+pub fn changed_beyond(
+    old: &PumpToken,
+    new: &PumpToken,
+    liquidity_pct: f64,
+    price_pct: f64,
+) -> bool {
+    fn rel_change(old: f64, new: f64) -> f64 {
+        if old == 0.0 {
+            if new == 0.0 {
+                0.0
+            } else {
+                f64::INFINITY
+            }
+        } else {
+            ((new - old) / old).abs() * 100.0
+        }
+    }
+    rel_change(old.liquidity, new.liquidity) > liquidity_pct
+        || rel_change(old.price, new.price) > price_pct
+}
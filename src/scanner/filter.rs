@@ -0,0 +1,500 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{collections::HashSet, path::Path};
+
+use super::{
+    analysis::WashWeights, clock::age_secs, creator::CreatorReport, patterns::PatternSet,
+    pump_fun::PumpToken,
+};
+
+/// Пороговые значения отбора токенов.
+/// `Default` совпадает с прежними захардкоженными фильтрами.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScannerFilter {
+    /// Максимальный возраст токена, сек
+    pub max_age_secs: u64,
+    /// Минимальная ликвидность, SOL
+    pub min_liquidity_sol: f64,
+    /// Минимальный рост за 24ч, % (строго больше)
+    pub min_price_change_24h: f64,
+    /// Требовать отозванный mint authority
+    pub require_mint_revoked: bool,
+    /// Допустимые статусы LP
+    pub allowed_lp_statuses: Vec<String>,
+    /// Минимальный прогресс bonding curve, %
+    pub min_bonding_progress: Option<f64>,
+    /// Максимальный прогресс bonding curve, %
+    pub max_bonding_progress: Option<f64>,
+    /// Перепроверять mint/freeze authority on-chain (нужен RPC)
+    pub verify_on_chain: bool,
+    /// Требовать twitter/telegram/сайт в метаданных (нужна загрузка `metadata_uri`)
+    pub require_socials: bool,
+    /// Максимум запущенных создателем токенов
+    pub max_creator_tokens: Option<usize>,
+    /// Минимальный возраст кошелька создателя, дней
+    pub min_creator_age_days: Option<f64>,
+    /// Максимальная доля топ-10 держателей (без bonding curve), %
+    pub max_top10_holder_pct: Option<f64>,
+    /// Создатели, чьи токены всегда отбрасываются
+    pub creator_blacklist: HashSet<String>,
+    /// Создатели, чьи токены проходят мимо остальных фильтров (кроме чёрных списков)
+    pub creator_whitelist: Option<HashSet<String>>,
+    /// Подстроки символа (без учёта регистра), при которых токен отбрасывается
+    pub symbol_blacklist: Vec<String>,
+    /// Имя, символ или описание должны совпасть хотя бы с одним выражением (пусто — любые)
+    pub name_include_patterns: PatternSet,
+    /// Совпадение имени, символа или описания с любым выражением отбрасывает токен
+    pub name_exclude_patterns: PatternSet,
+    /// Отбрасывать копии недавних токенов (`PumpToken::is_copycat`)
+    pub reject_copycats: bool,
+    /// Минимальный объём торгов за 24ч, SOL
+    pub min_volume_sol: Option<f64>,
+    /// Минимум сделок за 24ч
+    pub min_txn_count: Option<u64>,
+    /// Что делать с токеном, для которого объём узнать не удалось
+    pub missing_volume_policy: MissingVolumePolicy,
+    /// Отбрасывать токены с пометкой NSFW
+    pub exclude_nsfw: bool,
+    /// Только токены, чей создатель сейчас в эфире
+    pub require_live: bool,
+    /// Минимум комментариев в треде
+    pub min_reply_count: Option<u64>,
+    /// Максимальная доля supply у создателя, %
+    pub max_creator_holding_pct: Option<f64>,
+    /// Не считать токены на bonding curve частью supply при расчёте доли создателя
+    pub creator_holding_exclude_curve: bool,
+    /// Максимальная оценка бандла при запуске (0..1); несколько RPC-запросов на токен
+    pub max_bundled_buy_score: Option<f64>,
+    /// Максимальная оценка накрутки объёма по последним сделкам (0..1)
+    pub max_wash_score: Option<f64>,
+    pub wash_weights: WashWeights,
+    /// Максимум секунд с последней сделки; без данных API — по ленте сделок
+    pub max_seconds_since_last_trade: Option<u64>,
+    /// Что делать с токеном, время последней сделки которого узнать не удалось
+    pub missing_last_trade_policy: MissingVolumePolicy,
+    /// Капитализация, USD (`usd_market_cap`); неизвестная не проходит
+    pub min_market_cap: Option<f64>,
+    pub max_market_cap: Option<f64>,
+    /// Отбрасывать токены, чья картинка не открывается (HEAD по `image_uri`)
+    pub require_image_ok: bool,
+    /// Отбрасывать токены с картинкой другого токена за последние 24ч
+    pub reject_reused_images: bool,
+    /// Что делать, если картинку проверить не удалось (по умолчанию пропускать)
+    pub missing_image_policy: MissingVolumePolicy,
+}
+
+/// Решение для токенов без данных об объёме/сделках (и о последней сделке)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingVolumePolicy {
+    #[default]
+    Reject,
+    Allow,
+}
+
+/// Чёрные/белые списки из файла (JSON с полями как в `ScannerFilter`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FilterLists {
+    pub creator_blacklist: HashSet<String>,
+    pub creator_whitelist: Option<HashSet<String>>,
+    pub symbol_blacklist: Vec<String>,
+}
+
+impl FilterLists {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("чтение списков {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("разбор списков {}", path.display()))
+    }
+}
+
+impl Default for ScannerFilter {
+    fn default() -> Self {
+        Self {
+            max_age_secs: 900,
+            min_liquidity_sol: 5.0,
+            min_price_change_24h: 20.0,
+            require_mint_revoked: true,
+            allowed_lp_statuses: vec!["initialized".to_string(), "pending".to_string()],
+            min_bonding_progress: None,
+            max_bonding_progress: None,
+            verify_on_chain: false,
+            require_socials: false,
+            max_creator_tokens: None,
+            min_creator_age_days: None,
+            max_top10_holder_pct: None,
+            creator_blacklist: HashSet::new(),
+            creator_whitelist: None,
+            symbol_blacklist: Vec::new(),
+            name_include_patterns: PatternSet::default(),
+            name_exclude_patterns: PatternSet::default(),
+            reject_copycats: false,
+            min_volume_sol: None,
+            min_txn_count: None,
+            missing_volume_policy: MissingVolumePolicy::default(),
+            exclude_nsfw: false,
+            require_live: false,
+            min_reply_count: None,
+            max_creator_holding_pct: None,
+            creator_holding_exclude_curve: true,
+            max_bundled_buy_score: None,
+            max_wash_score: None,
+            wash_weights: WashWeights::default(),
+            max_seconds_since_last_trade: None,
+            missing_last_trade_policy: MissingVolumePolicy::default(),
+            min_market_cap: None,
+            max_market_cap: None,
+            require_image_ok: false,
+            reject_reused_images: false,
+            missing_image_policy: MissingVolumePolicy::Allow,
+        }
+    }
+}
+
+impl ScannerFilter {
+    /// Проходит ли токен все фильтры на момент `now` (unix, сек)
+    pub fn matches(&self, t: &PumpToken, now: u64) -> bool {
+        self.matches_basic(t, now) && self.matches_socials(t)
+    }
+
+    /// Фильтры по полям ответа API, без дополнительных запросов
+    pub fn matches_basic(&self, t: &PumpToken, now: u64) -> bool {
+        self.rejection_reason(t, now).is_none()
+    }
+
+    /// Первый не пройденный базовый фильтр (`None` — токен проходит).
+    /// Чёрные списки проверяются первыми, затем белый список снимает остальные фильтры.
+    pub fn rejection_reason(&self, t: &PumpToken, now: u64) -> Option<&'static str> {
+        if self.creator_blacklist.contains(&t.creator_address) {
+            Some("creator_blacklist")
+        } else if self.symbol_blacklisted(&t.symbol) {
+            Some("symbol_blacklist")
+        } else if self.name_exclude_patterns.matches_any(&texts(t)) {
+            Some("name_excluded")
+        } else if self.is_whitelisted(t) {
+            None
+        } else if !self.name_include_patterns.is_empty()
+            && !self.name_include_patterns.matches_any(&texts(t))
+        {
+            Some("name_not_included")
+        } else if self.reject_copycats && t.is_copycat {
+            Some("copycat")
+        } else if age_secs(t.created_timestamp, now) >= self.max_age_secs {
+            Some("too_old")
+        } else if self.require_mint_revoked && !t.is_mint_authority_revoked {
+            Some("mint_not_revoked")
+        } else if t.liquidity < self.min_liquidity_sol {
+            Some("low_liquidity")
+        } else if !self.allowed_lp_statuses.contains(&t.lp_status) {
+            Some("lp_status")
+        } else if t.price_change_24h <= self.min_price_change_24h {
+            Some("low_price_change")
+        } else if self
+            .min_bonding_progress
+            .is_some_and(|min| t.bonding_progress < min)
+        {
+            Some("bonding_progress_low")
+        } else if self
+            .max_bonding_progress
+            .is_some_and(|max| t.bonding_progress > max)
+        {
+            Some("bonding_progress_high")
+        } else if self.exclude_nsfw && t.nsfw {
+            Some("nsfw")
+        } else if self.require_live && !t.is_currently_live {
+            Some("not_live")
+        } else if self.min_reply_count.is_some_and(|min| t.reply_count < min) {
+            Some("low_replies")
+        } else {
+            None
+        }
+    }
+
+    /// Создатель в белом списке
+    pub fn is_whitelisted(&self, t: &PumpToken) -> bool {
+        self.creator_whitelist
+            .as_ref()
+            .is_some_and(|w| w.contains(&t.creator_address))
+    }
+
+    fn symbol_blacklisted(&self, symbol: &str) -> bool {
+        if self.symbol_blacklist.is_empty() {
+            return false;
+        }
+        let symbol = symbol.to_lowercase();
+        self.symbol_blacklist
+            .iter()
+            .any(|s| symbol.contains(&s.to_lowercase()))
+    }
+
+    /// Выражения для отбора по имени/символу/описанию; ошибка — если какое-то неверно
+    pub fn with_name_patterns(mut self, include: &[&str], exclude: &[&str]) -> Result<Self> {
+        self.name_include_patterns = PatternSet::new(include)?;
+        self.name_exclude_patterns = PatternSet::new(exclude)?;
+        Ok(self)
+    }
+
+    /// Заменяет списки загруженными из файла
+    pub fn set_lists(&mut self, lists: FilterLists) {
+        self.creator_blacklist = lists.creator_blacklist;
+        self.creator_whitelist = lists.creator_whitelist;
+        self.symbol_blacklist = lists.symbol_blacklist;
+    }
+
+    /// Фильтр по соцсетям; без загруженных метаданных токен не проходит
+    pub fn matches_socials(&self, t: &PumpToken) -> bool {
+        !self.require_socials
+            || self.is_whitelisted(t)
+            || t.metadata.as_ref().is_some_and(|m| m.has_socials())
+    }
+
+    /// Оставляет только подходящие токены
+    pub fn apply(&self, tokens: Vec<PumpToken>, now: u64) -> Vec<PumpToken> {
+        tokens
+            .into_iter()
+            .filter(|t| self.matches(t, now))
+            .collect()
+    }
+
+    /// Нужны ли данные об объёме/сделках
+    pub fn checks_volume(&self) -> bool {
+        self.min_volume_sol.is_some() || self.min_txn_count.is_some()
+    }
+
+    /// Фильтр по объёму и числу сделок; без данных — по `missing_volume_policy`
+    pub fn matches_volume(&self, t: &PumpToken) -> bool {
+        let allow_missing = self.missing_volume_policy == MissingVolumePolicy::Allow;
+        self.min_volume_sol
+            .is_none_or(|min| t.volume_24h.map_or(allow_missing, |v| v >= min))
+            && self
+                .min_txn_count
+                .is_none_or(|min| t.txn_count.map_or(allow_missing, |n| n >= min))
+    }
+
+    /// Нужна ли проверка картинки (запросы к `image_uri`)
+    pub fn checks_image(&self) -> bool {
+        self.require_image_ok || self.reject_reused_images
+    }
+
+    /// Фильтр по результату `image_signals`; без данных — по `missing_image_policy`
+    pub fn matches_image(&self, ok: Option<bool>, reused: Option<bool>) -> bool {
+        let allow_missing = self.missing_image_policy == MissingVolumePolicy::Allow;
+        (!self.require_image_ok || ok.unwrap_or(allow_missing))
+            && (!self.reject_reused_images || reused.map_or(allow_missing, |r| !r))
+    }
+
+    /// Нужна ли USD-капитализация (может потребовать цену SOL)
+    pub fn checks_market_cap(&self) -> bool {
+        self.min_market_cap.is_some() || self.max_market_cap.is_some()
+    }
+
+    /// Капитализация в USD в пределах `min_market_cap..=max_market_cap`
+    pub fn matches_market_cap(&self, t: &PumpToken) -> bool {
+        let Some(cap) = t.usd_market_cap else {
+            return !self.checks_market_cap();
+        };
+        self.min_market_cap.is_none_or(|min| cap >= min)
+            && self.max_market_cap.is_none_or(|max| cap <= max)
+    }
+
+    /// Нужна ли проверка давности последней сделки (может потребовать ленту сделок)
+    pub fn checks_activity(&self) -> bool {
+        self.max_seconds_since_last_trade.is_some()
+    }
+
+    /// Была ли сделка за последние `max_seconds_since_last_trade` секунд;
+    /// без данных — по `missing_last_trade_policy`
+    pub fn matches_activity(&self, t: &PumpToken, now: u64) -> bool {
+        let allow_missing = self.missing_last_trade_policy == MissingVolumePolicy::Allow;
+        self.max_seconds_since_last_trade.is_none_or(|max| {
+            t.last_trade_timestamp
+                .map_or(allow_missing, |ts| age_secs(ts, now) <= max)
+        })
+    }
+
+    /// Нужна ли проверка создателя (дополнительные запросы)
+    pub fn checks_creator(&self) -> bool {
+        self.max_creator_tokens.is_some() || self.min_creator_age_days.is_some()
+    }
+
+    /// Фильтр по репутации создателя; неизвестный возраст не проходит
+    pub fn matches_creator(&self, report: &CreatorReport) -> bool {
+        self.max_creator_tokens
+            .is_none_or(|max| report.tokens_launched <= max)
+            && self
+                .min_creator_age_days
+                .is_none_or(|min| report.account_age_days.is_some_and(|age| age >= min))
+    }
+}
+
+/// Поля токена, по которым работают выражения
+fn texts(t: &PumpToken) -> [&str; 3] {
+    [&t.name, &t.symbol, &t.description]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::pump_fun::parse_coins;
+
+    /// Чуть позже самого свежего токена в `coins_01.json`
+    const NOW: u64 = 1_760_500_000;
+
+    fn fixture() -> Vec<PumpToken> {
+        parse_coins(include_str!("../../tests/fixtures/coins_01.json")).unwrap()
+    }
+
+    /// Причина отказа по символу токена
+    fn reasons(filter: &ScannerFilter) -> Vec<(String, Option<&'static str>)> {
+        fixture()
+            .iter()
+            .map(|t| (t.symbol.clone(), filter.rejection_reason(t, NOW)))
+            .collect()
+    }
+
+    fn reason(filter: &ScannerFilter, symbol: &str) -> Option<&'static str> {
+        reasons(filter)
+            .into_iter()
+            .find(|(s, _)| s == symbol)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn default_filter_on_fixture() {
+        assert_eq!(
+            reasons(&ScannerFilter::default()),
+            vec![
+                ("MCAT".to_string(), None),
+                ("RUGL".to_string(), Some("low_liquidity")),
+                ("ZZZ".to_string(), Some("low_price_change")),
+                ("MINT".to_string(), Some("mint_not_revoked")),
+                ("OLD".to_string(), Some("too_old")),
+            ]
+        );
+        let passed = ScannerFilter::default().apply(fixture(), NOW);
+        assert_eq!(passed.len(), 1);
+        assert_eq!(passed[0].symbol, "MCAT");
+    }
+
+    #[test]
+    fn max_age_secs() {
+        // OLD создан за 3600 с до NOW
+        let mut filter = ScannerFilter {
+            max_age_secs: 3_601,
+            ..Default::default()
+        };
+        assert_eq!(reason(&filter, "OLD"), None);
+        filter.max_age_secs = 3_600;
+        assert_eq!(reason(&filter, "OLD"), Some("too_old"));
+        filter.max_age_secs = 30;
+        assert_eq!(reason(&filter, "MCAT"), Some("too_old"));
+    }
+
+    #[test]
+    fn min_liquidity_sol() {
+        let mut filter = ScannerFilter {
+            min_liquidity_sol: 3.1,
+            ..Default::default()
+        };
+        assert_eq!(reason(&filter, "RUGL"), None);
+        filter.min_liquidity_sol = 12.5;
+        assert_eq!(reason(&filter, "MCAT"), Some("low_liquidity"));
+    }
+
+    #[test]
+    fn min_price_change_24h_is_strict() {
+        let mut filter = ScannerFilter {
+            min_price_change_24h: 4.9,
+            ..Default::default()
+        };
+        assert_eq!(reason(&filter, "ZZZ"), None);
+        filter.min_price_change_24h = 85.0;
+        assert_eq!(reason(&filter, "MCAT"), Some("low_price_change"));
+    }
+
+    #[test]
+    fn require_mint_revoked() {
+        let filter = ScannerFilter {
+            require_mint_revoked: false,
+            ..Default::default()
+        };
+        assert_eq!(reason(&filter, "MINT"), None);
+    }
+
+    #[test]
+    fn allowed_lp_statuses() {
+        let mut filter = ScannerFilter {
+            allowed_lp_statuses: vec!["pending".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(reason(&filter, "MCAT"), Some("lp_status"));
+        filter.allowed_lp_statuses = vec!["initialized".to_string()];
+        assert_eq!(reason(&filter, "MCAT"), None);
+    }
+
+    #[test]
+    fn name_patterns() {
+        // MCAT: "Moon Cat" / "MCAT" / "Moon Cat on pump.fun"
+        let cases: &[(&[&str], &[&str], Option<&str>)] = &[
+            (&[], &[], None),
+            (&["cat"], &[], None),
+            (&["^mcat$"], &[], None),
+            (&["pump\\.fun"], &[], None),
+            (&["dog", "moon"], &[], None),
+            (&["dog"], &[], Some("name_not_included")),
+            (&["^cat"], &[], Some("name_not_included")),
+            (&[], &["MOON"], Some("name_excluded")),
+            (&[], &["pump\\.fun$"], Some("name_excluded")),
+            (&[], &["dog"], None),
+            // Исключение сильнее включения
+            (&["cat"], &["moon"], Some("name_excluded")),
+        ];
+        for (include, exclude, expected) in cases {
+            let filter = ScannerFilter::default()
+                .with_name_patterns(include, exclude)
+                .unwrap();
+            assert_eq!(
+                reason(&filter, "MCAT"),
+                *expected,
+                "include {:?}, exclude {:?}",
+                include,
+                exclude
+            );
+        }
+        assert!(ScannerFilter::default()
+            .with_name_patterns(&["(moon"], &[])
+            .is_err());
+    }
+
+    #[test]
+    fn missing_volume_policy() {
+        let mut token = fixture().remove(0);
+        assert_eq!((token.volume_24h, token.txn_count), (None, None));
+        let mut filter = ScannerFilter {
+            min_volume_sol: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(filter.missing_volume_policy, MissingVolumePolicy::Reject);
+        assert!(!filter.matches_volume(&token));
+        filter.missing_volume_policy = MissingVolumePolicy::Allow;
+        assert!(filter.matches_volume(&token));
+
+        // Известный объём сверяется с порогом при любой политике
+        token.volume_24h = Some(9.9);
+        assert!(!filter.matches_volume(&token));
+        token.volume_24h = Some(10.0);
+        assert!(filter.matches_volume(&token));
+
+        // Число сделок — по той же политике
+        filter.min_txn_count = Some(50);
+        assert!(filter.matches_volume(&token));
+        filter.missing_volume_policy = MissingVolumePolicy::Reject;
+        assert!(!filter.matches_volume(&token));
+        token.txn_count = Some(50);
+        assert!(filter.matches_volume(&token));
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{
+    pump_fun::{parse_coins, PumpToken},
+    ScannerFilter, TokenScanner,
+};
+
+/// Источник, проигрывающий записанные ответы `/coins` из файлов — без сети.
+/// Каждый вызов берёт следующий файл, на последнем останавливается.
+#[derive(Debug)]
+pub struct FixtureScanner {
+    files: Vec<PathBuf>,
+    cursor: AtomicUsize,
+    filter: ScannerFilter,
+    now: Option<u64>,
+}
+
+impl FixtureScanner {
+    /// `path` — один JSON-файл или каталог с ними (по порядку имён)
+    pub fn from_fixture(path: &Path) -> Result<Self> {
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("не удалось прочитать {}", path.display()))?
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+        if files.is_empty() {
+            anyhow::bail!("в {} нет файлов *.json", path.display());
+        }
+        Ok(Self {
+            files,
+            cursor: AtomicUsize::new(0),
+            filter: ScannerFilter::default(),
+            now: None,
+        })
+    }
+
+    /// Дешёвые фильтры (`rejection_reason`); проверки с запросами не выполняются
+    pub fn filter(mut self, filter: ScannerFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Текущее время для фильтра возраста (unix, сек).
+    /// По умолчанию — самый свежий `created_timestamp` в файле, как в момент записи.
+    pub fn now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Все монеты следующего файла, без фильтрации
+    pub fn next_coins(&self) -> Result<Vec<PumpToken>> {
+        let i = self
+            .cursor
+            .fetch_add(1, Ordering::Relaxed)
+            .min(self.files.len() - 1);
+        let path = &self.files[i];
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("не удалось прочитать {}", path.display()))?;
+        let mut coins = parse_coins(&text)?;
+        for t in &mut coins {
+            t.update_bonding_progress();
+        }
+        Ok(coins)
+    }
+}
+
+#[async_trait]
+impl TokenScanner for FixtureScanner {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    async fn eligible_tokens(&self) -> Result<Vec<PumpToken>> {
+        let coins = self.next_coins()?;
+        let now = self.now.unwrap_or_else(|| {
+            coins
+                .iter()
+                .map(|t| t.created_timestamp)
+                .max()
+                .unwrap_or_default()
+        });
+        Ok(coins
+            .into_iter()
+            .filter(|t| self.filter.rejection_reason(t, now).is_none())
+            .collect())
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::{fmt, str::FromStr, time::Duration};
+
+use super::{authority::AuthorityStatus, pump_fun::PumpToken};
+use crate::config::Config;
+
+const HELIUS_RPC_URL: &str = "https://mainnet.helius-rpc.com";
+
+/// Клиент Helius DAS (`getAsset`) — запасной источник данных о mint-е,
+/// когда API pump.fun или RPC недоступны
+#[derive(Clone)]
+pub struct HeliusClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl fmt::Debug for HeliusClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // URL содержит API-ключ — в лог не выводим
+        f.debug_struct("HeliusClient").finish_non_exhaustive()
+    }
+}
+
+impl HeliusClient {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build HTTP client"),
+            url: format!("{}/?api-key={}", HELIUS_RPC_URL, api_key),
+        }
+    }
+
+    /// Клиент по `helius_api_key` из конфига; без ключа — `None`
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .helius_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(Self::new)
+    }
+
+    /// DAS `getAsset` по mint-у
+    pub async fn get_asset(&self, mint: &str) -> Result<DasAsset> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "scanner",
+            "method": "getAsset",
+            "params": { "id": mint },
+        });
+        let res: Value = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(err) = res.get("error") {
+            anyhow::bail!("Helius getAsset: {}", err);
+        }
+        DasAsset::from_value(&res["result"])
+    }
+}
+
+/// Нужная сканеру часть ответа DAS `getAsset`
+#[derive(Debug, Clone, Default)]
+pub struct DasAsset {
+    pub mint: String,
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub image_uri: String,
+    pub metadata_uri: String,
+    pub supply: u64,
+    pub decimals: u8,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+}
+
+impl DasAsset {
+    fn from_value(v: &Value) -> Result<Self> {
+        let mint = v["id"].as_str().context("в ответе getAsset нет id")?;
+        let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+        let key = |v: &Value| v.as_str().filter(|s| !s.is_empty()).map(str::to_string);
+        let meta = &v["content"]["metadata"];
+        let info = &v["token_info"];
+        Ok(Self {
+            mint: mint.to_string(),
+            name: text(&meta["name"]),
+            symbol: text(&meta["symbol"]),
+            description: text(&meta["description"]),
+            image_uri: text(&v["content"]["links"]["image"]),
+            metadata_uri: text(&v["content"]["json_uri"]),
+            supply: info["supply"].as_u64().unwrap_or_default(),
+            decimals: info["decimals"].as_u64().unwrap_or_default() as u8,
+            mint_authority: key(&info["mint_authority"]),
+            freeze_authority: key(&info["freeze_authority"]),
+        })
+    }
+
+    /// Полномочия mint-а в формате on-chain проверки
+    pub fn authority_status(&self) -> Result<AuthorityStatus> {
+        let parse = |k: &Option<String>| k.as_deref().map(Pubkey::from_str).transpose();
+        Ok(AuthorityStatus {
+            mint_authority: parse(&self.mint_authority)?,
+            freeze_authority: parse(&self.freeze_authority)?,
+            supply: self.supply,
+            decimals: self.decimals,
+        })
+    }
+
+    /// Токен без рыночных данных: цены, ликвидности и времени создания в DAS нет
+    pub fn into_token(self) -> PumpToken {
+        PumpToken {
+            is_mint_authority_revoked: self.mint_authority.is_none(),
+            mint: self.mint,
+            name: self.name,
+            symbol: self.symbol,
+            description: self.description,
+            image_uri: self.image_uri,
+            metadata_uri: self.metadata_uri,
+            ..Default::default()
+        }
+    }
+}
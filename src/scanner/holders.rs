@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, str::FromStr, sync::Mutex};
+
+use super::onchain::{associated_token_address, bonding_curve_pda};
+
+/// Сколько токенов проверять параллельно
+pub const HOLDER_CONCURRENCY: usize = 8;
+
+/// Концентрация держателей, % от общего предложения
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HolderStats {
+    pub top1_pct: f64,
+    pub top5_pct: f64,
+    pub top10_pct: f64,
+    /// Сколько счетов учтено (без счёта bonding curve)
+    pub holders_counted: usize,
+}
+
+impl HolderStats {
+    /// Расчёт по балансам крупнейших счетов (любой порядок) и общему предложению.
+    /// Счёт `exclude` (токены на bonding curve) не учитывается.
+    pub fn compute(balances: &[(Pubkey, u64)], supply: u64, exclude: &Pubkey) -> Self {
+        let mut amounts: Vec<u64> = balances
+            .iter()
+            .filter(|(addr, _)| addr != exclude)
+            .map(|(_, amount)| *amount)
+            .collect();
+        amounts.sort_unstable_by(|a, b| b.cmp(a));
+
+        let pct = |n: usize| {
+            if supply == 0 {
+                return 0.0;
+            }
+            let top: u128 = amounts.iter().take(n).map(|a| *a as u128).sum();
+            top as f64 / supply as f64 * 100.0
+        };
+
+        Self {
+            top1_pct: pct(1),
+            top5_pct: pct(5),
+            top10_pct: pct(10),
+            holders_counted: amounts.len(),
+        }
+    }
+}
+
+/// `getTokenLargestAccounts` + `getTokenSupply` → концентрация держателей
+pub async fn holder_concentration(client: &RpcClient, mint: &Pubkey) -> Result<HolderStats> {
+    let (largest, supply) = tokio::try_join!(
+        client.get_token_largest_accounts(mint),
+        client.get_token_supply(mint)
+    )?;
+
+    let balances = largest
+        .iter()
+        .map(|acc| Ok((Pubkey::from_str(&acc.address)?, acc.amount.amount.parse()?)))
+        .collect::<Result<Vec<(Pubkey, u64)>>>()?;
+    let supply: u64 = supply.amount.parse()?;
+
+    let curve_ata = associated_token_address(&bonding_curve_pda(mint), mint);
+    Ok(HolderStats::compute(&balances, supply, &curve_ata))
+}
+
+/// Supply mint-ов в пределах одного цикла скана
+pub type SupplyCache = Mutex<HashMap<Pubkey, u64>>;
+
+async fn cached_supply(client: &RpcClient, mint: &Pubkey, cache: &SupplyCache) -> Result<u64> {
+    if let Some(supply) = cache.lock().unwrap().get(mint) {
+        return Ok(*supply);
+    }
+    let supply: u64 = client.get_token_supply(mint).await?.amount.parse()?;
+    cache.lock().unwrap().insert(*mint, supply);
+    Ok(supply)
+}
+
+/// Сумма балансов всех счетов `owner` для `mint` (сырые единицы)
+async fn owner_balance(client: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+    let res: serde_json::Value = client
+        .send(
+            RpcRequest::GetTokenAccountsByOwner,
+            serde_json::json!([
+                owner.to_string(),
+                { "mint": mint.to_string() },
+                { "encoding": "jsonParsed" },
+            ]),
+        )
+        .await?;
+    res["value"]
+        .as_array()
+        .context("нет списка счетов в ответе")?
+        .iter()
+        .map(|acc| {
+            acc["account"]["data"]["parsed"]["info"]["tokenAmount"]["amount"]
+                .as_str()
+                .context("нет баланса счёта")?
+                .parse::<u64>()
+                .map_err(Into::into)
+        })
+        .sum()
+}
+
+/// Доля создателя в supply, %; учитываются все его счета этого mint-а.
+/// С `exclude_curve` токены на bonding curve не входят в знаменатель.
+pub async fn creator_holding_pct(
+    client: &RpcClient,
+    mint: &Pubkey,
+    creator: &Pubkey,
+    exclude_curve: bool,
+    supply_cache: &SupplyCache,
+) -> Result<f64> {
+    let (held, supply) = tokio::try_join!(
+        owner_balance(client, creator, mint),
+        cached_supply(client, mint, supply_cache)
+    )?;
+
+    let mut denominator = supply;
+    if exclude_curve {
+        let curve_ata = associated_token_address(&bonding_curve_pda(mint), mint);
+        // Кривая могла уже закрыться (миграция) — тогда вычитать нечего
+        if let Ok(balance) = client.get_token_account_balance(&curve_ata).await {
+            denominator = denominator.saturating_sub(balance.amount.parse()?);
+        }
+    }
+    if denominator == 0 {
+        return Ok(0.0);
+    }
+    Ok(held as f64 / denominator as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use solana_client::{
+        client_error::{ClientErrorKind, Result as ClientResult},
+        rpc_client::RpcClientConfig,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+
+    /// RPC с готовыми ответами по типу запроса; на остальные — ошибка
+    struct Canned(Vec<(RpcRequest, Value)>);
+
+    #[async_trait]
+    impl RpcSender for Canned {
+        async fn send(&self, request: RpcRequest, _: Value) -> ClientResult<Value> {
+            self.0
+                .iter()
+                .find(|(r, _)| *r == request)
+                .map(|(_, value)| json!({ "context": { "slot": 1 }, "value": value }))
+                .ok_or_else(|| ClientErrorKind::Custom(format!("нет ответа на {}", request)).into())
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "canned".to_string()
+        }
+    }
+
+    fn client(responses: Vec<(RpcRequest, Value)>) -> RpcClient {
+        RpcClient::new_sender(Canned(responses), RpcClientConfig::default())
+    }
+
+    fn amount(raw: u64) -> Value {
+        json!({
+            "amount": raw.to_string(),
+            "decimals": 6,
+            "uiAmount": raw as f64 / 1e6,
+            "uiAmountString": (raw as f64 / 1e6).to_string(),
+        })
+    }
+
+    #[test]
+    fn compute_top_n_without_curve() {
+        let curve = Pubkey::new_unique();
+        let mut balances: Vec<(Pubkey, u64)> =
+            (1..=12).map(|i| (Pubkey::new_unique(), i * 10)).collect();
+        balances.push((curve, 5_000));
+        balances.reverse();
+        let stats = HolderStats::compute(&balances, 10_000, &curve);
+        // 120; 120+110+100+90+80; десять крупнейших — 120..=30
+        assert_eq!(stats.top1_pct, 1.2);
+        assert_eq!(stats.top5_pct, 5.0);
+        assert_eq!(stats.top10_pct, 7.5);
+        assert_eq!(stats.holders_counted, 12);
+
+        // Меньше пяти счетов — top5 и top10 по всем
+        let few = HolderStats::compute(&balances[..3], 10_000, &curve);
+        assert_eq!(few.holders_counted, 2);
+        assert_eq!(few.top5_pct, few.top10_pct);
+    }
+
+    #[test]
+    fn compute_zero_supply() {
+        let balances = [(Pubkey::new_unique(), 100)];
+        let stats = HolderStats::compute(&balances, 0, &Pubkey::new_unique());
+        assert_eq!((stats.top1_pct, stats.top10_pct), (0.0, 0.0));
+        assert_eq!(stats.holders_counted, 1);
+    }
+
+    #[tokio::test]
+    async fn concentration_from_rpc() {
+        let mint = Pubkey::new_unique();
+        let curve_ata = associated_token_address(&bonding_curve_pda(&mint), &mint);
+        let holder = Pubkey::new_unique();
+        let largest = json!([
+            { "address": curve_ata.to_string(), "amount": "800000", "decimals": 6,
+              "uiAmount": 0.8, "uiAmountString": "0.8" },
+            { "address": holder.to_string(), "amount": "50000", "decimals": 6,
+              "uiAmount": 0.05, "uiAmountString": "0.05" },
+        ]);
+        let rpc = client(vec![
+            (RpcRequest::GetTokenLargestAccounts, largest),
+            (RpcRequest::GetTokenSupply, amount(1_000_000)),
+        ]);
+        let stats = holder_concentration(&rpc, &mint).await.unwrap();
+        assert_eq!(stats.top1_pct, 5.0);
+        assert_eq!(stats.holders_counted, 1);
+
+        // Без ответа на supply — ошибка, а не нулевая концентрация
+        let broken = client(vec![]);
+        assert!(holder_concentration(&broken, &mint).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn creator_share_excludes_curve() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let account = |raw: u64| {
+            json!({
+                "pubkey": Pubkey::new_unique().to_string(),
+                "account": { "data": { "parsed": { "info": { "tokenAmount": amount(raw) } } } },
+            })
+        };
+        // Два счёта создателя: 30 + 20 из 1000, на кривой 500
+        let accounts = json!([account(30), account(20)]);
+        let responses = vec![
+            (RpcRequest::GetTokenAccountsByOwner, accounts.clone()),
+            (RpcRequest::GetTokenSupply, amount(1_000)),
+            (RpcRequest::GetTokenAccountBalance, amount(500)),
+        ];
+        let cache = SupplyCache::default();
+        let rpc = client(responses);
+        let pct = creator_holding_pct(&rpc, &mint, &creator, false, &cache);
+        assert_eq!(pct.await.unwrap(), 5.0);
+        let pct = creator_holding_pct(&rpc, &mint, &creator, true, &cache);
+        assert_eq!(pct.await.unwrap(), 10.0);
+
+        // Кривая закрыта (миграция), supply из кэша — RPC его уже не спрашивают
+        let rpc = client(vec![(RpcRequest::GetTokenAccountsByOwner, accounts)]);
+        let pct = creator_holding_pct(&rpc, &mint, &creator, true, &cache);
+        assert_eq!(pct.await.unwrap(), 5.0);
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use reqwest::header::RANGE;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hasher},
+    time::{Duration, Instant},
+};
+
+use super::metadata::candidate_urls;
+
+/// Таймаут проверки картинки
+pub const IMAGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Сколько картинок проверять параллельно
+pub const IMAGE_CONCURRENCY: usize = 8;
+
+/// Сколько помнить хэши картинок для поиска повторов
+pub const DEFAULT_IMAGE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Сколько первых байт картинки хэшировать
+const HASH_PREFIX_BYTES: usize = 4096;
+
+/// Результат проверки `image_uri`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImageCheck {
+    /// HEAD вернул успешный статус
+    pub ok: bool,
+    /// Хэш первых `HASH_PREFIX_BYTES` байт (hex); `None`, если картинка недоступна
+    pub hash: Option<String>,
+}
+
+/// HEAD по `image_uri` (с запасными IPFS-шлюзами), затем хэш начала файла.
+/// Ответ с ошибочным статусом — `ok: false`; сетевая ошибка на всех адресах — `Err`.
+pub async fn check_image(client: &reqwest::Client, uri: &str) -> Result<ImageCheck> {
+    let mut last_err = anyhow::anyhow!("пустой image_uri");
+    let mut responded = false;
+
+    for url in candidate_urls(uri) {
+        match client.head(&url).timeout(IMAGE_TIMEOUT).send().await {
+            Ok(r) if r.status().is_success() => {
+                let hash = prefix_hash(client, &url)
+                    .await
+                    .inspect_err(|e| log::debug!("Картинка {} не захэширована: {}", url, e))
+                    .ok();
+                return Ok(ImageCheck { ok: true, hash });
+            }
+            Ok(r) => {
+                responded = true;
+                last_err = anyhow::anyhow!("HTTP {} от {}", r.status(), url);
+            }
+            Err(e) => last_err = e.into(),
+        }
+        log::debug!("Картинка недоступна через {}: {}", url, last_err);
+    }
+    if responded {
+        Ok(ImageCheck {
+            ok: false,
+            hash: None,
+        })
+    } else {
+        Err(last_err)
+    }
+}
+
+async fn prefix_hash(client: &reqwest::Client, url: &str) -> Result<String> {
+    let bytes = client
+        .get(url)
+        .header(RANGE, format!("bytes=0-{}", HASH_PREFIX_BYTES - 1))
+        .timeout(IMAGE_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    // Сервер без поддержки Range отдаст файл целиком
+    let prefix = &bytes[..bytes.len().min(HASH_PREFIX_BYTES)];
+    let mut hasher = DefaultHasher::new();
+    hasher.write(prefix);
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Хэши картинок за последние `window`: чья картинка и когда впервые встречена
+#[derive(Debug)]
+pub struct ImageRegistry {
+    window: Duration,
+    entries: HashMap<String, (String, Instant)>,
+}
+
+impl Default for ImageRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_IMAGE_WINDOW)
+    }
+}
+
+impl ImageRegistry {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Запоминает хэш за `mint`; `true`, если в окне он уже был у другого токена
+    pub fn observe(&mut self, mint: &str, hash: &str) -> bool {
+        let window = self.window;
+        self.entries.retain(|_, (_, at)| at.elapsed() < window);
+        match self.entries.get(hash) {
+            Some((owner, _)) => owner != mint,
+            None => {
+                self.entries
+                    .insert(hash.to_string(), (mint.to_string(), Instant::now()));
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Таймаут загрузки JSON метаданных
+pub const METADATA_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Запасные IPFS-шлюзы, по порядку
+pub const IPFS_GATEWAYS: &[&str] = &[
+    "https://ipfs.io/ipfs/",
+    "https://cloudflare-ipfs.com/ipfs/",
+    "https://gateway.pinata.cloud/ipfs/",
+];
+
+/// Off-chain метаданные токена (JSON по `metadata_uri`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub image: String,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+impl TokenMetadata {
+    /// Есть хотя бы одна непустая соцсеть или сайт
+    pub fn has_socials(&self) -> bool {
+        [&self.twitter, &self.telegram, &self.website]
+            .iter()
+            .any(|s| s.as_deref().is_some_and(|s| !s.trim().is_empty()))
+    }
+}
+
+/// CID из IPFS-ссылки любого шлюза (`.../ipfs/<cid>`) или `ipfs://<cid>`
+pub fn ipfs_cid(uri: &str) -> Option<&str> {
+    uri.strip_prefix("ipfs://")
+        .or_else(|| uri.split_once("/ipfs/").map(|(_, cid)| cid))
+        .filter(|cid| !cid.is_empty())
+}
+
+/// Исходный URI и затем тот же CID через запасные шлюзы
+pub fn candidate_urls(uri: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    if uri.starts_with("http") {
+        urls.push(uri.to_string());
+    }
+    if let Some(cid) = ipfs_cid(uri) {
+        for gw in IPFS_GATEWAYS {
+            let url = format!("{}{}", gw, cid);
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+/// Загружает метаданные, перебирая шлюзы до первого успешного ответа
+pub async fn fetch_metadata(client: &reqwest::Client, uri: &str) -> Result<TokenMetadata> {
+    let mut last_err = anyhow::anyhow!("пустой metadata_uri");
+
+    for url in candidate_urls(uri) {
+        let res = client.get(&url).timeout(METADATA_TIMEOUT).send().await;
+        match res {
+            Ok(r) if r.status().is_success() => match r.json::<TokenMetadata>().await {
+                Ok(meta) => return Ok(meta),
+                Err(e) => last_err = e.into(),
+            },
+            Ok(r) => last_err = anyhow::anyhow!("HTTP {} от {}", r.status(), url),
+            Err(e) => last_err = e.into(),
+        }
+        log::debug!("Метаданные недоступны через {}: {}", url, last_err);
+    }
+    Err(last_err)
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use super::{pump_fun::PumpToken, TokenScanner};
+
+/// Источник с заранее заданными токенами — для тестов и отладки конвейера
+#[derive(Debug, Default)]
+pub struct MockScanner {
+    tokens: Mutex<Vec<PumpToken>>,
+    fail_with: Mutex<Option<String>>,
+    calls: AtomicUsize,
+}
+
+impl MockScanner {
+    pub fn new(tokens: Vec<PumpToken>) -> Self {
+        Self {
+            tokens: Mutex::new(tokens),
+            ..Default::default()
+        }
+    }
+
+    /// Подменяет токены, которые вернёт следующий вызов
+    pub fn set_tokens(&self, tokens: Vec<PumpToken>) {
+        *self.tokens.lock().unwrap() = tokens;
+    }
+
+    /// Следующие вызовы вернут ошибку (`None` — снова токены)
+    pub fn set_error(&self, error: Option<&str>) {
+        *self.fail_with.lock().unwrap() = error.map(str::to_string);
+    }
+
+    /// Сколько раз вызывался `eligible_tokens`
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl TokenScanner for MockScanner {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn eligible_tokens(&self) -> Result<Vec<PumpToken>> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if let Some(e) = self.fail_with.lock().unwrap().as_ref() {
+            anyhow::bail!("{}", e);
+        }
+        Ok(self.tokens.lock().unwrap().clone())
+    }
+}
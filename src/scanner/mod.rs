@@ -1,3 +1,61 @@
+pub mod analysis;
+pub mod authority;
+pub mod bundle;
+pub mod candles;
+pub mod clock;
+pub mod copycat;
+pub mod creator;
+pub mod enrich;
+pub mod events;
+pub mod filter;
+pub mod fixture;
+pub mod helius;
+pub mod holders;
+pub mod http;
+pub mod image;
+pub mod metadata;
+pub mod mock;
+pub mod onchain;
+pub mod patterns;
 pub mod pump_fun;
+pub mod pump_ws;
+pub mod ratelimit;
+pub mod raydium;
+pub mod report;
+pub mod ring;
+pub mod score;
+pub mod seen;
+pub mod source;
+pub mod stats;
+pub mod store;
+pub mod trades;
 
-pub use pump_fun::PumpFunScanner;
\ No newline at end of file
+pub use analysis::{wash_trading_score, wash_trading_score_with, WashWeights};
+pub use authority::{is_freezable, verify_authorities, AuthorityStatus};
+pub use bundle::BundleReport;
+pub use candles::{Candle, CandleTimeframe};
+pub use clock::{age_secs, normalize_timestamp};
+pub use copycat::CopycatDetector;
+pub use creator::{CreatorAnalyzer, CreatorReport};
+pub use enrich::EnrichedToken;
+pub use events::ScannerEvent;
+pub use filter::{FilterLists, MissingVolumePolicy, ScannerFilter};
+pub use fixture::FixtureScanner;
+pub use helius::{DasAsset, HeliusClient};
+pub use holders::HolderStats;
+pub use http::ScannerHttpConfig;
+pub use image::{ImageCheck, ImageRegistry};
+pub use metadata::TokenMetadata;
+pub use mock::MockScanner;
+pub use onchain::OnchainScanner;
+pub use patterns::PatternSet;
+pub use pump_fun::{PumpFunScanner, PumpToken, ScanSource};
+pub use raydium::RaydiumScanner;
+pub use report::ScanReport;
+pub use ring::{ring_channel, RingReceiver, RingSender};
+pub use score::{score, ScoreWeights};
+pub use seen::SeenCache;
+pub use source::{monitor_tokens, TokenScanner};
+pub use stats::ScannerStats;
+pub use store::{StoredToken, TokenStore};
+pub use trades::{buy_sell_ratio, unique_buyers, PumpTrade};
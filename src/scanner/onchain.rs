@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::StreamExt;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey, pubkey::Pubkey};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+use super::{
+    pump_fun::{unix_now, PumpToken},
+    SeenCache,
+};
+
+/// Программа pump.fun
+pub const PUMP_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+pub const PUMP_PROGRAM: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+pub const TOKEN_PROGRAM: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Anchor-дискриминатор `CreateEvent` (sha256("event:CreateEvent")[..8])
+const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// PDA bonding curve для mint-а pump.fun
+pub fn bonding_curve_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &PUMP_PROGRAM).0
+}
+
+/// Associated token account владельца для mint-а (SPL Token)
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM,
+    )
+    .0
+}
+
+/// Минимальные данные о токене из события создания
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub creator: Pubkey,
+}
+
+impl CreateEvent {
+    /// Разбор borsh-данных события (вместе с дискриминатором)
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut r = Reader(data.strip_prefix(&CREATE_EVENT_DISCRIMINATOR)?);
+        Some(Self {
+            name: r.string()?,
+            symbol: r.string()?,
+            uri: r.string()?,
+            mint: r.pubkey()?,
+            bonding_curve: r.pubkey()?,
+            creator: r.pubkey()?,
+        })
+    }
+
+    /// Ищет событие создания в логах транзакции
+    pub fn from_logs(logs: &[String]) -> Option<Self> {
+        if !logs.iter().any(|l| l.contains("Instruction: Create")) {
+            return None;
+        }
+        logs.iter()
+            .filter_map(|l| l.strip_prefix("Program data: "))
+            .filter_map(|b64| STANDARD.decode(b64).ok())
+            .find_map(|data| Self::decode(&data))
+    }
+
+    pub fn into_token(self, created_timestamp: u64) -> PumpToken {
+        PumpToken {
+            mint: self.mint.to_string(),
+            name: self.name,
+            symbol: self.symbol,
+            description: String::new(),
+            image_uri: String::new(),
+            created_timestamp,
+            metadata_uri: self.uri,
+            market_cap: 0.0,
+            liquidity: 0.0,
+            price: 0.0,
+            price_change_24h: 0.0,
+            // pump.fun отзывает mint authority при создании
+            is_mint_authority_revoked: true,
+            lp_status: "pending".to_string(),
+            creator_address: self.creator.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Курсор по borsh-буферу
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Option<&[u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().ok()?) as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Some(Pubkey::new_from_array(self.take(32)?.try_into().ok()?))
+    }
+}
+
+/// Детектор новых mint-ов через `logsSubscribe` на программу pump.fun.
+/// Задержка — доли секунды против нескольких секунд у HTTP API.
+#[derive(Debug, Clone)]
+pub struct OnchainScanner {
+    ws_url: String,
+    seen: Arc<Mutex<SeenCache>>,
+}
+
+impl OnchainScanner {
+    pub fn new(ws_url: &str) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            seen: Arc::new(Mutex::new(SeenCache::default())),
+        }
+    }
+
+    /// Общий кэш виденных mint-ов (например, с HTTP-сканером)
+    pub fn with_seen(mut self, seen: Arc<Mutex<SeenCache>>) -> Self {
+        self.seen = seen;
+        self
+    }
+
+    /// Основной цикл: подписка, переподключение с backoff.
+    /// Завершается, когда получатель канала закрыт.
+    pub async fn run(self, tx: mpsc::Sender<PumpToken>) {
+        let mut backoff = MIN_BACKOFF;
+
+        while !tx.is_closed() {
+            match self.subscribe(&tx, &mut backoff).await {
+                Ok(()) => log::warn!("Подписка logsSubscribe закрыта, переподключение..."),
+                Err(e) => log::warn!("Ошибка logsSubscribe: {}", e),
+            }
+            if tx.is_closed() {
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    async fn subscribe(&self, tx: &mpsc::Sender<PumpToken>, backoff: &mut Duration) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url)
+            .await
+            .context("подключение к RPC websocket")?;
+        let (mut stream, unsubscribe) = client
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![PUMP_PROGRAM_ID.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                },
+            )
+            .await?;
+        log::info!("logsSubscribe на pump.fun активен");
+        *backoff = MIN_BACKOFF;
+
+        while let Some(resp) = stream.next().await {
+            if resp.value.err.is_some() {
+                continue;
+            }
+            let Some(event) = CreateEvent::from_logs(&resp.value.logs) else {
+                continue;
+            };
+            if !self.seen.lock().unwrap().insert(&event.mint.to_string()) {
+                continue;
+            }
+
+            log::debug!("On-chain Create: {} ({})", event.symbol, event.mint);
+            if tx.send(event.into_token(unix_now())).await.is_err() {
+                break;
+            }
+        }
+
+        unsubscribe().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::{Context, Result};
+use regex::{RegexSet, RegexSetBuilder};
+use serde::{Deserialize, Deserializer};
+
+/// Набор регулярных выражений (без учёта регистра), скомпилированный при создании.
+/// Ошибка в выражении всплывает при создании фильтра, а не во время скана.
+#[derive(Debug, Clone)]
+pub struct PatternSet {
+    set: RegexSet,
+}
+
+impl Default for PatternSet {
+    fn default() -> Self {
+        Self {
+            set: RegexSet::empty(),
+        }
+    }
+}
+
+impl PatternSet {
+    pub fn new<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .map(|p| p.as_ref().to_string())
+            .collect();
+        // Компилируем по одному, чтобы в ошибке было видно, какое выражение неверно
+        for p in &patterns {
+            RegexSetBuilder::new([p])
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("неверное регулярное выражение {:?}", p))?;
+        }
+        let set = RegexSetBuilder::new(&patterns)
+            .case_insensitive(true)
+            .build()?;
+        Ok(Self { set })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        self.set.patterns()
+    }
+
+    /// Совпадает ли хотя бы одно выражение хотя бы с одним из текстов
+    pub fn matches_any(&self, texts: &[&str]) -> bool {
+        texts.iter().any(|t| self.set.is_match(t))
+    }
+}
+
+impl<'de> Deserialize<'de> for PatternSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let patterns = Vec::<String>::deserialize(deserializer)?;
+        PatternSet::new(patterns).map_err(|e| serde::de::Error::custom(format!("{:#}", e)))
+    }
+}
@@ -1,120 +1,1994 @@
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
-use tokio::time;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    future::Future,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::{sync::mpsc, time};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::pricing::SolPriceFeed;
+
+use super::{
+    analysis::{wash_trading_score_with, WashWeights},
+    authority::{verify_authorities, AuthorityStatus, AUTHORITY_CONCURRENCY},
+    bundle::{detect_bundle, BundleReport},
+    candles::{fetch_candles, Candle, CandleTimeframe},
+    clock::{age_secs, normalize_timestamp, opt_timestamp_secs, timestamp_secs},
+    copycat::{CopycatDetector, DEFAULT_COPYCAT_WINDOW},
+    creator::{CreatorAnalyzer, CreatorReport},
+    enrich::{EnrichedToken, DEFAULT_ENRICH_CONCURRENCY},
+    events::{changed_beyond, RateLimitedError},
+    filter::FilterLists,
+    helius::HeliusClient,
+    holders::{
+        creator_holding_pct, holder_concentration, HolderStats, SupplyCache, HOLDER_CONCURRENCY,
+    },
+    http::{read_text, HttpPool, ScannerHttpConfig},
+    image::{check_image, ImageRegistry, IMAGE_CONCURRENCY},
+    metadata::{fetch_metadata, TokenMetadata},
+    pump_ws,
+    report::ScanReport,
+    ring::{ring_channel, RingReceiver, RingSender},
+    seen::DEFAULT_SEEN_TTL,
+    stats::StatsCounters,
+    store::TokenStore,
+    trades::{fetch_trades, volume_since, PumpTrade, VOLUME_TRADES_LIMIT},
+    OnchainScanner, ScannerEvent, ScannerFilter, ScannerStats, ScoreWeights, SeenCache,
+    TokenScanner,
+};
+
+/// Монета pump.fun. Все поля, кроме `mint`, терпят null и отсутствие:
+/// схема API меняется без предупреждения.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct PumpToken {
     pub mint: String,
+    #[serde(default, deserialize_with = "null_default")]
     pub name: String,
+    #[serde(default, deserialize_with = "null_default")]
     pub symbol: String,
+    #[serde(default, deserialize_with = "null_default")]
     pub description: String,
+    #[serde(default, deserialize_with = "null_default")]
     pub image_uri: String,
+    /// unix, сек (миллисекунды из API приводятся к секундам)
+    #[serde(default, deserialize_with = "timestamp_secs")]
     pub created_timestamp: u64,
-    #[serde(rename = "uri")]
+    #[serde(rename = "uri", default, deserialize_with = "null_default")]
     pub metadata_uri: String,
+    /// Капитализация в SOL
+    #[serde(default, deserialize_with = "null_default")]
     pub market_cap: f64,
+    /// Капитализация в USD; из API или по цене SOL (`SolPriceFeed`)
+    #[serde(default)]
+    pub usd_market_cap: Option<f64>,
+    #[serde(default, deserialize_with = "null_default")]
     pub liquidity: f64,
+    #[serde(default, deserialize_with = "null_default")]
     pub price: f64,
+    #[serde(default, deserialize_with = "null_default")]
     pub price_change_24h: f64,
+    #[serde(default, deserialize_with = "null_default")]
     pub is_mint_authority_revoked: bool,
-    #[serde(rename = "lp_creation_status")]
+    #[serde(
+        rename = "lp_creation_status",
+        default,
+        deserialize_with = "null_default"
+    )]
     pub lp_status: String,
-    #[serde(rename = "creator")]
+    #[serde(rename = "creator", default, deserialize_with = "null_default")]
     pub creator_address: String,
+    /// Кривая завершена, токен ушёл на Raydium
+    #[serde(default, deserialize_with = "null_default")]
+    pub complete: bool,
+    /// Остаток токенов на кривой (сырые единицы); нужен для прогресса
+    #[serde(default)]
+    pub real_token_reserves: Option<u64>,
+    /// Прогресс bonding curve, 0–100%; без данных о кривой — 0
+    #[serde(default, deserialize_with = "null_default")]
+    pub bonding_progress: f64,
+    /// Заполняется `enrich_metadata`
+    #[serde(default)]
+    pub metadata: Option<TokenMetadata>,
+    /// Повторяет имя/символ/картинку недавнего токена (см. `CopycatDetector`)
+    #[serde(default, deserialize_with = "null_default")]
+    pub is_copycat: bool,
+    /// Объём за 24ч, SOL; из API или по ленте сделок
+    #[serde(default)]
+    pub volume_24h: Option<f64>,
+    /// Сделок за 24ч
+    #[serde(default)]
+    pub txn_count: Option<u64>,
+    #[serde(default, deserialize_with = "null_default")]
+    pub nsfw: bool,
+    /// Создатель сейчас ведёт стрим
+    #[serde(default, deserialize_with = "null_default")]
+    pub is_currently_live: bool,
+    /// Комментариев в треде токена
+    #[serde(default, deserialize_with = "null_default")]
+    pub reply_count: u64,
+    /// Последняя сделка, unix, сек; из API или по ленте сделок
+    #[serde(default, deserialize_with = "opt_timestamp_secs")]
+    pub last_trade_timestamp: Option<u64>,
+}
+
+/// null → значение по умолчанию
+fn null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Разбор ответа со списком монет: массив, `{ "coins": [...] }` или одна монета.
+/// Битые элементы пропускаются (с записью в debug-лог), остальные возвращаются.
+pub fn parse_coins(text: &str) -> Result<Vec<PumpToken>> {
+    let items = match serde_json::from_str::<serde_json::Value>(text)? {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(obj) if obj.contains_key("mint") => {
+            vec![serde_json::Value::Object(obj)]
+        }
+        serde_json::Value::Object(mut obj) => match obj.remove("coins") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => anyhow::bail!("в ответе нет списка монет"),
+        },
+        _ => anyhow::bail!("неожиданный формат ответа со списком монет"),
+    };
+
+    let total = items.len();
+    let tokens: Vec<PumpToken> = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let mint = item["mint"].as_str().unwrap_or("?").to_string();
+            match serde_json::from_value::<PumpToken>(item) {
+                Ok(t) => Some(t),
+                Err(e) => {
+                    log::debug!("Монета #{} ({}) пропущена: {}", i, mint, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    if tokens.len() < total {
+        log::debug!("Разобрано {} из {} монет", tokens.len(), total);
+    }
+    Ok(tokens)
+}
+
+/// Токенов на кривой pump.fun в начале (793.1M × 10^6)
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+
+/// Прогресс bonding curve по остатку токенов на ней, %
+pub fn curve_progress(real_token_reserves: u64) -> f64 {
+    let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(real_token_reserves);
+    (sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0).clamp(0.0, 100.0)
 }
 
-#[derive(Debug, Clone)]
+impl PumpToken {
+    /// Пересчитывает `bonding_progress` из `complete` / `real_token_reserves`
+    pub fn update_bonding_progress(&mut self) {
+        self.bonding_progress = if self.complete {
+            100.0
+        } else {
+            self.real_token_reserves.map(curve_progress).unwrap_or(0.0)
+        };
+    }
+}
+
+/// Интервал опроса по умолчанию
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Сколько по умолчанию следить за токеном после `NewToken`
+pub const DEFAULT_TRACK_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Во сколько раз максимум растягивается интервал при серии ошибок
+const MAX_BACKOFF_FACTOR: u32 = 32;
+
+/// Сколько лент сделок запрашивать одновременно
+const TRADES_CONCURRENCY: usize = 8;
+
+#[derive(Clone)]
 pub struct PumpFunScanner {
-    client: reqwest::Client,
+    http: HttpPool,
+    /// Mint-ы, уже отданные наружу (общие для polling и сокета)
+    seen: Arc<Mutex<SeenCache>>,
+    /// Общий для клонов: списки перечитываются на лету (`reload_lists`)
+    filter: Arc<RwLock<ScannerFilter>>,
+    lists_path: Option<PathBuf>,
+    poll_interval: Duration,
+    jitter_pct: f64,
+    liquidity_delta_pct: f64,
+    price_delta_pct: f64,
+    track_ttl: Duration,
+    score_weights: ScoreWeights,
+    creators: CreatorAnalyzer,
+    rpc: Option<Arc<RpcClient>>,
+    helius: Option<HeliusClient>,
+    /// Журнал просмотренных токенов
+    store: Option<Arc<TokenStore>>,
+    /// Счётчики отбора, общие для всех клонов
+    stats: Arc<StatsCounters>,
+    copycats: Arc<Mutex<CopycatDetector>>,
+    /// Хэши картинок для поиска повторов
+    images: Arc<Mutex<ImageRegistry>>,
+    coins_cache: Arc<Mutex<CoinsCache>>,
+    enrich_concurrency: usize,
+    bundle_check: bool,
+    image_check: bool,
+    source: ScanSource,
+    /// Не отдавать токены, созданные до этого момента (unix, сек); 0 — отдавать все
+    emit_after: u64,
+    sol_price: Arc<SolPriceFeed>,
+}
+
+/// Сколько монет брать из трендов в цикле сканирования
+pub const TRENDING_LIMIT: usize = 50;
+
+/// Откуда цикл сканирования берёт кандидатов (`PumpFunScannerBuilder::source`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanSource {
+    /// Свежесозданные монеты
+    #[default]
+    NewCoins,
+    /// Текущий «king of the hill»
+    KingOfTheHill,
+    /// Монеты с последними сделками (`TRENDING_LIMIT` штук)
+    Trending,
+    /// Все источники вместе, без повторов
+    All,
+}
+
+/// Время запроса и разбора списка монет
+#[derive(Debug, Default)]
+struct FetchTiming {
+    fetch: Duration,
+    parse: Duration,
+}
+
+/// Последний ответ со списком монет и валидаторы для условного запроса
+#[derive(Debug, Default)]
+struct CoinsCache {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    coins: Vec<PumpToken>,
+    /// Результат последнего отбора — отдаётся при 304
+    eligible: Vec<PumpToken>,
+}
+
+impl CoinsCache {
+    fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(v) = self.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(reqwest::header::IF_NONE_MATCH, v);
+        }
+        if let Some(v) = self.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(reqwest::header::IF_MODIFIED_SINCE, v);
+        }
+        headers
+    }
+}
+
+impl fmt::Debug for PumpFunScanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PumpFunScanner")
+            .field("http", &self.http)
+            .field("filter", &*self.filter.read().unwrap())
+            .field("poll_interval", &self.poll_interval)
+            .field("jitter_pct", &self.jitter_pct)
+            .field("rpc", &self.rpc.as_ref().map(|r| r.url()))
+            .field("store", &self.store.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub struct PumpFunScannerBuilder {
+    filter: ScannerFilter,
+    lists_path: Option<PathBuf>,
+    seen_ttl: Duration,
+    poll_interval: Duration,
+    jitter_pct: f64,
+    liquidity_delta_pct: f64,
+    price_delta_pct: f64,
+    track_ttl: Duration,
+    score_weights: ScoreWeights,
+    rpc: Option<Arc<RpcClient>>,
+    helius: Option<HeliusClient>,
+    http_config: ScannerHttpConfig,
+    store: Option<Arc<TokenStore>>,
+    copycat_window: Duration,
+    enrich_concurrency: usize,
+    bundle_check: bool,
+    image_check: bool,
+    source: ScanSource,
+    skip_preexisting: bool,
+    preexisting_grace: Duration,
+    sol_price: Option<Arc<SolPriceFeed>>,
+}
+
+impl Default for PumpFunScannerBuilder {
+    fn default() -> Self {
+        Self {
+            filter: ScannerFilter::default(),
+            lists_path: None,
+            seen_ttl: DEFAULT_SEEN_TTL,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            jitter_pct: 0.0,
+            liquidity_delta_pct: 5.0,
+            price_delta_pct: 5.0,
+            track_ttl: DEFAULT_TRACK_TTL,
+            score_weights: ScoreWeights::default(),
+            rpc: None,
+            helius: None,
+            http_config: ScannerHttpConfig::default(),
+            store: None,
+            copycat_window: DEFAULT_COPYCAT_WINDOW,
+            enrich_concurrency: DEFAULT_ENRICH_CONCURRENCY,
+            bundle_check: false,
+            image_check: false,
+            source: ScanSource::default(),
+            skip_preexisting: false,
+            preexisting_grace: Duration::ZERO,
+            sol_price: None,
+        }
+    }
+}
+
+impl PumpFunScannerBuilder {
+    pub fn filter(mut self, filter: ScannerFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// JSON-файл с чёрными/белыми списками (`FilterLists`), перечитывается `reload_lists`
+    pub fn lists_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.lists_path = Some(path.into());
+        self
+    }
+
+    pub fn seen_ttl(mut self, ttl: Duration) -> Self {
+        self.seen_ttl = ttl;
+        self
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Случайный разброс паузы, доля от интервала (0.2 = ±20%)
+    pub fn jitter_pct(mut self, jitter_pct: f64) -> Self {
+        self.jitter_pct = jitter_pct.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Порог изменения ликвидности и цены (%) для `ScannerEvent::TokenUpdated`
+    pub fn update_delta_pct(mut self, delta_pct: f64) -> Self {
+        self.liquidity_delta_pct = delta_pct;
+        self.price_delta_pct = delta_pct;
+        self
+    }
+
+    /// Порог изменения ликвидности (%) для `ScannerEvent::TokenUpdated`
+    pub fn liquidity_delta_pct(mut self, delta_pct: f64) -> Self {
+        self.liquidity_delta_pct = delta_pct;
+        self
+    }
+
+    /// Порог изменения цены (%) для `ScannerEvent::TokenUpdated`
+    pub fn price_delta_pct(mut self, delta_pct: f64) -> Self {
+        self.price_delta_pct = delta_pct;
+        self
+    }
+
+    /// Сколько следить за токеном после `NewToken` (события `TokenUpdated`, `Graduated`)
+    pub fn track_ttl(mut self, ttl: Duration) -> Self {
+        self.track_ttl = ttl;
+        self
+    }
+
+    pub fn score_weights(mut self, weights: ScoreWeights) -> Self {
+        self.score_weights = weights;
+        self
+    }
+
+    /// RPC для on-chain проверок (возраст кошелька создателя и т.п.)
+    pub fn rpc(mut self, rpc: Arc<RpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Запасной источник данных о mint-е (`HeliusClient::from_config`)
+    pub fn helius(mut self, helius: Option<HeliusClient>) -> Self {
+        self.helius = helius;
+        self
+    }
+
+    /// Прокси и их ротация
+    pub fn http_config(mut self, config: ScannerHttpConfig) -> Self {
+        self.http_config = config;
+        self
+    }
+
+    /// Записывать каждый просмотренный токен и результат отбора в SQLite
+    pub fn store(mut self, store: Arc<TokenStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Сколько помнить имена/символы для поиска копий
+    pub fn copycat_window(mut self, window: Duration) -> Self {
+        self.copycat_window = window;
+        self
+    }
+
+    /// Сколько токенов `get_enriched_tokens` обогащает одновременно
+    pub fn enrich_concurrency(mut self, n: usize) -> Self {
+        self.enrich_concurrency = n.max(1);
+        self
+    }
+
+    /// Считать `bundled_buy_score` при обогащении (несколько RPC-запросов на токен)
+    pub fn bundle_check(mut self, enabled: bool) -> Self {
+        self.bundle_check = enabled;
+        self
+    }
+
+    /// Откуда брать кандидатов для `scan`, monitor-циклов и `run`.
+    /// Фильтры (включая `max_age_secs`) применяются ко всем источникам одинаково.
+    pub fn source(mut self, source: ScanSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Не отдавать токены, созданные до запуска процесса: после рестарта
+    /// первый скан не должен снайпить то, что уже отторговалось
+    pub fn skip_preexisting(mut self, enabled: bool) -> Self {
+        self.skip_preexisting = enabled;
+        self
+    }
+
+    /// С `skip_preexisting`: токены, созданные не раньше чем за `grace` до запуска, всё же отдаются
+    pub fn preexisting_grace(mut self, grace: Duration) -> Self {
+        self.preexisting_grace = grace;
+        self
+    }
+
+    /// Проверять `image_uri` при обогащении (`image_ok`, `image_reused`)
+    pub fn image_check(mut self, enabled: bool) -> Self {
+        self.image_check = enabled;
+        self
+    }
+
+    /// Источник цены SOL для `usd_market_cap`; по умолчанию Pyth, затем CoinGecko
+    pub fn sol_price(mut self, feed: Arc<SolPriceFeed>) -> Self {
+        self.sol_price = Some(feed);
+        self
+    }
+
+    pub fn build(mut self) -> PumpFunScanner {
+        if let Some(path) = &self.lists_path {
+            match FilterLists::load(path) {
+                Ok(lists) => self.filter.set_lists(lists),
+                Err(e) => log::error!("Списки не загружены: {:#}", e),
+            }
+        }
+
+        let http = HttpPool::new(self.http_config);
+
+        let mut creators = CreatorAnalyzer::new(http.clone());
+        if let Some(rpc) = &self.rpc {
+            creators = creators.with_rpc(rpc.clone());
+        }
+
+        PumpFunScanner {
+            http,
+            seen: Arc::new(Mutex::new(SeenCache::new(self.seen_ttl))),
+            filter: Arc::new(RwLock::new(self.filter)),
+            lists_path: self.lists_path,
+            poll_interval: self.poll_interval,
+            jitter_pct: self.jitter_pct,
+            liquidity_delta_pct: self.liquidity_delta_pct,
+            price_delta_pct: self.price_delta_pct,
+            track_ttl: self.track_ttl,
+            score_weights: self.score_weights,
+            creators,
+            rpc: self.rpc,
+            helius: self.helius,
+            store: self.store,
+            stats: Arc::new(StatsCounters::default()),
+            copycats: Arc::new(Mutex::new(CopycatDetector::new(self.copycat_window))),
+            images: Arc::new(Mutex::new(ImageRegistry::default())),
+            coins_cache: Arc::new(Mutex::new(CoinsCache::default())),
+            enrich_concurrency: self.enrich_concurrency,
+            bundle_check: self.bundle_check,
+            image_check: self.image_check,
+            source: self.source,
+            emit_after: if self.skip_preexisting {
+                unix_now().saturating_sub(self.preexisting_grace.as_secs())
+            } else {
+                0
+            },
+            sol_price: self.sol_price.unwrap_or_default(),
+        }
+    }
+}
+
+/// Текущее время, unix-секунды
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Пауза перед следующим опросом.
+/// После ошибок интервал удваивается (до `MAX_BACKOFF_FACTOR`),
+/// затем к нему применяется разброс: `r` ∈ [-1, 1] масштабируется на `jitter_pct`.
+pub fn poll_delay(base: Duration, jitter_pct: f64, consecutive_errors: u32, r: f64) -> Duration {
+    let factor = 2u32
+        .saturating_pow(consecutive_errors)
+        .min(MAX_BACKOFF_FACTOR);
+    let scaled = base.as_secs_f64() * factor as f64;
+    let jitter = jitter_pct.clamp(0.0, 1.0) * r.clamp(-1.0, 1.0);
+    Duration::from_secs_f64(scaled * (1.0 + jitter))
+}
+
+impl Default for PumpFunScanner {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PumpFunScanner {
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(10))
-            .gzip(true)
-            .default_headers({
-                let mut headers = reqwest::header::HeaderMap::new();
-                headers.insert(
-                    reqwest::header::USER_AGENT,
-                    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".parse().unwrap()
-                );
-                headers.insert(
-                    reqwest::header::ACCEPT,
-                    "application/json".parse().unwrap()
-                );
-                headers.insert(
-                    reqwest::header::ORIGIN,
-                    "https://pump.fun".parse().unwrap()
-                );
-                headers.insert(
-                    reqwest::header::REFERER,
-                    "https://pump.fun".parse().unwrap()
-                );
-                headers
-            })
-            .build()
-            .expect("Failed to build HTTP client");
-        
-        Self { client }
+        Self::builder().build()
+    }
+
+    pub fn builder() -> PumpFunScannerBuilder {
+        PumpFunScannerBuilder::default()
+    }
+
+    pub fn with_filter(filter: ScannerFilter) -> Self {
+        Self::builder().filter(filter).build()
+    }
+
+    /// Время, через которое уже отданный mint может прийти снова
+    pub fn with_seen_ttl(self, ttl: Duration) -> Self {
+        self.seen.lock().unwrap().set_ttl(ttl);
+        self
+    }
+
+    /// Забыть все отданные mint-ы
+    pub fn reset_seen(&self) {
+        self.seen.lock().unwrap().clear();
+    }
+
+    /// Снимок текущего фильтра
+    pub fn filter(&self) -> ScannerFilter {
+        self.filter.read().unwrap().clone()
+    }
+
+    /// Перечитывает чёрные/белые списки из `lists_path`.
+    /// При ошибке действуют прежние списки.
+    pub fn reload_lists(&self) -> Result<()> {
+        let Some(path) = &self.lists_path else {
+            anyhow::bail!("путь к спискам не задан (PumpFunScanner::builder().lists_path(..))");
+        };
+        let lists = FilterLists::load(path)?;
+        log::info!(
+            "Списки обновлены: {} создателей в чёрном, {} в белом, {} символов",
+            lists.creator_blacklist.len(),
+            lists.creator_whitelist.as_ref().map_or(0, |w| w.len()),
+            lists.symbol_blacklist.len()
+        );
+        self.filter.write().unwrap().set_lists(lists);
+        Ok(())
     }
 
     pub async fn get_eligible_tokens(&self) -> Result<Vec<PumpToken>> {
+        self.get_eligible_tokens_filtered(&self.filter()).await
+    }
+
+    pub async fn get_eligible_tokens_filtered(
+        &self,
+        filter: &ScannerFilter,
+    ) -> Result<Vec<PumpToken>> {
+        Ok(self.scan_filtered(filter).await?.into_tokens())
+    }
+
+    /// Как `get_eligible_tokens`, плюс время этапов и причины отказов
+    pub async fn scan(&self) -> Result<ScanReport> {
+        self.scan_filtered(&self.filter()).await
+    }
+
+    /// Запрос, фильтры и запись в журнал.
+    /// Если список монет не изменился (304), отдаётся прошлый результат,
+    /// из которого убраны постаревшие токены.
+    pub async fn scan_filtered(&self, filter: &ScannerFilter) -> Result<ScanReport> {
+        let mut timing = FetchTiming::default();
+        let res = self.fetch_candidates(&mut timing).await;
+        let mut report = ScanReport {
+            fetch_ms: timing.fetch.as_millis() as u64,
+            parse_ms: timing.parse.as_millis() as u64,
+            endpoint_used: self.http.active_endpoint(),
+            ..Default::default()
+        };
+
+        let Some(coins) = res? else {
+            let now = unix_now();
+            let mut cache = self.coins_cache.lock().unwrap();
+            cache
+                .eligible
+                .retain(|t| age_secs(t.created_timestamp, now) < filter.max_age_secs);
+            report.tokens = cache.eligible.clone();
+            report.not_modified = true;
+            return Ok(report);
+        };
+
+        report.total_candidates = coins.len();
+        let started = Instant::now();
+        let (eligible, rejected) = self.filter_tokens_explained(coins, filter).await;
+        report.enrich_ms = started.elapsed().as_millis() as u64;
+        self.record_scan(&eligible, &rejected);
+        for (_, reason) in &rejected {
+            *report.rejections.entry(reason.clone()).or_default() += 1;
+        }
+        self.coins_cache.lock().unwrap().eligible = eligible.clone();
+        report.tokens = eligible;
+        Ok(report)
+    }
+
+    /// Текущий «king of the hill» (пусто, если API его не отдал)
+    pub async fn get_king_of_the_hill(&self) -> Result<Vec<PumpToken>> {
+        self.fetch_coin_list("/coins/king-of-the-hill?includeNsfw=true")
+            .await
+    }
+
+    /// Монеты с самыми свежими сделками — как «trending» на сайте
+    pub async fn get_trending(&self, limit: usize) -> Result<Vec<PumpToken>> {
+        let path = format!(
+            "/coins?offset=0&limit={}&sort=last_trade_timestamp&order=DESC&includeNsfw=true",
+            limit
+        );
+        self.fetch_coin_list(&path).await
+    }
+
+    async fn fetch_coin_list(&self, path: &str) -> Result<Vec<PumpToken>> {
+        let res = self.http.send_api(path, HeaderMap::new()).await?;
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let text = read_text(res).await?;
+        if text.trim().is_empty() || text.trim() == "null" {
+            return Ok(Vec::new());
+        }
+        let mut tokens = parse_coins(&text)?;
+        for t in &mut tokens {
+            t.update_bonding_progress();
+        }
+        Ok(tokens)
+    }
+
+    /// Кандидаты из источников `source`; `None` — новых монет нет (304) и других источников нет
+    async fn fetch_candidates(&self, timing: &mut FetchTiming) -> Result<Option<Vec<PumpToken>>> {
+        let res = match self.source {
+            ScanSource::NewCoins => self.request_coins(timing).await,
+            ScanSource::KingOfTheHill => self.get_king_of_the_hill().await.map(Some),
+            ScanSource::Trending => self.get_trending(TRENDING_LIMIT).await.map(Some),
+            ScanSource::All => self.fetch_all_sources(timing).await.map(Some),
+        };
+        if res.is_err() {
+            self.stats.add_api_error();
+        }
+        res
+    }
+
+    /// Новые монеты, king of the hill и тренды без повторов по mint.
+    /// Ошибка одного источника не мешает остальным.
+    async fn fetch_all_sources(&self, timing: &mut FetchTiming) -> Result<Vec<PumpToken>> {
+        let (new, king, trending) = futures_util::join!(
+            self.request_coins(timing),
+            self.get_king_of_the_hill(),
+            self.get_trending(TRENDING_LIMIT),
+        );
+        let new = new
+            .map(|coins| coins.unwrap_or_else(|| self.coins_cache.lock().unwrap().coins.clone()));
+
+        let mut merged: Vec<PumpToken> = Vec::new();
+        let mut mints = HashSet::new();
+        let mut last_err = None;
+        for (name, res) in [
+            ("новые", new),
+            ("king of the hill", king),
+            ("тренды", trending),
+        ] {
+            match res {
+                Ok(coins) => {
+                    merged.extend(coins.into_iter().filter(|t| mints.insert(t.mint.clone())))
+                }
+                Err(e) => {
+                    log::warn!("Источник «{}» недоступен: {}", name, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        match last_err {
+            Some(e) if merged.is_empty() => Err(e),
+            _ => Ok(merged),
+        }
+    }
+
+    /// Последние монеты pump.fun без фильтрации
+    pub async fn fetch_coins(&self) -> Result<Vec<PumpToken>> {
+        match self.fetch_coins_if_modified().await? {
+            Some(coins) => Ok(coins),
+            None => Ok(self.coins_cache.lock().unwrap().coins.clone()),
+        }
+    }
+
+    /// Условный запрос списка монет: `None`, если с прошлого ответа ничего не изменилось
+    pub async fn fetch_coins_if_modified(&self) -> Result<Option<Vec<PumpToken>>> {
+        let res = self.request_coins(&mut FetchTiming::default()).await;
+        if res.is_err() {
+            self.stats.add_api_error();
+        }
+        res
+    }
+
+    async fn request_coins(&self, timing: &mut FetchTiming) -> Result<Option<Vec<PumpToken>>> {
+        let started = Instant::now();
         // Используем beta-эндпоинт — он более стабилен
-        let url = "https://frontend-api.pump.fun/coins?limit=50&offset=0&sort=created_timestamp&order=DESC";
-        
-        log::debug!("Запрос к Pump.fun: {}", url);
-        let res = self.client.get(url).send().await?;
-        
-        let status = res.status();
-        let text = res.text().await?;
-        
-        if !status.is_success() {
-            log::error!("Pump.fun вернул {}: {}", status, text);
-            anyhow::bail!("HTTP {}: {}", status, text);
-        }
-
-        let tokens: Vec<PumpToken> = serde_json::from_str(&text)?;
-        
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let path = "/coins?limit=50&offset=0&sort=created_timestamp&order=DESC";
+
+        log::debug!("Запрос к Pump.fun: {}", path);
+        let headers = self.coins_cache.lock().unwrap().conditional_headers();
+        let res = self.http.send_api(path, headers).await?;
+        if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+            log::debug!("Pump.fun: список монет не изменился (304)");
+            return Ok(None);
+        }
+
+        let header = |name: reqwest::header::HeaderName| {
+            res.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        let text = read_text(res).await?;
+        timing.fetch = started.elapsed();
+
+        let started = Instant::now();
+        let mut tokens = parse_coins(&text)?;
+        for t in &mut tokens {
+            t.update_bonding_progress();
+        }
+        timing.parse = started.elapsed();
+
+        let mut cache = self.coins_cache.lock().unwrap();
+        cache.etag = etag;
+        cache.last_modified = last_modified;
+        cache.coins = tokens.clone();
+        Ok(Some(tokens))
+    }
+
+    /// Одна монета по mint; `None`, если pump.fun её не знает (404).
+    /// При ошибке API — из Helius DAS (без рыночных данных), если он задан.
+    pub async fn get_token_by_mint(&self, mint: &str) -> Result<Option<PumpToken>> {
+        match (self.fetch_token(mint).await, &self.helius) {
+            (Err(e), Some(helius)) => {
+                log::warn!("Pump.fun не отдал {}, пробуем Helius: {}", mint, e);
+                Ok(Some(helius.get_asset(mint).await?.into_token()))
+            }
+            (res, _) => res,
+        }
+    }
+
+    async fn fetch_token(&self, mint: &str) -> Result<Option<PumpToken>> {
+        let path = format!("/coins/{}", mint);
+        let res = self.http.send_api(&path, HeaderMap::new()).await?;
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let text = read_text(res).await?;
+        let mut token: PumpToken = serde_json::from_str(&text)?;
+        token.update_bonding_progress();
+        Ok(Some(token))
+    }
+
+    /// Последние `limit` сделок по mint-у, новые первыми
+    pub async fn get_recent_trades(&self, mint: &str, limit: usize) -> Result<Vec<PumpTrade>> {
+        fetch_trades(&self.http, mint, limit).await
+    }
+
+    /// История цены токена свечами, от старых к новым
+    pub async fn get_candles(
+        &self,
+        mint: &str,
+        timeframe: CandleTimeframe,
+        limit: usize,
+    ) -> Result<Vec<Candle>> {
+        fetch_candles(&self.http, mint, timeframe, limit).await
+    }
+
+    /// Прогоняет токены через дешёвые фильтры, затем через проверки
+    /// с дополнительными запросами (только для выживших)
+    pub async fn filter_tokens(
+        &self,
+        tokens: Vec<PumpToken>,
+        filter: &ScannerFilter,
+    ) -> Vec<PumpToken> {
+        self.filter_tokens_explained(tokens, filter).await.0
+    }
+
+    /// Как `filter_tokens`, но возвращает и отброшенные токены с причиной отказа
+    pub async fn filter_tokens_explained(
+        &self,
+        tokens: Vec<PumpToken>,
+        filter: &ScannerFilter,
+    ) -> (Vec<PumpToken>, Vec<(PumpToken, String)>) {
+        let now = unix_now();
+        let tokens = self.mark_copycats(tokens);
+        let mut rejected = Vec::new();
+        let mut filtered = Vec::new();
+        // Белый список создателей обходит проверки с доп. запросами
+        let mut whitelisted = Vec::new();
+        for t in tokens {
+            match filter.rejection_reason(&t, now) {
+                Some(reason) => rejected.push((t, reason.to_string())),
+                None if filter.is_whitelisted(&t) => whitelisted.push(t),
+                None => filtered.push(t),
+            }
+        }
+
+        // Метаданные качаем только для прошедших дешёвые фильтры
+        if filter.require_socials {
+            futures_util::future::join_all(filtered.iter_mut().map(|t| self.enrich_metadata(t)))
+                .await;
+            let (kept, dropped): (Vec<_>, Vec<_>) = filtered
+                .into_iter()
+                .partition(|t| filter.matches_socials(t));
+            filtered = kept;
+            reject_all(&mut rejected, dropped, "no_socials");
+        }
+
+        if filter.checks_image() {
+            let checked: Vec<_> = futures_util::stream::iter(filtered)
+                .map(|t| async move {
+                    let signals = self.image_signals(&t).await;
+                    (t, signals)
+                })
+                .buffered(IMAGE_CONCURRENCY)
+                .collect()
+                .await;
+            let (kept, dropped): (Vec<_>, Vec<_>) = checked
+                .into_iter()
+                .partition(|(_, (ok, reused))| filter.matches_image(*ok, *reused));
+            filtered = kept.into_iter().map(|(t, _)| t).collect();
+            reject_all(&mut rejected, dropped.into_iter().map(|(t, _)| t), "image");
+        }
+
+        if filter.checks_market_cap() {
+            self.fill_usd_market_cap(&mut filtered).await;
+            let (kept, dropped): (Vec<_>, Vec<_>) = filtered
+                .into_iter()
+                .partition(|t| filter.matches_market_cap(t));
+            filtered = kept;
+            reject_all(&mut rejected, dropped, "market_cap");
+        }
+
+        if filter.checks_volume() {
+            futures_util::stream::iter(filtered.iter_mut())
+                .for_each_concurrent(TRADES_CONCURRENCY, |t| self.enrich_volume(t))
+                .await;
+            let (kept, dropped): (Vec<_>, Vec<_>) =
+                filtered.into_iter().partition(|t| filter.matches_volume(t));
+            filtered = kept;
+            reject_all(&mut rejected, dropped, "low_volume");
+        }
+
+        if filter.checks_activity() {
+            futures_util::stream::iter(filtered.iter_mut())
+                .for_each_concurrent(TRADES_CONCURRENCY, |t| self.enrich_volume(t))
+                .await;
+            let now = unix_now();
+            let (kept, dropped): (Vec<_>, Vec<_>) = filtered
+                .into_iter()
+                .partition(|t| filter.matches_activity(t, now));
+            filtered = kept;
+            reject_all(&mut rejected, dropped, "stale");
+        }
+
+        if let Some(max_score) = filter.max_wash_score {
+            let before = filtered.clone();
+            filtered = self
+                .retain_by_wash_score(filtered, max_score, &filter.wash_weights)
+                .await;
+            reject_missing(&mut rejected, before, &filtered, "wash_trading");
+        }
+
+        if filter.checks_creator() {
+            let reports = futures_util::future::join_all(
+                filtered
+                    .iter()
+                    .map(|t| self.creators.analyze_creator(&t.creator_address)),
+            )
+            .await;
+            let (kept, dropped): (Vec<_>, Vec<_>) =
+                filtered
+                    .into_iter()
+                    .zip(reports)
+                    .partition(|(t, report)| match report {
+                        Ok(report) => filter.matches_creator(report),
+                        Err(e) => {
+                            log::debug!("Создатель {} не проверен: {}", t.creator_address, e);
+                            false
+                        }
+                    });
+            filtered = kept.into_iter().map(|(t, _)| t).collect();
+            reject_all(
+                &mut rejected,
+                dropped.into_iter().map(|(t, _)| t),
+                "creator",
+            );
+        }
+
+        if filter.verify_on_chain {
+            let before = filtered.clone();
+            filtered = self.retain_by_authorities(filtered, filter).await;
+            reject_missing(&mut rejected, before, &filtered, "authorities");
+        }
+
+        if let Some(max_pct) = filter.max_top10_holder_pct {
+            let before = filtered.clone();
+            filtered = self.retain_by_holders(filtered, max_pct).await;
+            reject_missing(&mut rejected, before, &filtered, "holders");
+        }
+
+        if let Some(max_score) = filter.max_bundled_buy_score {
+            let before = filtered.clone();
+            filtered = self.retain_by_bundle(filtered, max_score).await;
+            reject_missing(&mut rejected, before, &filtered, "bundled_buy");
+        }
+
+        if let Some(max_pct) = filter.max_creator_holding_pct {
+            let before = filtered.clone();
+            filtered = self
+                .retain_by_creator_holding(filtered, max_pct, filter.creator_holding_exclude_curve)
+                .await;
+            reject_missing(&mut rejected, before, &filtered, "creator_holding");
+        }
+
+        filtered.extend(whitelisted);
+
+        let mut by_reason: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, reason) in &rejected {
+            self.stats.add_rejection(reason);
+            *by_reason.entry(reason.as_str()).or_default() += 1;
+        }
+        self.stats.add_passed(filtered.len());
+        log::debug!(
+            "Цикл отбора: прошло {}, отброшено {:?}",
+            filtered.len(),
+            by_reason
+        );
+        (filtered, rejected)
+    }
+
+    /// Проставляет `is_copycat`; в окно попадают все токены, включая отброшенные,
+    /// ведь оригинал мог и не пройти фильтры
+    fn mark_copycats(&self, mut tokens: Vec<PumpToken>) -> Vec<PumpToken> {
+        // Более ранние токены — оригиналы, поэтому идём от старых к новым
+        tokens.sort_by_key(|t| std::cmp::Reverse(t.created_timestamp));
+        let mut copycats = self.copycats.lock().unwrap();
+        for t in tokens.iter_mut().rev() {
+            t.is_copycat = copycats.observe(t);
+        }
+        tokens
+    }
+
+    /// Подходящие токены с метаданными, держателями и репутацией создателя
+    pub async fn get_enriched_tokens(&self) -> Result<Vec<EnrichedToken>> {
+        let tokens = self.get_eligible_tokens().await?;
+        Ok(self.enrich_tokens(tokens).await)
+    }
+
+    /// Проверки по каждому токену идут параллельно, токены — не более
+    /// `enrich_concurrency` одновременно. Порядок результата не сохраняется.
+    pub async fn enrich_tokens(&self, tokens: Vec<PumpToken>) -> Vec<EnrichedToken> {
+        let started = Instant::now();
+        let count = tokens.len();
+        let filter = self.filter();
+        let exclude_curve = filter.creator_holding_exclude_curve;
+        let check_image = self.image_check || filter.checks_image();
+        let supply_cache = SupplyCache::default();
+        let enriched: Vec<EnrichedToken> = futures_util::stream::iter(tokens)
+            .map(|t| self.enrich_token(t, exclude_curve, check_image, &supply_cache))
+            .buffer_unordered(self.enrich_concurrency)
+            .collect()
+            .await;
+        log::debug!(
+            "Обогащение {} токенов заняло {:?}",
+            count,
+            started.elapsed()
+        );
+        enriched
+    }
+
+    async fn enrich_token(
+        &self,
+        mut token: PumpToken,
+        exclude_curve: bool,
+        check_image: bool,
+        supply_cache: &SupplyCache,
+    ) -> EnrichedToken {
+        let (metadata, holders, creator, creator_holding_pct, bundle, image) = futures_util::join!(
+            async {
+                match &token.metadata {
+                    Some(meta) => Some(meta.clone()),
+                    None => {
+                        self.http.throttle().await;
+                        fetch_metadata(&self.http.client(), &token.metadata_uri)
+                            .await
+                            .inspect_err(|e| {
+                                log::debug!("Метаданные {} недоступны: {}", token.mint, e)
+                            })
+                            .ok()
+                    }
+                }
+            },
+            self.holder_stats(&token.mint),
+            self.creators.analyze_creator(&token.creator_address),
+            self.creator_holding_pct(&token, exclude_curve, supply_cache),
+            async {
+                if !self.bundle_check {
+                    return None;
+                }
+                self.bundle_report(&token)
+                    .await
+                    .inspect_err(|e| log::debug!("Бандл {} не проверен: {}", token.mint, e))
+                    .ok()
+            },
+            async {
+                if check_image {
+                    self.image_signals(&token).await
+                } else {
+                    (None, None)
+                }
+            },
+        );
+        let (image_ok, image_reused) = image;
+        token.metadata = metadata.clone();
+        EnrichedToken {
+            metadata,
+            holders: holders
+                .inspect_err(|e| log::debug!("Держатели {} не проверены: {}", token.mint, e))
+                .ok(),
+            creator: creator
+                .inspect_err(|e| log::debug!("Создатель {} не проверен: {}", token.mint, e))
+                .ok(),
+            creator_holding_pct: creator_holding_pct
+                .inspect_err(|e| log::debug!("Доля создателя {} не проверена: {}", token.mint, e))
+                .ok(),
+            bundled_buy_score: bundle.map(|b| b.score()),
+            image_ok,
+            image_reused,
+            token,
+        }
+    }
+
+    /// Доступна ли картинка и не повторяет ли она картинку другого токена за 24ч.
+    /// Неудавшаяся проверка — `(None, None)`.
+    pub async fn image_signals(&self, token: &PumpToken) -> (Option<bool>, Option<bool>) {
+        match check_image(&self.http.client(), &token.image_uri).await {
+            Ok(check) => {
+                let reused = check
+                    .hash
+                    .map(|hash| self.images.lock().unwrap().observe(&token.mint, &hash));
+                (Some(check.ok), reused)
+            }
+            Err(e) => {
+                log::debug!("Картинка {} не проверена: {}", token.mint, e);
+                (None, None)
+            }
+        }
+    }
+
+    /// Дозаполняет `usd_market_cap` по текущей цене SOL, если API его не дал.
+    /// Цена запрашивается один раз на вызов; при ошибке поля остаются `None`.
+    pub async fn fill_usd_market_cap(&self, tokens: &mut [PumpToken]) {
+        if tokens.iter().all(|t| t.usd_market_cap.is_some()) {
+            return;
+        }
+        match self.sol_price.sol_usd().await {
+            Ok(sol_usd) => {
+                for t in tokens.iter_mut() {
+                    t.usd_market_cap.get_or_insert(t.market_cap * sol_usd);
+                }
+            }
+            Err(e) => log::warn!("Цена SOL недоступна, USD-капитализация не посчитана: {}", e),
+        }
+    }
+
+    /// Дозаполняет `volume_24h`/`txn_count`/`last_trade_timestamp` по ленте сделок,
+    /// если API их не дал. При ошибке поля остаются `None`.
+    pub async fn enrich_volume(&self, token: &mut PumpToken) {
+        if token.volume_24h.is_some()
+            && token.txn_count.is_some()
+            && token.last_trade_timestamp.is_some()
+        {
+            return;
+        }
+        match fetch_trades(&self.http, &token.mint, VOLUME_TRADES_LIMIT).await {
+            Ok(trades) => {
+                let (volume, count) = volume_since(&trades, unix_now().saturating_sub(86_400));
+                token.volume_24h.get_or_insert(volume);
+                token.txn_count.get_or_insert(count);
+                if token.last_trade_timestamp.is_none() {
+                    token.last_trade_timestamp = trades
+                        .iter()
+                        .map(|t| normalize_timestamp(t.timestamp))
+                        .filter(|ts| *ts > 0)
+                        .max();
+                }
+            }
+            Err(e) => log::debug!("Сделки {} недоступны: {}", token.mint, e),
+        }
+    }
+
+    /// Загружает JSON по `metadata_uri` (с запасными IPFS-шлюзами).
+    /// При недоступности метаданные остаются `None`, ошибка только логируется.
+    pub async fn enrich_metadata(&self, token: &mut PumpToken) {
+        self.http.throttle().await;
+        match fetch_metadata(&self.http.client(), &token.metadata_uri).await {
+            Ok(meta) => {
+                if token.description.is_empty() || meta.description.len() > token.description.len()
+                {
+                    token.description = meta.description.clone();
+                }
+                token.metadata = Some(meta);
+            }
+            Err(e) => {
+                log::debug!("Метаданные {} недоступны: {}", token.mint, e);
+            }
+        }
+    }
+
+    /// Концентрация держателей токена (нужен `rpc` в builder)
+    pub async fn holder_stats(&self, mint: &str) -> Result<HolderStats> {
+        let Some(rpc) = &self.rpc else {
+            anyhow::bail!("для проверки держателей нужен RPC (PumpFunScanner::builder().rpc(..))");
+        };
+        holder_concentration(rpc, &Pubkey::from_str(mint)?).await
+    }
+
+    /// Полномочия mint-а по данным on-chain (нужен `rpc` в builder).
+    /// Без RPC или при его ошибке — через Helius DAS, если он задан.
+    pub async fn verify_authorities(&self, mint: &str) -> Result<AuthorityStatus> {
+        let on_chain = match &self.rpc {
+            Some(rpc) => verify_authorities(rpc, &Pubkey::from_str(mint)?).await,
+            None => Err(anyhow::anyhow!(
+                "для on-chain проверки нужен RPC (PumpFunScanner::builder().rpc(..))"
+            )),
+        };
+        match (on_chain, &self.helius) {
+            (Err(e), Some(helius)) => {
+                log::debug!("Полномочия {} через Helius: {}", mint, e);
+                helius.get_asset(mint).await?.authority_status()
+            }
+            (res, _) => res,
+        }
+    }
+
+    /// Перепроверяет mint/freeze authority on-chain вместо доверия API.
+    /// Токен с живым freeze authority или неудавшейся проверкой отбрасывается.
+    async fn retain_by_authorities(
+        &self,
+        tokens: Vec<PumpToken>,
+        filter: &ScannerFilter,
+    ) -> Vec<PumpToken> {
+        futures_util::stream::iter(tokens)
+            .map(|t| async move {
+                let status = self.verify_authorities(&t.mint).await;
+                (t, status)
+            })
+            .buffered(AUTHORITY_CONCURRENCY)
+            .filter_map(|(mut t, status)| async move {
+                match status {
+                    Ok(status) => {
+                        t.is_mint_authority_revoked = status.mint_revoked();
+                        if !status.freeze_revoked() {
+                            log::debug!("{}: freeze authority не отозван", t.mint);
+                            return None;
+                        }
+                        if filter.require_mint_revoked && !status.mint_revoked() {
+                            log::debug!("{}: mint authority не отозван (API врёт)", t.mint);
+                            return None;
+                        }
+                        Some(t)
+                    }
+                    Err(e) => {
+                        log::debug!("Полномочия {} не проверены: {}", t.mint, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Оставляет токены с долей топ-10 не выше `max_pct`.
+    /// Проверки идут параллельно, не более `HOLDER_CONCURRENCY` одновременно;
+    /// токен с неудавшейся проверкой отбрасывается.
+    async fn retain_by_holders(&self, tokens: Vec<PumpToken>, max_pct: f64) -> Vec<PumpToken> {
+        futures_util::stream::iter(tokens)
+            .map(|t| async move {
+                let stats = self.holder_stats(&t.mint).await;
+                (t, stats)
+            })
+            .buffered(HOLDER_CONCURRENCY)
+            .filter_map(|(t, stats)| async move {
+                match stats {
+                    Ok(stats) if stats.top10_pct <= max_pct => Some(t),
+                    Ok(stats) => {
+                        log::debug!("{}: топ-10 держат {:.1}%", t.mint, stats.top10_pct);
+                        None
+                    }
+                    Err(e) => {
+                        log::debug!("Держатели {} не проверены: {}", t.mint, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Доля supply у создателя токена, % (нужен `rpc` в builder)
+    pub async fn creator_holding_pct(
+        &self,
+        token: &PumpToken,
+        exclude_curve: bool,
+        supply_cache: &SupplyCache,
+    ) -> Result<f64> {
+        let Some(rpc) = &self.rpc else {
+            anyhow::bail!("для доли создателя нужен RPC (PumpFunScanner::builder().rpc(..))");
+        };
+        creator_holding_pct(
+            rpc,
+            &Pubkey::from_str(&token.mint)?,
+            &Pubkey::from_str(&token.creator_address)?,
+            exclude_curve,
+            supply_cache,
+        )
+        .await
+    }
+
+    /// Оставляет токены, у создателя которых не больше `max_pct` supply.
+    /// Токен с неудавшейся проверкой отбрасывается.
+    async fn retain_by_creator_holding(
+        &self,
+        tokens: Vec<PumpToken>,
+        max_pct: f64,
+        exclude_curve: bool,
+    ) -> Vec<PumpToken> {
+        let supply_cache = SupplyCache::default();
+        let supply_cache = &supply_cache;
+        futures_util::stream::iter(tokens)
+            .map(|t| async move {
+                let pct = self
+                    .creator_holding_pct(&t, exclude_curve, supply_cache)
+                    .await;
+                (t, pct)
+            })
+            .buffered(HOLDER_CONCURRENCY)
+            .filter_map(|(t, pct)| async move {
+                match pct {
+                    Ok(pct) if pct <= max_pct => Some(t),
+                    Ok(pct) => {
+                        log::debug!("{}: у создателя {:.1}% supply", t.mint, pct);
+                        None
+                    }
+                    Err(e) => {
+                        log::debug!("Доля создателя {} не проверена: {}", t.mint, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Оставляет токены с оценкой накрутки не выше `max_score`.
+    /// Токен, сделки которого не загрузились, отбрасывается.
+    async fn retain_by_wash_score(
+        &self,
+        tokens: Vec<PumpToken>,
+        max_score: f64,
+        weights: &WashWeights,
+    ) -> Vec<PumpToken> {
+        futures_util::stream::iter(tokens)
+            .map(|t| async move {
+                let trades = fetch_trades(&self.http, &t.mint, VOLUME_TRADES_LIMIT).await;
+                (t, trades)
+            })
+            .buffered(TRADES_CONCURRENCY)
+            .filter_map(|(t, trades)| async move {
+                match trades {
+                    Ok(trades) => {
+                        let score = wash_trading_score_with(&trades, weights);
+                        if score <= max_score {
+                            Some(t)
+                        } else {
+                            log::debug!("{}: накрутка объёма, оценка {:.2}", t.mint, score);
+                            None
+                        }
+                    }
+                    Err(e) => {
+                        log::debug!("Сделки {} недоступны: {}", t.mint, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Покупки в слоте создания токена и их связь с создателем (нужен `rpc` в builder)
+    pub async fn bundle_report(&self, token: &PumpToken) -> Result<BundleReport> {
+        let Some(rpc) = &self.rpc else {
+            anyhow::bail!("для проверки бандла нужен RPC (PumpFunScanner::builder().rpc(..))");
+        };
+        let trades = fetch_trades(&self.http, &token.mint, VOLUME_TRADES_LIMIT).await?;
+        detect_bundle(rpc, &trades, &token.creator_address).await
+    }
 
-        let filtered: Vec<PumpToken> = tokens
+    /// Оставляет токены с оценкой бандла не выше `max_score`.
+    /// Токен с неудавшейся проверкой отбрасывается.
+    async fn retain_by_bundle(&self, tokens: Vec<PumpToken>, max_score: f64) -> Vec<PumpToken> {
+        futures_util::stream::iter(tokens)
+            .map(|t| async move {
+                let report = self.bundle_report(&t).await;
+                (t, report)
+            })
+            .buffered(HOLDER_CONCURRENCY)
+            .filter_map(|(t, report)| async move {
+                match report {
+                    Ok(report) if report.score() <= max_score => Some(t),
+                    Ok(report) => {
+                        log::debug!(
+                            "{}: бандл при запуске, {} покупателей в слоте, {} от создателя",
+                            t.mint,
+                            report.same_slot_buyers.len(),
+                            report.funded_by_creator.len()
+                        );
+                        None
+                    }
+                    Err(e) => {
+                        log::debug!("Бандл {} не проверен: {}", t.mint, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Репутация создателя (кэшируется на время жизни сканера)
+    pub async fn analyze_creator(&self, address: &str) -> Result<CreatorReport> {
+        self.creators.analyze_creator(address).await
+    }
+
+    /// `n` лучших подходящих токенов по `ScoreWeights`, по убыванию оценки
+    pub async fn get_top_tokens(&self, n: usize) -> Result<Vec<PumpToken>> {
+        let tokens = self.get_eligible_tokens().await?;
+        let mut ranked = self.score_weights.rank(tokens, unix_now());
+        ranked.truncate(n);
+        Ok(ranked)
+    }
+
+    /// Оставляет только токены, которые ещё не отдавались потребителю,
+    /// и отмечает их как отданные
+    pub fn take_unseen(&self, tokens: Vec<PumpToken>) -> Vec<PumpToken> {
+        let mut seen = self.seen.lock().unwrap();
+        seen.prune();
+        tokens
             .into_iter()
-            // Только новые (< 15 минут)
-            .filter(|t| now.saturating_sub(t.created_timestamp) < 900)
-            // Только с отозванным mint
-            .filter(|t| t.is_mint_authority_revoked)
-            // Только с LP ≥ 5 SOL (реалистичный минимум)
-            .filter(|t| t.liquidity >= 5.0)
-            // Только активные статусы
-            .filter(|t| t.lp_status == "initialized" || t.lp_status == "pending")
-            // Рост за 24ч > 20% (фильтр мёртвых)
-            .filter(|t| t.price_change_24h > 20.0)
-            .collect();
+            .filter(|t| self.first_sight(&mut seen, t))
+            .collect()
+    }
+
+    /// Отмечает токен виденным; `true`, если его нужно отдать.
+    /// Созданные до запуска (`skip_preexisting`) тоже попадают в кэш, но не отдаются.
+    fn first_sight(&self, seen: &mut SeenCache, t: &PumpToken) -> bool {
+        if !seen.insert(&t.mint) {
+            return false;
+        }
+        if t.created_timestamp < self.emit_after {
+            log::debug!("{} создан до запуска, пропускаем", t.mint);
+            return false;
+        }
+        true
+    }
+
+    /// Подписка на новые токены через сокет pump.fun.
+    /// Переподключается сама; токены, уже отданные через monitor, пропускаются.
+    pub fn subscribe_new_tokens(&self) -> impl Stream<Item = PumpToken> {
+        let (tx, rx) = mpsc::channel(256);
+        tokio::spawn(pump_ws::run_feed(self.seen.clone(), tx));
+        ReceiverStream::new(rx)
+    }
+
+    /// On-chain детектор, делящий с этим сканером кэш виденных mint-ов
+    pub fn onchain(&self, ws_url: &str) -> OnchainScanner {
+        OnchainScanner::new(ws_url).with_seen(self.seen.clone())
+    }
+
+    /// Счётчики отбора с момента создания сканера
+    pub fn stats(&self) -> ScannerStats {
+        let (rate_limit_available, rate_limit_waiting) = self.http.limiter_state();
+        ScannerStats {
+            active_endpoint: self.http.active_endpoint(),
+            rate_limit_available,
+            rate_limit_waiting,
+            ..self.stats.snapshot()
+        }
+    }
+
+    /// Запись результата скана в журнал; ошибка базы сканирование не прерывает
+    fn record_scan(&self, eligible: &[PumpToken], rejected: &[(PumpToken, String)]) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.record_scan(eligible, rejected) {
+                log::warn!("Не удалось записать токены в базу: {}", e);
+            }
+        }
+    }
 
-        log::info!("Найдено {} подходящих токенов", filtered.len());
-        Ok(filtered)
+    fn next_delay(&self, consecutive_errors: u32) -> Duration {
+        let r = rand::thread_rng().gen_range(-1.0..=1.0);
+        poll_delay(self.poll_interval, self.jitter_pct, consecutive_errors, r)
     }
 
-    pub async fn monitor_eligible_tokens<F>(&self, mut callback: F) -> !
+    /// Цикл сканирования до отмены `cancel`.
+    /// Запрос, начатый до отмены, доводится до конца и его результат отдаётся.
+    pub async fn monitor_eligible_tokens<F>(
+        &self,
+        cancel: CancellationToken,
+        mut callback: F,
+    ) -> Result<()>
     where
         F: FnMut(Vec<PumpToken>) + Send + 'static,
     {
-        loop {
-            match self.get_eligible_tokens().await {
-                Ok(tokens) if !tokens.is_empty() => {
-                    callback(tokens);
+        self.monitor_eligible_tokens_async(cancel, move |tokens| {
+            callback(tokens);
+            std::future::ready(Ok(()))
+        })
+        .await
+    }
+
+    /// Как `monitor_eligible_tokens`, но колбэк асинхронный и ожидается в цикле:
+    /// медленная покупка естественно притормаживает сканирование.
+    /// Ошибка колбэка логируется, цикл продолжается.
+    pub async fn monitor_eligible_tokens_async<F, Fut>(
+        &self,
+        cancel: CancellationToken,
+        mut callback: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Vec<PumpToken>) -> Fut + Send,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        let mut errors = 0u32;
+        while !cancel.is_cancelled() {
+            match self.scan().await {
+                Ok(report) => {
+                    errors = 0;
+                    log_report(&report);
+                    let fresh = self.take_unseen(report.into_tokens());
+                    if !fresh.is_empty() {
+                        if let Err(e) = callback(fresh).await {
+                            log::error!("Ошибка обработчика токенов: {}", e);
+                        }
+                    }
                 }
                 Err(e) => {
+                    errors = errors.saturating_add(1);
                     log::warn!("Ошибка сканирования Pump.fun: {}", e);
                 }
-                _ => {}
             }
-            time::sleep(Duration::from_millis(200)).await;
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = time::sleep(self.next_delay(errors)) => {}
+            }
+        }
+        log::info!("Сканирование Pump.fun остановлено");
+        Ok(())
+    }
+
+    /// Цикл сканирования с отдачей событий в канал.
+    /// Завершается, когда получатель закрыт или `cancel` отменён.
+    pub async fn run(self, tx: mpsc::Sender<ScannerEvent>, cancel: CancellationToken) {
+        self.run_into(EventSink::Mpsc(tx), cancel).await
+    }
+
+    /// Запускает `run` в фоне с очередью на `capacity` событий.
+    /// Медленный потребитель не тормозит сканер: при переполнении выбрасываются
+    /// самые старые события (`ScannerStats::dropped_stale`), а потребитель
+    /// получает `ScannerEvent::Dropped`. Нужен tokio runtime.
+    pub fn run_with_channel(self, capacity: usize, cancel: CancellationToken) -> RingReceiver {
+        let (tx, rx) = ring_channel(capacity);
+        tokio::spawn(self.run_into(EventSink::Ring(tx), cancel));
+        rx
+    }
+
+    async fn run_into(self, tx: EventSink, cancel: CancellationToken) {
+        // mint → (последний отданный снимок, когда впервые отдан)
+        let mut tracked: HashMap<String, (PumpToken, Instant)> = HashMap::new();
+        let mut errors = 0u32;
+
+        while !tx.is_closed() && !cancel.is_cancelled() {
+            let mut delay = None;
+            let events = match self.fetch_candidates(&mut FetchTiming::default()).await {
+                Ok(None) => {
+                    errors = 0;
+                    Vec::new()
+                }
+                Ok(Some(coins)) => {
+                    errors = 0;
+                    tracked.retain(|_, (_, at)| at.elapsed() < self.track_ttl);
+
+                    // Отслеживаемые смотрим до фильтров: токен мог их уже не проходить
+                    // (вырос из max_age_secs, завершил кривую)
+                    let mut events = graduations(&coins, &mut tracked);
+                    events.extend(self.token_updates(&coins, &mut tracked));
+                    let (eligible, rejected) =
+                        self.filter_tokens_explained(coins, &self.filter()).await;
+                    self.record_scan(&eligible, &rejected);
+                    events.extend(self.new_tokens(eligible, &mut tracked));
+                    events
+                }
+                Err(e) => {
+                    errors = errors.saturating_add(1);
+                    match e.downcast_ref::<RateLimitedError>() {
+                        Some(rl) => {
+                            let retry_after =
+                                rl.retry_after.unwrap_or_else(|| self.next_delay(errors));
+                            delay = Some(retry_after);
+                            vec![ScannerEvent::RateLimited { retry_after }]
+                        }
+                        None => {
+                            log::warn!("Ошибка сканирования Pump.fun: {}", e);
+                            vec![ScannerEvent::ScanError(e.to_string())]
+                        }
+                    }
+                }
+            };
+
+            for event in events {
+                let sent = tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    sent = tx.send(event, &self.stats) => sent,
+                };
+                if !sent {
+                    log::info!("Получатель событий сканера закрыт, остановка");
+                    return;
+                }
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = time::sleep(delay.unwrap_or_else(|| self.next_delay(errors))) => {}
+            }
+        }
+        log::info!("Сканирование Pump.fun остановлено");
+    }
+
+    /// Новые токены → `NewToken`; с этого момента они отслеживаются
+    fn new_tokens(
+        &self,
+        tokens: Vec<PumpToken>,
+        tracked: &mut HashMap<String, (PumpToken, Instant)>,
+    ) -> Vec<ScannerEvent> {
+        let mut seen = self.seen.lock().unwrap();
+        seen.prune();
+
+        let mut events = Vec::new();
+        for t in tokens {
+            if self.first_sight(&mut seen, &t) {
+                tracked.insert(t.mint.clone(), (t.clone(), Instant::now()));
+                events.push(ScannerEvent::NewToken(t));
+            }
+        }
+        events
+    }
+
+    /// Заметно изменившиеся отслеживаемые токены → `TokenUpdated` (старый и новый снимок)
+    fn token_updates(
+        &self,
+        coins: &[PumpToken],
+        tracked: &mut HashMap<String, (PumpToken, Instant)>,
+    ) -> Vec<ScannerEvent> {
+        let mut events = Vec::new();
+        for t in coins {
+            let Some((last, _)) = tracked.get_mut(&t.mint) else {
+                continue;
+            };
+            if changed_beyond(last, t, self.liquidity_delta_pct, self.price_delta_pct) {
+                let old = std::mem::replace(last, t.clone());
+                events.push(ScannerEvent::TokenUpdated {
+                    old: Box::new(old),
+                    new: t.clone(),
+                });
+            }
+        }
+        events
+    }
+}
+
+#[async_trait]
+impl TokenScanner for PumpFunScanner {
+    fn name(&self) -> &str {
+        "pump.fun"
+    }
+
+    async fn eligible_tokens(&self) -> Result<Vec<PumpToken>> {
+        self.get_eligible_tokens().await
+    }
+}
+
+/// Куда `run` отдаёт события
+enum EventSink {
+    /// Ждёт места в канале
+    Mpsc(mpsc::Sender<ScannerEvent>),
+    /// Не ждёт, выбрасывает самые старые
+    Ring(RingSender),
+}
+
+impl EventSink {
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Mpsc(tx) => tx.is_closed(),
+            Self::Ring(tx) => tx.is_closed(),
+        }
+    }
+
+    /// `false` — получатель закрыт
+    async fn send(&self, event: ScannerEvent, stats: &StatsCounters) -> bool {
+        match self {
+            Self::Mpsc(tx) => tx.send(event).await.is_ok(),
+            Self::Ring(tx) => match tx.send(event) {
+                Some(dropped) => {
+                    if dropped > 0 {
+                        log::debug!(
+                            "Потребитель не успевает: выброшено {} старых событий",
+                            dropped
+                        );
+                        stats.add_dropped_stale(dropped);
+                    }
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// `Graduated` для отслеживаемых токенов, у которых кривая завершилась
+fn graduations(
+    coins: &[PumpToken],
+    tracked: &mut HashMap<String, (PumpToken, Instant)>,
+) -> Vec<ScannerEvent> {
+    let mut events = Vec::new();
+    for t in coins.iter().filter(|t| t.complete) {
+        if let Some((last, _)) = tracked.get_mut(&t.mint) {
+            if !last.complete {
+                log::info!("🎓 {} завершил bonding curve", t.mint);
+                last.complete = true;
+                last.bonding_progress = 100.0;
+                events.push(ScannerEvent::Graduated(t.mint.clone()));
+            }
+        }
+    }
+    events
+}
+
+/// Одна строка о цикле: info, если что-то нашлось, иначе debug
+fn log_report(report: &ScanReport) {
+    if report.tokens.is_empty() {
+        log::debug!("{}", report);
+    } else {
+        log::info!("{}", report);
+    }
+}
+
+fn reject_all(
+    rejected: &mut Vec<(PumpToken, String)>,
+    tokens: impl IntoIterator<Item = PumpToken>,
+    reason: &str,
+) {
+    rejected.extend(tokens.into_iter().map(|t| (t, reason.to_string())));
+}
+
+/// Токены из `before`, которых нет в `after`, уходят в отказ с причиной `reason`
+fn reject_missing(
+    rejected: &mut Vec<(PumpToken, String)>,
+    before: Vec<PumpToken>,
+    after: &[PumpToken],
+    reason: &str,
+) {
+    let kept: HashSet<&str> = after.iter().map(|t| t.mint.as_str()).collect();
+    reject_all(
+        rejected,
+        before
+            .into_iter()
+            .filter(|t| !kept.contains(t.mint.as_str())),
+        reason,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Сканер без сети: endpoint отказывает в соединении, каждый опрос — ошибка API
+    fn offline_scanner(poll_interval: Duration) -> PumpFunScanner {
+        PumpFunScanner::builder()
+            .http_config(ScannerHttpConfig {
+                endpoints: vec!["http://127.0.0.1:9".to_string()],
+                requests_per_second: 0.0,
+                ..Default::default()
+            })
+            .poll_interval(poll_interval)
+            .build()
+    }
+
+    #[test]
+    fn poll_delay_stays_within_bounds() {
+        let base = Duration::from_millis(200);
+        let jitter = 0.3;
+        let cap = base.mul_f64(MAX_BACKOFF_FACTOR as f64 * (1.0 + jitter));
+        let mut rng = rand::thread_rng();
+        for errors in 0..12 {
+            let factor = 2u32.saturating_pow(errors).min(MAX_BACKOFF_FACTOR) as f64;
+            let low = base.mul_f64(factor * (1.0 - jitter));
+            let high = base.mul_f64(factor * (1.0 + jitter));
+            for _ in 0..1_000 {
+                let delay = poll_delay(base, jitter, errors, rng.gen_range(-1.0..=1.0));
+                assert!(
+                    low <= delay && delay <= high,
+                    "{:?} вне {:?}..{:?}",
+                    delay,
+                    low,
+                    high
+                );
+                assert!(delay <= cap);
+            }
+        }
+        // Разброс и `r` обрезаются
+        assert_eq!(poll_delay(base, 5.0, 0, -1.0), Duration::ZERO);
+        assert_eq!(poll_delay(base, 0.5, 0, 7.0), base.mul_f64(1.5));
+        assert_eq!(
+            poll_delay(base, 0.0, u32::MAX, 1.0),
+            base * MAX_BACKOFF_FACTOR
+        );
+    }
+
+    /// Ответ `/coins` с монетами `mints`
+    fn coins_json(mints: &[&str]) -> String {
+        let coins: Vec<_> = mints
+            .iter()
+            .map(|m| serde_json::json!({ "mint": m, "created_timestamp": 1_760_499_940 }))
+            .collect();
+        serde_json::to_string(&coins).unwrap()
+    }
+
+    #[test]
+    fn parse_coins_skips_broken_entries() {
+        let mut coins: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../../tests/fixtures/coins_01.json")).unwrap();
+        coins[1]["liquidity"] = serde_json::json!("много");
+        coins[3]["mint"] = serde_json::json!(42);
+        coins.push(serde_json::json!("не монета"));
+        let text = serde_json::to_string(&coins).unwrap();
+
+        let symbols: Vec<_> = parse_coins(&text)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.symbol)
+            .collect();
+        assert_eq!(symbols, ["MCAT", "ZZZ", "OLD"]);
+
+        // Та же партия в обёртке `{"coins": [...]}`
+        let wrapped = serde_json::json!({ "coins": coins }).to_string();
+        assert_eq!(parse_coins(&wrapped).unwrap().len(), 3);
+
+        assert!(parse_coins(r#"{"coins": null}"#).is_err());
+        assert!(parse_coins("[{]").is_err());
+    }
+
+    #[test]
+    fn overlapping_batches_emit_each_mint_once() {
+        let scanner = PumpFunScanner::new();
+        let first = parse_coins(&coins_json(&["A", "B", "C"])).unwrap();
+        let second = parse_coins(&coins_json(&["B", "C", "D", "D"])).unwrap();
+
+        let mut emitted: Vec<String> = scanner
+            .take_unseen(first)
+            .into_iter()
+            .map(|t| t.mint)
+            .collect();
+        emitted.extend(scanner.take_unseen(second).into_iter().map(|t| t.mint));
+        assert_eq!(emitted, ["A", "B", "C", "D"]);
+
+        scanner.reset_seen();
+        let again = parse_coins(&coins_json(&["A"])).unwrap();
+        assert_eq!(scanner.take_unseen(again).len(), 1);
+    }
+
+    #[test]
+    fn skip_preexisting_hides_tokens_created_before_start() {
+        let now = unix_now();
+        let token = |mint: &str, created: u64| PumpToken {
+            mint: mint.to_string(),
+            created_timestamp: created,
+            ..Default::default()
+        };
+        let batch = || {
+            vec![
+                token("old", now - 3_600),
+                token("grace", now - 30),
+                token("new", now + 5),
+            ]
+        };
+        let mints = |tokens: Vec<PumpToken>| -> Vec<String> {
+            tokens.into_iter().map(|t| t.mint).collect()
+        };
+
+        let scanner = PumpFunScanner::builder()
+            .skip_preexisting(true)
+            .preexisting_grace(Duration::from_secs(60))
+            .build();
+        assert_eq!(mints(scanner.take_unseen(batch())), ["grace", "new"]);
+        // Старый токен запомнен, в следующих сканах тоже не отдаётся
+        assert!(scanner.take_unseen(batch()).is_empty());
+
+        let scanner = PumpFunScanner::builder().skip_preexisting(true).build();
+        assert_eq!(mints(scanner.take_unseen(batch())), ["new"]);
+
+        let scanner = PumpFunScanner::builder().build();
+        assert_eq!(mints(scanner.take_unseen(batch())), ["old", "grace", "new"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn monitor_returns_after_cancel() {
+        let poll = Duration::from_secs(1);
+        let scanner = offline_scanner(poll);
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn({
+            let scanner = scanner.clone();
+            let cancel = cancel.clone();
+            async move { scanner.monitor_eligible_tokens(cancel, |_| {}).await }
+        });
+
+        while scanner.stats().api_errors < 3 {
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        cancel.cancel();
+        let started = time::Instant::now();
+        time::timeout(poll, task)
+            .await
+            .expect("цикл не остановился после отмены")
+            .unwrap()
+            .unwrap();
+        assert!(started.elapsed() < poll);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_stops_on_cancel() {
+        let poll = Duration::from_secs(1);
+        let scanner = offline_scanner(poll);
+        let (tx, mut rx) = mpsc::channel(16);
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(scanner.clone().run(tx, cancel.clone()));
+
+        for _ in 0..3 {
+            let event = rx.recv().await.expect("канал закрыт раньше отмены");
+            assert!(matches!(event, ScannerEvent::ScanError(_)));
         }
+        cancel.cancel();
+        time::timeout(poll, task)
+            .await
+            .expect("run не остановился после отмены")
+            .unwrap();
+        // Получатель жив, но цикл закрыл свою сторону
+        assert!(rx.recv().await.is_none());
     }
-}
\ No newline at end of file
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_consumer_gets_latest_events() {
+        let poll = Duration::from_secs(1);
+        let scanner = offline_scanner(poll);
+        let cancel = CancellationToken::new();
+        let mut rx = scanner.clone().run_with_channel(2, cancel.clone());
+
+        // Потребитель спит, сканер продолжает опрашивать
+        while scanner.stats().dropped_stale < 3 {
+            time::sleep(poll).await;
+        }
+        assert_eq!(rx.len(), 2);
+        cancel.cancel();
+
+        let dropped = match rx.recv().await {
+            Some(ScannerEvent::Dropped(n)) => n,
+            other => panic!("ожидали Dropped, получили {:?}", other),
+        };
+        for _ in 0..2 {
+            assert!(matches!(rx.recv().await, Some(ScannerEvent::ScanError(_))));
+        }
+        assert!(rx.recv().await.is_none());
+        assert_eq!(dropped, scanner.stats().dropped_stale);
+        assert!(dropped >= 3);
+    }
+
+    #[tokio::test]
+    async fn stats_count_fixture_scan() {
+        let mut coins = parse_coins(include_str!("../../tests/fixtures/coins_01.json")).unwrap();
+        // Фикстура записана в 1_760_500_000; сдвигаем к текущему времени
+        let shift = unix_now() - 1_760_500_000;
+        for t in &mut coins {
+            t.created_timestamp += shift;
+        }
+        let scanner = offline_scanner(Duration::from_secs(1));
+        let filter = ScannerFilter::default();
+
+        let (eligible, rejected) = scanner
+            .filter_tokens_explained(coins.clone(), &filter)
+            .await;
+        assert_eq!(eligible.len(), 1);
+        assert_eq!(rejected.len(), 4);
+        let stats = scanner.stats();
+        assert_eq!(
+            stats,
+            ScannerStats {
+                rejected_age: 1,
+                rejected_liquidity: 1,
+                rejected_mint_authority: 1,
+                rejected_price_change: 1,
+                passed: 1,
+                ..stats.clone()
+            }
+        );
+        assert_eq!(
+            (
+                stats.rejected_lp_status,
+                stats.rejected_other,
+                stats.api_errors
+            ),
+            (0, 0, 0)
+        );
+
+        // Счётчики общие для клонов и копятся между сканами
+        scanner
+            .clone()
+            .filter_tokens_explained(coins, &filter)
+            .await;
+        let stats = scanner.stats();
+        assert_eq!((stats.passed, stats.rejected_age), (2, 2));
+    }
+}
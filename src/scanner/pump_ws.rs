@@ -0,0 +1,157 @@
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::{
+    clock::timestamp_secs,
+    pump_fun::{curve_progress, PumpToken},
+    SeenCache,
+};
+
+/// socket.io (Engine.IO v4) фид pump.fun
+pub const PUMP_WS_URL: &str = "wss://frontend-api.pump.fun/socket.io/?EIO=4&transport=websocket";
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Событие `newCoinCreated` в том виде, в каком его отдаёт сокет
+#[derive(Debug, Clone, Deserialize)]
+struct NewCoinEvent {
+    mint: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    symbol: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    image_uri: String,
+    #[serde(default, deserialize_with = "timestamp_secs")]
+    created_timestamp: u64,
+    #[serde(default)]
+    uri: String,
+    #[serde(default)]
+    market_cap: f64,
+    #[serde(default)]
+    virtual_sol_reserves: u64,
+    #[serde(default)]
+    virtual_token_reserves: u64,
+    #[serde(default)]
+    real_sol_reserves: u64,
+    #[serde(default)]
+    real_token_reserves: Option<u64>,
+    #[serde(default)]
+    creator: String,
+}
+
+impl From<NewCoinEvent> for PumpToken {
+    fn from(e: NewCoinEvent) -> Self {
+        // Цена в SOL за токен: у pump.fun 9 знаков у SOL и 6 у токена
+        let price = if e.virtual_token_reserves > 0 {
+            (e.virtual_sol_reserves as f64 / 1e9) / (e.virtual_token_reserves as f64 / 1e6)
+        } else {
+            0.0
+        };
+
+        PumpToken {
+            mint: e.mint,
+            name: e.name,
+            symbol: e.symbol,
+            description: e.description,
+            image_uri: e.image_uri,
+            created_timestamp: e.created_timestamp,
+            metadata_uri: e.uri,
+            market_cap: e.market_cap,
+            liquidity: e.real_sol_reserves as f64 / 1e9,
+            price,
+            // Только что созданный токен: истории цены нет
+            price_change_24h: 0.0,
+            // pump.fun отзывает mint authority при создании
+            is_mint_authority_revoked: true,
+            lp_status: "pending".to_string(),
+            creator_address: e.creator,
+            real_token_reserves: e.real_token_reserves,
+            bonding_progress: e.real_token_reserves.map(curve_progress).unwrap_or(0.0),
+            ..Default::default()
+        }
+    }
+}
+
+/// Разбор одного текстового фрейма socket.io.
+/// Возвращает токен, если это событие `newCoinCreated`.
+fn parse_frame(text: &str) -> Option<PumpToken> {
+    // Формат события: 42["newCoinCreated",{...}]
+    let body = text.strip_prefix("42")?;
+    let (event, payload): (String, serde_json::Value) = serde_json::from_str(body).ok()?;
+    if event != "newCoinCreated" {
+        return None;
+    }
+    match serde_json::from_value::<NewCoinEvent>(payload) {
+        Ok(e) => Some(e.into()),
+        Err(e) => {
+            log::debug!("Не удалось разобрать newCoinCreated: {}", e);
+            None
+        }
+    }
+}
+
+/// Фоновая задача: держит соединение, переподключается с backoff
+/// и отправляет в канал только ещё не виденные токены.
+pub(crate) async fn run_feed(seen: Arc<Mutex<SeenCache>>, tx: mpsc::Sender<PumpToken>) {
+    let mut backoff = MIN_BACKOFF;
+
+    while !tx.is_closed() {
+        match read_feed(&seen, &tx, &mut backoff).await {
+            Ok(()) => log::warn!("Сокет pump.fun закрыт, переподключение..."),
+            Err(e) => log::warn!("Ошибка сокета pump.fun: {}", e),
+        }
+        if tx.is_closed() {
+            break;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+    log::debug!("Подписка на новые токены pump.fun завершена");
+}
+
+async fn read_feed(
+    seen: &Mutex<SeenCache>,
+    tx: &mpsc::Sender<PumpToken>,
+    backoff: &mut Duration,
+) -> Result<()> {
+    let (mut ws, _) = connect_async(PUMP_WS_URL).await?;
+    log::info!("Подключено к сокету pump.fun");
+
+    while let Some(msg) = ws.next().await {
+        let text = match msg? {
+            Message::Text(text) => text,
+            Message::Close(_) => return Ok(()),
+            _ => continue,
+        };
+
+        // Engine.IO: 0 — open, 2 — ping, 40 — подтверждение namespace
+        if text.starts_with('0') {
+            ws.send(Message::Text("40".into())).await?;
+        } else if text == "2" {
+            ws.send(Message::Text("3".into())).await?;
+        } else if text.starts_with("40") {
+            *backoff = MIN_BACKOFF;
+        } else if let Some(token) = parse_frame(&text) {
+            // Уже отдан через polling или ранее через сокет
+            if !seen.lock().unwrap().insert(&token.mint) {
+                continue;
+            }
+            if tx.send(token).await.is_err() {
+                // Потребитель отписался
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
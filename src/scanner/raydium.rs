@@ -0,0 +1,279 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::StreamExt;
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey, pubkey::Pubkey};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    authority::verify_authorities,
+    clock::age_secs,
+    pump_fun::{unix_now, PumpToken},
+    SeenCache, TokenScanner,
+};
+
+/// Raydium AMM v4
+pub const RAYDIUM_AMM_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const RAYDIUM_AMM_PROGRAM: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// Индексы coin/pc mint в аккаунтах инструкции `initialize2`
+const COIN_MINT_INDEX: usize = 8;
+const PC_MINT_INDEX: usize = 9;
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// `ray_log` инициализации пула (log_type = 0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitLog {
+    pub open_time: u64,
+    pub pc_decimals: u8,
+    pub coin_decimals: u8,
+    pub pc_amount: u64,
+    pub coin_amount: u64,
+    pub market: Pubkey,
+}
+
+impl InitLog {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        // log_type u8, time u64, pc_decimals u8, coin_decimals u8,
+        // pc_lot_size u64, coin_lot_size u64, pc_amount u64, coin_amount u64, market
+        if data.len() < 75 || data[0] != 0 {
+            return None;
+        }
+        let u64_at = |i: usize| u64::from_le_bytes(data[i..i + 8].try_into().unwrap());
+        Some(Self {
+            open_time: u64_at(1),
+            pc_decimals: data[9],
+            coin_decimals: data[10],
+            pc_amount: u64_at(27),
+            coin_amount: u64_at(35),
+            market: Pubkey::new_from_array(data[43..75].try_into().ok()?),
+        })
+    }
+
+    pub fn from_logs(logs: &[String]) -> Option<Self> {
+        if !logs.iter().any(|l| l.contains("initialize2")) {
+            return None;
+        }
+        logs.iter()
+            .filter_map(|l| l.split_once("ray_log: ").map(|(_, b64)| b64.trim()))
+            .filter_map(|b64| STANDARD.decode(b64).ok())
+            .find_map(|data| Self::decode(&data))
+    }
+}
+
+/// Сканер новых пулов Raydium AMM через `logsSubscribe`.
+/// Найденные пулы копятся и отдаются через `TokenScanner::eligible_tokens`.
+#[derive(Clone)]
+pub struct RaydiumScanner {
+    rpc: Arc<RpcClient>,
+    ws_url: String,
+    pending: Arc<Mutex<Vec<PumpToken>>>,
+    seen: Arc<Mutex<SeenCache>>,
+    min_liquidity_sol: f64,
+    max_age_secs: u64,
+}
+
+impl fmt::Debug for RaydiumScanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RaydiumScanner")
+            .field("rpc", &self.rpc.url())
+            .field("ws_url", &self.ws_url)
+            .field("min_liquidity_sol", &self.min_liquidity_sol)
+            .field("max_age_secs", &self.max_age_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RaydiumScanner {
+    pub fn new(rpc: Arc<RpcClient>, ws_url: &str) -> Self {
+        Self {
+            rpc,
+            ws_url: ws_url.to_string(),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            seen: Arc::new(Mutex::new(SeenCache::default())),
+            min_liquidity_sol: 5.0,
+            max_age_secs: 900,
+        }
+    }
+
+    pub fn with_min_liquidity(mut self, sol: f64) -> Self {
+        self.min_liquidity_sol = sol;
+        self
+    }
+
+    pub fn with_max_age(mut self, secs: u64) -> Self {
+        self.max_age_secs = secs;
+        self
+    }
+
+    /// Запускает фоновую подписку на инициализации пулов
+    pub fn start(&self) -> JoinHandle<()> {
+        tokio::spawn(self.clone().watch())
+    }
+
+    async fn watch(self) {
+        let mut backoff = MIN_BACKOFF;
+        loop {
+            match self.subscribe(&mut backoff).await {
+                Ok(()) => log::warn!("Подписка на Raydium закрыта, переподключение..."),
+                Err(e) => log::warn!("Ошибка подписки на Raydium: {}", e),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    async fn subscribe(&self, backoff: &mut Duration) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url)
+            .await
+            .context("подключение к RPC websocket")?;
+        let (mut stream, unsubscribe) = client
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![RAYDIUM_AMM_PROGRAM_ID.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        log::info!("logsSubscribe на Raydium AMM активен");
+        *backoff = MIN_BACKOFF;
+
+        while let Some(resp) = stream.next().await {
+            if resp.value.err.is_some() {
+                continue;
+            }
+            let Some(init) = InitLog::from_logs(&resp.value.logs) else {
+                continue;
+            };
+            match self.resolve_pool(&resp.value.signature, &init).await {
+                Ok(Some(token)) => {
+                    log::info!(
+                        "🆕 Raydium пул: {} — LP {:.2} SOL",
+                        token.mint,
+                        token.liquidity
+                    );
+                    self.pending.lock().unwrap().push(token);
+                }
+                Ok(None) => {}
+                Err(e) => log::debug!("Пул {} не разобран: {}", resp.value.signature, e),
+            }
+        }
+
+        unsubscribe().await;
+        Ok(())
+    }
+
+    /// Достаёт mint-ы пула из транзакции и собирает `PumpToken`.
+    /// Пулы без SOL-стороны пропускаются.
+    async fn resolve_pool(&self, signature: &str, init: &InitLog) -> Result<Option<PumpToken>> {
+        let tx: serde_json::Value = self
+            .rpc
+            .send(
+                RpcRequest::GetTransaction,
+                serde_json::json!([signature, {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0,
+                }]),
+            )
+            .await?;
+
+        let keys = account_keys(&tx);
+        let ix = tx["transaction"]["message"]["instructions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|ix| {
+                ix["programIdIndex"]
+                    .as_u64()
+                    .and_then(|i| keys.get(i as usize))
+                    .is_some_and(|k| *k == RAYDIUM_AMM_PROGRAM)
+            })
+            .context("нет инструкции Raydium в транзакции")?;
+        let account = |pos: usize| -> Result<Pubkey> {
+            let idx = ix["accounts"][pos].as_u64().context("нет аккаунта")? as usize;
+            keys.get(idx).copied().context("индекс аккаунта вне списка")
+        };
+        let coin_mint = account(COIN_MINT_INDEX)?;
+        let pc_mint = account(PC_MINT_INDEX)?;
+
+        let coin = init.coin_amount as f64 / 10f64.powi(init.coin_decimals as i32);
+        let pc = init.pc_amount as f64 / 10f64.powi(init.pc_decimals as i32);
+        let (mint, sol, tokens) = if pc_mint == WSOL_MINT {
+            (coin_mint, pc, coin)
+        } else if coin_mint == WSOL_MINT {
+            (pc_mint, coin, pc)
+        } else {
+            return Ok(None);
+        };
+
+        if !self.seen.lock().unwrap().insert(&mint.to_string()) {
+            return Ok(None);
+        }
+
+        let mint_revoked = match verify_authorities(&self.rpc, &mint).await {
+            Ok(status) => status.mint_revoked(),
+            Err(e) => {
+                log::debug!("Полномочия {} не проверены: {}", mint, e);
+                false
+            }
+        };
+
+        Ok(Some(PumpToken {
+            mint: mint.to_string(),
+            created_timestamp: tx["blockTime"].as_u64().unwrap_or(init.open_time),
+            liquidity: sol,
+            price: if tokens > 0.0 { sol / tokens } else { 0.0 },
+            is_mint_authority_revoked: mint_revoked,
+            lp_status: "initialized".to_string(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// Все ключи транзакции: статические и загруженные из lookup-таблиц
+fn account_keys(tx: &serde_json::Value) -> Vec<Pubkey> {
+    let loaded = &tx["meta"]["loadedAddresses"];
+    [
+        &tx["transaction"]["message"]["accountKeys"],
+        &loaded["writable"],
+        &loaded["readonly"],
+    ]
+    .into_iter()
+    .filter_map(|v| v.as_array())
+    .flatten()
+    .filter_map(|k| k.as_str().and_then(|k| Pubkey::from_str(k).ok()))
+    .collect()
+}
+
+#[async_trait]
+impl TokenScanner for RaydiumScanner {
+    fn name(&self) -> &str {
+        "raydium"
+    }
+
+    async fn eligible_tokens(&self) -> Result<Vec<PumpToken>> {
+        let now = unix_now();
+        let pools = std::mem::take(&mut *self.pending.lock().unwrap());
+        Ok(pools
+            .into_iter()
+            .filter(|t| age_secs(t.created_timestamp, now) < self.max_age_secs)
+            .filter(|t| t.liquidity >= self.min_liquidity_sol)
+            .collect())
+    }
+}
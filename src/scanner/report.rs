@@ -0,0 +1,60 @@
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+use super::pump_fun::PumpToken;
+
+/// Результат одного цикла сканирования с разбивкой по времени
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    /// Прошедшие фильтры токены
+    pub tokens: Vec<PumpToken>,
+    /// Запрос к API до получения тела ответа, мс
+    pub fetch_ms: u64,
+    /// Разбор JSON, мс
+    pub parse_ms: u64,
+    /// Фильтры и дополнительные проверки (метаданные, сделки, RPC), мс
+    pub enrich_ms: u64,
+    /// Монет в ответе API до фильтров
+    pub total_candidates: usize,
+    /// Базовый URL API, ответивший на запрос
+    pub endpoint_used: String,
+    /// Причина отказа → сколько токенов отброшено
+    pub rejections: BTreeMap<String, usize>,
+    /// Список не изменился (304), `tokens` — из прошлого цикла
+    pub not_modified: bool,
+}
+
+impl ScanReport {
+    pub fn into_tokens(self) -> Vec<PumpToken> {
+        self.tokens
+    }
+
+    pub fn total_ms(&self) -> u64 {
+        self.fetch_ms + self.parse_ms + self.enrich_ms
+    }
+}
+
+impl fmt::Display for ScanReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.not_modified {
+            return write!(
+                f,
+                "скан {}: без изменений (304), {} токенов из кэша, {} мс",
+                self.endpoint_used,
+                self.tokens.len(),
+                self.fetch_ms
+            );
+        }
+        write!(
+            f,
+            "скан {}: {}/{} прошло, запрос {} мс, разбор {} мс, проверки {} мс, отказы {:?}",
+            self.endpoint_used,
+            self.tokens.len(),
+            self.total_candidates,
+            self.fetch_ms,
+            self.parse_ms,
+            self.enrich_ms,
+            self.rejections
+        )
+    }
+}
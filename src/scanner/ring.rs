@@ -0,0 +1,127 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Notify;
+
+use super::ScannerEvent;
+
+/// Ограниченная очередь событий: при переполнении выбрасываются самые старые,
+/// чтобы медленный потребитель всегда видел свежие токены.
+pub fn ring_channel(capacity: usize) -> (RingSender, RingReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            buf: VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+            dropped: 0,
+            sender_closed: false,
+            receiver_closed: false,
+        }),
+        notify: Notify::new(),
+    });
+    (
+        RingSender {
+            shared: shared.clone(),
+        },
+        RingReceiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+#[derive(Debug)]
+struct State {
+    buf: VecDeque<ScannerEvent>,
+    capacity: usize,
+    /// Выброшено с прошлого `ScannerEvent::Dropped`
+    dropped: u64,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+/// Отправляющая сторона `ring_channel`
+#[derive(Debug)]
+pub struct RingSender {
+    shared: Arc<Shared>,
+}
+
+impl RingSender {
+    /// Кладёт событие, не дожидаясь потребителя.
+    /// `Some(n)` — выброшено `n` старых событий; `None` — получатель закрыт.
+    pub fn send(&self, event: ScannerEvent) -> Option<usize> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.receiver_closed {
+            return None;
+        }
+        let mut dropped = 0;
+        while state.buf.len() >= state.capacity {
+            state.buf.pop_front();
+            dropped += 1;
+        }
+        state.dropped += dropped as u64;
+        state.buf.push_back(event);
+        drop(state);
+        self.shared.notify.notify_one();
+        Some(dropped)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.state.lock().unwrap().receiver_closed
+    }
+}
+
+impl Drop for RingSender {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().sender_closed = true;
+        self.shared.notify.notify_one();
+    }
+}
+
+/// Принимающая сторона `ring_channel`
+#[derive(Debug)]
+pub struct RingReceiver {
+    shared: Arc<Shared>,
+}
+
+impl RingReceiver {
+    /// Следующее событие; если с прошлого вызова что-то выброшено,
+    /// сначала приходит `ScannerEvent::Dropped(n)`.
+    /// `None` — сканер остановлен и очередь пуста.
+    pub async fn recv(&mut self) -> Option<ScannerEvent> {
+        loop {
+            {
+                let mut state = self.shared.state.lock().unwrap();
+                if state.dropped > 0 {
+                    return Some(ScannerEvent::Dropped(std::mem::take(&mut state.dropped)));
+                }
+                if let Some(event) = state.buf.pop_front() {
+                    return Some(event);
+                }
+                if state.sender_closed {
+                    return None;
+                }
+            }
+            // notify_one сохраняет разрешение, если мы ещё не ждём, — событие не потеряется
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Событий в очереди
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().unwrap().buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for RingReceiver {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().receiver_closed = true;
+    }
+}
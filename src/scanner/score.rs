@@ -0,0 +1,161 @@
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+use super::{
+    clock::age_secs,
+    pump_fun::{unix_now, PumpToken},
+};
+
+/// Веса компонентов оценки токена
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    /// log10(1 + ликвидность в SOL)
+    pub liquidity: f64,
+    /// Свежесть: 1 / (1 + возраст в минутах)
+    pub age: f64,
+    /// ln(1 + рост за 24ч / 100), отрицательный рост даёт 0
+    pub price_change_24h: f64,
+    /// log10(1 + капитализация в SOL)
+    pub market_cap: f64,
+    /// Бонус за отозванный mint authority
+    pub mint_revoked_bonus: f64,
+    /// Активность: exp(-сек с последней сделки / `activity_decay_secs`);
+    /// без данных о последней сделке — 0
+    pub activity: f64,
+    pub activity_decay_secs: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            liquidity: 1.0,
+            age: 2.0,
+            price_change_24h: 1.0,
+            market_cap: 0.25,
+            mint_revoked_bonus: 0.5,
+            activity: 1.0,
+            activity_decay_secs: 60.0,
+        }
+    }
+}
+
+impl ScoreWeights {
+    /// Оценка токена на момент `now` (unix, сек); больше — лучше
+    pub fn score(&self, t: &PumpToken, now: u64) -> f64 {
+        let age_min = age_secs(t.created_timestamp, now) as f64 / 60.0;
+        let growth = (t.price_change_24h / 100.0).max(0.0);
+        let activity = match t.last_trade_timestamp {
+            Some(ts) if self.activity_decay_secs > 0.0 => {
+                (-(age_secs(ts, now) as f64) / self.activity_decay_secs).exp()
+            }
+            _ => 0.0,
+        };
+
+        self.liquidity * t.liquidity.max(0.0).ln_1p() / std::f64::consts::LN_10
+            + self.age / (1.0 + age_min)
+            + self.price_change_24h * growth.ln_1p()
+            + self.market_cap * t.market_cap.max(0.0).ln_1p() / std::f64::consts::LN_10
+            + self.activity * activity
+            + if t.is_mint_authority_revoked {
+                self.mint_revoked_bonus
+            } else {
+                0.0
+            }
+    }
+
+    /// Сортирует по убыванию оценки; при равенстве — сначала более новые
+    pub fn rank(&self, mut tokens: Vec<PumpToken>, now: u64) -> Vec<PumpToken> {
+        tokens.sort_by(|a, b| {
+            self.score(b, now)
+                .partial_cmp(&self.score(a, now))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| b.created_timestamp.cmp(&a.created_timestamp))
+        });
+        tokens
+    }
+}
+
+/// Оценка с весами по умолчанию на текущий момент
+pub fn score(t: &PumpToken) -> f64 {
+    ScoreWeights::default().score(t, unix_now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_760_500_000;
+
+    fn token(mint: &str, liquidity: f64, age_secs: u64, price_change_24h: f64) -> PumpToken {
+        PumpToken {
+            mint: mint.to_string(),
+            liquidity,
+            created_timestamp: NOW - age_secs,
+            price_change_24h,
+            is_mint_authority_revoked: true,
+            ..Default::default()
+        }
+    }
+
+    fn mints(tokens: &[PumpToken]) -> Vec<&str> {
+        tokens.iter().map(|t| t.mint.as_str()).collect()
+    }
+
+    /// Только один компонент оценки
+    fn only(set: impl FnOnce(&mut ScoreWeights)) -> ScoreWeights {
+        let mut weights = ScoreWeights {
+            liquidity: 0.0,
+            age: 0.0,
+            price_change_24h: 0.0,
+            market_cap: 0.0,
+            mint_revoked_bonus: 0.0,
+            activity: 0.0,
+            activity_decay_secs: 60.0,
+        };
+        set(&mut weights);
+        weights
+    }
+
+    #[test]
+    fn score_components() {
+        let t = token("a", 9.0, 60, 100.0);
+        assert!((only(|w| w.liquidity = 1.0).score(&t, NOW) - 1.0).abs() < 1e-9);
+        assert!((only(|w| w.age = 2.0).score(&t, NOW) - 1.0).abs() < 1e-9);
+        let growth = only(|w| w.price_change_24h = 1.0).score(&t, NOW);
+        assert!((growth - 2f64.ln()).abs() < 1e-9);
+        assert_eq!(only(|w| w.mint_revoked_bonus = 0.5).score(&t, NOW), 0.5);
+        // Падение цены не штрафует сверх нуля
+        let falling = token("b", 0.0, 60, -50.0);
+        assert_eq!(only(|w| w.price_change_24h = 1.0).score(&falling, NOW), 0.0);
+    }
+
+    #[test]
+    fn rank_orders_by_score_descending() {
+        let tokens = vec![
+            token("old-thin", 1.0, 3_600, 10.0),
+            token("fresh-deep", 50.0, 30, 200.0),
+            token("fresh-thin", 2.0, 30, 50.0),
+            token("mid-deep", 50.0, 600, 200.0),
+        ];
+        let ranked = ScoreWeights::default().rank(tokens, NOW);
+        assert_eq!(
+            mints(&ranked),
+            ["fresh-deep", "mid-deep", "fresh-thin", "old-thin"]
+        );
+    }
+
+    #[test]
+    fn rank_breaks_ties_by_newest_first() {
+        let weights = only(|w| w.liquidity = 1.0);
+        let tokens = vec![
+            token("older", 10.0, 300, 0.0),
+            token("newer", 10.0, 10, 0.0),
+            token("deepest", 20.0, 900, 0.0),
+        ];
+        assert_eq!(
+            mints(&weights.rank(tokens, NOW)),
+            ["deepest", "newer", "older"]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// TTL по умолчанию для виденных mint-ов
+pub const DEFAULT_SEEN_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Кэш уже отданных mint-ов с временем жизни,
+/// чтобы один и тот же токен не уходил потребителю повторно.
+#[derive(Debug, Clone)]
+pub struct SeenCache {
+    ttl: Duration,
+    entries: HashMap<String, Instant>,
+}
+
+impl Default for SeenCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEEN_TTL)
+    }
+}
+
+impl SeenCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Отмечает mint как виденный. `true`, если он новый (или запись истекла).
+    pub fn insert(&mut self, mint: &str) -> bool {
+        self.insert_at(mint, Instant::now())
+    }
+
+    pub fn insert_at(&mut self, mint: &str, now: Instant) -> bool {
+        match self.entries.get(mint) {
+            Some(at) if now.duration_since(*at) < self.ttl => false,
+            _ => {
+                self.entries.insert(mint.to_string(), now);
+                true
+            }
+        }
+    }
+
+    pub fn contains(&self, mint: &str) -> bool {
+        self.entries
+            .get(mint)
+            .is_some_and(|at| at.elapsed() < self.ttl)
+    }
+
+    /// Удаляет истёкшие записи
+    pub fn prune(&mut self) {
+        let ttl = self.ttl;
+        self.entries.retain(|_, at| at.elapsed() < ttl);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use super::{pump_fun::PumpToken, SeenCache};
+
+/// Общий интерфейс источника токенов (pump.fun, Raydium, ...).
+/// Результат всегда в форме `PumpToken`, чтобы остальной конвейер не зависел от источника.
+#[async_trait]
+pub trait TokenScanner: Send + Sync {
+    /// Короткое имя источника для логов
+    fn name(&self) -> &str;
+
+    /// Токены, прошедшие фильтры источника
+    async fn eligible_tokens(&self) -> Result<Vec<PumpToken>>;
+}
+
+/// Цикл опроса любого источника до отмены `cancel`.
+/// Каждый mint отдаётся в колбэк один раз (в пределах TTL `SeenCache`).
+pub async fn monitor_tokens<S, F>(
+    scanner: &S,
+    interval: Duration,
+    cancel: CancellationToken,
+    mut callback: F,
+) -> Result<()>
+where
+    S: TokenScanner + ?Sized,
+    F: FnMut(Vec<PumpToken>) + Send,
+{
+    let mut seen = SeenCache::default();
+    while !cancel.is_cancelled() {
+        match scanner.eligible_tokens().await {
+            Ok(tokens) => {
+                seen.prune();
+                let fresh: Vec<PumpToken> = tokens
+                    .into_iter()
+                    .filter(|t| seen.insert(&t.mint))
+                    .collect();
+                if !fresh.is_empty() {
+                    callback(fresh);
+                }
+            }
+            Err(e) => log::warn!("Ошибка сканирования {}: {}", scanner.name(), e),
+        }
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::MockScanner;
+    use std::sync::{Arc, Mutex};
+
+    fn token(mint: &str) -> PumpToken {
+        PumpToken {
+            mint: mint.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn monitor_tokens_emits_each_mint_once() {
+        let interval = Duration::from_secs(1);
+        let scanner = Arc::new(MockScanner::new(vec![token("A"), token("B")]));
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn({
+            let (scanner, emitted, cancel) = (scanner.clone(), emitted.clone(), cancel.clone());
+            async move {
+                monitor_tokens(scanner.as_ref(), interval, cancel, |tokens| {
+                    let mints = tokens.into_iter().map(|t| t.mint);
+                    emitted.lock().unwrap().extend(mints);
+                })
+                .await
+            }
+        });
+
+        tokio::time::sleep(interval / 2).await;
+        scanner.set_tokens(vec![token("B"), token("C")]);
+        tokio::time::sleep(interval).await;
+        // Ошибка источника не останавливает цикл
+        scanner.set_error(Some("сеть недоступна"));
+        tokio::time::sleep(interval).await;
+        scanner.set_error(None);
+        scanner.set_tokens(vec![token("A"), token("D")]);
+        tokio::time::sleep(interval).await;
+
+        cancel.cancel();
+        task.await.unwrap().unwrap();
+        assert_eq!(*emitted.lock().unwrap(), ["A", "B", "C", "D"]);
+        assert_eq!(scanner.calls(), 4);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Снимок счётчиков сканера с момента запуска
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScannerStats {
+    pub rejected_age: u64,
+    pub rejected_liquidity: u64,
+    pub rejected_mint_authority: u64,
+    pub rejected_lp_status: u64,
+    pub rejected_price_change: u64,
+    /// Прогресс кривой, соцсети, создатель, on-chain проверки, держатели
+    pub rejected_other: u64,
+    pub passed: u64,
+    pub api_errors: u64,
+    /// Событий, выброшенных из переполненной очереди `run_with_channel`
+    pub dropped_stale: u64,
+    /// Базовый URL API, через который сейчас идут запросы
+    pub active_endpoint: String,
+    /// Свободных мест в лимите запросов
+    pub rate_limit_available: u32,
+    /// Запросов, ждущих места в лимите
+    pub rate_limit_waiting: u32,
+}
+
+/// Атомарные счётчики, общие для всех клонов сканера
+#[derive(Debug, Default)]
+pub(crate) struct StatsCounters {
+    rejected_age: AtomicU64,
+    rejected_liquidity: AtomicU64,
+    rejected_mint_authority: AtomicU64,
+    rejected_lp_status: AtomicU64,
+    rejected_price_change: AtomicU64,
+    rejected_other: AtomicU64,
+    passed: AtomicU64,
+    api_errors: AtomicU64,
+    dropped_stale: AtomicU64,
+}
+
+impl StatsCounters {
+    /// Учитывает отказ по причине из `ScannerFilter::rejection_reason` и стадий обогащения
+    pub(crate) fn add_rejection(&self, reason: &str) {
+        let counter = match reason {
+            "too_old" => &self.rejected_age,
+            "low_liquidity" => &self.rejected_liquidity,
+            "mint_not_revoked" => &self.rejected_mint_authority,
+            "lp_status" => &self.rejected_lp_status,
+            "low_price_change" => &self.rejected_price_change,
+            _ => &self.rejected_other,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_passed(&self, n: usize) {
+        self.passed.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_api_error(&self) {
+        self.api_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_dropped_stale(&self, n: usize) {
+        self.dropped_stale.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ScannerStats {
+        let get = |c: &AtomicU64| c.load(Ordering::Relaxed);
+        ScannerStats {
+            rejected_age: get(&self.rejected_age),
+            rejected_liquidity: get(&self.rejected_liquidity),
+            rejected_mint_authority: get(&self.rejected_mint_authority),
+            rejected_lp_status: get(&self.rejected_lp_status),
+            rejected_price_change: get(&self.rejected_price_change),
+            rejected_other: get(&self.rejected_other),
+            passed: get(&self.passed),
+            api_errors: get(&self.api_errors),
+            dropped_stale: get(&self.dropped_stale),
+            active_endpoint: String::new(),
+            rate_limit_available: 0,
+            rate_limit_waiting: 0,
+        }
+    }
+}
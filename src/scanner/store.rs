@@ -0,0 +1,302 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::{path::Path, sync::Mutex};
+
+use super::pump_fun::{unix_now, PumpToken};
+
+/// Текущая версия схемы (`PRAGMA user_version`)
+const SCHEMA_VERSION: i32 = 1;
+
+const MIGRATIONS: &[&str] = &[
+    // v1
+    "CREATE TABLE tokens (
+        mint              TEXT PRIMARY KEY,
+        name              TEXT NOT NULL,
+        symbol            TEXT NOT NULL,
+        created_timestamp INTEGER NOT NULL,
+        market_cap        REAL NOT NULL,
+        liquidity         REAL NOT NULL,
+        price             REAL NOT NULL,
+        first_seen_at     INTEGER NOT NULL,
+        last_seen_at      INTEGER NOT NULL,
+        passed            INTEGER NOT NULL,
+        reject_reason     TEXT,
+        data              TEXT NOT NULL
+    );
+    CREATE INDEX tokens_first_seen ON tokens (first_seen_at);
+    CREATE INDEX tokens_reject_reason ON tokens (reject_reason);",
+];
+
+/// Сохранённый токен со статусом отбора
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredToken {
+    pub token: PumpToken,
+    /// Когда токен впервые попал в выдачу сканера (unix, сек)
+    pub first_seen_at: u64,
+    /// Последний раз, когда токен был в выдаче (unix, сек)
+    pub last_seen_at: u64,
+    /// Проходил ли фильтры хотя бы раз
+    pub passed: bool,
+    /// Причина последнего отказа (у прошедших — `None`)
+    pub reject_reason: Option<String>,
+}
+
+/// Журнал всех просмотренных токенов в SQLite.
+/// Одна строка на mint: снимок обновляется при каждом скане.
+pub struct TokenStore {
+    conn: Mutex<Connection>,
+}
+
+impl std::fmt::Debug for TokenStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenStore").finish_non_exhaustive()
+    }
+}
+
+impl TokenStore {
+    /// Открывает (или создаёт) базу и применяет миграции
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// База в памяти — для тестов и разовых прогонов
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "схема базы v{} новее поддерживаемой v{}",
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        let tx = conn.transaction()?;
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            log::info!("Миграция базы токенов до v{}", i + 1);
+            tx.execute_batch(sql)?;
+        }
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Записывает результат отбора токена.
+    /// `passed` однажды став `true`, больше не сбрасывается.
+    pub fn record(&self, token: &PumpToken, passed: bool, reason: Option<&str>) -> Result<()> {
+        upsert(
+            &self.conn.lock().unwrap(),
+            token,
+            passed,
+            reason,
+            unix_now(),
+        )
+    }
+
+    /// Записывает результат одного скана одной транзакцией
+    pub fn record_scan(
+        &self,
+        eligible: &[PumpToken],
+        rejected: &[(PumpToken, String)],
+    ) -> Result<()> {
+        let now = unix_now();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for t in eligible {
+            upsert(&tx, t, true, None, now)?;
+        }
+        for (t, reason) in rejected {
+            upsert(&tx, t, false, Some(reason), now)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Снимок токена по mint
+    pub fn get(&self, mint: &str) -> Result<Option<StoredToken>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT data, first_seen_at, last_seen_at, passed, reject_reason
+             FROM tokens WHERE mint = ?1",
+            params![mint],
+            row_to_stored,
+        )
+        .optional()?
+        .transpose()
+    }
+
+    /// Токены, впервые замеченные в `[start, end)` (unix, сек), по времени
+    pub fn tokens_between(&self, start: u64, end: u64) -> Result<Vec<StoredToken>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT data, first_seen_at, last_seen_at, passed, reject_reason
+             FROM tokens WHERE first_seen_at >= ?1 AND first_seen_at < ?2
+             ORDER BY first_seen_at",
+        )?;
+        let rows = stmt.query_map(params![start as i64, end as i64], row_to_stored)?;
+        rows.map(|r| r?).collect()
+    }
+
+    /// Сколько токенов отброшено по каждой причине, по убыванию
+    pub fn rejection_stats(&self) -> Result<Vec<(String, u64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT reject_reason, COUNT(*) FROM tokens
+             WHERE passed = 0 AND reject_reason IS NOT NULL
+             GROUP BY reject_reason ORDER BY COUNT(*) DESC, reject_reason",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)? as u64))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+fn upsert(
+    conn: &Connection,
+    token: &PumpToken,
+    passed: bool,
+    reason: Option<&str>,
+    now: u64,
+) -> Result<()> {
+    let data = serde_json::to_string(token)?;
+    conn.execute(
+        "INSERT INTO tokens (mint, name, symbol, created_timestamp, market_cap, liquidity,
+                             price, first_seen_at, last_seen_at, passed, reject_reason, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?9, ?10, ?11)
+         ON CONFLICT (mint) DO UPDATE SET
+            name = excluded.name,
+            symbol = excluded.symbol,
+            market_cap = excluded.market_cap,
+            liquidity = excluded.liquidity,
+            price = excluded.price,
+            last_seen_at = excluded.last_seen_at,
+            passed = max(passed, excluded.passed),
+            reject_reason = CASE WHEN max(passed, excluded.passed) = 1
+                                 THEN NULL ELSE excluded.reject_reason END,
+            data = excluded.data",
+        params![
+            token.mint,
+            token.name,
+            token.symbol,
+            token.created_timestamp as i64,
+            token.market_cap,
+            token.liquidity,
+            token.price,
+            now as i64,
+            passed,
+            reason,
+            data,
+        ],
+    )?;
+    Ok(())
+}
+
+fn row_to_stored(r: &rusqlite::Row<'_>) -> rusqlite::Result<Result<StoredToken>> {
+    let data: String = r.get(0)?;
+    let first_seen_at: i64 = r.get(1)?;
+    let last_seen_at: i64 = r.get(2)?;
+    let passed: bool = r.get(3)?;
+    let reject_reason: Option<String> = r.get(4)?;
+    Ok(serde_json::from_str(&data)
+        .map(|token| StoredToken {
+            token,
+            first_seen_at: first_seen_at as u64,
+            last_seen_at: last_seen_at as u64,
+            passed,
+            reject_reason,
+        })
+        .map_err(Into::into))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(mint: &str, liquidity: f64) -> PumpToken {
+        PumpToken {
+            mint: mint.to_string(),
+            name: format!("Token {}", mint),
+            symbol: mint.to_uppercase(),
+            created_timestamp: 1_760_499_940,
+            liquidity,
+            price: 3.15e-8,
+            ..Default::default()
+        }
+    }
+
+    fn store() -> TokenStore {
+        TokenStore::init(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn record_scan_round_trip() {
+        let store = store();
+        let started = unix_now();
+        store
+            .record_scan(
+                &[token("a", 12.5)],
+                &[
+                    (token("b", 1.0), "low_liquidity".to_string()),
+                    (token("c", 2.0), "low_liquidity".to_string()),
+                    (token("d", 9.0), "too_old".to_string()),
+                ],
+            )
+            .unwrap();
+
+        let a = store.get("a").unwrap().unwrap();
+        assert!(a.passed);
+        assert_eq!(a.reject_reason, None);
+        assert_eq!(a.token.name, "Token a");
+        assert_eq!(a.token.liquidity, 12.5);
+        assert_eq!(a.token.created_timestamp, 1_760_499_940);
+        assert!(a.first_seen_at >= started && a.first_seen_at == a.last_seen_at);
+
+        let b = store.get("b").unwrap().unwrap();
+        assert!(!b.passed);
+        assert_eq!(b.reject_reason.as_deref(), Some("low_liquidity"));
+        assert!(store.get("missing").unwrap().is_none());
+
+        assert_eq!(
+            store.tokens_between(started, unix_now() + 1).unwrap().len(),
+            4
+        );
+        assert!(store.tokens_between(0, started).unwrap().is_empty());
+        assert_eq!(
+            store.rejection_stats().unwrap(),
+            [("low_liquidity".to_string(), 2), ("too_old".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn passed_is_sticky() {
+        let store = store();
+        store
+            .record(&token("a", 1.0), false, Some("low_liquidity"))
+            .unwrap();
+        store.record(&token("a", 8.0), true, None).unwrap();
+        store
+            .record(&token("a", 2.0), false, Some("too_old"))
+            .unwrap();
+
+        let a = store.get("a").unwrap().unwrap();
+        assert!(a.passed);
+        assert_eq!(a.reject_reason, None);
+        // Снимок — последний
+        assert_eq!(a.token.liquidity, 2.0);
+        assert!(store.rejection_stats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        assert!(TokenStore::init(conn).is_err());
+    }
+}
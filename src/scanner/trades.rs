@@ -0,0 +1,72 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::http::{read_text, HttpPool};
+
+/// Сколько сделок запрашивать для оценки объёма
+pub const VOLUME_TRADES_LIMIT: usize = 200;
+
+/// Одна сделка на bonding curve из pump.fun API
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PumpTrade {
+    #[serde(default)]
+    pub signature: String,
+    /// Кошелёк покупателя (или продавца при `is_buy == false`)
+    #[serde(default)]
+    pub user: String,
+    /// SOL, lamports
+    #[serde(default)]
+    pub sol_amount: u64,
+    /// Токены, сырые единицы
+    #[serde(default)]
+    pub token_amount: u64,
+    #[serde(default)]
+    pub is_buy: bool,
+    /// unix, сек
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(default)]
+    pub slot: u64,
+}
+
+impl PumpTrade {
+    pub fn sol(&self) -> f64 {
+        self.sol_amount as f64 / 1e9
+    }
+}
+
+/// Последние сделки по mint-у, новые первыми
+pub async fn fetch_trades(http: &HttpPool, mint: &str, limit: usize) -> Result<Vec<PumpTrade>> {
+    let path = format!(
+        "/trades/all/{}?limit={}&offset=0&minimumSize=0",
+        mint, limit
+    );
+    let res = http.send_api(&path, Default::default()).await?;
+    Ok(serde_json::from_str(&read_text(res).await?)?)
+}
+
+/// Объём (SOL) и число сделок с момента `since` (unix, сек)
+pub fn volume_since(trades: &[PumpTrade], since: u64) -> (f64, u64) {
+    trades
+        .iter()
+        .filter(|t| t.timestamp >= since)
+        .fold((0.0, 0), |(vol, n), t| (vol + t.sol(), n + 1))
+}
+
+/// Число разных кошельков-покупателей
+pub fn unique_buyers(trades: &[PumpTrade]) -> usize {
+    trades
+        .iter()
+        .filter(|t| t.is_buy)
+        .map(|t| t.user.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Отношение числа покупок к числу продаж; без продаж — число покупок
+pub fn buy_sell_ratio(trades: &[PumpTrade]) -> f64 {
+    let buys = trades.iter().filter(|t| t.is_buy).count();
+    let sells = trades.len() - buys;
+    buys as f64 / sells.max(1) as f64
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Секунд в сутках: день UTC — `unix / SECS_PER_DAY`
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Почему остановлена торговля
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TripReason {
+    /// Убыток закрытых позиций за день UTC дошёл до `max_daily_loss_sol`
+    DailyLoss { loss_sol: f64, limit_sol: f64 },
+    /// `count` убыточных позиций подряд
+    ConsecutiveLosses { count: u32 },
+    /// Ручная остановка (`PositionManager::kill_switch`)
+    KillSwitch,
+}
+
+impl fmt::Display for TripReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DailyLoss {
+                loss_sol,
+                limit_sol,
+            } => write!(
+                f,
+                "дневной убыток {:.4} SOL (лимит {:.4} SOL)",
+                loss_sol, limit_sol
+            ),
+            Self::ConsecutiveLosses { count } => write!(f, "{} убыточных позиций подряд", count),
+            Self::KillSwitch => f.write_str("ручная остановка"),
+        }
+    }
+}
+
+/// Счётчики предохранителя; сохраняются в `PositionStore`, чтобы перезапуск
+/// не снимал остановку
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BreakerState {
+    /// День UTC, к которому относится `daily_pnl_sol`
+    pub day: u64,
+    /// PnL закрытых за день позиций, SOL
+    pub daily_pnl_sol: f64,
+    pub consecutive_losses: u32,
+    /// Торговля остановлена; `None` — работает
+    pub tripped: Option<TripReason>,
+    /// Когда остановлена, unix, сек
+    pub tripped_at: Option<u64>,
+}
+
+/// Предохранитель: после дневного лимита убытка или серии убытков — стоп торговли
+/// до ручного сброса или до смены дня UTC. Ручная остановка снимается только сбросом.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreaker {
+    /// 0 — без лимита
+    max_daily_loss_sol: f64,
+    /// 0 — без лимита
+    max_consecutive_losses: u32,
+    state: BreakerState,
+}
+
+impl CircuitBreaker {
+    pub fn new(max_daily_loss_sol: f64, max_consecutive_losses: u32) -> Self {
+        Self {
+            max_daily_loss_sol,
+            max_consecutive_losses,
+            state: BreakerState::default(),
+        }
+    }
+
+    /// Продолжает с сохранённых счётчиков
+    pub fn with_state(mut self, state: BreakerState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn state(&self) -> &BreakerState {
+        &self.state
+    }
+
+    /// Новый день UTC: дневной PnL и серия обнуляются, автоматическая остановка снимается.
+    /// `true` — состояние изменилось.
+    pub fn roll(&mut self, now: u64) -> bool {
+        let day = now / SECS_PER_DAY;
+        if day == self.state.day {
+            return false;
+        }
+        self.state.day = day;
+        self.state.daily_pnl_sol = 0.0;
+        self.state.consecutive_losses = 0;
+        if !matches!(self.state.tripped, None | Some(TripReason::KillSwitch)) {
+            log::info!("🌅 Новый день UTC — торговля снова разрешена");
+            self.state.tripped = None;
+            self.state.tripped_at = None;
+        }
+        true
+    }
+
+    /// Причина остановки на момент `now`; `None` — торговать можно
+    pub fn tripped(&mut self, now: u64) -> Option<TripReason> {
+        self.roll(now);
+        self.state.tripped
+    }
+
+    /// Учитывает PnL закрытой позиции; возвращает причину, если предохранитель
+    /// сработал именно сейчас
+    pub fn record(&mut self, pnl_sol: f64, now: u64) -> Option<TripReason> {
+        self.roll(now);
+        self.state.daily_pnl_sol += pnl_sol;
+        if pnl_sol < 0.0 {
+            self.state.consecutive_losses += 1;
+        } else {
+            self.state.consecutive_losses = 0;
+        }
+        if self.state.tripped.is_some() {
+            return None;
+        }
+        let loss_sol = -self.state.daily_pnl_sol;
+        let reason = if self.max_daily_loss_sol > 0.0 && loss_sol >= self.max_daily_loss_sol {
+            TripReason::DailyLoss {
+                loss_sol,
+                limit_sol: self.max_daily_loss_sol,
+            }
+        } else if self.max_consecutive_losses > 0
+            && self.state.consecutive_losses >= self.max_consecutive_losses
+        {
+            TripReason::ConsecutiveLosses {
+                count: self.state.consecutive_losses,
+            }
+        } else {
+            return None;
+        };
+        self.trip(reason, now);
+        Some(reason)
+    }
+
+    /// Останавливает торговлю; уже остановленная остаётся с прежней причиной,
+    /// кроме ручной остановки — она заменяет автоматическую
+    pub fn trip(&mut self, reason: TripReason, now: u64) {
+        self.roll(now);
+        if self.state.tripped.is_none() || reason == TripReason::KillSwitch {
+            self.state.tripped = Some(reason);
+            self.state.tripped_at = Some(now);
+        }
+    }
+
+    /// Ручной сброс: торговля разрешена, серия убытков обнулена
+    pub fn reset(&mut self) {
+        self.state.tripped = None;
+        self.state.tripped_at = None;
+        self.state.consecutive_losses = 0;
+    }
+}
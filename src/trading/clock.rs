@@ -0,0 +1,68 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Источник текущего времени монитора: время удержания, таймеры и отметки
+/// в истории цены считаются от него. В бэктесте время идёт по записи, а не по часам.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Текущее время, unix, мс
+    fn now_ms(&self) -> u64;
+}
+
+/// Системные часы; между вызовами время идёт монотонно (перевод часов не влияет)
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    base_ms: u64,
+    base: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            base_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            base: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        self.base_ms + self.base.elapsed().as_millis() as u64
+    }
+}
+
+/// Часы, которые двигает вызывающий (бэктест, проигрывание записи)
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ms: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(now_ms: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(now_ms),
+        }
+    }
+
+    /// Переводит часы; назад не идут
+    pub fn set_ms(&self, now_ms: u64) {
+        self.now_ms.fetch_max(now_ms, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::Relaxed)
+    }
+}
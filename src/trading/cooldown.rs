@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, fmt};
+
+use super::risk::{ExitReason, ExitSummary};
+
+/// По чему запрещён повторный вход
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CooldownKind {
+    Mint,
+    Creator,
+}
+
+/// Запрет входа в mint или в токены создателя до `until` (unix, сек)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cooldown {
+    pub kind: CooldownKind,
+    pub key: Pubkey,
+    /// Чем закрылась позиция, после которой наложен запрет
+    pub reason: ExitReason,
+    pub until: u64,
+}
+
+/// Почему вход запрещён (`PositionManager::is_blocked`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockReason {
+    /// Позиция по этому mint-у недавно закрыта по `reason`
+    Mint { reason: ExitReason, until: u64 },
+    /// Токен этого создателя недавно закрыт по `reason`
+    Creator {
+        creator: Pubkey,
+        reason: ExitReason,
+        until: u64,
+    },
+}
+
+impl fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mint { reason, until } => {
+                write!(f, "mint закрыт по {:?}, пауза до {}", reason, until)
+            }
+            Self::Creator {
+                creator,
+                reason,
+                until,
+            } => write!(
+                f,
+                "токен создателя {} закрыт по {:?}, пауза до {}",
+                creator, reason, until
+            ),
+        }
+    }
+}
+
+impl From<Cooldown> for BlockReason {
+    fn from(c: Cooldown) -> Self {
+        match c.kind {
+            CooldownKind::Mint => Self::Mint {
+                reason: c.reason,
+                until: c.until,
+            },
+            CooldownKind::Creator => Self::Creator {
+                creator: c.key,
+                reason: c.reason,
+                until: c.until,
+            },
+        }
+    }
+}
+
+/// Паузы перед повторным входом после выхода по стопу или rug-pull.
+/// Rug-класс (rug-pull, порог ликвидности, создатель, полномочия, допечатка)
+/// запрещает и mint, и другие токены создателя на `rug_secs`; стопы (trailing,
+/// безубыток, panic-sell, крупная продажа) — только mint на `stop_secs`.
+/// Фиксация прибыли и ручные закрытия паузы не дают.
+#[derive(Debug, Clone, Default)]
+pub struct CooldownRegistry {
+    rug_secs: u64,
+    stop_secs: u64,
+    entries: HashMap<(CooldownKind, Pubkey), Cooldown>,
+}
+
+impl CooldownRegistry {
+    pub fn new(rug_secs: u64, stop_secs: u64) -> Self {
+        Self {
+            rug_secs,
+            stop_secs,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Пауза после выхода по `reason`, сек, и распространяется ли она на создателя
+    pub fn duration(&self, reason: ExitReason) -> (u64, bool) {
+        match reason {
+            ExitReason::RugPull
+            | ExitReason::LiquidityFloor
+            | ExitReason::CreatorDump
+            | ExitReason::FreezeAuthority
+            | ExitReason::Frozen
+            | ExitReason::SupplyInflated => (self.rug_secs, true),
+            ExitReason::TrailingStop
+            | ExitReason::BreakevenStop
+            | ExitReason::PanicSell
+            | ExitReason::WhaleDump => (self.stop_secs, false),
+            _ => (0, false),
+        }
+    }
+
+    /// Запреты по итогу позиции на момент `now` (unix, сек); возвращает новые записи
+    pub fn record(&mut self, summary: &ExitSummary, now: u64) -> Vec<Cooldown> {
+        let (secs, creator_too) = self.duration(summary.exit_reason);
+        if secs == 0 {
+            return Vec::new();
+        }
+        let mut keys = vec![(CooldownKind::Mint, summary.mint)];
+        if let Some(creator) = summary.creator.filter(|_| creator_too) {
+            keys.push((CooldownKind::Creator, creator));
+        }
+        let added: Vec<Cooldown> = keys
+            .into_iter()
+            .map(|(kind, key)| Cooldown {
+                kind,
+                key,
+                reason: summary.exit_reason,
+                until: now + secs,
+            })
+            .collect();
+        for cooldown in &added {
+            self.insert(*cooldown);
+        }
+        added
+    }
+
+    /// Добавляет запрет (например, из хранилища); из двух остаётся более долгий
+    pub fn insert(&mut self, cooldown: Cooldown) {
+        let entry = self
+            .entries
+            .entry((cooldown.kind, cooldown.key))
+            .or_insert(cooldown);
+        if cooldown.until > entry.until {
+            *entry = cooldown;
+        }
+    }
+
+    /// Действующий на `now` запрет входа в `mint` или в токены `creator`
+    pub fn is_blocked(
+        &self,
+        mint: &Pubkey,
+        creator: Option<&Pubkey>,
+        now: u64,
+    ) -> Option<BlockReason> {
+        let active = |kind, key: &Pubkey| {
+            self.entries
+                .get(&(kind, *key))
+                .filter(|c| c.until > now)
+                .copied()
+        };
+        active(CooldownKind::Mint, mint)
+            .or_else(|| creator.and_then(|c| active(CooldownKind::Creator, c)))
+            .map(BlockReason::from)
+    }
+
+    /// Убирает истёкшие запреты
+    pub fn prune(&mut self, now: u64) {
+        self.entries.retain(|_, c| c.until > now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::{self, Instant};
+
+    use crate::trading::positions::PositionLimits;
+
+    /// Unix-время теста, сек
+    const START: u64 = 1_760_500_000;
+
+    /// Время по приостановленным часам tokio: идёт только через `time::advance`
+    fn now(start: Instant) -> u64 {
+        START + start.elapsed().as_secs()
+    }
+
+    fn summary(reason: ExitReason, creator: Pubkey) -> ExitSummary {
+        ExitSummary {
+            mint: Pubkey::new_unique(),
+            creator: Some(creator),
+            entry_price: 1.0,
+            exit_reason: reason,
+            sol_recovered: 0.0,
+            realized_pnl_sol: -1.0,
+            realized_pnl_pct: -100.0,
+            peak_multiple: 1.0,
+            hold_duration: Duration::from_secs(60),
+            entry_cost_usd: None,
+            sol_recovered_usd: None,
+            realized_pnl_usd: None,
+        }
+    }
+
+    fn registry() -> CooldownRegistry {
+        let limits = PositionLimits::default();
+        CooldownRegistry::new(limits.rug_cooldown_secs, limits.stop_cooldown_secs)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rug_exit_blocks_mint_and_creator_for_a_day() {
+        let start = Instant::now();
+        let mut cooldowns = registry();
+        let creator = Pubkey::new_unique();
+        let rug = summary(ExitReason::RugPull, creator);
+        assert_eq!(cooldowns.record(&rug, now(start)).len(), 2);
+
+        // Другой токен того же создателя тоже под запретом
+        let other = Pubkey::new_unique();
+        time::advance(Duration::from_secs(24 * 60 * 60 - 1)).await;
+        assert_eq!(
+            cooldowns.is_blocked(&rug.mint, None, now(start)),
+            Some(BlockReason::Mint {
+                reason: ExitReason::RugPull,
+                until: START + 24 * 60 * 60,
+            })
+        );
+        assert!(matches!(
+            cooldowns.is_blocked(&other, Some(&creator), now(start)),
+            Some(BlockReason::Creator { creator: c, .. }) if c == creator
+        ));
+
+        time::advance(Duration::from_secs(1)).await;
+        assert_eq!(
+            cooldowns.is_blocked(&rug.mint, Some(&creator), now(start)),
+            None
+        );
+        cooldowns.prune(now(start));
+        assert!(cooldowns.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn trailing_stop_blocks_only_mint_for_fifteen_minutes() {
+        let start = Instant::now();
+        let mut cooldowns = registry();
+        let creator = Pubkey::new_unique();
+        let stop = summary(ExitReason::TrailingStop, creator);
+        assert_eq!(cooldowns.record(&stop, now(start)).len(), 1);
+        assert_eq!(
+            cooldowns.is_blocked(&Pubkey::new_unique(), Some(&creator), now(start)),
+            None
+        );
+
+        time::advance(Duration::from_secs(15 * 60 - 1)).await;
+        assert!(cooldowns
+            .is_blocked(&stop.mint, Some(&creator), now(start))
+            .is_some());
+        time::advance(Duration::from_secs(1)).await;
+        assert_eq!(
+            cooldowns.is_blocked(&stop.mint, Some(&creator), now(start)),
+            None
+        );
+
+        // Фиксация прибыли паузы не даёт
+        let profit = summary(ExitReason::TakeProfit { tier: 0 }, creator);
+        assert!(cooldowns.record(&profit, now(start)).is_empty());
+        assert_eq!(cooldowns.is_blocked(&profit.mint, None, now(start)), None);
+    }
+
+    #[test]
+    fn longer_cooldown_wins() {
+        let mut cooldowns = registry();
+        let key = Pubkey::new_unique();
+        let cooldown = |until| Cooldown {
+            kind: CooldownKind::Mint,
+            key,
+            reason: ExitReason::PanicSell,
+            until,
+        };
+        cooldowns.insert(cooldown(START + 100));
+        cooldowns.insert(cooldown(START + 50));
+        assert_eq!(cooldowns.len(), 1);
+        assert!(cooldowns.is_blocked(&key, None, START + 99).is_some());
+        assert!(cooldowns.is_blocked(&key, None, START + 100).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+
+use crate::scanner::onchain::bonding_curve_pda;
+
+/// Anchor-дискриминатор аккаунта `BondingCurve` (sha256("account:BondingCurve")[..8])
+pub(crate) const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Комиссия pump.fun со сделки на кривой, б.п.
+pub const PUMP_FEE_BPS: u64 = 100;
+
+/// Десятичные знаки токенов pump.fun
+pub const TOKEN_DECIMALS: u32 = 6;
+
+/// Состояние bonding curve pump.fun (сырые единицы: lamports и 10^-6 токена)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    /// Кривая завершена, токен мигрирует на Raydium
+    pub complete: bool,
+    /// Создатель токена; в старых аккаунтах поля нет
+    pub creator: Option<Pubkey>,
+}
+
+impl BondingCurve {
+    /// Разбор данных аккаунта (вместе с дискриминатором)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let body = data
+            .strip_prefix(&BONDING_CURVE_DISCRIMINATOR)
+            .context("аккаунт не является bonding curve pump.fun")?;
+        anyhow::ensure!(body.len() >= 41, "аккаунт bonding curve слишком короткий");
+        let u64_at = |i: usize| u64::from_le_bytes(body[i * 8..i * 8 + 8].try_into().unwrap());
+        Ok(Self {
+            virtual_token_reserves: u64_at(0),
+            virtual_sol_reserves: u64_at(1),
+            real_token_reserves: u64_at(2),
+            real_sol_reserves: u64_at(3),
+            token_total_supply: u64_at(4),
+            complete: body[40] != 0,
+            creator: body
+                .get(41..73)
+                .map(|b| Pubkey::new_from_array(b.try_into().unwrap())),
+        })
+    }
+
+    /// Спот-цена по виртуальным резервам, SOL за целый токен
+    pub fn spot_price(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        let sol = self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+        let tokens = self.virtual_token_reserves as f64 / 10f64.powi(TOKEN_DECIMALS as i32);
+        sol / tokens
+    }
+
+    /// Сколько lamports придёт за продажу `tokens` (сырые единицы) с учётом комиссии
+    pub fn sell_quote(&self, tokens: u64) -> u64 {
+        let denominator = self.virtual_token_reserves as u128 + tokens as u128;
+        if denominator == 0 {
+            return 0;
+        }
+        let gross = tokens as u128 * self.virtual_sol_reserves as u128 / denominator;
+        let fee = gross * PUMP_FEE_BPS as u128 / 10_000;
+        (gross - fee) as u64
+    }
+
+    /// Сколько токенов (сырые единицы) купит `lamports` вместе с комиссией;
+    /// не больше реального остатка кривой
+    pub fn buy_quote(&self, lamports: u64) -> u64 {
+        let sol_in = lamports as u128 * 10_000 / (10_000 + PUMP_FEE_BPS as u128);
+        let denominator = self.virtual_sol_reserves as u128 + sol_in;
+        if denominator == 0 {
+            return 0;
+        }
+        let tokens = sol_in * self.virtual_token_reserves as u128 / denominator;
+        (tokens as u64).min(self.real_token_reserves)
+    }
+
+    /// Стоимость покупки ровно `tokens` (сырые единицы) с комиссией, lamports;
+    /// больше виртуального резерва не купить — `None`
+    pub fn buy_cost(&self, tokens: u64) -> Option<u64> {
+        let left = (self.virtual_token_reserves as u128).checked_sub(tokens as u128)?;
+        if left == 0 {
+            return None;
+        }
+        let sol = tokens as u128 * self.virtual_sol_reserves as u128 / left + 1;
+        let fee = sol * PUMP_FEE_BPS as u128 / 10_000;
+        u64::try_from(sol + fee).ok()
+    }
+}
+
+/// Снимок пула для мониторинга рисков
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolSnapshot {
+    /// SOL за целый токен
+    pub price: f64,
+    /// Реальная ликвидность в SOL, lamports
+    pub sol_reserve: u64,
+    /// Реальный остаток токенов в пуле (сырые единицы)
+    pub token_reserve: u64,
+    /// Слот, на котором прочитан аккаунт
+    pub slot: u64,
+}
+
+impl PoolSnapshot {
+    pub fn from_curve(curve: &BondingCurve, slot: u64) -> Self {
+        Self {
+            price: curve.spot_price(),
+            sol_reserve: curve.real_sol_reserves,
+            token_reserve: curve.real_token_reserves,
+            slot,
+        }
+    }
+}
+
+/// Читает bonding curve mint-а; отсутствие аккаунта — ошибка
+pub async fn fetch_curve(client: &RpcClient, mint: &Pubkey) -> Result<(BondingCurve, u64)> {
+    let curve = bonding_curve_pda(mint);
+    let response = client
+        .get_account_with_commitment(&curve, CommitmentConfig::confirmed())
+        .await?;
+    let account = response
+        .value
+        .with_context(|| format!("bonding curve {} для {} не найдена", curve, mint))?;
+    Ok((BondingCurve::decode(&account.data)?, response.context.slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Кривая сразу после создания токена
+    fn fresh() -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: None,
+        }
+    }
+
+    #[test]
+    fn spot_price() {
+        // 30 SOL / 1.073 млрд токенов
+        assert!((fresh().spot_price() - 30.0 / 1_073_000_000.0).abs() < 1e-18);
+        let empty = BondingCurve {
+            virtual_token_reserves: 0,
+            ..fresh()
+        };
+        assert_eq!(empty.spot_price(), 0.0);
+    }
+
+    #[test]
+    fn quotes_match_constant_product() {
+        let curve = fresh();
+        // 1 SOL: комиссия 1% сверху → 990_099_009 в кривую,
+        // 990_099_009 × 1.073e15 / (30e9 + 990_099_009)
+        assert_eq!(curve.buy_quote(LAMPORTS_PER_SOL), 34_281_150_129_545);
+        // 10M токенов: 1e13 × 30e9 / (1.073e15 + 1e13) = 277_008_310, минус 1%
+        assert_eq!(curve.sell_quote(10_000_000_000_000), 274_238_227);
+        // Обратно: 1e13 × 30e9 / (1.073e15 − 1e13) + 1 = 282_220_132, плюс 1%
+        assert_eq!(curve.buy_cost(10_000_000_000_000), Some(285_042_333));
+    }
+
+    #[test]
+    fn quote_edges() {
+        let curve = fresh();
+        assert_eq!(curve.buy_quote(0), 0);
+        assert_eq!(curve.sell_quote(0), 0);
+        assert_eq!(curve.buy_cost(0), Some(1));
+
+        // Купить больше реального остатка нельзя
+        assert_eq!(curve.buy_quote(u64::MAX), curve.real_token_reserves);
+        // Продажа всего u64 не переполняется и не выносит весь резерв
+        assert!(curve.sell_quote(u64::MAX) < curve.virtual_sol_reserves);
+        // Весь виртуальный резерв и почти весь — не купить
+        assert_eq!(curve.buy_cost(curve.virtual_token_reserves), None);
+        assert_eq!(curve.buy_cost(curve.virtual_token_reserves - 1), None);
+
+        let empty = BondingCurve {
+            virtual_token_reserves: 0,
+            virtual_sol_reserves: 0,
+            ..fresh()
+        };
+        assert_eq!(empty.sell_quote(0), 0);
+        assert_eq!(empty.buy_quote(0), 0);
+    }
+
+    #[test]
+    fn decode_account() {
+        let curve = BondingCurve {
+            real_sol_reserves: 12_500_000_000,
+            complete: true,
+            creator: Some(Pubkey::new_unique()),
+            ..fresh()
+        };
+        let mut data = BONDING_CURVE_DISCRIMINATOR.to_vec();
+        for v in [
+            curve.virtual_token_reserves,
+            curve.virtual_sol_reserves,
+            curve.real_token_reserves,
+            curve.real_sol_reserves,
+            curve.token_total_supply,
+        ] {
+            data.extend(v.to_le_bytes());
+        }
+        data.push(1);
+        data.extend(curve.creator.unwrap().to_bytes());
+        assert_eq!(BondingCurve::decode(&data).unwrap(), curve);
+
+        // Старый аккаунт без создателя
+        let old = BondingCurve::decode(&data[..8 + 41]).unwrap();
+        assert_eq!(old.creator, None);
+
+        assert!(BondingCurve::decode(&data[..8 + 40]).is_err());
+        assert!(BondingCurve::decode(&[0; 81]).is_err());
+
+        let snapshot = PoolSnapshot::from_curve(&curve, 42);
+        assert_eq!(snapshot.sol_reserve, 12_500_000_000);
+        assert_eq!(snapshot.token_reserve, curve.real_token_reserves);
+        assert_eq!(snapshot.slot, 42);
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Публичный API DexScreener
+pub const DEXSCREENER_API_URL: &str = "https://api.dexscreener.com";
+
+/// Сколько держать ответы DexScreener (лимит их API — десятки запросов в минуту)
+pub const DEXSCREENER_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Сводка по токену из самой ликвидной пары DexScreener
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenProfile {
+    pub mint: String,
+    pub symbol: String,
+    pub dex_id: String,
+    pub pair_address: String,
+    pub price_usd: Option<f64>,
+    pub liquidity_usd: Option<f64>,
+    pub volume_h24_usd: Option<f64>,
+    pub market_cap_usd: Option<f64>,
+    /// Сколько пар токена знает DexScreener
+    pub pairs: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pair {
+    dex_id: String,
+    pair_address: String,
+    base_token: BaseToken,
+    #[serde(default)]
+    price_usd: Option<String>,
+    #[serde(default)]
+    liquidity: Option<Liquidity>,
+    #[serde(default)]
+    volume: HashMap<String, f64>,
+    #[serde(default)]
+    market_cap: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaseToken {
+    address: String,
+    #[serde(default)]
+    symbol: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Liquidity {
+    #[serde(default)]
+    usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Boost {
+    chain_id: String,
+    token_address: String,
+}
+
+/// Клиент DexScreener с кэшем на `DEXSCREENER_CACHE_TTL`
+#[derive(Debug)]
+pub struct DexScreenerClient {
+    http: reqwest::Client,
+    base_url: String,
+    /// Одновременные запросы ждут один поход в сеть
+    trending: tokio::sync::Mutex<HashMap<String, (Vec<String>, Instant)>>,
+    profiles: tokio::sync::Mutex<HashMap<String, (Option<TokenProfile>, Instant)>>,
+}
+
+impl Default for DexScreenerClient {
+    fn default() -> Self {
+        Self::new(DEXSCREENER_API_URL)
+    }
+}
+
+impl DexScreenerClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("Failed to build HTTP client"),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            trending: tokio::sync::Mutex::new(HashMap::new()),
+            profiles: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Профиль токена на Solana; `None` — DexScreener токен ещё не проиндексировал
+    pub async fn token_profile(&self, mint: &str) -> Result<Option<TokenProfile>> {
+        let mut cache = self.profiles.lock().await;
+        if let Some((profile, at)) = cache.get(mint) {
+            if at.elapsed() < DEXSCREENER_CACHE_TTL {
+                return Ok(profile.clone());
+            }
+        }
+
+        let url = format!("{}/tokens/v1/solana/{}", self.base_url, mint);
+        let response = self.http.get(url).send().await?;
+        let pairs: Vec<Pair> = if response.status() == reqwest::StatusCode::NOT_FOUND {
+            Vec::new()
+        } else {
+            response.error_for_status()?.json().await?
+        };
+        let profile = profile_from_pairs(mint, pairs);
+        cache.insert(mint.to_string(), (profile.clone(), Instant::now()));
+        Ok(profile)
+    }
+
+    /// Mint-ы в трендах сети `chain` ("solana") по порядку, первый — топ-1.
+    /// Тренды — топ бустов DexScreener (`/token-boosts/top/v1`).
+    pub async fn trending(&self, chain: &str) -> Result<Vec<String>> {
+        let mut cache = self.trending.lock().await;
+        if let Some((mints, at)) = cache.get(chain) {
+            if at.elapsed() < DEXSCREENER_CACHE_TTL {
+                return Ok(mints.clone());
+            }
+        }
+
+        let boosts: Vec<Boost> = self
+            .http
+            .get(format!("{}/token-boosts/top/v1", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let mut mints: Vec<String> = Vec::new();
+        for boost in boosts.into_iter().filter(|b| b.chain_id == chain) {
+            if !mints.contains(&boost.token_address) {
+                mints.push(boost.token_address);
+            }
+        }
+        cache.insert(chain.to_string(), (mints.clone(), Instant::now()));
+        Ok(mints)
+    }
+
+    /// Токен Solana входит в первые `n` трендов; неизвестный токен — `false`
+    pub async fn is_in_top_n(&self, mint: &str, n: usize) -> Result<bool> {
+        if n == 0 {
+            return Ok(false);
+        }
+        let trending = self.trending("solana").await?;
+        Ok(trending.iter().take(n).any(|m| m == mint))
+    }
+}
+
+/// Самая ликвидная пара, где токен — базовый
+fn profile_from_pairs(mint: &str, pairs: Vec<Pair>) -> Option<TokenProfile> {
+    let count = pairs.len();
+    let liquidity = |p: &Pair| p.liquidity.as_ref().and_then(|l| l.usd).unwrap_or(0.0);
+    let best = pairs
+        .into_iter()
+        .filter(|p| p.base_token.address == mint)
+        .max_by(|a, b| liquidity(a).total_cmp(&liquidity(b)))?;
+    Some(TokenProfile {
+        mint: mint.to_string(),
+        symbol: best.base_token.symbol.clone(),
+        dex_id: best.dex_id.clone(),
+        pair_address: best.pair_address.clone(),
+        price_usd: best.price_usd.as_deref().and_then(|p| p.parse().ok()),
+        liquidity_usd: best.liquidity.as_ref().and_then(|l| l.usd),
+        volume_h24_usd: best.volume.get("h24").copied(),
+        market_cap_usd: best.market_cap,
+        pairs: count,
+    })
+}
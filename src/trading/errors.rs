@@ -0,0 +1,87 @@
+use solana_client::{client_error::ClientErrorKind, rpc_request::RpcError};
+use std::time::Duration;
+
+use super::rpc_pool::RpcTimeout;
+
+/// Дольше этого между тиками после сбоев не ждём
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Коды JSON-RPC отстающей или перегруженной ноды: слот ещё не доступен,
+/// пропущен, нода позади кластера
+const TRANSIENT_RPC_CODES: [i64; 5] = [-32004, -32005, -32007, -32014, -32016];
+
+/// Кривая завершена, а пул Raydium ещё не найден — миграция в процессе
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationPending;
+
+impl std::fmt::Display for MigrationPending {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("кривая завершена, пул Raydium ещё не найден")
+    }
+}
+
+/// Сбой, который стоит переждать: таймаут (в т.ч. `RpcTimeout`), 429 и 5xx, обрыв соединения,
+/// отстающая нода, незавершённая миграция. Остальное — фатальное
+/// (неверные данные аккаунта, отказ программы).
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    if e.downcast_ref::<MigrationPending>().is_some() || e.downcast_ref::<RpcTimeout>().is_some() {
+        return true;
+    }
+    e.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<solana_client::client_error::ClientError>() {
+            return match &err.kind {
+                ClientErrorKind::Io(_) => true,
+                ClientErrorKind::Reqwest(e) => {
+                    e.is_timeout()
+                        || e.is_connect()
+                        || e.status()
+                            .is_some_and(|s| s.as_u16() == 429 || (500..600).contains(&s.as_u16()))
+                }
+                ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+                    TRANSIENT_RPC_CODES.contains(code)
+                }
+                _ => false,
+            };
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return err.is_timeout()
+                || err.is_connect()
+                || err.status().is_some_and(|s| {
+                    s == reqwest::StatusCode::TOO_MANY_REQUESTS || s.is_server_error()
+                });
+        }
+        cause.downcast_ref::<std::io::Error>().is_some()
+    })
+}
+
+/// Сбои тиков мониторинга подряд
+#[derive(Debug, Clone, Default)]
+pub struct ErrorStreak {
+    consecutive: u32,
+}
+
+impl ErrorStreak {
+    pub fn consecutive(&self) -> u32 {
+        self.consecutive
+    }
+
+    /// Тик прошёл; возвращает, сколько сбоев было перед ним (0 — не было)
+    pub fn success(&mut self) -> u32 {
+        std::mem::take(&mut self.consecutive)
+    }
+
+    /// Тик не прошёл. Возвращает паузу до следующего тика (для временных сбоев —
+    /// `tick`, удваиваемый с каждым сбоем, до `MAX_BACKOFF`) и `true`, если сбоев
+    /// набралось `limit` (и снова через каждые `limit`); `limit` 0 — без эскалации.
+    pub fn failure(&mut self, e: &anyhow::Error, tick: Duration, limit: u32) -> (Duration, bool) {
+        self.consecutive += 1;
+        let pause = if is_transient(e) {
+            let factor = 1u32 << (self.consecutive - 1).min(5);
+            (tick * factor).min(MAX_BACKOFF.max(tick))
+        } else {
+            tick
+        };
+        let escalate = limit > 0 && self.consecutive.is_multiple_of(limit);
+        (pause, escalate)
+    }
+}
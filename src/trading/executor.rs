@@ -0,0 +1,516 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use super::{
+    curve::TOKEN_DECIMALS,
+    fees::{self, Urgency},
+    jito::JitoClient,
+    jupiter::{is_no_route, JupiterClient},
+    pump_sell::{self, SellOptions, SellReceipt, SellRoute, TokenAmount, BASE_FEE_LAMPORTS},
+    risk::RiskConfig,
+    rpc_pool::RpcPool,
+    simulate::{sim_error, ExitSimError},
+    slippage::{self, DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS},
+};
+use crate::scanner::{onchain::bonding_curve_pda, raydium::WSOL_MINT};
+
+/// Проскальзывание продажи вслепую (`Urgency::Forced`), б.п.
+pub const BLIND_SELL_SLIPPAGE_BPS: u16 = 3_000;
+
+/// Как исполняются плановые выходы (ступени, trailing stop, time-out)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitStyle {
+    /// Одной продажей
+    #[default]
+    Single,
+    /// `parts` продаж через `interval_ms`, чтобы меньше двигать цену против себя
+    Tranches { parts: u8, interval_ms: u64 },
+}
+
+impl ExitStyle {
+    /// На сколько частей делить продажу такой срочности; срочные — всегда одной
+    pub fn parts(self, urgency: Urgency) -> u8 {
+        match (self, urgency) {
+            (Self::Tranches { parts, .. }, Urgency::Normal) => parts.max(1),
+            _ => 1,
+        }
+    }
+
+    pub fn interval(self) -> Duration {
+        match self {
+            Self::Single => Duration::ZERO,
+            Self::Tranches { interval_ms, .. } => Duration::from_millis(interval_ms),
+        }
+    }
+}
+
+/// Часть `k` (с нуля) из `parts` продажи `amount`. Доля считается от баланса,
+/// оставшегося после предыдущих частей; остаток от деления — в последней части.
+pub fn tranche_amount(amount: TokenAmount, k: u8, parts: u8) -> TokenAmount {
+    let (k, parts) = (k as u64, parts.max(1) as u64);
+    match amount {
+        TokenAmount::Share(share) => {
+            let part = share / parts as f64;
+            let left = 1.0 - part * k as f64;
+            TokenAmount::Share(if left > 0.0 {
+                (part / left).min(1.0)
+            } else {
+                1.0
+            })
+        }
+        TokenAmount::Raw(raw) if k + 1 == parts => TokenAmount::Raw(raw - raw / parts * k),
+        TokenAmount::Raw(raw) => TokenAmount::Raw(raw / parts),
+    }
+}
+
+/// Сводная квитанция частей: суммы складываются, подпись и маршрут — последней;
+/// проскальзывание — по суммам, если оно известно у всех частей
+pub fn combine_receipts(receipts: &[SellReceipt]) -> Option<SellReceipt> {
+    let last = receipts.last()?;
+    let sol_received = receipts.iter().map(|r| r.sol_received).sum();
+    let expected_sol = receipts.iter().map(|r| r.expected_sol).sum();
+    Some(SellReceipt {
+        signature: last.signature,
+        route: last.route,
+        tokens_sold: receipts.iter().map(|r| r.tokens_sold).sum(),
+        sol_received,
+        expected_sol,
+        realized_slippage_bps: receipts
+            .iter()
+            .all(|r| r.realized_slippage_bps.is_some())
+            .then(|| slippage::sell_slippage_bps(expected_sol, sol_received))
+            .flatten(),
+        fee_lamports: receipts.iter().map(|r| r.fee_lamports).sum(),
+        simulated: receipts.iter().all(|r| r.simulated),
+    })
+}
+
+/// Колбэк неудачной попытки: номер, проскальзывание (б.п.), ошибка
+pub type RetryHook<'a> = dyn Fn(usize, u16, &anyhow::Error) + Send + Sync + 'a;
+
+/// Исполнение решений о выходе. Монитор решает, что и насколько срочно продать,
+/// исполнитель — как (DEX, подпись, комиссии, повторы).
+#[async_trait]
+pub trait ExitExecutor: Send + Sync {
+    /// Продаёт `amount` токена `mint`
+    async fn sell(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+    ) -> Result<SellReceipt>;
+
+    /// То же, но с отчётом о неудачных попытках (для `RiskEvent::SellRetry`);
+    /// по умолчанию попытка одна — `sell`
+    async fn sell_reporting(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+        on_retry: &RetryHook<'_>,
+    ) -> Result<SellReceipt> {
+        let _ = on_retry;
+        self.sell(mint, amount, urgency).await
+    }
+}
+
+/// Параметры продаж встроенных исполнителей: лестница, порог выручки,
+/// приоритетная комиссия и чаевые — из `RiskConfig`
+#[derive(Debug, Clone)]
+pub struct SellSettings {
+    pub config: Arc<RiskConfig>,
+    /// Базовое проскальзывание плановых продаж, б.п.
+    pub slippage_bps: u16,
+    /// Базовое проскальзывание срочных продаж (`Urgency::Emergency`), б.п.
+    pub emergency_slippage_bps: u16,
+    pub jito: Option<Arc<JitoClient>>,
+    /// Срочные продажи на bonding curve отправляются во все endpoints пула
+    pub rpc: Option<Arc<RpcPool>>,
+    /// Подписывать и прогонять через `simulateTransaction`, не отправляя
+    pub dry_run: bool,
+}
+
+impl SellSettings {
+    pub fn new(config: Arc<RiskConfig>, slippage_bps: u16) -> Self {
+        Self {
+            config,
+            slippage_bps,
+            emergency_slippage_bps: slippage_bps.max(DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS),
+            jito: None,
+            rpc: None,
+            dry_run: false,
+        }
+    }
+
+    /// Проскальзывание срочных продаж
+    pub fn with_emergency_slippage(mut self, bps: u16) -> Self {
+        self.emergency_slippage_bps = bps;
+        self
+    }
+
+    /// Проскальзывание по попыткам; вслепую начинаем с `BLIND_SELL_SLIPPAGE_BPS`
+    pub fn ladder(&self, urgency: Urgency) -> Vec<u16> {
+        let base = match urgency {
+            Urgency::Forced => self.slippage_bps.max(BLIND_SELL_SLIPPAGE_BPS),
+            Urgency::Emergency => self.emergency_slippage_bps,
+            Urgency::Normal => self.slippage_bps,
+        };
+        self.config.slippage_ladder(base)
+    }
+
+    /// Порог выручки, приоритетная комиссия (по записываемому `account`) и чаевые Jito.
+    /// Комиссия не оценилась — продаём без неё, а не ждём.
+    async fn options(
+        &self,
+        client: &RpcClient,
+        account: Pubkey,
+        urgency: Urgency,
+        with_jito: bool,
+    ) -> SellOptions<'_> {
+        let config = &self.config;
+        let priority_fee = if config.priority_fee_cap_lamports == 0 {
+            None
+        } else {
+            match fees::estimate_priority_fee(
+                client,
+                &[account],
+                config.compute_unit_limit,
+                config.priority_fee_multiplier,
+                config.priority_fee_cap_lamports,
+                urgency,
+            )
+            .await
+            {
+                Ok(fee) => Some(fee),
+                Err(e) => {
+                    log::warn!("Приоритетная комиссия не оценена: {}", e);
+                    None
+                }
+            }
+        };
+        let tip = match urgency {
+            Urgency::Emergency => config
+                .jito_emergency_tip_lamports
+                .max(config.jito_tip_lamports),
+            Urgency::Normal | Urgency::Forced => config.jito_tip_lamports,
+        };
+        let jito = match &self.jito {
+            Some(jito) if tip > 0 && with_jito => Some((jito.as_ref(), tip)),
+            _ => None,
+        };
+        SellOptions {
+            slippage_bps: self.slippage_bps,
+            floor_lamports: (config.min_exit_proceeds_sol * LAMPORTS_PER_SOL as f64) as u64,
+            priority_fee,
+            jito,
+            dry_run: self.dry_run,
+            simulate: config.simulates(urgency),
+            broadcast: match urgency {
+                Urgency::Emergency => self.rpc.as_deref(),
+                Urgency::Normal | Urgency::Forced => None,
+            },
+        }
+    }
+}
+
+/// Продажа на bonding curve pump.fun по лестнице проскальзывания
+pub struct CurveExecutor {
+    client: Arc<RpcClient>,
+    wallet: Arc<Keypair>,
+    settings: SellSettings,
+}
+
+impl CurveExecutor {
+    pub fn new(client: Arc<RpcClient>, wallet: Arc<Keypair>, settings: SellSettings) -> Self {
+        Self {
+            client,
+            wallet,
+            settings,
+        }
+    }
+}
+
+#[async_trait]
+impl ExitExecutor for CurveExecutor {
+    async fn sell(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+    ) -> Result<SellReceipt> {
+        self.sell_reporting(mint, amount, urgency, &|_, _, _| {})
+            .await
+    }
+
+    async fn sell_reporting(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+        on_retry: &RetryHook<'_>,
+    ) -> Result<SellReceipt> {
+        let options = self
+            .settings
+            .options(&self.client, bonding_curve_pda(&mint), urgency, true)
+            .await;
+        pump_sell::sell_with_escalation(
+            &self.settings.ladder(urgency),
+            |bps| {
+                let options = options.with_slippage(bps);
+                async move {
+                    pump_sell::sell(&self.client, &self.wallet, &mint, amount, &options).await
+                }
+            },
+            on_retry,
+        )
+        .await
+    }
+}
+
+/// Продажа через Jupiter (после миграции на Raydium или как запасной путь)
+pub struct JupiterExecutor {
+    client: Arc<RpcClient>,
+    wallet: Arc<Keypair>,
+    jupiter: JupiterClient,
+    settings: SellSettings,
+}
+
+impl JupiterExecutor {
+    pub fn new(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        jupiter: JupiterClient,
+        settings: SellSettings,
+    ) -> Self {
+        Self {
+            client,
+            wallet,
+            jupiter,
+            settings,
+        }
+    }
+
+    async fn sell_once(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        options: SellOptions<'_>,
+    ) -> Result<SellReceipt> {
+        let amount =
+            pump_sell::sell_amount(&self.client, &self.wallet.pubkey(), &mint, amount).await?;
+        let quote = self
+            .jupiter
+            .quote(&mint, &WSOL_MINT, amount, options.slippage_bps)
+            .await
+            .inspect_err(|e| {
+                if is_no_route(e) {
+                    log::warn!("🛣️ Jupiter пока не видит маршрута для {}", mint);
+                }
+            })?;
+        pump_sell::ensure_floor(quote.other_amount_threshold, options.floor_lamports)?;
+        let priority_lamports = options.priority_fee.map_or(0, |fee| fee.lamports());
+        let signature = self
+            .jupiter
+            .swap(
+                &self.client,
+                &quote,
+                &self.wallet,
+                priority_lamports,
+                options.dry_run,
+                options.simulate,
+            )
+            .await?;
+        let fill = match options.dry_run {
+            true => None,
+            false => slippage::realized_fill(&self.client, &signature, &self.wallet.pubkey()).await,
+        };
+        Ok(SellReceipt {
+            signature,
+            route: SellRoute::Jupiter,
+            tokens_sold: quote.in_amount,
+            sol_received: fill.unwrap_or(quote.out_amount),
+            expected_sol: quote.out_amount,
+            realized_slippage_bps: fill
+                .and_then(|actual| slippage::sell_slippage_bps(quote.out_amount, actual)),
+            fee_lamports: BASE_FEE_LAMPORTS + priority_lamports,
+            simulated: options.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl ExitExecutor for JupiterExecutor {
+    async fn sell(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+    ) -> Result<SellReceipt> {
+        self.sell_reporting(mint, amount, urgency, &|_, _, _| {})
+            .await
+    }
+
+    async fn sell_reporting(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+        on_retry: &RetryHook<'_>,
+    ) -> Result<SellReceipt> {
+        // Jupiter собирает транзакцию сам — чаевые Jito не добавить
+        let options = self
+            .settings
+            .options(&self.client, mint, urgency, false)
+            .await;
+        pump_sell::sell_with_escalation(
+            &self.settings.ladder(urgency),
+            |bps| self.sell_once(mint, amount, options.with_slippage(bps)),
+            on_retry,
+        )
+        .await
+    }
+}
+
+/// Основной исполнитель, при неудаче — запасной. Выручка ниже порога,
+/// пустой кошелёк и отказ симуляции из-за кошелька (заморожен, нет SOL) запасным не лечатся.
+pub struct FallbackExecutor {
+    primary: Arc<dyn ExitExecutor>,
+    fallback: Arc<dyn ExitExecutor>,
+}
+
+impl FallbackExecutor {
+    pub fn new(primary: Arc<dyn ExitExecutor>, fallback: Arc<dyn ExitExecutor>) -> Self {
+        Self { primary, fallback }
+    }
+
+    /// bonding curve, если не прошла или кривая завершена — Jupiter
+    pub fn pump_fun(client: Arc<RpcClient>, wallet: Arc<Keypair>, settings: SellSettings) -> Self {
+        Self::new(
+            Arc::new(CurveExecutor::new(
+                client.clone(),
+                wallet.clone(),
+                settings.clone(),
+            )),
+            Arc::new(JupiterExecutor::new(
+                client,
+                wallet,
+                JupiterClient::default(),
+                settings,
+            )),
+        )
+    }
+}
+
+#[async_trait]
+impl ExitExecutor for FallbackExecutor {
+    async fn sell(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+    ) -> Result<SellReceipt> {
+        self.sell_reporting(mint, amount, urgency, &|_, _, _| {})
+            .await
+    }
+
+    async fn sell_reporting(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+        on_retry: &RetryHook<'_>,
+    ) -> Result<SellReceipt> {
+        match self
+            .primary
+            .sell_reporting(mint, amount, urgency, on_retry)
+            .await
+        {
+            Ok(receipt) => Ok(receipt),
+            Err(e)
+                if pump_sell::is_below_floor(&e)
+                    || pump_sell::is_no_tokens(&e)
+                    || sim_error(&e).is_some_and(ExitSimError::is_wallet_problem) =>
+            {
+                Err(e)
+            }
+            Err(e) => {
+                log::warn!("⚠️ Продажа не прошла ({}) → запасной путь", e);
+                self.fallback
+                    .sell_reporting(mint, amount, urgency, on_retry)
+                    .await
+            }
+        }
+    }
+}
+
+/// Без транзакций. Без цены — пустая квитанция (выручка 0); с ценой (`paper`) —
+/// выручка по ней минус проскальзывание. Все вызовы запоминаются (`sales`).
+#[derive(Debug, Default)]
+pub struct DryRunExecutor {
+    /// (SOL за целый токен, проскальзывание б.п., маршрут в квитанции)
+    fill: Option<(f64, u16, SellRoute)>,
+    sales: Mutex<Vec<(Pubkey, TokenAmount, Urgency)>>,
+}
+
+impl DryRunExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Бумажная продажа по цене `price` (SOL за целый токен)
+    pub fn paper(price: f64, slippage_bps: u16, route: SellRoute) -> Self {
+        Self {
+            fill: Some((price, slippage_bps, route)),
+            sales: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Вызовы `sell` по порядку
+    pub fn sales(&self) -> Vec<(Pubkey, TokenAmount, Urgency)> {
+        self.sales.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ExitExecutor for DryRunExecutor {
+    async fn sell(
+        &self,
+        mint: Pubkey,
+        amount: TokenAmount,
+        urgency: Urgency,
+    ) -> Result<SellReceipt> {
+        self.sales.lock().unwrap().push((mint, amount, urgency));
+        // Баланса нет — долю считать не от чего
+        let tokens_sold = match amount {
+            TokenAmount::Raw(raw) => raw,
+            TokenAmount::Share(_) => 0,
+        };
+        let (price, slippage_bps, route) = self.fill.unwrap_or((0.0, 0, SellRoute::BondingCurve));
+        let keep = 1.0 - slippage_bps as f64 / 10_000.0;
+        let tokens = pump_sell::raw_to_ui(tokens_sold, TOKEN_DECIMALS);
+        log::debug!("🧪 Продажа {:?} {} без транзакции", amount, mint);
+        let sol_received = (tokens * price * keep * LAMPORTS_PER_SOL as f64) as u64;
+        Ok(SellReceipt {
+            signature: Signature::default(),
+            route,
+            tokens_sold,
+            sol_received,
+            expected_sol: sol_received,
+            realized_slippage_bps: None,
+            fee_lamports: BASE_FEE_LAMPORTS,
+            simulated: true,
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use super::curve::{BondingCurve, PoolSnapshot};
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Как часто сверять, не сменился ли аккаунт подписки (миграция на Raydium)
+const TARGET_CHECK: Duration = Duration::from_secs(2);
+
+/// Откуда монитор узнаёт об изменении цены
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceFeed {
+    /// Опрос по HTTP каждые `tick_interval_ms`
+    #[default]
+    Polling,
+    /// `accountSubscribe` на bonding curve (после миграции — на SOL-хранилище Raydium);
+    /// опрос остаётся сторожем на случай тишины или обрыва websocket
+    AccountSubscribe { ws_url: String },
+}
+
+/// Что пришло из подписки
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FeedUpdate {
+    /// Новое состояние bonding curve — цена уже посчитана
+    Curve(PoolSnapshot),
+    /// Аккаунт изменился, снимок нужно прочитать по HTTP
+    Changed,
+}
+
+/// Подписка на аккаунт, который возвращает `target`, с переподключением и backoff.
+/// Обновления пишутся в `tx` (держится только последнее); цикл завершается
+/// по `cancel` или когда монитор перестал читать обновления.
+pub(crate) async fn account_updates(
+    ws_url: String,
+    target: impl Fn() -> (Pubkey, bool),
+    tx: watch::Sender<Option<FeedUpdate>>,
+    cancel: CancellationToken,
+) {
+    let mut backoff = MIN_BACKOFF;
+    while !cancel.is_cancelled() && !tx.is_closed() {
+        match subscribe(&ws_url, &target, &tx, &cancel, &mut backoff).await {
+            Ok(()) => log::debug!("Подписка accountSubscribe закрыта, переподключение..."),
+            Err(e) => log::warn!("Ошибка accountSubscribe: {}", e),
+        }
+        if cancel.is_cancelled() || tx.is_closed() {
+            break;
+        }
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// `target` — аккаунт и признак того, что это bonding curve
+async fn subscribe(
+    ws_url: &str,
+    target: &impl Fn() -> (Pubkey, bool),
+    tx: &watch::Sender<Option<FeedUpdate>>,
+    cancel: &CancellationToken,
+    backoff: &mut Duration,
+) -> Result<()> {
+    let (account, is_curve) = target();
+    let client = PubsubClient::new(ws_url)
+        .await
+        .context("подключение к RPC websocket")?;
+    let (mut stream, unsubscribe) = client
+        .account_subscribe(
+            &account,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            }),
+        )
+        .await?;
+    log::debug!("accountSubscribe на {} активен", account);
+    *backoff = MIN_BACKOFF;
+
+    let mut check = tokio::time::interval(TARGET_CHECK);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = check.tick() => {
+                if target().0 != account {
+                    log::debug!("Аккаунт подписки сменился, переподписка");
+                    break;
+                }
+            }
+            resp = stream.next() => {
+                let Some(resp) = resp else { break };
+                let update = match resp.value.data.decode() {
+                    Some(data) if is_curve => match BondingCurve::decode(&data) {
+                        // Завершённая кривая — цена уже не отсюда, пусть разберётся HTTP
+                        Ok(curve) if !curve.complete => {
+                            FeedUpdate::Curve(PoolSnapshot::from_curve(&curve, resp.context.slot))
+                        }
+                        _ => FeedUpdate::Changed,
+                    },
+                    _ => FeedUpdate::Changed,
+                };
+                if tx.send(Some(update)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    unsubscribe().await;
+    Ok(())
+}
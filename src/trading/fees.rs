@@ -0,0 +1,105 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
+};
+
+/// Перцентиль недавних приоритетных комиссий, от которого считается ставка
+const FEE_PERCENTILE: f64 = 0.75;
+
+/// Насколько срочна продажа: от этого зависят приоритетная комиссия и чаевые Jito
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Urgency {
+    /// Плановые продажи (ступени, trailing, таймеры)
+    #[default]
+    Normal,
+    /// Rug-pull, panic-sell: комиссия по максимуму
+    Emergency,
+    /// Срок вышел или цены нет: продаём при любой цене, комиссия обычная
+    Forced,
+}
+
+/// Приоритетная комиссия транзакции (инструкции ComputeBudget)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PriorityFee {
+    pub compute_unit_limit: u32,
+    /// Цена вычислительной единицы, микро-lamports
+    pub micro_lamports_per_cu: u64,
+}
+
+impl PriorityFee {
+    /// Комиссия сверх базовой при полном расходе лимита, lamports
+    pub fn lamports(&self) -> u64 {
+        (self.compute_unit_limit as u128 * self.micro_lamports_per_cu as u128 / 1_000_000) as u64
+    }
+
+    /// Инструкции, которые ставятся в начало транзакции
+    pub fn instructions(&self) -> Vec<Instruction> {
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(self.compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.micro_lamports_per_cu),
+        ]
+    }
+
+    /// Ставка по недавним комиссиям (микро-lamports за CU): перцентиль × `multiplier`,
+    /// но не дороже `cap_lamports` за транзакцию; срочная продажа берёт весь `cap_lamports`
+    pub fn from_recent(
+        recent: &[u64],
+        compute_unit_limit: u32,
+        multiplier: f64,
+        cap_lamports: u64,
+        urgency: Urgency,
+    ) -> Self {
+        let units = compute_unit_limit.max(1) as u128;
+        let max_price = (cap_lamports as u128 * 1_000_000 / units) as u64;
+        let price = match urgency {
+            Urgency::Emergency => max_price,
+            Urgency::Normal | Urgency::Forced => {
+                let mut fees: Vec<u64> = recent.iter().copied().filter(|f| *f > 0).collect();
+                fees.sort_unstable();
+                let base = fees
+                    .get(
+                        ((fees.len() as f64 - 1.0) * FEE_PERCENTILE)
+                            .round()
+                            .max(0.0) as usize,
+                    )
+                    .copied()
+                    .unwrap_or(0);
+                ((base as f64 * multiplier) as u64).min(max_price)
+            }
+        };
+        Self {
+            compute_unit_limit,
+            micro_lamports_per_cu: price,
+        }
+    }
+}
+
+/// Приоритетная комиссия по `getRecentPrioritizationFees` для записываемых аккаунтов
+pub async fn estimate_priority_fee(
+    client: &RpcClient,
+    accounts: &[Pubkey],
+    compute_unit_limit: u32,
+    multiplier: f64,
+    cap_lamports: u64,
+    urgency: Urgency,
+) -> Result<PriorityFee> {
+    let recent = match urgency {
+        // Срочной продаже статистика не нужна — сразу по максимуму
+        Urgency::Emergency => Vec::new(),
+        Urgency::Normal | Urgency::Forced => client
+            .get_recent_prioritization_fees(accounts)
+            .await?
+            .into_iter()
+            .map(|f| f.prioritization_fee)
+            .collect(),
+    };
+    Ok(PriorityFee::from_recent(
+        &recent,
+        compute_unit_limit,
+        multiplier,
+        cap_lamports,
+        urgency,
+    ))
+}
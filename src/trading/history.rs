@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::{collections::VecDeque, time::Duration};
+
+/// Окна скользящей доходности, сек
+pub const ROLLING_WINDOWS_SECS: [u64; 3] = [5, 15, 30];
+
+/// Точка истории цены позиции
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceSample {
+    /// unix, мс
+    pub timestamp_ms: u64,
+    /// SOL за токен
+    pub price: f64,
+    /// Резерв SOL пула, lamports; 0 — неизвестен (свечи до входа)
+    pub sol_reserve: u64,
+}
+
+/// Доходность за последние 5/15/30 сек; `None` — истории пока не хватает
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct RollingReturns {
+    pub r5s: Option<f64>,
+    pub r15s: Option<f64>,
+    pub r30s: Option<f64>,
+}
+
+/// Скорость оттока резерва SOL (положительная — резерв уменьшается)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ReserveDrain {
+    /// SOL в секунду
+    pub sol_per_sec: f64,
+    /// % резерва начала окна в секунду
+    pub pct_per_sec: f64,
+}
+
+/// Кольцевой буфер цен: при переполнении выбрасываются самые старые точки
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceHistory {
+    samples: VecDeque<PriceSample>,
+    capacity: usize,
+}
+
+impl PriceHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Добавляет точку; точки старше последней отбрасываются
+    pub fn push(&mut self, sample: PriceSample) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| sample.timestamp_ms < last.timestamp_ms)
+        {
+            return;
+        }
+        while self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Точки от старых к новым
+    pub fn samples(&self) -> impl Iterator<Item = &PriceSample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&PriceSample> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Изменение цены между двумя последними точками по модулю, %
+    pub fn last_change_pct(&self) -> Option<f64> {
+        let n = self.samples.len();
+        let prev = self.samples.get(n.checked_sub(2)?)?;
+        let last = self.samples.back()?;
+        (prev.price > 0.0).then(|| (last.price / prev.price - 1.0).abs() * 100.0)
+    }
+
+    /// Цена на момент `timestamp_ms` — последняя точка не позже него
+    pub fn price_at(&self, timestamp_ms: u64) -> Option<f64> {
+        let idx = self
+            .samples
+            .partition_point(|s| s.timestamp_ms <= timestamp_ms);
+        idx.checked_sub(1).map(|i| self.samples[i].price)
+    }
+
+    /// Доходность за последние `window` (0.1 — +10%)
+    pub fn return_over(&self, window: Duration) -> Option<f64> {
+        let last = self.latest()?;
+        let from = last.timestamp_ms.checked_sub(window.as_millis() as u64)?;
+        let base = self.price_at(from).filter(|p| *p > 0.0)?;
+        Some(last.price / base - 1.0)
+    }
+
+    pub fn rolling_returns(&self) -> RollingReturns {
+        let [r5s, r15s, r30s] =
+            ROLLING_WINDOWS_SECS.map(|secs| self.return_over(Duration::from_secs(secs)));
+        RollingReturns { r5s, r15s, r30s }
+    }
+
+    /// Отток резерва за последние `samples` точек с известным резервом.
+    /// Берётся меньшая из двух оценок — окно до последней и до предпоследней точки,
+    /// так что один шумный снимок срабатывания не даёт.
+    pub fn reserve_drain(&self, samples: usize) -> Option<ReserveDrain> {
+        let samples = samples.max(2);
+        let known: Vec<&PriceSample> = self.samples.iter().filter(|s| s.sol_reserve > 0).collect();
+        if known.len() < samples + 1 {
+            return None;
+        }
+        let rate = |window: &[&PriceSample]| -> Option<ReserveDrain> {
+            let (first, last) = (window.first()?, window.last()?);
+            let secs = last.timestamp_ms.saturating_sub(first.timestamp_ms) as f64 / 1000.0;
+            if secs <= 0.0 {
+                return None;
+            }
+            let drained =
+                (first.sol_reserve as f64 - last.sol_reserve as f64) / LAMPORTS_PER_SOL as f64;
+            let sol_per_sec = drained / secs;
+            Some(ReserveDrain {
+                sol_per_sec,
+                pct_per_sec: sol_per_sec * LAMPORTS_PER_SOL as f64 / first.sol_reserve as f64
+                    * 100.0,
+            })
+        };
+        let n = known.len();
+        let latest = rate(&known[n - samples..])?;
+        let previous = rate(&known[n - samples - 1..n - 1])?;
+        Some(ReserveDrain {
+            sol_per_sec: latest.sol_per_sec.min(previous.sol_per_sec),
+            pct_per_sec: latest.pct_per_sec.min(previous.pct_per_sec),
+        })
+    }
+
+    /// Сколько последних интервалов `interval` подряд цена падала
+    /// не меньше чем на `drop_pct` за интервал
+    pub fn consecutive_drops(&self, interval: Duration, drop_pct: f64) -> usize {
+        let Some(last) = self.latest() else {
+            return 0;
+        };
+        let step = (interval.as_millis() as u64).max(1);
+        let mut count = 0;
+        let mut end = last.price;
+        while let Some(from) = last.timestamp_ms.checked_sub(step * (count as u64 + 1)) {
+            let Some(start) = self.price_at(from).filter(|p| *p > 0.0) else {
+                break;
+            };
+            if (start - end) / start * 100.0 < drop_pct {
+                break;
+            }
+            count += 1;
+            end = start;
+        }
+        count
+    }
+}
@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::seq::SliceRandom;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use std::time::{Duration, Instant};
+
+use super::pump_sell::SYSTEM_PROGRAM;
+
+/// Индекс инструкции `Transfer` System Program
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// Аккаунты для чаевых Jito (mainnet); берётся случайный, чтобы не упираться в один
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// Сколько ждать подтверждения транзакции из бандла
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Пауза между проверками статуса бандла
+const POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Через сколько слотов без посадки бандла транзакция уходит обычным RPC
+pub const DEFAULT_FALLBACK_SLOTS: u64 = 20;
+
+/// Block engine по региону из `Config.jito_region`; пусто или "mainnet" — общий адрес
+pub fn block_engine_url(region: &str) -> Option<&'static str> {
+    Some(match region.trim().to_ascii_lowercase().as_str() {
+        "" | "mainnet" => "https://mainnet.block-engine.jito.wtf",
+        "ny" => "https://ny.mainnet.block-engine.jito.wtf",
+        "amsterdam" => "https://amsterdam.mainnet.block-engine.jito.wtf",
+        "frankfurt" => "https://frankfurt.mainnet.block-engine.jito.wtf",
+        "tokyo" => "https://tokyo.mainnet.block-engine.jito.wtf",
+        "slc" => "https://slc.mainnet.block-engine.jito.wtf",
+        _ => return None,
+    })
+}
+
+/// Инструкция чаевых Jito на случайный tip-аккаунт
+pub fn tip_instruction(payer: &Pubkey, lamports: u64) -> Instruction {
+    let tip_account = JITO_TIP_ACCOUNTS
+        .choose(&mut rand::thread_rng())
+        .copied()
+        .unwrap_or(JITO_TIP_ACCOUNTS[0]);
+    let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction {
+        program_id: SYSTEM_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(tip_account, false),
+        ],
+        data,
+    }
+}
+
+/// Состояние бандла по `getInflightBundleStatuses` (последние ~5 минут)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BundleStatus {
+    Pending,
+    Landed {
+        slot: u64,
+    },
+    /// Ни одна транзакция бандла не прошла
+    Failed,
+    /// Бандл неизвестен block engine (ещё не принят или слишком старый)
+    Invalid,
+}
+
+/// Разбор ответа `getInflightBundleStatuses` по первому бандлу
+pub fn parse_inflight_status(body: &serde_json::Value) -> Result<BundleStatus> {
+    if let Some(err) = body.get("error") {
+        anyhow::bail!("Jito: {}", err);
+    }
+    let Some(entry) = body["result"]["value"].get(0) else {
+        return Ok(BundleStatus::Invalid);
+    };
+    Ok(match entry["status"].as_str() {
+        Some("Pending") => BundleStatus::Pending,
+        Some("Landed") => BundleStatus::Landed {
+            slot: entry["landed_slot"].as_u64().unwrap_or_default(),
+        },
+        Some("Failed") => BundleStatus::Failed,
+        Some("Invalid") => BundleStatus::Invalid,
+        other => anyhow::bail!("неизвестный статус бандла Jito: {:?}", other),
+    })
+}
+
+/// Чем закончилась отправка через Jito
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundleOutcome {
+    pub signature: Signature,
+    /// id бандла; `None` — block engine его не принял
+    pub bundle_id: Option<String>,
+    /// Транзакция прошла в бандле (чаевые заплачены); `false` — обычным RPC
+    pub via_bundle: bool,
+}
+
+/// Отправка бандлов в block engine Jito
+#[derive(Debug, Clone)]
+pub struct JitoClient {
+    http: reqwest::Client,
+    url: String,
+    /// 0 — без запасного пути: ждём бандл до `CONFIRM_TIMEOUT`
+    fallback_slots: u64,
+}
+
+impl JitoClient {
+    /// Клиент для региона `Config.jito_region`
+    pub fn new(region: &str) -> Result<Self> {
+        let url = block_engine_url(region)
+            .with_context(|| format!("неизвестный регион Jito: {}", region))?;
+        Ok(Self::with_url(url))
+    }
+
+    pub fn with_url(url: &str) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("Failed to build HTTP client"),
+            url: url.trim_end_matches('/').to_string(),
+            fallback_slots: DEFAULT_FALLBACK_SLOTS,
+        }
+    }
+
+    /// Через сколько слотов без посадки слать транзакцию обычным RPC (0 — не слать)
+    pub fn with_fallback_slots(mut self, slots: u64) -> Self {
+        self.fallback_slots = slots;
+        self
+    }
+
+    /// JSON-RPC вызов block engine по пути `/api/v1/<path>`
+    async fn call(
+        &self,
+        path: &str,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        Ok(self
+            .http
+            .post(format!("{}/api/v1/{}", self.url, path))
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Отправляет подписанные транзакции одним бандлом; возвращает id бандла
+    pub async fn send_bundle(&self, txs: &[Transaction]) -> Result<String> {
+        let encoded = txs
+            .iter()
+            .map(|tx| Ok(STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let body = self
+            .call(
+                "bundles",
+                "sendBundle",
+                serde_json::json!([encoded, { "encoding": "base64" }]),
+            )
+            .await?;
+        if let Some(err) = body.get("error") {
+            anyhow::bail!("Jito отклонил бандл: {}", err);
+        }
+        body["result"]
+            .as_str()
+            .map(str::to_string)
+            .context("в ответе Jito нет id бандла")
+    }
+
+    /// Статус недавно отправленного бандла
+    pub async fn bundle_status(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let body = self
+            .call(
+                "getInflightBundleStatuses",
+                "getInflightBundleStatuses",
+                serde_json::json!([[bundle_id]]),
+            )
+            .await?;
+        parse_inflight_status(&body)
+    }
+
+    /// Бандл [`tx`, отдельная транзакция чаевых `tip` lamports от `payer`] с ожиданием
+    /// посадки. Бандл не принят, провалился или не сел за `fallback_slots` слотов —
+    /// та же транзакция уходит обычным RPC (дважды она пройти не может).
+    pub async fn send_with_tip(
+        &self,
+        client: &RpcClient,
+        payer: &Keypair,
+        tx: &Transaction,
+        tip: u64,
+    ) -> Result<BundleOutcome> {
+        let signature = tx.signatures[0];
+        let tip_tx = Transaction::new_signed_with_payer(
+            &[tip_instruction(&payer.pubkey(), tip)],
+            Some(&payer.pubkey()),
+            &[payer],
+            tx.message.recent_blockhash,
+        );
+        let start_slot = client.get_slot().await?;
+        let bundle_id = match self.send_bundle(&[tx.clone(), tip_tx]).await {
+            Ok(id) => id,
+            Err(e) if self.fallback_slots > 0 => {
+                log::warn!("⚡ Бандл Jito не принят ({}) — отправка через RPC", e);
+                return self.send_plain(client, tx, None).await;
+            }
+            Err(e) => return Err(e),
+        };
+        log::debug!("Бандл Jito {} отправлен ({})", bundle_id, signature);
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            if let Some(result) = client.get_signature_status(&signature).await? {
+                result?;
+                return Ok(BundleOutcome {
+                    signature,
+                    bundle_id: Some(bundle_id),
+                    via_bundle: true,
+                });
+            }
+            match self.bundle_status(&bundle_id).await {
+                Ok(BundleStatus::Failed) if self.fallback_slots > 0 => {
+                    log::warn!("⚡ Бандл Jito {} не прошёл — отправка через RPC", bundle_id);
+                    return self.send_plain(client, tx, Some(bundle_id)).await;
+                }
+                Ok(status) => log::trace!("Бандл Jito {}: {:?}", bundle_id, status),
+                Err(e) => log::debug!("Статус бандла Jito {} не получен: {}", bundle_id, e),
+            }
+            if self.fallback_slots > 0 {
+                let waited = client.get_slot().await?.saturating_sub(start_slot);
+                if waited >= self.fallback_slots {
+                    log::warn!(
+                        "⚡ Бандл Jito {} не сел за {} слотов — отправка через RPC",
+                        bundle_id,
+                        waited
+                    );
+                    return self.send_plain(client, tx, Some(bundle_id)).await;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        anyhow::bail!(
+            "бандл Jito {} не подтвердился за {:?}",
+            bundle_id,
+            CONFIRM_TIMEOUT
+        )
+    }
+
+    /// Запасной путь: обычная отправка. Если бандл всё же сел в последний момент,
+    /// RPC отклонит повтор — тогда засчитывается бандл.
+    async fn send_plain(
+        &self,
+        client: &RpcClient,
+        tx: &Transaction,
+        bundle_id: Option<String>,
+    ) -> Result<BundleOutcome> {
+        let signature = tx.signatures[0];
+        match client.send_and_confirm_transaction(tx).await {
+            Ok(signature) => Ok(BundleOutcome {
+                signature,
+                bundle_id,
+                via_bundle: false,
+            }),
+            Err(e) => match client.get_signature_status(&signature).await {
+                Ok(Some(Ok(()))) if bundle_id.is_some() => Ok(BundleOutcome {
+                    signature,
+                    bundle_id,
+                    via_bundle: true,
+                }),
+                _ => Err(e.into()),
+            },
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use super::{history::PriceSample, risk::ExitReason, store::PositionStore};
+
+/// Сколько записей ждёт писателя; сверх этого новые выбрасываются
+const JOURNAL_CAPACITY: usize = 4096;
+
+/// Что монитор видел и решил на тике
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// Время тика, unix, мс
+    pub timestamp_ms: u64,
+    pub price: f64,
+    /// Резерв SOL пула, lamports
+    pub sol_reserve: u64,
+    /// Цена к цене входа
+    pub multiple: f64,
+    /// Просадка от пика, %
+    pub drawdown_from_peak_pct: f64,
+    /// Просадка от входа, % (отрицательная — в плюсе)
+    pub drawdown_from_entry_pct: f64,
+    /// Падение резерва от входа, %
+    pub reserve_drop_pct: f64,
+    pub peak_price: f64,
+    pub stop_price: f64,
+    pub breakeven_armed: bool,
+    pub trailing_armed: bool,
+    /// Цена срабатывания trailing stop; `None` — не включён
+    pub trailing_stop_price: Option<f64>,
+    pub remaining: f64,
+    pub paused: bool,
+    /// События решений тика (`RiskEvent` в JSON), по порядку
+    pub actions: Vec<serde_json::Value>,
+}
+
+/// Чем закончилась продажа
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ActionOutcome {
+    Sold {
+        signature: String,
+        sol_received: u64,
+        fee_lamports: u64,
+        /// Проданная доля продажи (меньше 1 — часть траншей отменена)
+        filled: f64,
+        /// Реализованное проскальзывание, б.п.; `None` — выручка по ожиданию
+        #[serde(default)]
+        realized_slippage_bps: Option<i32>,
+        simulated: bool,
+    },
+    /// На паузе: доля вернулась в позицию
+    Paused,
+    /// Выручка не окупала комиссии: остаток списан
+    Dust,
+    /// Токенов на кошельке не было
+    NoTokens,
+    Failed {
+        error: String,
+    },
+}
+
+/// Исполненное (или неудавшееся) действие
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub timestamp_ms: u64,
+    pub reason: ExitReason,
+    /// Доля исходной позиции
+    pub fraction: f64,
+    #[serde(flatten)]
+    pub outcome: ActionOutcome,
+}
+
+/// Строка журнала позиции
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Tick(TickRecord),
+    Action(ActionRecord),
+}
+
+impl JournalEntry {
+    pub fn timestamp_ms(&self) -> u64 {
+        match self {
+            Self::Tick(tick) => tick.timestamp_ms,
+            Self::Action(action) => action.timestamp_ms,
+        }
+    }
+}
+
+/// Куда пишется журнал
+#[derive(Debug, Clone)]
+pub enum JournalSink {
+    /// Файл `<каталог>/<mint>.jsonl` на позицию, по строке JSON на запись
+    Dir(PathBuf),
+    /// Общая таблица `journal` в базе позиций
+    Store(Arc<PositionStore>),
+}
+
+/// Журнал решений мониторов. Запись не блокирует: строки уходят в очередь,
+/// пишет их фоновый поток; при переполнении очереди запись теряется, а не тормозит тик.
+#[derive(Debug, Clone)]
+pub struct Journal {
+    tx: mpsc::Sender<(Pubkey, JournalEntry)>,
+}
+
+impl Journal {
+    /// Запускает писателя; он работает, пока живы копии `Journal`, затем дописывает очередь
+    pub fn spawn(sink: JournalSink) -> Result<(Self, JoinHandle<()>)> {
+        if let JournalSink::Dir(dir) = &sink {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("не удалось создать {}", dir.display()))?;
+        }
+        let (tx, rx) = mpsc::channel(JOURNAL_CAPACITY);
+        let handle = tokio::task::spawn_blocking(move || write_all(sink, rx));
+        Ok((Self { tx }, handle))
+    }
+
+    /// Ставит запись в очередь; не ждёт и не падает
+    pub fn record(&self, mint: Pubkey, entry: JournalEntry) {
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.tx.try_send((mint, entry)) {
+            log::warn!("Очередь журнала переполнена, запись по {} пропущена", mint);
+        }
+    }
+}
+
+fn write_all(sink: JournalSink, mut rx: mpsc::Receiver<(Pubkey, JournalEntry)>) {
+    let mut files: HashMap<Pubkey, BufWriter<File>> = HashMap::new();
+    while let Some(first) = rx.blocking_recv() {
+        // Всё, что накопилось, — одной пачкой, затем сброс на диск
+        let mut batch = vec![first];
+        while let Ok(next) = rx.try_recv() {
+            batch.push(next);
+        }
+        for (mint, entry) in batch {
+            let result = match &sink {
+                JournalSink::Dir(dir) => append_line(&mut files, dir, mint, &entry),
+                JournalSink::Store(store) => store.append_journal(&mint.to_string(), &entry),
+            };
+            if let Err(e) = result {
+                log::warn!("Журнал по {} не записан: {}", mint, e);
+            }
+        }
+        for file in files.values_mut() {
+            if let Err(e) = file.flush() {
+                log::warn!("Журнал не сброшен на диск: {}", e);
+            }
+        }
+    }
+}
+
+fn append_line(
+    files: &mut HashMap<Pubkey, BufWriter<File>>,
+    dir: &Path,
+    mint: Pubkey,
+    entry: &JournalEntry,
+) -> Result<()> {
+    let file = match files.entry(mint) {
+        std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+        std::collections::hash_map::Entry::Vacant(e) => {
+            let path = dir.join(format!("{}.jsonl", mint));
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("не удалось открыть {}", path.display()))?;
+            e.insert(BufWriter::new(file))
+        }
+    };
+    serde_json::to_writer(&mut *file, entry)?;
+    file.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Записи журнала из JSONL; пустые строки пропускаются
+pub fn parse(text: &str) -> Result<Vec<JournalEntry>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).with_context(|| format!("строка {} журнала", i + 1))
+        })
+        .collect()
+}
+
+/// Журнал позиции из файла `.jsonl`
+pub fn read_file(path: &Path) -> Result<Vec<JournalEntry>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("не удалось прочитать {}", path.display()))?;
+    parse(&text)
+}
+
+/// Точки цены тиков журнала — серия для `ReplayFeed`
+pub fn samples(entries: &[JournalEntry]) -> Vec<PriceSample> {
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            JournalEntry::Tick(tick) => Some(PriceSample {
+                timestamp_ms: tick.timestamp_ms,
+                price: tick.price,
+                sol_reserve: tick.sol_reserve,
+            }),
+            JournalEntry::Action(_) => None,
+        })
+        .collect()
+}
+
+/// Серия тиков журнала в CSV `timestamp_ms,price,sol_reserve` (формат `ReplayFeed::from_csv`)
+pub fn to_replay_csv(entries: &[JournalEntry]) -> String {
+    let mut csv = String::from("timestamp_ms,price,sol_reserve\n");
+    for sample in samples(entries) {
+        csv.push_str(&format!(
+            "{},{:e},{}\n",
+            sample.timestamp_ms, sample.price, sample.sol_reserve
+        ));
+    }
+    csv
+}
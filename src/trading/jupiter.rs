@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use std::{fmt, time::Duration};
+
+use super::simulate;
+
+/// Публичный API Jupiter v6
+pub const JUPITER_API_URL: &str = "https://quote-api.jup.ag/v6";
+
+/// Коды ответа Jupiter, означающие отсутствие маршрута
+const NO_ROUTE_CODES: [&str; 2] = ["COULD_NOT_FIND_ANY_ROUTE", "NO_ROUTES_FOUND"];
+
+/// Отказ Jupiter; приходит внутри `anyhow::Error`, см. `is_no_route`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JupiterError {
+    /// Маршрута пока нет (пул ещё не проиндексирован) — можно повторить позже
+    NoRoute,
+    /// Прочие ошибки API: код и сообщение из ответа
+    Api { status: u16, message: String },
+}
+
+impl fmt::Display for JupiterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRoute => write!(f, "Jupiter не нашёл маршрут"),
+            Self::Api { status, message } => write!(f, "Jupiter: HTTP {}: {}", status, message),
+        }
+    }
+}
+
+impl std::error::Error for JupiterError {}
+
+/// Ошибка означает отсутствие маршрута в Jupiter
+pub fn is_no_route(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<JupiterError>() == Some(&JupiterError::NoRoute)
+}
+
+/// Котировка обмена; исходный JSON нужен для запроса `/swap`
+#[derive(Debug, Clone)]
+pub struct JupiterQuote {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// Минимальный выход с учётом проскальзывания
+    pub other_amount_threshold: u64,
+    pub price_impact_pct: f64,
+    raw: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapResponse {
+    swap_transaction: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct JupiterClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for JupiterClient {
+    fn default() -> Self {
+        Self::new(JUPITER_API_URL)
+    }
+}
+
+impl JupiterClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("Failed to build HTTP client"),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Котировка обмена `amount` (сырые единицы) `input_mint` → `output_mint`
+    pub async fn quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<JupiterQuote> {
+        let response = self
+            .http
+            .get(format!("{}/quote", self.base_url))
+            .query(&[
+                ("inputMint", input_mint.to_string()),
+                ("outputMint", output_mint.to_string()),
+                ("amount", amount.to_string()),
+                ("slippageBps", slippage_bps.to_string()),
+            ])
+            .send()
+            .await?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await?;
+
+        let code = body["errorCode"].as_str().unwrap_or_default();
+        if NO_ROUTE_CODES.contains(&code) {
+            return Err(JupiterError::NoRoute.into());
+        }
+        if !status.is_success() || body.get("error").is_some() {
+            return Err(JupiterError::Api {
+                status: status.as_u16(),
+                message: body["error"].as_str().unwrap_or_default().to_string(),
+            }
+            .into());
+        }
+
+        let amount_at = |key: &str| -> Result<u64> {
+            Ok(body[key]
+                .as_str()
+                .with_context(|| format!("в котировке Jupiter нет {}", key))?
+                .parse()?)
+        };
+        Ok(JupiterQuote {
+            in_amount: amount_at("inAmount")?,
+            out_amount: amount_at("outAmount")?,
+            other_amount_threshold: amount_at("otherAmountThreshold")?,
+            price_impact_pct: body["priceImpactPct"]
+                .as_str()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0.0),
+            raw: body,
+        })
+    }
+
+    /// Собирает транзакцию обмена по котировке, подписывает и отправляет.
+    /// `priority_fee_lamports` — приоритетная комиссия (0 — без неё).
+    /// В dry-run транзакция только симулируется; `simulate` — симуляция перед отправкой.
+    pub async fn swap(
+        &self,
+        client: &RpcClient,
+        quote: &JupiterQuote,
+        wallet: &Keypair,
+        priority_fee_lamports: u64,
+        dry_run: bool,
+        simulate: bool,
+    ) -> Result<Signature> {
+        let mut request = serde_json::json!({
+            "quoteResponse": quote.raw,
+            "userPublicKey": wallet.pubkey().to_string(),
+            "wrapAndUnwrapSol": true,
+            "dynamicComputeUnitLimit": true,
+        });
+        if priority_fee_lamports > 0 {
+            request["prioritizationFeeLamports"] = priority_fee_lamports.into();
+        }
+        let response: SwapResponse = self
+            .http
+            .post(format!("{}/swap", self.base_url))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let bytes = STANDARD.decode(response.swap_transaction)?;
+        let unsigned: VersionedTransaction = bincode::deserialize(&bytes)?;
+        let tx = VersionedTransaction::try_new(unsigned.message, &[wallet])?;
+
+        if dry_run {
+            simulate::simulate_and_classify(client, &tx).await?;
+            return Ok(tx.signatures[0]);
+        }
+        if simulate {
+            simulate::presimulate(client, &tx).await?;
+        }
+        Ok(client.send_and_confirm_transaction(&tx).await?)
+    }
+}
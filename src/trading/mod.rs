@@ -1,2 +1,59 @@
+pub mod breaker;
+pub mod clock;
+pub mod cooldown;
+pub mod curve;
+pub mod dexscreener;
+pub mod errors;
+pub mod executor;
+pub mod feed;
+pub mod fees;
+pub mod history;
+pub mod jito;
+pub mod journal;
+pub mod jupiter;
+pub mod pool;
+pub mod positions;
 pub mod pump_arb;
-pub use pump_arb::PumpArbTrader;
\ No newline at end of file
+pub mod pump_buy;
+pub mod pump_sell;
+pub mod risk;
+pub mod rpc_pool;
+pub mod simulate;
+pub mod slippage;
+pub mod snipe;
+pub mod store;
+pub mod volume;
+pub mod wallets;
+
+pub use breaker::{BreakerState, CircuitBreaker, TripReason};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use cooldown::{BlockReason, Cooldown, CooldownKind, CooldownRegistry};
+pub use curve::{BondingCurve, PoolSnapshot};
+pub use dexscreener::{DexScreenerClient, TokenProfile};
+pub use errors::{is_transient, ErrorStreak};
+pub use executor::{
+    CurveExecutor, DryRunExecutor, ExitExecutor, ExitStyle, FallbackExecutor, JupiterExecutor,
+    SellSettings,
+};
+pub use feed::PriceFeed;
+pub use fees::{PriorityFee, Urgency};
+pub use history::{PriceHistory, PriceSample, ReserveDrain, RollingReturns};
+pub use jito::{BundleOutcome, BundleStatus, JitoClient};
+pub use journal::{Journal, JournalEntry, JournalSink};
+pub use jupiter::{JupiterClient, JupiterError, JupiterQuote};
+pub use pool::{PriceSource, RaydiumPool};
+pub use positions::{EntryBlock, PositionLimits, PositionManager};
+pub use pump_arb::PumpArbTrader;
+pub use pump_buy::{BuyOptions, BuyReceipt, CurveBuyer, EntryExecutor, PaperBuyer};
+pub use pump_sell::{SellReceipt, SellRoute, TokenAmount};
+pub use risk::{
+    ExecutionMode, ExitReason, ExitSummary, MonitorHandle, PositionEvent, PositionStatus,
+    RiskAction, RiskConfig, RiskEvent, RiskMonitor, RiskState, Sale, WhaleReaction,
+};
+pub use rpc_pool::{EndpointHealth, RpcPool};
+pub use simulate::{simulate_and_classify, ExitSimError};
+pub use slippage::SlippageConfig;
+pub use snipe::{SkipReason, SnipeDecision, SnipeEngine};
+pub use store::{DustAccount, PersistedPosition, PositionStore};
+pub use volume::VolumeTracker;
+pub use wallets::{SplitMode, WalletManager, WalletSplitConfig};
@@ -0,0 +1,426 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+
+use super::curve::PoolSnapshot;
+use crate::scanner::raydium::{RAYDIUM_AMM_PROGRAM, RAYDIUM_AMM_PROGRAM_ID, WSOL_MINT};
+
+/// Размер аккаунта пула Raydium AMM v4 (`AmmInfo`)
+const AMM_ACCOUNT_LEN: usize = 752;
+
+/// Смещения полей в `AmmInfo`
+const COIN_DECIMALS_OFFSET: usize = 32;
+const PC_DECIMALS_OFFSET: usize = 40;
+const NEED_TAKE_PNL_COIN_OFFSET: usize = 192;
+const NEED_TAKE_PNL_PC_OFFSET: usize = 200;
+const COIN_VAULT_OFFSET: usize = 336;
+const PC_VAULT_OFFSET: usize = 368;
+const COIN_MINT_OFFSET: usize = 400;
+const PC_MINT_OFFSET: usize = 432;
+
+/// Смещение `amount` в SPL token account
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+
+/// Откуда монитор берёт цену позиции
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum PriceSource {
+    /// Аккаунт bonding curve pump.fun
+    #[default]
+    BondingCurve,
+    /// Балансы хранилищ пула Raydium AMM после миграции
+    Raydium,
+}
+
+/// Пул Raydium AMM v4 с токеном против WSOL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaydiumPool {
+    pub address: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_decimals: u8,
+    pub pc_decimals: u8,
+}
+
+/// Комиссия пула, ещё не выведенная из хранилищ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PendingPnl {
+    coin: u64,
+    pc: u64,
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn pubkey_at(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+impl RaydiumPool {
+    /// Разбор данных аккаунта `AmmInfo`
+    pub fn decode(address: Pubkey, data: &[u8]) -> Result<Self> {
+        anyhow::ensure!(
+            data.len() >= AMM_ACCOUNT_LEN,
+            "аккаунт {} не похож на пул Raydium AMM",
+            address
+        );
+        Ok(Self {
+            address,
+            coin_vault: pubkey_at(data, COIN_VAULT_OFFSET),
+            pc_vault: pubkey_at(data, PC_VAULT_OFFSET),
+            coin_mint: pubkey_at(data, COIN_MINT_OFFSET),
+            pc_mint: pubkey_at(data, PC_MINT_OFFSET),
+            coin_decimals: u64_at(data, COIN_DECIMALS_OFFSET) as u8,
+            pc_decimals: u64_at(data, PC_DECIMALS_OFFSET) as u8,
+        })
+    }
+
+    fn pending_pnl(data: &[u8]) -> PendingPnl {
+        PendingPnl {
+            coin: u64_at(data, NEED_TAKE_PNL_COIN_OFFSET),
+            pc: u64_at(data, NEED_TAKE_PNL_PC_OFFSET),
+        }
+    }
+
+    /// Снимок по данным пула и балансам хранилищ (сырые единицы).
+    /// SOL может быть как coin, так и pc — цена всегда в SOL за токен.
+    fn snapshot(
+        &self,
+        pnl: PendingPnl,
+        coin_amount: u64,
+        pc_amount: u64,
+        slot: u64,
+    ) -> PoolSnapshot {
+        let coin = coin_amount.saturating_sub(pnl.coin);
+        let pc = pc_amount.saturating_sub(pnl.pc);
+        let (sol, sol_decimals, token, token_decimals) = if self.pc_mint == WSOL_MINT {
+            (pc, self.pc_decimals, coin, self.coin_decimals)
+        } else {
+            (coin, self.coin_decimals, pc, self.pc_decimals)
+        };
+        let price = if token == 0 {
+            0.0
+        } else {
+            (sol as f64 / 10f64.powi(sol_decimals as i32))
+                / (token as f64 / 10f64.powi(token_decimals as i32))
+        };
+        PoolSnapshot {
+            price,
+            sol_reserve: sol,
+            token_reserve: token,
+            slot,
+        }
+    }
+
+    /// Снимок из аккаунтов пула и хранилищ (`PoolSnapshot::slot` — переданный слот)
+    pub fn snapshot_from_accounts(
+        &self,
+        pool_data: &[u8],
+        coin_vault_data: &[u8],
+        pc_vault_data: &[u8],
+        slot: u64,
+    ) -> Result<PoolSnapshot> {
+        anyhow::ensure!(
+            pool_data.len() >= AMM_ACCOUNT_LEN,
+            "аккаунт пула {} повреждён",
+            self.address
+        );
+        let amount = |data: &[u8]| -> Result<u64> {
+            anyhow::ensure!(
+                data.len() >= TOKEN_AMOUNT_OFFSET + 8,
+                "хранилище пула {} не является token account",
+                self.address
+            );
+            Ok(u64_at(data, TOKEN_AMOUNT_OFFSET))
+        };
+        Ok(self.snapshot(
+            Self::pending_pnl(pool_data),
+            amount(coin_vault_data)?,
+            amount(pc_vault_data)?,
+            slot,
+        ))
+    }
+
+    /// Текущая цена и ликвидность: пул и оба хранилища одним запросом
+    pub async fn fetch_snapshot(&self, client: &RpcClient) -> Result<PoolSnapshot> {
+        let response = client
+            .get_multiple_accounts_with_commitment(
+                &[self.address, self.coin_vault, self.pc_vault],
+                CommitmentConfig::confirmed(),
+            )
+            .await?;
+        let [pool, coin_vault, pc_vault] = response.value.as_slice() else {
+            anyhow::bail!("RPC вернул неполный ответ по пулу {}", self.address);
+        };
+        let data = |acc: &Option<solana_sdk::account::Account>| -> Result<Vec<u8>> {
+            Ok(acc
+                .as_ref()
+                .with_context(|| format!("аккаунт пула {} не найден", self.address))?
+                .data
+                .clone())
+        };
+        self.snapshot_from_accounts(
+            &data(pool)?,
+            &data(coin_vault)?,
+            &data(pc_vault)?,
+            response.context.slot,
+        )
+    }
+}
+
+/// Ищет пул Raydium AMM v4 для mint-а в паре с WSOL
+pub async fn find_raydium_pool(client: &RpcClient, mint: &Pubkey) -> Result<RaydiumPool> {
+    // mint может быть как coin, так и pc — пробуем оба варианта
+    for (mint_offset, quote_offset) in [
+        (COIN_MINT_OFFSET, PC_MINT_OFFSET),
+        (PC_MINT_OFFSET, COIN_MINT_OFFSET),
+    ] {
+        let accounts: serde_json::Value = client
+            .send(
+                RpcRequest::GetProgramAccounts,
+                serde_json::json!([RAYDIUM_AMM_PROGRAM_ID, {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "dataSlice": { "offset": 0, "length": 0 },
+                    "filters": [
+                        { "dataSize": AMM_ACCOUNT_LEN },
+                        { "memcmp": { "offset": mint_offset, "bytes": mint.to_string() } },
+                        { "memcmp": { "offset": quote_offset, "bytes": WSOL_MINT.to_string() } },
+                    ],
+                }]),
+            )
+            .await?;
+        let Some(address) = accounts
+            .as_array()
+            .and_then(|a| a.first())
+            .and_then(|a| a["pubkey"].as_str())
+        else {
+            continue;
+        };
+        let address = Pubkey::from_str(address)?;
+        let account = client.get_account(&address).await?;
+        anyhow::ensure!(
+            account.owner == RAYDIUM_AMM_PROGRAM,
+            "{} не принадлежит Raydium AMM",
+            address
+        );
+        return RaydiumPool::decode(address, &account.data);
+    }
+    anyhow::bail!("пул Raydium для {} не найден", mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::{json, Value};
+    use solana_client::{
+        client_error::{ClientErrorKind, Result as ClientResult},
+        rpc_client::RpcClientConfig,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+
+    use crate::trading::curve::{fetch_curve, BondingCurve, BONDING_CURVE_DISCRIMINATOR};
+
+    /// Пул мигрировавшего токена: токен — coin (6 знаков), WSOL — pc (9 знаков)
+    fn pool() -> RaydiumPool {
+        RaydiumPool {
+            address: Pubkey::new_unique(),
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: WSOL_MINT,
+            coin_decimals: 6,
+            pc_decimals: 9,
+        }
+    }
+
+    /// Данные `AmmInfo` с невыведенной комиссией пула
+    fn amm_account(pool: &RaydiumPool, pnl_coin: u64, pnl_pc: u64) -> Vec<u8> {
+        let mut data = vec![0; AMM_ACCOUNT_LEN];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(
+            COIN_DECIMALS_OFFSET,
+            &(pool.coin_decimals as u64).to_le_bytes(),
+        );
+        put(PC_DECIMALS_OFFSET, &(pool.pc_decimals as u64).to_le_bytes());
+        put(NEED_TAKE_PNL_COIN_OFFSET, &pnl_coin.to_le_bytes());
+        put(NEED_TAKE_PNL_PC_OFFSET, &pnl_pc.to_le_bytes());
+        put(COIN_VAULT_OFFSET, pool.coin_vault.as_ref());
+        put(PC_VAULT_OFFSET, pool.pc_vault.as_ref());
+        put(COIN_MINT_OFFSET, pool.coin_mint.as_ref());
+        put(PC_MINT_OFFSET, pool.pc_mint.as_ref());
+        data
+    }
+
+    /// SPL token account с балансом `amount`
+    fn vault(amount: u64) -> Vec<u8> {
+        let mut data = vec![0; 165];
+        data[TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    fn curve_account(curve: &BondingCurve) -> Vec<u8> {
+        let mut data = BONDING_CURVE_DISCRIMINATOR.to_vec();
+        for v in [
+            curve.virtual_token_reserves,
+            curve.virtual_sol_reserves,
+            curve.real_token_reserves,
+            curve.real_sol_reserves,
+            curve.token_total_supply,
+        ] {
+            data.extend(v.to_le_bytes());
+        }
+        data.push(curve.complete as u8);
+        data
+    }
+
+    #[test]
+    fn decode_amm_account() {
+        let pool = pool();
+        let data = amm_account(&pool, 0, 0);
+        assert_eq!(RaydiumPool::decode(pool.address, &data).unwrap(), pool);
+        assert!(RaydiumPool::decode(pool.address, &data[..AMM_ACCOUNT_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn price_through_both_phases() {
+        // На кривой: 85 SOL виртуального резерва на 280 млн токенов
+        let mut curve = BondingCurve {
+            virtual_token_reserves: 280_000_000_000_000,
+            virtual_sol_reserves: 85_000_000_000,
+            real_token_reserves: 200_000_000_000_000,
+            real_sol_reserves: 55_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: None,
+        };
+        let decoded = BondingCurve::decode(&curve_account(&curve)).unwrap();
+        assert!(!decoded.complete);
+        let on_curve = PoolSnapshot::from_curve(&decoded, 10);
+        assert!((on_curve.price - 85.0 / 280_000_000.0).abs() < 1e-15);
+        assert_eq!(on_curve.sol_reserve, 55_000_000_000);
+
+        // Кривая завершена — её резервы больше не цена
+        curve.complete = true;
+        assert!(
+            BondingCurve::decode(&curve_account(&curve))
+                .unwrap()
+                .complete
+        );
+
+        // В пуле 79 SOL на 206.9 млн токенов за вычетом комиссии пула (1 SOL и 900 токенов)
+        let pool = pool();
+        let snapshot = pool
+            .snapshot_from_accounts(
+                &amm_account(&pool, 900_000_000, 1_000_000_000),
+                &vault(206_900_900_000_000),
+                &vault(80_000_000_000),
+                11,
+            )
+            .unwrap();
+        assert_eq!(snapshot.sol_reserve, 79_000_000_000);
+        assert_eq!(snapshot.token_reserve, 206_900_000_000_000);
+        assert!((snapshot.price - 79.0 / 206_900_000.0).abs() < 1e-15);
+        assert_eq!(snapshot.slot, 11);
+
+        // WSOL может быть и coin-стороной
+        let flipped = RaydiumPool {
+            coin_mint: WSOL_MINT,
+            pc_mint: pool.coin_mint,
+            coin_decimals: 9,
+            pc_decimals: 6,
+            ..pool
+        };
+        let same = flipped
+            .snapshot_from_accounts(
+                &amm_account(&flipped, 1_000_000_000, 900_000_000),
+                &vault(80_000_000_000),
+                &vault(206_900_900_000_000),
+                11,
+            )
+            .unwrap();
+        assert_eq!(same, snapshot);
+
+        assert!(pool
+            .snapshot_from_accounts(&amm_account(&pool, 0, 0), &vault(1)[..70], &vault(1), 0)
+            .is_err());
+    }
+
+    /// RPC с данными аккаунтов по адресу; неизвестные — как отсутствующие
+    struct Accounts(Vec<(Pubkey, Vec<u8>)>);
+
+    impl Accounts {
+        fn account(&self, address: &Value) -> Value {
+            self.0
+                .iter()
+                .find(|(key, _)| address == key.to_string().as_str())
+                .map_or(Value::Null, |(_, data)| {
+                    json!({
+                        "data": [STANDARD.encode(data), "base64"],
+                        "executable": false,
+                        "lamports": 2_039_280,
+                        "owner": RAYDIUM_AMM_PROGRAM.to_string(),
+                        "rentEpoch": 0,
+                        "space": data.len(),
+                    })
+                })
+        }
+    }
+
+    #[async_trait]
+    impl RpcSender for Accounts {
+        async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+            let value = match request {
+                RpcRequest::GetAccountInfo => self.account(&params[0]),
+                RpcRequest::GetMultipleAccounts => params[0]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|address| self.account(address))
+                    .collect(),
+                _ => return Err(ClientErrorKind::Custom(request.to_string()).into()),
+            };
+            Ok(json!({ "context": { "slot": 7 }, "value": value }))
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "accounts".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_snapshot_and_missing_accounts() {
+        let pool = pool();
+        let accounts = vec![
+            (pool.address, amm_account(&pool, 0, 0)),
+            (pool.coin_vault, vault(200_000_000_000_000)),
+            (pool.pc_vault, vault(80_000_000_000)),
+        ];
+        let client = RpcClient::new_sender(Accounts(accounts.clone()), RpcClientConfig::default());
+        let snapshot = pool.fetch_snapshot(&client).await.unwrap();
+        assert_eq!((snapshot.sol_reserve, snapshot.slot), (80_000_000_000, 7));
+
+        // Хранилище закрыто — ошибка, а не нулевая цена
+        let client =
+            RpcClient::new_sender(Accounts(accounts[..2].to_vec()), RpcClientConfig::default());
+        let err = pool.fetch_snapshot(&client).await.unwrap_err();
+        assert!(err.to_string().contains("не найден"), "{}", err);
+
+        // Нет и bonding curve
+        let err = fetch_curve(&client, &Pubkey::new_unique())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("не найдена"), "{}", err);
+    }
+}
@@ -0,0 +1,677 @@
+use anyhow::Result;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+use super::{
+    breaker::{BreakerState, CircuitBreaker, TripReason},
+    cooldown::{BlockReason, CooldownRegistry},
+    pump_arb::PumpArbTrader,
+    pump_buy::BuyReceipt,
+    risk::{ExitSummary, MonitorHandle, PositionEvent, PositionStatus, RiskConfig, RiskEvent},
+};
+use crate::scanner::{pump_fun::unix_now, PumpToken};
+
+/// Ограничения на одновременно открытые позиции
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PositionLimits {
+    pub max_open_positions: usize,
+    /// Сумма ставок открытых позиций, SOL
+    pub max_total_exposure_sol: f64,
+    /// Пауза перед повторным входом в mint (и в токены его создателя)
+    /// после rug-pull и похожих выходов, сек
+    pub rug_cooldown_secs: u64,
+    /// Пауза перед повторным входом в mint после стопа (trailing, panic-sell), сек
+    pub stop_cooldown_secs: u64,
+    /// Убыток закрытых позиций за день UTC, после которого торговля останавливается,
+    /// SOL (0 — без лимита)
+    pub max_daily_loss_sol: f64,
+    /// Убыточных позиций подряд до остановки торговли (0 — без лимита)
+    pub max_consecutive_losses: u32,
+}
+
+impl Default for PositionLimits {
+    fn default() -> Self {
+        Self {
+            max_open_positions: 5,
+            max_total_exposure_sol: 5.0,
+            rug_cooldown_secs: 24 * 60 * 60,
+            stop_cooldown_secs: 15 * 60,
+            max_daily_loss_sol: 0.0,
+            max_consecutive_losses: 0,
+        }
+    }
+}
+
+/// Почему новую позицию открыть нельзя (`PositionManager::check_entry`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryBlock {
+    /// Предохранитель остановил торговлю
+    Tripped(TripReason),
+    AlreadyOpen,
+    /// Пауза после недавнего выхода по стопу или rug-pull
+    Cooldown(BlockReason),
+    MaxOpen {
+        limit: usize,
+    },
+    /// Вложения с новой позицией превысили бы лимит, SOL
+    Exposure {
+        total_sol: f64,
+        limit_sol: f64,
+    },
+}
+
+impl fmt::Display for EntryBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tripped(reason) => write!(f, "торговля остановлена: {}", reason),
+            Self::AlreadyOpen => f.write_str("позиция уже открыта"),
+            Self::Cooldown(block) => write!(f, "вход запрещён: {}", block),
+            Self::MaxOpen { limit } => write!(f, "лимит открытых позиций: {}", limit),
+            Self::Exposure {
+                total_sol,
+                limit_sol,
+            } => write!(
+                f,
+                "лимит вложений: {:.3} SOL > {:.3} SOL",
+                total_sol, limit_sol
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EntryBlock {}
+
+/// Логическая позиция: по суб-позиции (свой `RiskMonitor`) на каждый кошелёк покупки.
+/// Мониторы видят одну цену, поэтому выходы срабатывают на всех кошельках.
+#[derive(Debug)]
+struct Position {
+    legs: Vec<MonitorHandle>,
+}
+
+impl Position {
+    fn stake_sol(&self) -> f64 {
+        self.legs.iter().map(|leg| leg.monitor().stake_sol()).sum()
+    }
+
+    /// Работает хотя бы один монитор
+    fn is_running(&self) -> bool {
+        self.legs.iter().any(|leg| leg.is_running())
+    }
+
+    fn has_wallet(&self, wallet: &Pubkey) -> bool {
+        self.legs
+            .iter()
+            .any(|leg| leg.monitor().wallet() == *wallet)
+    }
+
+    fn status(&self) -> PositionStatus {
+        merge_statuses(self.legs.iter().map(|leg| leg.status()).collect())
+    }
+}
+
+/// Доли суб-позиций по ставкам; нулевые ставки — поровну
+fn stake_weights(stakes: &[f64]) -> Vec<f64> {
+    let total: f64 = stakes.iter().sum();
+    if total > 0.0 {
+        stakes.iter().map(|s| s / total).collect()
+    } else {
+        vec![1.0 / stakes.len() as f64; stakes.len()]
+    }
+}
+
+/// Состояние логической позиции: суммы складываются, цены и доли — среднее по ставкам
+fn merge_statuses(mut legs: Vec<PositionStatus>) -> PositionStatus {
+    if legs.len() == 1 {
+        return legs.remove(0);
+    }
+    let weights = stake_weights(&legs.iter().map(|s| s.stake_sol).collect::<Vec<_>>());
+    let weighted = |f: fn(&PositionStatus) -> f64| -> f64 {
+        legs.iter().zip(&weights).map(|(s, w)| f(s) * w).sum()
+    };
+    let entry_price = weighted(|s| s.entry_price);
+    PositionStatus {
+        stake_sol: legs.iter().map(|s| s.stake_sol).sum(),
+        entry_price,
+        multiple: if entry_price > 0.0 {
+            legs[0].last_price / entry_price
+        } else {
+            legs[0].multiple
+        },
+        peak_price: legs.iter().map(|s| s.peak_price).fold(0.0, f64::max),
+        stop_price: weighted(|s| s.stop_price),
+        remaining: weighted(|s| s.remaining),
+        moon_remaining: weighted(|s| s.moon_remaining),
+        sol_recovered: legs.iter().map(|s| s.sol_recovered).sum(),
+        unrealized_pnl_sol: legs.iter().map(|s| s.unrealized_pnl_sol).sum(),
+        stake_usd: legs.iter().map(|s| s.stake_usd).sum(),
+        unrealized_pnl_usd: legs.iter().map(|s| s.unrealized_pnl_usd).sum(),
+        elapsed_secs: legs
+            .iter()
+            .map(|s| s.elapsed_secs)
+            .max()
+            .unwrap_or_default(),
+        paused: legs.iter().any(|s| s.paused),
+        running: legs.iter().any(|s| s.running),
+        ..legs[0].clone()
+    }
+}
+
+/// Итог логической позиции по итогам суб-позиций и их ставкам: причина выхода —
+/// крупнейшей доли, суммы складываются, цена входа и PnL в % — среднее по ставкам
+fn merge_summaries(mut legs: Vec<(f64, ExitSummary)>) -> ExitSummary {
+    if legs.len() == 1 {
+        return legs.remove(0).1;
+    }
+    let weights = stake_weights(&legs.iter().map(|(stake, _)| *stake).collect::<Vec<_>>());
+    let largest = legs
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+        .map_or(0, |(i, _)| i);
+    let summaries: Vec<&ExitSummary> = legs.iter().map(|(_, s)| s).collect();
+    ExitSummary {
+        entry_price: summaries
+            .iter()
+            .zip(&weights)
+            .map(|(s, w)| s.entry_price * w)
+            .sum(),
+        sol_recovered: summaries.iter().map(|s| s.sol_recovered).sum(),
+        realized_pnl_sol: summaries.iter().map(|s| s.realized_pnl_sol).sum(),
+        realized_pnl_pct: summaries
+            .iter()
+            .zip(&weights)
+            .map(|(s, w)| s.realized_pnl_pct * w)
+            .sum(),
+        peak_multiple: summaries
+            .iter()
+            .map(|s| s.peak_multiple)
+            .fold(0.0, f64::max),
+        hold_duration: summaries
+            .iter()
+            .map(|s| s.hold_duration)
+            .max()
+            .unwrap_or(Duration::ZERO),
+        entry_cost_usd: summaries.iter().map(|s| s.entry_cost_usd).sum(),
+        sol_recovered_usd: summaries.iter().map(|s| s.sol_recovered_usd).sum(),
+        realized_pnl_usd: summaries.iter().map(|s| s.realized_pnl_usd).sum(),
+        ..summaries[largest].clone()
+    }
+}
+
+/// Реестр открытых позиций: у каждой свой `RiskMonitor` (при покупке с нескольких
+/// кошельков — по монитору на кошелёк под одной позицией).
+/// Завершившиеся мониторы убираются при следующем обращении, их итог идёт в PnL.
+#[derive(Debug)]
+pub struct PositionManager {
+    trader: PumpArbTrader,
+    limits: PositionLimits,
+    positions: HashMap<Pubkey, Position>,
+    closed: Vec<ExitSummary>,
+    /// Запреты повторного входа; сохраняются в хранилище трейдера
+    cooldowns: CooldownRegistry,
+    /// Дневной лимит убытка и серия убытков; сохраняется в хранилище трейдера
+    breaker: CircuitBreaker,
+    events: Option<mpsc::Sender<PositionEvent>>,
+}
+
+impl PositionManager {
+    /// Состояние предохранителя берётся из хранилища трейдера, если оно есть:
+    /// остановленная торговля остаётся остановленной после перезапуска
+    pub fn new(trader: PumpArbTrader, limits: PositionLimits) -> Self {
+        let mut breaker =
+            CircuitBreaker::new(limits.max_daily_loss_sol, limits.max_consecutive_losses);
+        if let Some(store) = trader.position_store() {
+            match store.load_breaker() {
+                Ok(Some(state)) => {
+                    if let Some(reason) = state.tripped {
+                        log::warn!("🛑 Торговля остановлена до перезапуска: {}", reason);
+                    }
+                    breaker = breaker.with_state(state);
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("Состояние предохранителя не загружено: {}", e),
+            }
+        }
+        Self {
+            cooldowns: CooldownRegistry::new(limits.rug_cooldown_secs, limits.stop_cooldown_secs),
+            breaker,
+            trader,
+            limits,
+            positions: HashMap::new(),
+            closed: Vec::new(),
+            events: None,
+        }
+    }
+
+    /// Канал для `RiskEvent::CircuitBreaker` (mint в событии — пустой ключ)
+    pub fn with_events(mut self, events: mpsc::Sender<PositionEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Счётчики предохранителя и причина остановки
+    pub fn breaker_status(&mut self) -> BreakerState {
+        if self.breaker.roll(unix_now()) {
+            self.save_breaker();
+        }
+        self.breaker.state().clone()
+    }
+
+    /// Ручная остановка: закрывает все позиции и не даёт открывать новые
+    /// до `reset_breaker` (смена дня её не снимает)
+    pub async fn kill_switch(&mut self) -> Vec<ExitSummary> {
+        self.trip(TripReason::KillSwitch);
+        self.close_every().await
+    }
+
+    /// Снова разрешает открывать позиции после остановки
+    pub fn reset_breaker(&mut self) {
+        self.breaker.reset();
+        self.save_breaker();
+        log::info!("🟢 Предохранитель сброшен, торговля разрешена");
+    }
+
+    /// Остановка торговли: сохранение и событие; позиции закрывает вызывающий
+    fn trip(&mut self, reason: TripReason) {
+        self.breaker.trip(reason, unix_now());
+        self.save_breaker();
+        log::error!(
+            "🛑 Предохранитель: {} — закрываем всё, новые входы запрещены",
+            reason
+        );
+        if let Some(events) = &self.events {
+            let event = PositionEvent {
+                mint: Pubkey::default(),
+                event: RiskEvent::CircuitBreaker { reason },
+            };
+            if let Err(e) = events.try_send(event) {
+                log::warn!("Событие предохранителя не отправлено: {}", e);
+            }
+        }
+    }
+
+    fn save_breaker(&self) {
+        if let Some(store) = self.trader.position_store() {
+            if let Err(e) = store.save_breaker(self.breaker.state()) {
+                log::warn!("Состояние предохранителя не сохранено: {}", e);
+            }
+        }
+    }
+
+    /// Запрещён ли сейчас вход в `mint` (или в токены `creator`) после недавнего
+    /// выхода по стопу или rug-pull. Путь покупки обязан проверить это до сделки.
+    pub fn is_blocked(&self, mint: &Pubkey, creator: Option<&Pubkey>) -> Option<BlockReason> {
+        self.cooldowns.is_blocked(mint, creator, unix_now())
+    }
+
+    /// Учитывает закрытую позицию: PnL, предохранитель и паузу перед повторным входом.
+    /// `true` — предохранитель сработал на этой позиции (остальные закрывает вызывающий).
+    fn record_exit(&mut self, summary: ExitSummary) -> bool {
+        let tripped = self.breaker.record(summary.realized_pnl_sol, unix_now());
+        match tripped {
+            Some(reason) => self.trip(reason),
+            None => self.save_breaker(),
+        }
+        for cooldown in self.cooldowns.record(&summary, unix_now()) {
+            log::info!(
+                "⛔ Повторный вход запрещён: {}",
+                BlockReason::from(cooldown)
+            );
+            if let Some(store) = self.trader.position_store() {
+                if let Err(e) = store.save_cooldown(&cooldown) {
+                    log::warn!("Запрет входа не сохранён: {}", e);
+                }
+            }
+        }
+        self.closed.push(summary);
+        tripped.is_some()
+    }
+
+    pub fn limits(&self) -> &PositionLimits {
+        &self.limits
+    }
+
+    pub fn trader(&self) -> &PumpArbTrader {
+        &self.trader
+    }
+
+    /// Можно ли открыть позицию `mint` на `stake_sol`: предохранитель, повтор,
+    /// пауза после выхода, число позиций и сумма вложений. Путь покупки
+    /// проверяет это до сделки.
+    pub fn check_entry(
+        &mut self,
+        mint: &Pubkey,
+        creator: Option<&Pubkey>,
+        stake_sol: f64,
+    ) -> Result<(), EntryBlock> {
+        if let Some(reason) = self.breaker_status().tripped {
+            return Err(EntryBlock::Tripped(reason));
+        }
+        if self.positions.contains_key(mint) {
+            return Err(EntryBlock::AlreadyOpen);
+        }
+        if let Some(block) = self.is_blocked(mint, creator) {
+            return Err(EntryBlock::Cooldown(block));
+        }
+        if self.positions.len() >= self.limits.max_open_positions {
+            return Err(EntryBlock::MaxOpen {
+                limit: self.limits.max_open_positions,
+            });
+        }
+        let total_sol = self.exposure_sol() + stake_sol;
+        if total_sol > self.limits.max_total_exposure_sol {
+            return Err(EntryBlock::Exposure {
+                total_sol,
+                limit_sol: self.limits.max_total_exposure_sol,
+            });
+        }
+        Ok(())
+    }
+
+    /// Запускает мониторинг уже купленной позиции, если позволяют лимиты
+    pub async fn open(&mut self, token: &PumpToken, stake_sol: f64) -> Result<()> {
+        self.reap().await;
+        let mint = Pubkey::from_str(&token.mint)?;
+        let creator = Pubkey::from_str(&token.creator_address).ok();
+        self.check_entry(&mint, creator.as_ref(), stake_sol)
+            .map_err(|block| anyhow::anyhow!("{}: {}", mint, block))?;
+
+        let handle = self.trader.start_risk_monitoring(token, stake_sol).await?;
+        self.insert(mint, vec![handle]);
+        Ok(())
+    }
+
+    /// Ставит на мониторинг позицию по фактической покупке (цена входа и ставка —
+    /// из `BuyReceipt`). Лимиты проверяются до покупки (`check_entry`): купленное
+    /// берётся на учёт в любом случае.
+    pub async fn open_bought(&mut self, token: &PumpToken, receipt: &BuyReceipt) -> Result<()> {
+        self.open_split(token, std::slice::from_ref(receipt)).await
+    }
+
+    /// То же для покупки, разделённой между кошельками: по монитору на квитанцию
+    /// под одной позицией. Монитор какой-то доли не запустился — остальные
+    /// всё равно на учёте; ошибка — только если не запустился ни один.
+    pub async fn open_split(&mut self, token: &PumpToken, receipts: &[BuyReceipt]) -> Result<()> {
+        let mint = receipts
+            .first()
+            .map(|r| r.mint)
+            .ok_or_else(|| anyhow::anyhow!("нет квитанций покупки"))?;
+        anyhow::ensure!(
+            !self.positions.contains_key(&mint),
+            "позиция по {} уже открыта",
+            mint
+        );
+        let mut legs = Vec::with_capacity(receipts.len());
+        let mut first_error = None;
+        for receipt in receipts {
+            match self.trader.start_monitoring_bought(token, receipt).await {
+                Ok(handle) => legs.push(handle),
+                Err(e) => {
+                    log::error!(
+                        "Доля {} на {} куплена, но мониторинг не запущен: {}",
+                        mint,
+                        receipt.wallet,
+                        e
+                    );
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if let Some(e) = first_error.filter(|_| legs.is_empty()) {
+            return Err(e);
+        }
+        self.insert(mint, legs);
+        Ok(())
+    }
+
+    fn insert(&mut self, mint: Pubkey, legs: Vec<MonitorHandle>) {
+        let position = Position { legs };
+        log::info!(
+            "📂 Открыта позиция {} на {} SOL ({} из {}){}",
+            mint,
+            position.stake_sol(),
+            self.positions.len() + 1,
+            self.limits.max_open_positions,
+            if position.legs.len() > 1 {
+                format!(", кошельков: {}", position.legs.len())
+            } else {
+                String::new()
+            }
+        );
+        self.positions.insert(mint, position);
+    }
+
+    /// Добавляет восстановленную суб-позицию к позиции её mint-а
+    fn attach(&mut self, mint: Pubkey, handle: MonitorHandle) {
+        self.positions
+            .entry(mint)
+            .or_insert_with(|| Position { legs: Vec::new() })
+            .legs
+            .push(handle);
+    }
+
+    /// Поднимает мониторы по позициям из хранилища трейдера после перезапуска.
+    /// Позиции без токенов на кошельке закрываются с `ExitReason::External`;
+    /// лимиты к восстановленным позициям не применяются.
+    pub async fn restore(&mut self) -> Result<Vec<ExitSummary>> {
+        let store = self
+            .trader
+            .position_store()
+            .ok_or_else(|| anyhow::anyhow!("у трейдера нет хранилища позиций"))?
+            .clone();
+        let now = unix_now();
+        self.cooldowns.prune(now);
+        for cooldown in store.load_cooldowns(now)? {
+            self.cooldowns.insert(cooldown);
+        }
+        let mut closed = Vec::new();
+        let mut tripped = false;
+        for saved in store.load_all()? {
+            let monitor = match self.trader.restore_monitor(&saved) {
+                Ok(monitor) => Arc::new(monitor),
+                Err(e) => {
+                    log::error!("Позиция {} не восстановлена: {}", saved.mint, e);
+                    continue;
+                }
+            };
+            let mint = monitor.mint();
+            if self
+                .positions
+                .get(&mint)
+                .is_some_and(|p| p.has_wallet(&monitor.wallet()))
+            {
+                continue;
+            }
+            match monitor.reconcile_balance().await {
+                Ok(true) => {
+                    self.attach(mint, monitor.start_monitoring());
+                }
+                Ok(false) => {
+                    let summary = monitor.exit_summary();
+                    tripped |= self.record_exit(summary.clone());
+                    closed.push(summary);
+                }
+                // Баланс неизвестен — мониторим дальше, токены могут быть на месте
+                Err(e) => {
+                    log::warn!("Баланс {} не проверен: {}", mint, e);
+                    self.attach(mint, monitor.start_monitoring());
+                }
+            }
+        }
+        log::info!(
+            "♻️ Восстановлено позиций: {}, закрыто вне бота: {}",
+            self.positions.len(),
+            closed.len()
+        );
+        if tripped {
+            self.close_every().await;
+        }
+        Ok(closed)
+    }
+
+    /// Останавливает мониторинг и продаёт остаток позиции; если на ней сработал
+    /// предохранитель — закрываются и остальные
+    pub async fn close(&mut self, mint: &Pubkey) -> Result<ExitSummary> {
+        let (summary, tripped) = self.close_one(mint).await?;
+        if tripped {
+            self.close_every().await;
+        }
+        Ok(summary)
+    }
+
+    /// Останавливает мониторы всех кошельков позиции и продаёт остаток с каждого
+    async fn close_one(&mut self, mint: &Pubkey) -> Result<(ExitSummary, bool)> {
+        let position = self
+            .positions
+            .remove(mint)
+            .ok_or_else(|| anyhow::anyhow!("позиция по {} не открыта", mint))?;
+        for leg in &position.legs {
+            leg.stop();
+        }
+        let mut summaries = Vec::with_capacity(position.legs.len());
+        for leg in position.legs {
+            let monitor = leg.monitor().clone();
+            if let Err(e) = leg.await_exit().await {
+                log::error!("Мониторинг {} ({}) прерван: {}", mint, monitor.wallet(), e);
+            }
+            if !monitor.sell_all().await {
+                log::error!(
+                    "Не удалось продать остаток {} с {}, позиция снята с учёта",
+                    mint,
+                    monitor.wallet()
+                );
+            }
+            summaries.push((monitor.stake_sol(), monitor.finish()));
+        }
+        let summary = merge_summaries(summaries);
+        let tripped = self.record_exit(summary.clone());
+        Ok((summary, tripped))
+    }
+
+    fn position(&self, mint: &Pubkey) -> Result<&Position> {
+        self.positions
+            .get(mint)
+            .ok_or_else(|| anyhow::anyhow!("позиция по {} не открыта", mint))
+    }
+
+    /// Ставит автопродажи позиции на паузу (держать через просадку)
+    pub fn pause(&self, mint: &Pubkey) -> Result<()> {
+        self.position(mint)?.legs.iter().for_each(|leg| leg.pause());
+        Ok(())
+    }
+
+    pub fn resume(&self, mint: &Pubkey) -> Result<()> {
+        self.position(mint)?
+            .legs
+            .iter()
+            .for_each(|leg| leg.resume());
+        Ok(())
+    }
+
+    /// Ручная продажа доли `share` (0–1) остатка без остановки мониторинга,
+    /// со всех кошельков позиции; `true` — продано везде
+    pub async fn sell_now(&self, mint: &Pubkey, share: f64) -> Result<bool> {
+        let mut sold = true;
+        for leg in &self.position(mint)?.legs {
+            sold &= leg.sell_now(share).await;
+        }
+        Ok(sold)
+    }
+
+    /// Новые пороги риска для открытой позиции
+    pub fn set_config(&self, mint: &Pubkey, config: RiskConfig) -> Result<()> {
+        for leg in &self.position(mint)?.legs {
+            leg.set_config(config.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Глобальный выход: закрывает все позиции
+    pub async fn close_all(&mut self) -> Vec<ExitSummary> {
+        self.close_every().await
+    }
+
+    async fn close_every(&mut self) -> Vec<ExitSummary> {
+        let mints: Vec<Pubkey> = self.positions.keys().copied().collect();
+        let mut summaries = Vec::with_capacity(mints.len());
+        for mint in mints {
+            match self.close_one(&mint).await {
+                Ok((summary, _)) => summaries.push(summary),
+                Err(e) => log::error!("Ошибка закрытия {}: {}", mint, e),
+            }
+        }
+        summaries
+    }
+
+    /// Число открытых позиций
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Открытые позиции (включая те, чей мониторинг уже завершился, но не убран);
+    /// позиция с нескольких кошельков — одной строкой
+    pub fn list(&self) -> Vec<PositionStatus> {
+        self.positions.values().map(|p| p.status()).collect()
+    }
+
+    /// Убирает позиции, у которых завершились мониторы всех кошельков; возвращает их итоги
+    pub async fn reap(&mut self) -> Vec<ExitSummary> {
+        let finished: Vec<Pubkey> = self
+            .positions
+            .iter()
+            .filter(|(_, p)| !p.is_running())
+            .map(|(mint, _)| *mint)
+            .collect();
+        let mut summaries = Vec::with_capacity(finished.len());
+        for mint in finished {
+            let Some(position) = self.positions.remove(&mint) else {
+                continue;
+            };
+            let mut legs = Vec::with_capacity(position.legs.len());
+            for leg in position.legs {
+                let monitor = leg.monitor().clone();
+                let summary = leg
+                    .await_exit()
+                    .await
+                    .unwrap_or_else(|_| monitor.exit_summary());
+                legs.push((monitor.stake_sol(), summary));
+            }
+            let summary = merge_summaries(legs);
+            log::info!(
+                "📁 Позиция {} завершена ({:?}), PnL {:+.4} SOL",
+                mint,
+                summary.exit_reason,
+                summary.realized_pnl_sol
+            );
+            let tripped = self.record_exit(summary.clone());
+            summaries.push(summary);
+            if tripped {
+                summaries.extend(self.close_every().await);
+                break;
+            }
+        }
+        summaries
+    }
+
+    /// Сумма ставок открытых позиций, SOL
+    pub fn exposure_sol(&self) -> f64 {
+        self.positions.values().map(|p| p.stake_sol()).sum()
+    }
+
+    /// Итоги закрытых позиций
+    pub fn closed(&self) -> &[ExitSummary] {
+        &self.closed
+    }
+
+    /// Суммарный PnL закрытых позиций, SOL
+    pub fn realized_pnl_sol(&self) -> f64 {
+        self.closed.iter().map(|s| s.realized_pnl_sol).sum()
+    }
+}
@@ -1,12 +1,381 @@
-use crate::trading::risk::RiskMonitor;
-use std::sync::Arc;
-
-async fn start_risk_monitoring(&self, token: &PumpToken, stake_sol: f64) {
-    let monitor = Arc::new(RiskMonitor::new(
-        self.client.clone(),
-        self.wallet.clone(),
-        token,
-        stake_sol,
-    ));
-    monitor.start_monitoring().await;
-}
\ No newline at end of file
+use crate::config::Config;
+use crate::pricing::SolPriceFeed;
+use crate::scanner::PumpToken;
+use crate::trading::{
+    dexscreener::DexScreenerClient,
+    executor::{ExitExecutor, FallbackExecutor, SellSettings},
+    feed::PriceFeed,
+    jito::JitoClient,
+    journal::Journal,
+    pump_buy::BuyReceipt,
+    pump_sell,
+    risk::{ExecutionMode, MonitorHandle, RiskConfig, RiskMonitor},
+    rpc_pool::{EndpointHealth, RpcPool},
+    slippage::SlippageConfig,
+    store::{PersistedPosition, PositionStore},
+    wallets::WalletManager,
+};
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{fmt, str::FromStr, sync::Arc};
+
+pub struct PumpArbTrader {
+    client: Arc<RpcClient>,
+    wallet: Arc<Keypair>,
+    risk: RiskConfig,
+    slippage: SlippageConfig,
+    mode: ExecutionMode,
+    price_feed: PriceFeed,
+    store: Option<Arc<PositionStore>>,
+    dexscreener: Option<Arc<DexScreenerClient>>,
+    jito: Option<Arc<JitoClient>>,
+    executor: Option<Arc<dyn ExitExecutor>>,
+    /// Настройки продаж для позиций дополнительных кошельков (`from_config`)
+    sell_settings: Option<SellSettings>,
+    wallets: Option<Arc<WalletManager>>,
+    rpc: Option<Arc<RpcPool>>,
+    journal: Option<Journal>,
+    sol_price: Option<Arc<SolPriceFeed>>,
+}
+
+impl fmt::Debug for PumpArbTrader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PumpArbTrader")
+            .field("wallet", &self.wallet.pubkey())
+            .finish()
+    }
+}
+
+impl PumpArbTrader {
+    pub fn new(client: Arc<RpcClient>, wallet: Arc<Keypair>) -> Self {
+        Self {
+            client,
+            wallet,
+            risk: RiskConfig::default(),
+            slippage: SlippageConfig::default(),
+            mode: ExecutionMode::Live,
+            price_feed: PriceFeed::Polling,
+            store: None,
+            dexscreener: None,
+            jito: None,
+            executor: None,
+            sell_settings: None,
+            wallets: None,
+            rpc: None,
+            journal: None,
+            sol_price: None,
+        }
+    }
+
+    /// Трейдер по `Config`: пороги выхода, режим исполнения, Jito (если заданы чаевые)
+    /// и исполнитель продаж — в боевом режиме bonding curve с запасным Jupiter,
+    /// при `dry_run` — бумажные продажи монитора
+    pub fn from_config(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        config: &Config,
+    ) -> Result<Self> {
+        config.validate()?;
+        let endpoints = config.rpc_endpoints();
+        let rpc = Arc::new(if endpoints.len() > 1 {
+            RpcPool::new(&endpoints)?
+        } else {
+            RpcPool::single(client.clone())
+        });
+        let mut trader = Self::new(client.clone(), wallet.clone())
+            .with_risk_config(config.risk.clone())
+            .with_slippage(config.slippage.clone())
+            .with_execution_mode(config.execution_mode())
+            .with_rpc_pool(rpc.clone());
+        if let Some(jito) = config.jito_client()? {
+            trader = trader.with_jito(Arc::new(jito));
+        }
+        if trader.mode.is_live() {
+            let settings = SellSettings {
+                config: Arc::new(config.risk.clone()),
+                slippage_bps: config.slippage.sell_bps,
+                emergency_slippage_bps: config.slippage.emergency_sell_bps,
+                jito: trader.jito.clone(),
+                rpc: Some(rpc),
+                dry_run: false,
+            };
+            trader = trader.with_executor(Arc::new(FallbackExecutor::pump_fun(
+                client,
+                wallet,
+                settings.clone(),
+            )));
+            trader.sell_settings = Some(settings);
+        }
+        Ok(trader)
+    }
+
+    /// Пороги выхода для новых позиций
+    pub fn with_risk_config(mut self, risk: RiskConfig) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Проскальзывание продаж новых позиций (покупки — у `EntryExecutor`)
+    pub fn with_slippage(mut self, slippage: SlippageConfig) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// Режим исполнения продаж новых позиций (`Config::dry_run` — `Paper`)
+    pub fn with_execution_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Источник цены для мониторов новых позиций
+    pub fn with_price_feed(mut self, feed: PriceFeed) -> Self {
+        self.price_feed = feed;
+        self
+    }
+
+    /// Хранилище позиций: мониторы пишут туда состояние, после перезапуска
+    /// из него восстанавливаются
+    pub fn with_position_store(mut self, store: Arc<PositionStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Клиент DexScreener, общий для всех мониторов (условие `moon_top_n`)
+    pub fn with_dexscreener(mut self, dexscreener: Arc<DexScreenerClient>) -> Self {
+        self.dexscreener = Some(dexscreener);
+        self
+    }
+
+    /// Jito для продаж (регион — `Config.jito_region`); чаевые задаются в `RiskConfig`
+    pub fn with_jito(mut self, jito: Arc<JitoClient>) -> Self {
+        self.jito = Some(jito);
+        self
+    }
+
+    /// RPC endpoints для мониторов новых позиций (`Config::rpc_endpoints`)
+    pub fn with_rpc_pool(mut self, rpc: Arc<RpcPool>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Задержка и сбои RPC endpoint-ов (пусто — пул не задан)
+    pub fn rpc_health(&self) -> Vec<EndpointHealth> {
+        self.rpc
+            .as_ref()
+            .map(|rpc| rpc.health())
+            .unwrap_or_default()
+    }
+
+    /// Дополнительные кошельки: позиции их долей покупки (`BuyReceipt.wallet`)
+    /// продаются с них же
+    pub fn with_wallets(mut self, wallets: Arc<WalletManager>) -> Self {
+        self.wallets = Some(wallets);
+        self
+    }
+
+    pub fn wallets(&self) -> Option<&Arc<WalletManager>> {
+        self.wallets.as_ref()
+    }
+
+    /// Ключ кошелька `pubkey`: основной или из `WalletManager`
+    fn wallet_for(&self, pubkey: &Pubkey) -> Result<Arc<Keypair>> {
+        if *pubkey == self.wallet.pubkey() {
+            return Ok(self.wallet.clone());
+        }
+        self.wallets
+            .as_ref()
+            .and_then(|wallets| wallets.get(pubkey))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("кошелёк {} не настроен у трейдера", pubkey))
+    }
+
+    /// Исполнитель продаж для новых позиций основного кошелька (по умолчанию —
+    /// встроенный в монитор); дополнительные кошельки продают исполнителем
+    /// из `from_config` или встроенным
+    pub fn with_executor(mut self, executor: Arc<dyn ExitExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Журнал решений для новых и восстановленных позиций
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Цена SOL для PnL позиций в USD
+    pub fn with_sol_price(mut self, feed: Arc<SolPriceFeed>) -> Self {
+        self.sol_price = Some(feed);
+        self
+    }
+
+    pub fn position_store(&self) -> Option<&Arc<PositionStore>> {
+        self.store.as_ref()
+    }
+
+    /// Закрывает токен-аккаунты с пылью из очереди хранилища (`DustSkipped`):
+    /// остаток сжигается, рента возвращается на кошелёк. Возвращает число закрытых;
+    /// неудачные остаются в очереди до следующего раза.
+    pub async fn reclaim_dust(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let mut closed = 0;
+        for dust in store.dust_accounts()? {
+            let Ok(mint) = Pubkey::from_str(&dust.mint) else {
+                log::warn!("Неверный mint в очереди пыли: {}", dust.mint);
+                store.remove_dust(&dust.account)?;
+                continue;
+            };
+            match pump_sell::reclaim_dust(&self.client, &self.wallet, &mint).await {
+                Ok(signature) => {
+                    log::info!(
+                        "🧹 Счёт {} закрыт, рента возвращена{}",
+                        dust.account,
+                        signature.map_or(String::new(), |s| format!(": {}", s))
+                    );
+                    store.remove_dust(&dust.account)?;
+                    closed += 1;
+                }
+                Err(e) => log::warn!("Счёт {} не закрыт: {}", dust.account, e),
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Монитор по сохранённой позиции (не запущенный)
+    pub fn restore_monitor(&self, saved: &PersistedPosition) -> Result<RiskMonitor> {
+        let wallet = match &saved.wallet {
+            Some(wallet) => self.wallet_for(&Pubkey::from_str(wallet)?)?,
+            None => self.wallet.clone(),
+        };
+        let monitor = RiskMonitor::restore(
+            self.client.clone(),
+            wallet.clone(),
+            saved,
+            self.risk.clone(),
+        )?;
+        Ok(self.configure(monitor, &wallet))
+    }
+
+    /// Общие настройки трейдера; позиции дополнительного кошелька пишутся
+    /// в хранилище отдельно и продаются своим исполнителем
+    fn configure(&self, monitor: RiskMonitor, wallet: &Arc<Keypair>) -> RiskMonitor {
+        let sub_wallet = wallet.pubkey() != self.wallet.pubkey();
+        let mut monitor = monitor
+            .with_execution_mode(self.mode)
+            .with_sell_slippage(self.slippage.sell_bps)
+            .with_emergency_sell_slippage(self.slippage.emergency_sell_bps)
+            .with_price_feed(self.price_feed.clone());
+        if let Some(store) = &self.store {
+            monitor = monitor.with_store(store.clone());
+        }
+        if let Some(rpc) = &self.rpc {
+            monitor = monitor.with_rpc_pool(rpc.clone());
+        }
+        if let Some(dexscreener) = &self.dexscreener {
+            monitor = monitor.with_dexscreener(dexscreener.clone());
+        }
+        if let Some(jito) = &self.jito {
+            monitor = monitor.with_jito(jito.clone());
+        }
+        if sub_wallet {
+            monitor = monitor.with_sub_wallet();
+            if let Some(settings) = &self.sell_settings {
+                monitor = monitor.with_executor(Arc::new(FallbackExecutor::pump_fun(
+                    self.client.clone(),
+                    wallet.clone(),
+                    settings.clone(),
+                )));
+            }
+        } else if let Some(executor) = &self.executor {
+            monitor = monitor.with_executor(executor.clone());
+        }
+        if let Some(journal) = &self.journal {
+            monitor = monitor.with_journal(journal.clone());
+        }
+        if let Some(sol_price) = &self.sol_price {
+            monitor = monitor.with_sol_price(sol_price.clone());
+        }
+        monitor
+    }
+
+    /// Кошелёк трейдера
+    pub fn wallet(&self) -> &Arc<Keypair> {
+        &self.wallet
+    }
+
+    /// Баланс кошелька, SOL; с `WalletManager` — сумма по всем кошелькам
+    pub async fn balance_sol(&self) -> Result<f64> {
+        if let Some(wallets) = &self.wallets {
+            wallets.refresh().await?;
+            return Ok(wallets.total_balance_sol());
+        }
+        let lamports = self.client.get_balance(&self.wallet.pubkey()).await?;
+        Ok(lamports as f64 / LAMPORTS_PER_SOL as f64)
+    }
+
+    /// Мониторинг позиции по фактической покупке: резерв — из пула, цена входа,
+    /// ставка и количество — из `receipt`, продажи — с кошелька `receipt.wallet`.
+    /// Пул не прочитался — монитор всё равно запускается (резерв возьмётся
+    /// с первого тика): купленное без надзора не остаётся.
+    pub async fn start_monitoring_bought(
+        &self,
+        token: &PumpToken,
+        receipt: &BuyReceipt,
+    ) -> Result<MonitorHandle> {
+        let wallet = self.wallet_for(&receipt.wallet)?;
+        let stake_sol = receipt.sol_spent_sol();
+        let monitor = match RiskMonitor::init(
+            self.client.clone(),
+            wallet.clone(),
+            token,
+            stake_sol,
+            self.risk.clone(),
+        )
+        .await
+        {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                log::warn!(
+                    "Пул {} при входе не прочитан ({}) — резерв возьмём с первого тика",
+                    receipt.mint,
+                    e
+                );
+                RiskMonitor::new(
+                    self.client.clone(),
+                    wallet.clone(),
+                    token,
+                    stake_sol,
+                    self.risk.clone(),
+                )?
+            }
+        };
+        let monitor = Arc::new(self.configure(monitor.with_buy(receipt), &wallet));
+        Ok(monitor.start_monitoring())
+    }
+
+    /// Запускает мониторинг рисков по открытой позиции; хэндл нужно держать,
+    /// чтобы остановить мониторинг и получить итог позиции
+    pub async fn start_risk_monitoring(
+        &self,
+        token: &PumpToken,
+        stake_sol: f64,
+    ) -> Result<MonitorHandle> {
+        let monitor = RiskMonitor::init(
+            self.client.clone(),
+            self.wallet.clone(),
+            token,
+            stake_sol,
+            self.risk.clone(),
+        )
+        .await?;
+        let monitor = Arc::new(self.configure(monitor, &self.wallet));
+        Ok(monitor.start_monitoring())
+    }
+}
@@ -0,0 +1,433 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use std::{str::FromStr, sync::Arc};
+
+use super::{
+    curve::{fetch_curve, BondingCurve, TOKEN_DECIMALS},
+    fees::PriorityFee,
+    jito::JitoClient,
+    pump_sell::{
+        self, creator_vault_pda, event_authority_pda, fetch_fee_recipient, global_pda,
+        CurveComplete, BASE_FEE_LAMPORTS, SYSTEM_PROGRAM,
+    },
+    simulate, slippage,
+    wallets::WalletManager,
+};
+use crate::config::Config;
+use crate::scanner::{
+    onchain::{
+        associated_token_address, bonding_curve_pda, ASSOCIATED_TOKEN_PROGRAM, PUMP_PROGRAM,
+        TOKEN_PROGRAM,
+    },
+    PumpToken,
+};
+
+/// Anchor-дискриминатор инструкции `buy` (sha256("global:buy")[..8])
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+/// Индекс инструкции `CreateIdempotent` Associated Token Program
+const CREATE_ATA_IDEMPOTENT: u8 = 1;
+
+/// Проскальзывание покупки по умолчанию, б.п.
+pub const DEFAULT_BUY_SLIPPAGE_BPS: u16 = 1_000;
+
+/// Результат покупки — всё, что нужно монитору для точной цены входа
+/// (`RiskMonitor::with_buy`)
+#[derive(Debug, Clone, Serialize)]
+pub struct BuyReceipt {
+    pub signature: Signature,
+    pub mint: Pubkey,
+    /// Кошелёк, на который куплено
+    pub wallet: Pubkey,
+    /// Куплено токенов (сырые единицы)
+    pub tokens_received: u64,
+    /// Потрачено на токены вместе с комиссией pump.fun, lamports: по транзакции,
+    /// если она прочиталась, иначе расчётное
+    pub sol_spent: u64,
+    /// Расчётная стоимость по кривой перед отправкой, lamports
+    pub expected_sol: u64,
+    /// Реализованное проскальзывание, б.п. (больше нуля — заплачено больше расчёта);
+    /// `None` — фактическая стоимость неизвестна (dry-run, бумажная покупка)
+    pub realized_slippage_bps: Option<i32>,
+    /// Цена входа: SOL за целый токен с учётом комиссии pump.fun
+    pub effective_price: f64,
+    /// Комиссия сети: базовая и приоритетная, lamports
+    pub fee_lamports: u64,
+    /// Транзакция только симулирована (dry-run)
+    pub simulated: bool,
+}
+
+impl BuyReceipt {
+    /// Потрачено на токены, SOL
+    pub fn sol_spent_sol(&self) -> f64 {
+        self.sol_spent as f64 / LAMPORTS_PER_SOL as f64
+    }
+}
+
+/// Параметры покупки на bonding curve
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuyOptions<'a> {
+    pub slippage_bps: u16,
+    pub priority_fee: Option<PriorityFee>,
+    /// Отправка бандлом Jito с такими чаевыми, lamports; не сел — обычным RPC
+    pub jito: Option<(&'a JitoClient, u64)>,
+    /// Транзакция подписывается и симулируется, но не отправляется
+    pub dry_run: bool,
+}
+
+impl BuyOptions<'_> {
+    pub fn new(slippage_bps: u16) -> Self {
+        Self {
+            slippage_bps,
+            ..Self::default()
+        }
+    }
+}
+
+/// Инструкция `CreateIdempotent`: создаёт ATA владельца, если его ещё нет
+pub fn create_ata_idempotent_instruction(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM, false),
+        ],
+        data: vec![CREATE_ATA_IDEMPOTENT],
+    }
+}
+
+/// Инструкция `buy` на bonding curve pump.fun: ровно `amount` токенов
+/// не дороже `max_sol_cost` lamports (с комиссией)
+pub fn buy_instruction(
+    user: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+    max_sol_cost: u64,
+) -> Instruction {
+    let curve = bonding_curve_pda(mint);
+    let mut data = BUY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&max_sol_cost.to_le_bytes());
+
+    Instruction {
+        program_id: PUMP_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(global_pda(), false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(curve, false),
+            AccountMeta::new(associated_token_address(&curve, mint), false),
+            AccountMeta::new(associated_token_address(user, mint), false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM, false),
+            AccountMeta::new(creator_vault_pda(creator), false),
+            AccountMeta::new_readonly(event_authority_pda(), false),
+            AccountMeta::new_readonly(PUMP_PROGRAM, false),
+        ],
+        data,
+    }
+}
+
+/// Сколько токенов просить за `lamports` при проскальзывании `slippage_bps`
+/// и во что они обойдутся по текущей кривой: котировка за вычетом проскальзывания —
+/// это гарантированный минимум, а потолок цены остаётся `lamports`
+pub fn plan_buy(curve: &BondingCurve, lamports: u64, slippage_bps: u16) -> Result<(u64, u64)> {
+    let tokens = pump_sell::min_out(curve.buy_quote(lamports), slippage_bps);
+    anyhow::ensure!(tokens > 0, "на {} lamports ничего не купить", lamports);
+    let cost = curve
+        .buy_cost(tokens)
+        .context("кривая не вмещает покупку")?
+        .min(lamports);
+    Ok((tokens, cost))
+}
+
+/// Покупает токен `mint` на `sol_amount` SOL (с комиссией pump.fun) на bonding curve.
+/// ATA создаётся при необходимости; минимум токенов считается по кривой
+/// и проскальзыванию; в dry-run транзакция только симулируется.
+pub async fn buy(
+    client: &RpcClient,
+    wallet: &Keypair,
+    mint: &Pubkey,
+    sol_amount: f64,
+    options: &BuyOptions<'_>,
+) -> Result<BuyReceipt> {
+    anyhow::ensure!(
+        sol_amount > 0.0,
+        "сумма покупки должна быть положительной: {}",
+        sol_amount
+    );
+    let lamports = (sol_amount * LAMPORTS_PER_SOL as f64) as u64;
+    let (curve, _) = fetch_curve(client, mint).await?;
+    if curve.complete {
+        return Err(CurveComplete(*mint).into());
+    }
+    let creator = curve
+        .creator
+        .context("в аккаунте bonding curve нет создателя")?;
+    let (tokens, cost) = plan_buy(&curve, lamports, options.slippage_bps)?;
+    let fee_recipient = fetch_fee_recipient(client).await?;
+
+    let user = wallet.pubkey();
+    let mut ixs = options
+        .priority_fee
+        .map(|fee| fee.instructions())
+        .unwrap_or_default();
+    ixs.push(create_ata_idempotent_instruction(&user, &user, mint));
+    ixs.push(buy_instruction(
+        &user,
+        mint,
+        &fee_recipient,
+        &creator,
+        tokens,
+        lamports,
+    ));
+
+    let blockhash = client.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&user), &[wallet], blockhash);
+    let tip = options.jito.map_or(0, |(_, tip)| tip);
+    let mut tip_paid = options.dry_run && tip > 0;
+    let signature = if options.dry_run {
+        simulate::simulate_and_classify(client, &tx.clone().into()).await?;
+        tx.signatures[0]
+    } else if let Some((jito, _)) = options.jito.filter(|_| tip > 0) {
+        let outcome = jito.send_with_tip(client, wallet, &tx, tip).await?;
+        tip_paid = outcome.via_bundle;
+        outcome.signature
+    } else {
+        client.send_and_confirm_transaction(&tx).await?
+    };
+    let (sol_spent, realized_slippage_bps) = match options.dry_run {
+        true => (cost, None),
+        false => match slippage::realized_fill(client, &signature, &user).await {
+            Some(actual) => (actual, slippage::buy_slippage_bps(cost, actual)),
+            None => (cost, None),
+        },
+    };
+    log::info!(
+        "🛒 Покупка {}: {} токенов за {:.4} SOL{}{}",
+        mint,
+        pump_sell::raw_to_ui(tokens, TOKEN_DECIMALS),
+        sol_spent as f64 / LAMPORTS_PER_SOL as f64,
+        slippage::describe(realized_slippage_bps),
+        if options.dry_run {
+            " (симуляция)"
+        } else {
+            ""
+        }
+    );
+
+    Ok(BuyReceipt {
+        signature,
+        mint: *mint,
+        wallet: user,
+        tokens_received: tokens,
+        sol_spent,
+        expected_sol: cost,
+        realized_slippage_bps,
+        effective_price: sol_spent as f64
+            / LAMPORTS_PER_SOL as f64
+            / pump_sell::raw_to_ui(tokens, TOKEN_DECIMALS),
+        fee_lamports: BASE_FEE_LAMPORTS * tx.signatures.len() as u64
+            + options.priority_fee.map_or(0, |fee| fee.lamports())
+            + if tip_paid { tip + BASE_FEE_LAMPORTS } else { 0 },
+        simulated: options.dry_run,
+    })
+}
+
+/// Исполнение входа: как купить токен на заданную сумму
+#[async_trait]
+pub trait EntryExecutor: Send + Sync {
+    /// Покупает `token` на `sol_amount` SOL; `dry_run` — без отправки транзакции
+    async fn buy(&self, token: &PumpToken, sol_amount: f64, dry_run: bool) -> Result<BuyReceipt>;
+
+    /// То же, но с делением суммы между кошельками: квитанция на каждый кошелёк.
+    /// По умолчанию — одна покупка `buy`.
+    async fn buy_split(
+        &self,
+        token: &PumpToken,
+        sol_amount: f64,
+        dry_run: bool,
+    ) -> Result<Vec<BuyReceipt>> {
+        Ok(vec![self.buy(token, sol_amount, dry_run).await?])
+    }
+}
+
+/// Покупка на bonding curve pump.fun (`buy`); в dry-run — симуляция
+pub struct CurveBuyer {
+    client: Arc<RpcClient>,
+    wallet: Arc<Keypair>,
+    options: BuyOptions<'static>,
+    jito: Option<(Arc<JitoClient>, u64)>,
+    wallets: Option<Arc<WalletManager>>,
+}
+
+impl CurveBuyer {
+    pub fn new(client: Arc<RpcClient>, wallet: Arc<Keypair>, options: BuyOptions<'static>) -> Self {
+        Self {
+            client,
+            wallet,
+            options,
+            jito: None,
+            wallets: None,
+        }
+    }
+
+    /// Деление покупок между кошельками (`buy_split`) по правилам `WalletManager`
+    pub fn with_wallets(mut self, wallets: Arc<WalletManager>) -> Self {
+        self.wallets = Some(wallets);
+        self
+    }
+
+    fn options(&self, dry_run: bool) -> BuyOptions<'_> {
+        BuyOptions {
+            jito: self.jito.as_ref().map(|(jito, tip)| (jito.as_ref(), *tip)),
+            dry_run: dry_run || self.options.dry_run,
+            ..self.options
+        }
+    }
+
+    /// Покупатель по `Config`: проскальзывание `slippage.buy_bps`, dry-run
+    /// и Jito с `jito_buy_tip_lamports`
+    pub fn from_config(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        config: &Config,
+    ) -> Result<Self> {
+        config.validate()?;
+        let options = BuyOptions {
+            dry_run: config.dry_run,
+            ..BuyOptions::new(config.slippage.buy_bps)
+        };
+        let mut buyer = Self::new(client, wallet, options);
+        if config.jito_buy_tip_lamports > 0 {
+            if let Some(jito) = config.jito_client()? {
+                buyer = buyer.with_jito(Arc::new(jito), config.jito_buy_tip_lamports);
+            }
+        }
+        Ok(buyer)
+    }
+
+    /// Покупки бандлом Jito с чаевыми `tip_lamports` (0 — обычным RPC)
+    pub fn with_jito(mut self, jito: Arc<JitoClient>, tip_lamports: u64) -> Self {
+        self.jito = (tip_lamports > 0).then_some((jito, tip_lamports));
+        self
+    }
+}
+
+#[async_trait]
+impl EntryExecutor for CurveBuyer {
+    async fn buy(&self, token: &PumpToken, sol_amount: f64, dry_run: bool) -> Result<BuyReceipt> {
+        let mint = Pubkey::from_str(&token.mint)?;
+        let options = self.options(dry_run);
+        buy(&self.client, &self.wallet, &mint, sol_amount, &options).await
+    }
+
+    /// Доли покупаются параллельно; неудавшиеся пропускаются, если куплена хоть одна
+    async fn buy_split(
+        &self,
+        token: &PumpToken,
+        sol_amount: f64,
+        dry_run: bool,
+    ) -> Result<Vec<BuyReceipt>> {
+        let Some(wallets) = &self.wallets else {
+            return Ok(vec![self.buy(token, sol_amount, dry_run).await?]);
+        };
+        let mint = Pubkey::from_str(&token.mint)?;
+        let options = self.options(dry_run);
+        let legs = wallets
+            .plan((sol_amount * LAMPORTS_PER_SOL as f64) as u64)
+            .await?;
+        let results = join_all(legs.iter().map(|(wallet, lamports)| {
+            let sol_amount = *lamports as f64 / LAMPORTS_PER_SOL as f64;
+            buy(&self.client, wallet, &mint, sol_amount, &options)
+        }))
+        .await;
+        let mut receipts = Vec::with_capacity(results.len());
+        let mut first_error = None;
+        for ((wallet, _), result) in legs.iter().zip(results) {
+            match result {
+                Ok(receipt) => receipts.push(receipt),
+                Err(e) => {
+                    log::error!(
+                        "❌ Покупка {} с {} не удалась: {}",
+                        mint,
+                        wallet.pubkey(),
+                        e
+                    );
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if receipts.is_empty() => Err(e),
+            _ => Ok(receipts),
+        }
+    }
+}
+
+/// Без транзакций: покупка по цене токена из сканера минус проскальзывание
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaperBuyer {
+    /// Кошелёк в квитанциях — тот, с которого монитор будет «продавать»
+    wallet: Pubkey,
+    slippage_bps: u16,
+}
+
+impl PaperBuyer {
+    pub fn new(wallet: Pubkey, slippage_bps: u16) -> Self {
+        Self {
+            wallet,
+            slippage_bps,
+        }
+    }
+}
+
+#[async_trait]
+impl EntryExecutor for PaperBuyer {
+    async fn buy(&self, token: &PumpToken, sol_amount: f64, _dry_run: bool) -> Result<BuyReceipt> {
+        let mint = Pubkey::from_str(&token.mint)?;
+        anyhow::ensure!(
+            token.price > 0.0,
+            "у {} нет цены для бумажной покупки",
+            mint
+        );
+        let keep = 1.0 - self.slippage_bps as f64 / 10_000.0;
+        let tokens = pump_sell::ui_to_raw(sol_amount / token.price * keep, TOKEN_DECIMALS);
+        anyhow::ensure!(tokens > 0, "на {} SOL ничего не купить", sol_amount);
+        let sol_spent = (sol_amount * LAMPORTS_PER_SOL as f64) as u64;
+        log::debug!("🧪 Покупка {} на {} SOL без транзакции", mint, sol_amount);
+        Ok(BuyReceipt {
+            signature: Signature::default(),
+            mint,
+            wallet: self.wallet,
+            tokens_received: tokens,
+            sol_spent,
+            expected_sol: sol_spent,
+            realized_slippage_bps: None,
+            effective_price: sol_amount / pump_sell::raw_to_ui(tokens, TOKEN_DECIMALS),
+            fee_lamports: BASE_FEE_LAMPORTS,
+            simulated: true,
+        })
+    }
+}
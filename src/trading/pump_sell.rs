@@ -0,0 +1,732 @@
+use anyhow::{Context, Result};
+use futures_util::FutureExt;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use super::{
+    curve::{fetch_curve, BondingCurve},
+    fees::PriorityFee,
+    jito::JitoClient,
+    rpc_pool::RpcPool,
+    simulate::{self, sim_error, ExitSimError},
+    slippage,
+};
+use crate::scanner::onchain::{
+    associated_token_address, bonding_curve_pda, PUMP_PROGRAM, TOKEN_PROGRAM,
+};
+
+/// Anchor-дискриминатор инструкции `sell` (sha256("global:sell")[..8])
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+pub const SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
+
+/// Базовая комиссия сети за подпись, lamports
+pub const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Проскальзывание продажи по умолчанию, б.п.
+pub const DEFAULT_SELL_SLIPPAGE_BPS: u16 = 500;
+
+/// Гарантированная выручка при текущем проскальзывании ниже допустимой;
+/// приходит внутри `anyhow::Error`, см. `is_below_floor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BelowFloor {
+    /// Минимальная выручка с учётом проскальзывания, lamports
+    pub min_out: u64,
+    pub floor: u64,
+}
+
+impl std::fmt::Display for BelowFloor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "минимальная выручка {} lamports ниже порога {}",
+            self.min_out, self.floor
+        )
+    }
+}
+
+impl std::error::Error for BelowFloor {}
+
+/// Ошибка означает, что продажа не дала бы допустимой выручки
+pub fn is_below_floor(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<BelowFloor>().is_some()
+}
+
+/// Проверка минимальной выручки против порога (`floor` 0 — без порога)
+pub fn ensure_floor(min_out: u64, floor: u64) -> Result<()> {
+    if min_out < floor {
+        return Err(BelowFloor { min_out, floor }.into());
+    }
+    Ok(())
+}
+
+/// Попытки продажи по лестнице проскальзывания, по одной на ступень.
+/// После каждой неудачи вызывается `on_retry(номер, б.п., ошибка)`;
+/// выручка ниже порога (`BelowFloor`), пустой кошелёк (`NoTokens`), завершённая
+/// кривая (`CurveComplete`) и отказы симуляции не из-за проскальзывания
+/// (`ExitSimError::stops_escalation`) эскалацию прекращают.
+pub async fn sell_with_escalation<F, Fut>(
+    ladder: &[u16],
+    mut attempt: F,
+    mut on_retry: impl FnMut(usize, u16, &anyhow::Error),
+) -> Result<SellReceipt>
+where
+    F: FnMut(u16) -> Fut,
+    Fut: std::future::Future<Output = Result<SellReceipt>>,
+{
+    let mut last_err = anyhow::anyhow!("лестница проскальзывания пуста");
+    for (i, &bps) in ladder.iter().enumerate() {
+        match attempt(bps).await {
+            Ok(receipt) => return Ok(receipt),
+            // Больше проскальзывания тут не поможет
+            Err(e)
+                if is_below_floor(&e)
+                    || is_no_tokens(&e)
+                    || is_curve_complete(&e)
+                    || sim_error(&e).is_some_and(ExitSimError::stops_escalation) =>
+            {
+                return Err(e)
+            }
+            Err(e) => {
+                on_retry(i + 1, bps, &e);
+                last_err = e;
+            }
+        }
+    }
+    Err(last_err)
+}
+
+/// Смещение `fee_recipient` в аккаунте `Global` (дискриминатор, initialized, authority)
+const FEE_RECIPIENT_OFFSET: usize = 8 + 1 + 32;
+
+/// Через что прошла продажа
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SellRoute {
+    BondingCurve,
+    Jupiter,
+}
+
+/// Результат продажи
+#[derive(Debug, Clone, Serialize)]
+pub struct SellReceipt {
+    pub signature: Signature,
+    pub route: SellRoute,
+    /// Продано токенов (сырые единицы)
+    pub tokens_sold: u64,
+    /// Выручка, lamports: по транзакции, если она прочиталась, иначе ожидаемая
+    pub sol_received: u64,
+    /// Ожидаемая выручка (по кривой или котировке Jupiter), lamports
+    pub expected_sol: u64,
+    /// Реализованное проскальзывание, б.п. (больше нуля — получено меньше ожидаемого);
+    /// `None` — фактическая выручка неизвестна (dry-run)
+    pub realized_slippage_bps: Option<i32>,
+    /// Комиссия: базовая, приоритетная и чаевые Jito, lamports
+    pub fee_lamports: u64,
+    /// Транзакция только симулирована (dry-run)
+    pub simulated: bool,
+}
+
+/// Параметры продажи на bonding curve
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SellOptions<'a> {
+    pub slippage_bps: u16,
+    /// Не продавать, если минимальная выручка ниже, lamports (0 — без порога)
+    pub floor_lamports: u64,
+    pub priority_fee: Option<PriorityFee>,
+    /// Отправить бандлом Jito с чаевыми (lamports) вместо обычного RPC
+    pub jito: Option<(&'a JitoClient, u64)>,
+    /// Транзакция подписывается и симулируется, но не отправляется
+    pub dry_run: bool,
+    /// Перед отправкой прогнать через `simulateTransaction` (см. `simulate::presimulate`)
+    pub simulate: bool,
+    /// Отправить во все endpoints пула сразу (срочные продажи без Jito)
+    pub broadcast: Option<&'a RpcPool>,
+}
+
+impl SellOptions<'_> {
+    /// Те же параметры с другим проскальзыванием (ступень лестницы)
+    pub fn with_slippage(self, slippage_bps: u16) -> Self {
+        Self {
+            slippage_bps,
+            ..self
+        }
+    }
+}
+
+/// PDA `Global` программы pump.fun
+pub fn global_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"global"], &PUMP_PROGRAM).0
+}
+
+/// PDA `__event_authority` программы pump.fun
+pub fn event_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &PUMP_PROGRAM).0
+}
+
+/// PDA хранилища комиссий создателя
+pub fn creator_vault_pda(creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator-vault", creator.as_ref()], &PUMP_PROGRAM).0
+}
+
+/// Минимальная выручка при заданном проскальзывании
+pub fn min_out(quote: u64, slippage_bps: u16) -> u64 {
+    let keep = 10_000u64.saturating_sub(slippage_bps as u64);
+    (quote as u128 * keep as u128 / 10_000) as u64
+}
+
+/// Ожидаемая выручка продажи `raw` токенов по цене `price` (SOL за токен)
+/// с учётом проскальзывания, lamports
+pub fn estimate_proceeds(raw: u64, decimals: u32, price: f64, slippage_bps: u16) -> u64 {
+    let quote = raw_to_ui(raw, decimals) * price.max(0.0) * LAMPORTS_PER_SOL as f64;
+    min_out(floor_exact(quote) as u64, slippage_bps)
+}
+
+/// Инструкция `sell` на bonding curve pump.fun
+pub fn sell_instruction(
+    user: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+    min_sol_output: u64,
+) -> Instruction {
+    let curve = bonding_curve_pda(mint);
+    let mut data = SELL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_sol_output.to_le_bytes());
+
+    Instruction {
+        program_id: PUMP_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(global_pda(), false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(curve, false),
+            AccountMeta::new(associated_token_address(&curve, mint), false),
+            AccountMeta::new(associated_token_address(user, mint), false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+            AccountMeta::new(creator_vault_pda(creator), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(event_authority_pda(), false),
+            AccountMeta::new_readonly(PUMP_PROGRAM, false),
+        ],
+        data,
+    }
+}
+
+/// Получатель комиссий из аккаунта `Global`
+pub async fn fetch_fee_recipient(client: &RpcClient) -> Result<Pubkey> {
+    let account = client.get_account(&global_pda()).await?;
+    let bytes = account
+        .data
+        .get(FEE_RECIPIENT_OFFSET..FEE_RECIPIENT_OFFSET + 32)
+        .context("аккаунт Global pump.fun слишком короткий")?;
+    Ok(Pubkey::new_from_array(bytes.try_into()?))
+}
+
+/// Баланс токенов кошелька по mint-у (сырые единицы); нет ATA — 0
+pub async fn token_balance(client: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+    let ata = associated_token_address(owner, mint);
+    match client.get_token_account_balance(&ata).await {
+        Ok(balance) => Ok(balance.amount.parse()?),
+        Err(e) if client.get_account(&ata).await.is_err() => {
+            log::debug!("ATA {} не найден: {}", ata, e);
+            Ok(0)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Инструкции SPL Token `Burn` (индекс 8) для остатка и `CloseAccount` (9):
+/// рента ATA возвращается владельцу
+pub fn close_dust_instructions(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Vec<Instruction> {
+    let ata = associated_token_address(owner, mint);
+    let mut ixs = Vec::with_capacity(2);
+    if amount > 0 {
+        let mut data = vec![8u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        ixs.push(Instruction {
+            program_id: TOKEN_PROGRAM,
+            accounts: vec![
+                AccountMeta::new(ata, false),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(*owner, true),
+            ],
+            data,
+        });
+    }
+    ixs.push(Instruction {
+        program_id: TOKEN_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(ata, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![9],
+    });
+    ixs
+}
+
+/// Сжигает пыль и закрывает ATA кошелька по mint-у; нет ATA — `None`
+pub async fn reclaim_dust(
+    client: &RpcClient,
+    wallet: &Keypair,
+    mint: &Pubkey,
+) -> Result<Option<Signature>> {
+    let ata = associated_token_address(&wallet.pubkey(), mint);
+    if client
+        .get_account_with_commitment(&ata, client.commitment())
+        .await?
+        .value
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let balance = token_balance(client, &wallet.pubkey(), mint).await?;
+    let ixs = close_dust_instructions(&wallet.pubkey(), mint, balance);
+    let blockhash = client.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&wallet.pubkey()), &[wallet], blockhash);
+    Ok(Some(client.send_and_confirm_transaction(&tx).await?))
+}
+
+/// Размер SPL Token аккаунта; у Token-2022 дальше идут расширения
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Смещение поля `state` (0 — не инициализирован, 1 — активен, 2 — заморожен)
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+
+/// Заморожен ли токен-аккаунт, по его данным
+pub fn is_frozen_account(data: &[u8]) -> Result<bool> {
+    anyhow::ensure!(
+        data.len() >= TOKEN_ACCOUNT_LEN,
+        "слишком короткие данные токен-аккаунта: {} байт",
+        data.len()
+    );
+    match data[TOKEN_ACCOUNT_STATE_OFFSET] {
+        1 => Ok(false),
+        2 => Ok(true),
+        0 => anyhow::bail!("токен-аккаунт не инициализирован"),
+        state => anyhow::bail!("неизвестное состояние токен-аккаунта: {}", state),
+    }
+}
+
+/// Заморожен ли ATA кошелька по mint-у; нет ATA — `false`
+pub async fn token_account_frozen(
+    client: &RpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<bool> {
+    let ata = associated_token_address(owner, mint);
+    match client
+        .get_account_with_commitment(&ata, client.commitment())
+        .await?
+        .value
+    {
+        Some(account) => is_frozen_account(&account.data),
+        None => Ok(false),
+    }
+}
+
+/// Точность доли при пересчёте в сырые единицы (1e-9)
+const SHARE_SCALE: u128 = 1_000_000_000;
+
+/// Сырые единицы → токены (`decimals` знаков; у pump.fun — 6)
+pub fn raw_to_ui(raw: u64, decimals: u32) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Округление вниз, не срезающее целое из-за погрешности f64 (1.001 × 1e6 = 1000999.99…)
+fn floor_exact(x: f64) -> f64 {
+    let rounded = x.round();
+    if (rounded - x).abs() <= x.abs() * f64::EPSILON * 4.0 {
+        rounded
+    } else {
+        x.floor()
+    }
+}
+
+/// Токены → сырые единицы с округлением вниз; отрицательное — 0
+pub fn ui_to_raw(amount: f64, decimals: u32) -> u64 {
+    let raw = floor_exact(amount.max(0.0) * 10f64.powi(decimals as i32));
+    if raw >= u64::MAX as f64 {
+        u64::MAX
+    } else {
+        raw as u64
+    }
+}
+
+/// Доля `share` (0–1) от `balance` в сырых единицах: без потерь точности f64
+/// на больших балансах, округление вниз, не больше самого баланса
+pub fn fraction_of(balance: u64, share: f64) -> u64 {
+    if share >= 1.0 {
+        return balance;
+    }
+    if share.is_nan() || share <= 0.0 {
+        return 0;
+    }
+    let scaled = floor_exact(share * SHARE_SCALE as f64) as u128;
+    (balance as u128 * scaled / SHARE_SCALE) as u64
+}
+
+/// Сколько продавать
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum TokenAmount {
+    /// Доля текущего баланса (0–1)
+    Share(f64),
+    /// Сырые единицы; больше баланса не продаётся
+    Raw(u64),
+}
+
+impl TokenAmount {
+    /// Количество к продаже при балансе `balance`
+    pub fn resolve(self, balance: u64) -> u64 {
+        match self {
+            Self::Share(share) => fraction_of(balance, share),
+            Self::Raw(raw) => raw.min(balance),
+        }
+    }
+}
+
+/// Кривая завершена (токен мигрировал) — продавать только через Jupiter;
+/// приходит внутри `anyhow::Error`, см. `is_curve_complete`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveComplete(pub Pubkey);
+
+impl std::fmt::Display for CurveComplete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "кривая {} завершена, продажа на ней невозможна", self.0)
+    }
+}
+
+impl std::error::Error for CurveComplete {}
+
+pub fn is_curve_complete(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<CurveComplete>().is_some()
+        || sim_error(e) == Some(&ExitSimError::CurveComplete)
+}
+
+/// На кошельке нет токенов, хотя позиция открыта;
+/// приходит внутри `anyhow::Error`, см. `is_no_tokens`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoTokens(pub Pubkey);
+
+impl std::fmt::Display for NoTokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "на кошельке нет токенов {}", self.0)
+    }
+}
+
+impl std::error::Error for NoTokens {}
+
+pub fn is_no_tokens(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<NoTokens>().is_some()
+}
+
+/// Количество к продаже (сырые единицы) по текущему балансу ATA
+pub async fn sell_amount(
+    client: &RpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: TokenAmount,
+) -> Result<u64> {
+    let balance = token_balance(client, owner, mint).await?;
+    if balance == 0 {
+        return Err(NoTokens(*mint).into());
+    }
+    let resolved = amount.resolve(balance);
+    if let TokenAmount::Raw(raw) = amount {
+        if raw > balance {
+            log::warn!(
+                "⚠️ Нужно продать {} токенов {}, а на кошельке {} — продаём всё",
+                raw,
+                mint,
+                balance
+            );
+        }
+    }
+    anyhow::ensure!(
+        resolved > 0,
+        "нечего продавать: {:?} от {}",
+        amount,
+        balance
+    );
+    Ok(resolved)
+}
+
+/// Продаёт `amount` токена на bonding curve; количество считается от баланса ATA.
+/// Кривая читается заново при каждом вызове; если минимальная выручка
+/// ниже `floor_lamports` — `BelowFloor` без отправки.
+pub async fn sell(
+    client: &RpcClient,
+    wallet: &Keypair,
+    mint: &Pubkey,
+    amount: TokenAmount,
+    options: &SellOptions<'_>,
+) -> Result<SellReceipt> {
+    let amount = sell_amount(client, &wallet.pubkey(), mint, amount).await?;
+    let (curve, _) = fetch_curve(client, mint).await?;
+    if curve.complete {
+        return Err(CurveComplete(*mint).into());
+    }
+    ensure_floor(
+        min_out(curve.sell_quote(amount), options.slippage_bps),
+        options.floor_lamports,
+    )?;
+    sell_on_curve(client, wallet, mint, &curve, amount, options).await
+}
+
+async fn sell_on_curve(
+    client: &RpcClient,
+    wallet: &Keypair,
+    mint: &Pubkey,
+    curve: &BondingCurve,
+    amount: u64,
+    options: &SellOptions<'_>,
+) -> Result<SellReceipt> {
+    if curve.complete {
+        return Err(CurveComplete(*mint).into());
+    }
+    let creator = curve
+        .creator
+        .context("в аккаунте bonding curve нет создателя")?;
+    let quote = curve.sell_quote(amount);
+    let fee_recipient = fetch_fee_recipient(client).await?;
+    let mut ixs = options
+        .priority_fee
+        .map(|fee| fee.instructions())
+        .unwrap_or_default();
+    ixs.push(sell_instruction(
+        &wallet.pubkey(),
+        mint,
+        &fee_recipient,
+        &creator,
+        amount,
+        min_out(quote, options.slippage_bps),
+    ));
+    let tip = options.jito.map_or(0, |(_, tip)| tip);
+
+    let blockhash = client.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&wallet.pubkey()), &[wallet], blockhash);
+    if options.dry_run {
+        simulate::simulate_and_classify(client, &tx.clone().into()).await?;
+    } else if options.simulate {
+        simulate::presimulate(client, &tx.clone().into()).await?;
+    }
+    // Чаевые — отдельной транзакцией бандла; платятся, только если бандл сел
+    let mut tip_paid = options.dry_run && tip > 0;
+    let signature = if options.dry_run {
+        tx.signatures[0]
+    } else if let Some((jito, _)) = options.jito.filter(|_| tip > 0) {
+        let outcome = jito.send_with_tip(client, wallet, &tx, tip).await?;
+        tip_paid = outcome.via_bundle;
+        outcome.signature
+    } else if let Some(pool) = options.broadcast {
+        pool.broadcast(|client| {
+            let tx = tx.clone();
+            async move { Ok(client.send_and_confirm_transaction(&tx).await?) }.boxed()
+        })
+        .await?
+    } else {
+        client.send_and_confirm_transaction(&tx).await?
+    };
+    let fill = match options.dry_run {
+        true => None,
+        false => slippage::realized_fill(client, &signature, &wallet.pubkey()).await,
+    };
+
+    Ok(SellReceipt {
+        signature,
+        route: SellRoute::BondingCurve,
+        tokens_sold: amount,
+        sol_received: fill.unwrap_or(quote),
+        expected_sol: quote,
+        realized_slippage_bps: fill.and_then(|actual| slippage::sell_slippage_bps(quote, actual)),
+        fee_lamports: BASE_FEE_LAMPORTS * tx.signatures.len() as u64
+            + options.priority_fee.map_or(0, |fee| fee.lamports())
+            + if tip_paid { tip + BASE_FEE_LAMPORTS } else { 0 },
+        simulated: options.dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::risk::RiskConfig;
+
+    fn receipt() -> SellReceipt {
+        SellReceipt {
+            signature: Signature::default(),
+            route: SellRoute::BondingCurve,
+            tokens_sold: 1,
+            sol_received: 1,
+            expected_sol: 1,
+            realized_slippage_bps: None,
+            fee_lamports: BASE_FEE_LAMPORTS,
+            simulated: false,
+        }
+    }
+
+    /// Прогон эскалации: `fail(попытка)` — ошибка попытки или `None` для успеха.
+    /// Возвращает проскальзывания попыток, номера повторов и успех.
+    async fn escalate(
+        ladder: &[u16],
+        fail: impl Fn(usize) -> Option<anyhow::Error>,
+    ) -> (Vec<u16>, Vec<usize>, bool) {
+        let mut tried = Vec::new();
+        let mut retries = Vec::new();
+        let result = sell_with_escalation(
+            ladder,
+            |bps| {
+                tried.push(bps);
+                std::future::ready(match fail(tried.len()) {
+                    Some(e) => Err(e),
+                    None => Ok(receipt()),
+                })
+            },
+            |n, _, _| retries.push(n),
+        )
+        .await;
+        (tried, retries, result.is_ok())
+    }
+
+    #[tokio::test]
+    async fn escalation_climbs_ladder_up_to_cap() {
+        let slippage = |_| Some(anyhow::anyhow!("slippage: too little SOL received"));
+        let mut config = RiskConfig {
+            sell_slippage_ladder_bps: vec![300, 800, 1500],
+            ..Default::default()
+        };
+        // Ступени не выше базовой пропускаются
+        assert_eq!(config.slippage_ladder(500), vec![500, 800, 1500]);
+        let (tried, retries, ok) = escalate(&config.slippage_ladder(500), slippage).await;
+        assert_eq!(tried, vec![500, 800, 1500]);
+        assert_eq!(retries, vec![1, 2, 3]);
+        assert!(!ok);
+
+        // Потолок попыток: лишние ступени отрезаются, недостающие повторяют последнюю
+        config.sell_max_attempts = 2;
+        let (tried, _, _) = escalate(&config.slippage_ladder(500), slippage).await;
+        assert_eq!(tried, vec![500, 800]);
+        config.sell_max_attempts = 5;
+        let (tried, _, _) = escalate(&config.slippage_ladder(500), slippage).await;
+        assert_eq!(tried, vec![500, 800, 1500, 1500, 1500]);
+
+        // Без ступеней — одна попытка с удвоенным, не выше 100%
+        let config = RiskConfig::default();
+        assert_eq!(config.slippage_ladder(500), vec![500, 1000]);
+        assert_eq!(config.slippage_ladder(6000), vec![6000, 10_000]);
+    }
+
+    #[tokio::test]
+    async fn escalation_stops_on_success_or_hopeless_error() {
+        let ladder = [500, 800, 1500];
+        let (tried, retries, ok) =
+            escalate(&ladder, |n| (n < 2).then(|| anyhow::anyhow!("slippage"))).await;
+        assert_eq!(tried, vec![500, 800]);
+        assert_eq!(retries, vec![1]);
+        assert!(ok);
+
+        // Выручка ниже порога и пустой кошелёк от проскальзывания не зависят
+        let (tried, retries, ok) = escalate(&ladder, |_| {
+            Some(
+                BelowFloor {
+                    min_out: 1,
+                    floor: 2,
+                }
+                .into(),
+            )
+        })
+        .await;
+        assert_eq!((tried, retries, ok), (vec![500], vec![], false));
+        let (tried, _, _) =
+            escalate(&ladder, |_| Some(NoTokens(Pubkey::new_unique()).into())).await;
+        assert_eq!(tried, vec![500]);
+
+        let (tried, _, ok) = escalate(&[], |_| None).await;
+        assert!(tried.is_empty() && !ok);
+    }
+
+    #[test]
+    fn ui_to_raw_at_token_decimals() {
+        for (amount, decimals, raw) in [
+            (1.0, 6, 1_000_000),
+            (1.001, 6, 1_001_000),
+            (4.35, 6, 4_350_000),
+            (0.000_001, 6, 1),
+            (0.000_000_9, 6, 0),
+            (1_234.567_891, 6, 1_234_567_891),
+            (1.001, 9, 1_001_000_000),
+            (0.1 + 0.2, 9, 300_000_000),
+            (0.000_000_001, 9, 1),
+            (-5.0, 9, 0),
+            (f64::MAX, 9, u64::MAX),
+        ] {
+            assert_eq!(
+                ui_to_raw(amount, decimals),
+                raw,
+                "{} при {} знаках",
+                amount,
+                decimals
+            );
+        }
+        assert_eq!(raw_to_ui(ui_to_raw(73.5, 6), 6), 73.5);
+        assert_eq!(raw_to_ui(ui_to_raw(73.5, 9), 9), 73.5);
+    }
+
+    #[test]
+    fn fraction_of_balance() {
+        // 1000 токенов при 6 и 9 знаках
+        for balance in [1_000_000_000, 1_000_000_000_000] {
+            assert_eq!(fraction_of(balance, 1.0), balance);
+            assert_eq!(fraction_of(balance, 1.5), balance);
+            assert_eq!(fraction_of(balance, 0.5), balance / 2);
+            assert_eq!(fraction_of(balance, 0.0157), balance / 10_000 * 157);
+            assert_eq!(fraction_of(balance, 0.0), 0);
+            assert_eq!(fraction_of(balance, -0.1), 0);
+            assert_eq!(fraction_of(balance, f64::NAN), 0);
+        }
+        // Округление вниз и точность на балансах больше 2^53
+        assert_eq!(fraction_of(3, 0.5), 1);
+        assert_eq!(fraction_of(u64::MAX, 1.0), u64::MAX);
+        assert_eq!(fraction_of(u64::MAX, 0.5), u64::MAX / 2);
+        assert!(fraction_of(u64::MAX, 0.999_999_999_9) < u64::MAX);
+    }
+
+    /// Данные SPL токен-аккаунта: mint, владелец, баланс, делегат, `state`, …
+    fn token_account(state: u8, amount: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(TOKEN_ACCOUNT_LEN);
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&[0; 36]);
+        data.push(state);
+        data.resize(TOKEN_ACCOUNT_LEN, 0);
+        data
+    }
+
+    #[test]
+    fn frozen_account_by_state() {
+        assert!(!is_frozen_account(&token_account(1, 1_000_000)).unwrap());
+        assert!(is_frozen_account(&token_account(2, 1_000_000)).unwrap());
+        // Баланс на состояние не влияет
+        assert!(is_frozen_account(&token_account(2, 0)).unwrap());
+        // Token-2022: расширения после базовых 165 байт
+        let mut extended = token_account(2, 1);
+        extended.extend_from_slice(&[2, 0, 7, 0, 1, 1, 1, 1, 1, 1, 1]);
+        assert!(is_frozen_account(&extended).unwrap());
+
+        assert!(is_frozen_account(&token_account(0, 0)).is_err());
+        assert!(is_frozen_account(&token_account(3, 0)).is_err());
+        assert!(is_frozen_account(&token_account(2, 0)[..TOKEN_ACCOUNT_LEN - 1]).is_err());
+        assert!(is_frozen_account(&[]).is_err());
+    }
+}
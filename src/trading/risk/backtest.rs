@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use std::{path::Path, sync::Arc, time::Duration};
+
+use super::{ExecutionMode, ExitSummary, RiskConfig, RiskMonitor};
+use crate::scanner::PumpToken;
+use crate::trading::{
+    clock::{Clock, ManualClock, SystemClock},
+    curve::{fetch_curve, PoolSnapshot},
+    executor::ExitStyle,
+    history::PriceSample,
+    journal::{self, JournalEntry},
+};
+
+/// Источник снимков пула для прогона решений монитора
+#[async_trait]
+pub trait PriceFeed: Send {
+    /// Следующий снимок и его время (unix, мс); `None` — данные кончились
+    async fn next_snapshot(&mut self) -> Result<Option<(u64, PoolSnapshot)>>;
+}
+
+/// Снимки bonding curve по RPC раз в `interval` (время — системное)
+pub struct RpcFeed {
+    client: Arc<RpcClient>,
+    mint: Pubkey,
+    interval: Duration,
+    clock: SystemClock,
+    started: bool,
+}
+
+impl RpcFeed {
+    pub fn new(client: Arc<RpcClient>, mint: Pubkey, interval: Duration) -> Self {
+        Self {
+            client,
+            mint,
+            interval,
+            clock: SystemClock::new(),
+            started: false,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for RpcFeed {
+    async fn next_snapshot(&mut self) -> Result<Option<(u64, PoolSnapshot)>> {
+        if std::mem::replace(&mut self.started, true) {
+            tokio::time::sleep(self.interval).await;
+        }
+        let (curve, slot) = fetch_curve(&self.client, &self.mint).await?;
+        Ok(Some((
+            self.clock.now_ms(),
+            PoolSnapshot::from_curve(&curve, slot),
+        )))
+    }
+}
+
+/// Строка записи в JSON: объект `PriceSample` или `[timestamp_ms, price, sol_reserve]`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Row {
+    Sample(PriceSample),
+    Tuple(u64, f64, u64),
+}
+
+impl From<Row> for PriceSample {
+    fn from(row: Row) -> Self {
+        match row {
+            Row::Sample(sample) => sample,
+            Row::Tuple(timestamp_ms, price, sol_reserve) => PriceSample {
+                timestamp_ms,
+                price,
+                sol_reserve,
+            },
+        }
+    }
+}
+
+/// Записанная серия (время unix в мс, цена в SOL за токен, резерв SOL в lamports)
+#[derive(Debug, Clone)]
+pub struct ReplayFeed {
+    samples: Vec<PriceSample>,
+    cursor: usize,
+}
+
+impl ReplayFeed {
+    /// Точки сортируются по времени
+    pub fn new(mut samples: Vec<PriceSample>) -> Self {
+        samples.sort_by_key(|s| s.timestamp_ms);
+        Self { samples, cursor: 0 }
+    }
+
+    /// CSV `timestamp_ms,price,sol_reserve`; заголовок, пустые строки и `#` пропускаются
+    pub fn from_csv(text: &str) -> Result<Self> {
+        let mut samples = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            // Заголовок — первая непустая строка, если в ней не число
+            if samples.is_empty() && fields[0].parse::<u64>().is_err() {
+                continue;
+            }
+            let [timestamp_ms, price, sol_reserve] = fields[..] else {
+                anyhow::bail!("строка {}: нужно 3 поля, а не {}", i + 1, fields.len());
+            };
+            samples.push(PriceSample {
+                timestamp_ms: timestamp_ms
+                    .parse()
+                    .with_context(|| format!("строка {}: время", i + 1))?,
+                price: price
+                    .parse()
+                    .with_context(|| format!("строка {}: цена", i + 1))?,
+                sol_reserve: sol_reserve
+                    .parse()
+                    .with_context(|| format!("строка {}: резерв", i + 1))?,
+            });
+        }
+        Ok(Self::new(samples))
+    }
+
+    /// JSON-массив `PriceSample` или кортежей `[timestamp_ms, price, sol_reserve]`
+    pub fn from_json(text: &str) -> Result<Self> {
+        let rows: Vec<Row> = serde_json::from_str(text).context("неверный JSON серии")?;
+        Ok(Self::new(rows.into_iter().map(PriceSample::from).collect()))
+    }
+
+    /// Тики журнала монитора (`RiskMonitor::with_journal`)
+    pub fn from_journal(entries: &[JournalEntry]) -> Self {
+        Self::new(journal::samples(entries))
+    }
+
+    /// Файл `.json`, журнал `.jsonl` или CSV (всё остальное)
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("не удалось прочитать {}", path.display()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&text),
+            Some("jsonl") => Ok(Self::from_journal(&journal::parse(&text)?)),
+            _ => Self::from_csv(&text),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+#[async_trait]
+impl PriceFeed for ReplayFeed {
+    async fn next_snapshot(&mut self) -> Result<Option<(u64, PoolSnapshot)>> {
+        let Some(sample) = self.samples.get(self.cursor) else {
+            return Ok(None);
+        };
+        self.cursor += 1;
+        Ok(Some((
+            sample.timestamp_ms,
+            PoolSnapshot {
+                price: sample.price,
+                sol_reserve: sample.sol_reserve,
+                token_reserve: 0,
+                slot: self.cursor as u64,
+            },
+        )))
+    }
+}
+
+/// Прогон всей логики выхода по записи: вход по первой точке, время — по отметкам
+/// записи, продажи — синтетические по цене тика минус `slippage_bps`.
+/// Позиция, не закрытая к концу записи, остаётся с `ExitReason::Stopped`.
+/// Условия, которым нужна сеть (объём, тренды, создатель, заморозка), не проверяются;
+/// `exit_style` — всегда одной продажей (частям нужна живая цена между ними).
+pub async fn run_backtest(
+    mut config: RiskConfig,
+    mut feed: ReplayFeed,
+    stake_sol: f64,
+    slippage_bps: u16,
+) -> Result<ExitSummary> {
+    let (start_ms, first) = feed
+        .next_snapshot()
+        .await?
+        .context("в записи нет ни одной точки")?;
+    config.exit_style = ExitStyle::Single;
+    let token = PumpToken {
+        mint: Pubkey::default().to_string(),
+        price: first.price,
+        ..Default::default()
+    };
+    let clock = Arc::new(ManualClock::new(start_ms));
+    let client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+    let monitor = RiskMonitor::new(client, Arc::new(Keypair::new()), &token, stake_sol, config)?
+        .with_execution_mode(ExecutionMode::Paper)
+        .with_sell_slippage(slippage_bps)
+        .with_clock(clock.clone());
+    let mut next = Some((start_ms, first));
+    while let Some((timestamp_ms, snapshot)) = next {
+        clock.set_ms(timestamp_ms);
+        let elapsed = Duration::from_millis(timestamp_ms.saturating_sub(start_ms));
+        if monitor.on_tick(&snapshot, elapsed).await? {
+            break;
+        }
+        next = feed.next_snapshot().await?;
+    }
+    Ok(monitor.exit_summary())
+}
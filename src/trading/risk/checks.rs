@@ -0,0 +1,228 @@
+use anyhow::Result;
+use solana_sdk::signature::Signer;
+use std::{sync::Arc, time::Duration};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use super::{ExitReason, RiskAction, RiskEvent, RiskMonitor};
+use crate::scanner::{onchain::associated_token_address, verify_authorities};
+use crate::trading::pump_sell;
+
+/// Как часто фоновая задача обновляет тренды и объём для moon-выхода
+const MARKET_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+impl RiskMonitor {
+    /// Каждые `freeze_check_ticks` тиков: заморожен ли наш ATA и не появились ли
+    /// freeze или mint authority. Заморозка закрывает позицию с `ExitReason::Frozen` без продаж
+    /// (они всё равно не пройдут); новый freeze authority — выход, пока счёт не заморожен;
+    /// mint authority — выход с `ExitReason::SupplyInflated`. `true` — позиция закрыта.
+    pub(super) async fn check_frozen(&self, tick: u64) -> bool {
+        let every = self.config().freeze_check_ticks;
+        if every == 0 || !tick.is_multiple_of(every) {
+            return false;
+        }
+        let owner = self.wallet.pubkey();
+        match pump_sell::token_account_frozen(&self.client, &owner, &self.token_mint).await {
+            Ok(true) => {
+                let account = associated_token_address(&owner, &self.token_mint);
+                log::error!(
+                    "🧊🚨 Счёт {} по {} заморожен — продать нельзя, позиция списана",
+                    account,
+                    self.token_mint
+                );
+                {
+                    let mut state = self.state.lock().unwrap();
+                    state.remaining = 0.0;
+                    state.last_exit = Some(ExitReason::Frozen);
+                }
+                self.publish(RiskEvent::Frozen { account });
+                self.persist();
+                return true;
+            }
+            Ok(false) => {}
+            Err(e) => log::debug!("Заморозка счёта не проверена: {}", e),
+        }
+        match verify_authorities(&self.client, &self.token_mint).await {
+            Ok(status) if !status.freeze_revoked() => {
+                log::error!(
+                    "🧊 У {} появился freeze authority — выходим",
+                    self.token_mint
+                );
+                let sale = {
+                    let mut state = self.state.lock().unwrap();
+                    // Параллельная проверка могла закрыть позицию, пока шёл запрос
+                    if state.is_closed() {
+                        return true;
+                    }
+                    state.take(1.0, ExitReason::FreezeAuthority)
+                };
+                self.act(sale, RiskEvent::FreezeAuthority).await;
+                self.persist();
+                self.state().is_closed()
+            }
+            Ok(status) if !status.mint_revoked() => {
+                let authority = status.mint_authority.unwrap_or_default();
+                log::error!(
+                    "🚨 У {} есть mint authority {} — могут допечатать, выходим",
+                    self.token_mint,
+                    authority
+                );
+                let sale = {
+                    let mut state = self.state.lock().unwrap();
+                    if state.supply_triggered || state.is_closed() {
+                        return false;
+                    }
+                    state.supply_triggered = true;
+                    state.take(1.0, ExitReason::SupplyInflated)
+                };
+                self.act(sale, RiskEvent::MintAuthority { authority }).await;
+                self.persist();
+                self.state().is_closed()
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::debug!("Полномочия mint-а не проверены: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Каждые `supply_check_ticks` тиков: общий supply mint-а против `check_supply`;
+    /// `true` — позиция закрыта
+    pub(super) async fn check_supply(&self, tick: u64) -> bool {
+        let every = self.config().supply_check_ticks;
+        if every == 0 || !tick.is_multiple_of(every) {
+            return false;
+        }
+        let supply = match self.token_supply().await {
+            Ok(supply) => supply,
+            Err(e) => {
+                log::debug!("Supply mint-а не получен: {}", e);
+                return false;
+            }
+        };
+        let action = {
+            let mut state = self.state.lock().unwrap();
+            self.config().check_supply(&mut state, supply)
+        };
+        let Some(RiskAction::Sell { sale, event }) = action else {
+            return false;
+        };
+        self.act(sale, event).await;
+        self.persist();
+        self.state().is_closed()
+    }
+
+    /// Общий supply mint-а, сырые единицы
+    pub(super) async fn token_supply(&self) -> Result<u64> {
+        let supply = self.client.get_token_supply(&self.token_mint).await?;
+        Ok(supply.amount.parse()?)
+    }
+
+    /// Баланс создателя по mint-у против `check_creator_dump`; `true` — позиция закрыта
+    pub(super) async fn check_creator(&self) -> bool {
+        let Some(creator) = self.creator else {
+            return false;
+        };
+        if self.config().creator_dump_pct <= 0.0 {
+            return false;
+        }
+        let balance = match pump_sell::token_balance(&self.client, &creator, &self.token_mint).await
+        {
+            Ok(balance) => balance,
+            Err(e) => {
+                log::debug!("Баланс создателя не получен: {}", e);
+                return false;
+            }
+        };
+        let action = {
+            let mut state = self.state.lock().unwrap();
+            self.config().check_creator_dump(&mut state, balance)
+        };
+        let Some(RiskAction::Sell { sale, event }) = action else {
+            return false;
+        };
+        self.act(sale, event).await;
+        self.persist();
+        self.state().is_closed()
+    }
+
+    /// Тренды и объём в фоне, чтобы тик не ждал HTTP; до отмены `cancel`
+    pub(super) async fn refresh_market(self: Arc<Self>, cancel: CancellationToken) {
+        loop {
+            self.refresh_trending().await;
+            self.refresh_volume().await;
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = time::sleep(MARKET_REFRESH_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// Обновляет `in_top_n`; если DexScreener недоступен, остаётся прежнее значение
+    pub(super) async fn refresh_trending(&self) {
+        let Some(dexscreener) = &self.dexscreener else {
+            return;
+        };
+        if self.config().moon_top_n == 0 || self.state.lock().unwrap().moon_sold {
+            return;
+        }
+        let mint = self.token_mint.to_string();
+        match dexscreener
+            .is_in_top_n(&mint, self.config().moon_top_n)
+            .await
+        {
+            Ok(in_top) => self.state.lock().unwrap().in_top_n = in_top,
+            Err(e) => log::debug!("Тренды DexScreener недоступны: {}", e),
+        }
+    }
+
+    /// Обновляет цену SOL в фоне, когда кэш устарел, — тик её не ждёт.
+    /// Цена на входе берётся из первой полученной.
+    pub(super) fn refresh_sol_price(&self) {
+        let Some(feed) = &self.sol_price else {
+            return;
+        };
+        let cached = feed.cached();
+        if let Some(price) = cached {
+            let mut state = self.state.lock().unwrap();
+            state.entry_sol_usd = state.entry_sol_usd.or(Some(price.usd));
+        }
+        if cached.is_none_or(|p| Duration::from_millis(p.age_ms) >= feed.ttl()) {
+            let feed = feed.clone();
+            tokio::spawn(async move {
+                if let Err(e) = feed.sol_usd().await {
+                    log::debug!("Цена SOL не обновлена: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Подтягивает новые сделки не чаще `volume_poll_secs`, пока объём нужен для moon-выхода
+    pub(super) async fn refresh_volume(&self) {
+        if self.config().moon_min_volume_sol <= 0.0 {
+            return;
+        }
+        if self.state.lock().unwrap().moon_sold {
+            return;
+        }
+        let (account, _) = self.pool_account();
+        {
+            let mut last = self.last_volume_poll.lock().unwrap();
+            let poll_ms = self.config().volume_poll_secs * 1000;
+            let now = self.clock.now_ms();
+            if last.is_some_and(|at| now.saturating_sub(at) < poll_ms) {
+                return;
+            }
+            *last = Some(now);
+        }
+        let mut volume = self.volume.lock().await;
+        match volume
+            .poll(&self.client, &account, self.clock.now_ms())
+            .await
+        {
+            Ok(sol) => self.state.lock().unwrap().volume_sol = Some(sol),
+            Err(e) => log::debug!("Объём торгов не обновлён: {}", e),
+        }
+    }
+}
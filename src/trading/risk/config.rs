@@ -0,0 +1,546 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::time::Duration;
+
+use crate::trading::{
+    curve::TOKEN_DECIMALS,
+    executor::ExitStyle,
+    fees::Urgency,
+    pump_sell::{self, BASE_FEE_LAMPORTS},
+};
+
+/// Что делать после крупной продажи в пул (`RiskConfig::whale_sell_pct`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhaleReaction {
+    /// Сразу продать долю исходной позиции (0–1)
+    Exit { fraction: f64 },
+    /// На `secs` секунд заменить `trailing_stop_pct` более узким
+    Tighten { trailing_stop_pct: f64, secs: u64 },
+}
+
+impl Default for WhaleReaction {
+    fn default() -> Self {
+        Self::Tighten {
+            trailing_stop_pct: 10.0,
+            secs: 60,
+        }
+    }
+}
+
+/// Пороги выхода из позиции.
+/// `Default` совпадает с прежними захардкоженными значениями.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Падение резерва SOL от резерва при входе для rug-pull, %
+    pub rug_reserve_drop_pct: f64,
+    /// Минимальный резерв SOL пула: ниже — выход целиком, на входе — отказ, SOL
+    /// (0 — без порога)
+    pub min_pool_sol: f64,
+    /// Падение цены от входа для panic-sell, %
+    pub panic_drawdown_pct: f64,
+    /// Через сколько секунд без роста продавать часть позиции
+    pub timeout_secs: u64,
+    /// Рост от входа, который считается ростом для time-out, %
+    pub timeout_min_gain_pct: f64,
+    /// Какую часть позиции продавать по time-out, %
+    pub timeout_sell_pct: f64,
+    /// Падение от пика для trailing stop, %
+    pub trailing_stop_pct: f64,
+    /// Trailing stop по пиковому множителю: (от какого множителя, падение от пика, %),
+    /// по возрастанию множителя; ниже первой строки — `trailing_stop_pct`
+    pub trailing_schedule: Vec<(f64, f64)>,
+    /// Исполнение плановых выходов: одной продажей или частями; срочные — всегда одной
+    pub exit_style: ExitStyle,
+    /// Trailing stop включается, только когда пик дошёл до этого множителя от входа
+    /// (1.0 — при любом росте); до этого работают только rug-pull и panic-sell
+    pub trailing_activation_multiple: f64,
+    /// Множитель от входа для продажи лунной доли
+    pub moon_multiplier: f64,
+    /// Через сколько секунд продавать лунную долю в любом случае
+    pub moon_timer_secs: u64,
+    /// Лунная доля позиции, %
+    pub moon_allocation_pct: f64,
+    /// Минимальный объём торгов за `volume_window_secs` для выхода по `moon_multiplier`,
+    /// SOL (0 — объём не проверяется)
+    pub moon_min_volume_sol: f64,
+    /// Окно скользящего объёма, сек
+    pub volume_window_secs: u64,
+    /// Как часто подтягивать новые сделки для объёма, сек
+    pub volume_poll_secs: u64,
+    /// Продавать лунную долю при попадании в топ-N трендов DexScreener (0 — выключено)
+    pub moon_top_n: usize,
+    /// Ступени проскальзывания продажи после базового, б.п., по возрастанию;
+    /// пусто — одна ступень с удвоенным базовым
+    pub sell_slippage_ladder_bps: Vec<u16>,
+    /// Сколько попыток продажи на bonding curve (0 — по числу ступеней)
+    pub sell_max_attempts: usize,
+    /// Не продавать, если гарантированная выручка продажи ниже, SOL (0 — без порога)
+    pub min_exit_proceeds_sol: f64,
+    /// Ожидаемая выручка продажи за вычетом комиссий ниже этого, SOL, — остаток
+    /// не продаётся, а списывается как пыль (0 — без порога)
+    pub min_proceeds_sol: f64,
+    /// Потолок приоритетной комиссии продажи, lamports (0 — без приоритетной комиссии);
+    /// срочные выходы (rug-pull, panic) платят его целиком
+    pub priority_fee_cap_lamports: u64,
+    /// Множитель к 75-му перцентилю недавних приоритетных комиссий
+    pub priority_fee_multiplier: f64,
+    /// Лимит вычислительных единиц транзакции продажи
+    pub compute_unit_limit: u32,
+    /// Чаевые Jito за плановую продажу, lamports (0 — без Jito)
+    pub jito_tip_lamports: u64,
+    /// Чаевые Jito за срочный выход (rug-pull, panic), lamports; обычно больше плановых.
+    /// Только они при нулевых `jito_tip_lamports` — бандлом идут лишь срочные выходы
+    pub jito_emergency_tip_lamports: u64,
+    /// Симулировать продажу перед отправкой: отказ разбирается (`ExitSimError`)
+    /// вместо слепой эскалации проскальзывания
+    pub simulate_exits: bool,
+    /// Симулировать и срочные выходы (rug-pull, panic); по умолчанию они уходят сразу
+    pub simulate_emergency_exits: bool,
+    /// Дольше этого (сек) позиция не держится: остаток продаётся при любой цене
+    pub max_hold_secs: Option<u64>,
+    /// Интервал опроса цены, мс (без `adaptive_interval`)
+    pub tick_interval_ms: u64,
+    /// Интервал зависит от возраста позиции и волатильности
+    pub adaptive_interval: bool,
+    /// Быстрый интервал: первые `fast_window_secs` и на резких движениях, мс
+    pub fast_interval_ms: u64,
+    /// Медленный интервал для спокойной старой позиции, мс
+    pub slow_interval_ms: u64,
+    /// Сколько секунд после входа опрашивать с быстрым интервалом
+    pub fast_window_secs: u64,
+    /// К какому возрасту позиции интервал доходит до медленного, сек
+    pub slow_after_secs: u64,
+    /// Изменение цены за тик, после которого интервал снова быстрый, %
+    pub volatility_pct: f64,
+    /// Создатель продал столько от максимума своего баланса — выходим целиком, % (0 — выключено)
+    pub creator_dump_pct: f64,
+    /// Одна продажа (за тик) вынула из резерва SOL пула столько, % — крупный игрок
+    /// выходит (0 — выключено)
+    pub whale_sell_pct: f64,
+    /// Реакция на крупную продажу
+    pub whale_reaction: WhaleReaction,
+    /// После стольких сбоев тиков подряд — `RiskEvent::Degraded` и защитный выход
+    /// (0 — только повторять)
+    pub max_consecutive_errors: u32,
+    /// Раз в сколько тиков проверять заморозку нашего счёта и freeze authority (0 — выключено)
+    pub freeze_check_ticks: u64,
+    /// Раз в сколько тиков сверять общий supply mint-а со входом (0 — выключено)
+    pub supply_check_ticks: u64,
+    /// Фиксация прибыли: (множитель от входа, доля позиции без лунной доли).
+    /// Каждая ступень срабатывает один раз; остаток ведёт trailing stop.
+    pub take_profit_tiers: Vec<(f64, f64)>,
+    /// Множитель от входа, после которого стоп переносится в безубыток
+    pub move_stop_to_breakeven_after: Option<f64>,
+    /// Запас над ценой входа для стопа в безубытке (комиссии, проскальзывание), %
+    pub breakeven_buffer_pct: f64,
+    /// Сколько точек цены хранить в истории позиции
+    pub history_len: usize,
+    /// Длина интервала для детекта ступенчатого слива, сек
+    pub drop_interval_secs: u64,
+    /// Падение за интервал, которое считается ступенью слива, %
+    pub drop_interval_pct: f64,
+    /// Сколько ступеней подряд дают panic-sell (0 — выключено)
+    pub drop_intervals: usize,
+    /// По скольким последним точкам истории считать скорость оттока резерва
+    pub drain_window_samples: usize,
+    /// Отток резерва для мгновенного выхода, SOL/сек (0 — выключено)
+    pub max_drain_sol_per_sec: f64,
+    /// Отток резерва для мгновенного выхода, %/сек (0 — выключено)
+    pub max_drain_pct_per_sec: f64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            rug_reserve_drop_pct: 40.0,
+            panic_drawdown_pct: 60.0,
+            timeout_secs: 90,
+            timeout_min_gain_pct: 10.0,
+            timeout_sell_pct: 50.0,
+            trailing_stop_pct: 30.0,
+            trailing_schedule: Vec::new(),
+            trailing_activation_multiple: 1.3,
+            exit_style: ExitStyle::Single,
+            moon_multiplier: 50.0,
+            moon_timer_secs: 24 * 60 * 60,
+            moon_allocation_pct: 20.0,
+            moon_min_volume_sol: 0.0,
+            volume_window_secs: 60 * 60,
+            volume_poll_secs: 30,
+            moon_top_n: 0,
+            sell_slippage_ladder_bps: Vec::new(),
+            sell_max_attempts: 0,
+            min_exit_proceeds_sol: 0.0,
+            min_proceeds_sol: 0.0,
+            priority_fee_cap_lamports: 0,
+            priority_fee_multiplier: 1.5,
+            compute_unit_limit: 120_000,
+            jito_tip_lamports: 0,
+            jito_emergency_tip_lamports: 0,
+            simulate_exits: true,
+            simulate_emergency_exits: false,
+            max_hold_secs: None,
+            tick_interval_ms: 500,
+            adaptive_interval: false,
+            fast_interval_ms: 100,
+            slow_interval_ms: 2000,
+            fast_window_secs: 60,
+            slow_after_secs: 600,
+            volatility_pct: 2.0,
+            creator_dump_pct: 0.0,
+            min_pool_sol: 0.0,
+            whale_sell_pct: 0.0,
+            whale_reaction: WhaleReaction::default(),
+            max_consecutive_errors: 20,
+            freeze_check_ticks: 20,
+            supply_check_ticks: 20,
+            take_profit_tiers: Vec::new(),
+            move_stop_to_breakeven_after: None,
+            breakeven_buffer_pct: 2.0,
+            history_len: 240,
+            drop_interval_secs: 5,
+            drop_interval_pct: 5.0,
+            drop_intervals: 0,
+            drain_window_samples: 4,
+            max_drain_sol_per_sec: 0.0,
+            max_drain_pct_per_sec: 0.0,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Отклоняет бессмысленные сочетания порогов
+    pub fn validate(&self) -> Result<()> {
+        let in_range = |name: &str, v: f64, max_inclusive: bool| -> Result<()> {
+            let ok = v > 0.0 && if max_inclusive { v <= 100.0 } else { v < 100.0 };
+            anyhow::ensure!(ok, "{} вне допустимого диапазона: {}", name, v);
+            Ok(())
+        };
+        in_range("rug_reserve_drop_pct", self.rug_reserve_drop_pct, true)?;
+        in_range("panic_drawdown_pct", self.panic_drawdown_pct, true)?;
+        in_range("timeout_sell_pct", self.timeout_sell_pct, true)?;
+        in_range("trailing_stop_pct", self.trailing_stop_pct, false)?;
+        for &(multiple, pct) in &self.trailing_schedule {
+            anyhow::ensure!(
+                multiple >= 1.0,
+                "trailing_schedule: множитель меньше 1: {}",
+                multiple
+            );
+            in_range("trailing_schedule", pct, false)?;
+        }
+        anyhow::ensure!(
+            self.trailing_schedule.windows(2).all(|w| w[0].0 < w[1].0),
+            "trailing_schedule должен идти по возрастанию множителя: {:?}",
+            self.trailing_schedule
+        );
+        anyhow::ensure!(
+            !matches!(self.exit_style, ExitStyle::Tranches { parts: 0, .. }),
+            "exit_style: parts должен быть больше 0"
+        );
+        anyhow::ensure!(
+            self.trailing_activation_multiple >= 1.0,
+            "trailing_activation_multiple меньше 1: {}",
+            self.trailing_activation_multiple
+        );
+        anyhow::ensure!(
+            (0.0..100.0).contains(&self.moon_allocation_pct),
+            "moon_allocation_pct вне допустимого диапазона: {}",
+            self.moon_allocation_pct
+        );
+        anyhow::ensure!(
+            self.timeout_min_gain_pct >= 0.0,
+            "timeout_min_gain_pct не может быть отрицательным"
+        );
+        anyhow::ensure!(
+            self.moon_multiplier > 1.0,
+            "moon_multiplier должен быть больше 1: {}",
+            self.moon_multiplier
+        );
+        anyhow::ensure!(
+            self.moon_min_volume_sol >= 0.0,
+            "moon_min_volume_sol не может быть отрицательным"
+        );
+        anyhow::ensure!(
+            self.volume_window_secs > 0 && self.volume_poll_secs > 0,
+            "volume_window_secs и volume_poll_secs должны быть больше 0"
+        );
+        anyhow::ensure!(
+            self.sell_slippage_ladder_bps
+                .iter()
+                .all(|bps| (1..=10_000).contains(bps)),
+            "ступени проскальзывания должны быть в 1..=10000 б.п.: {:?}",
+            self.sell_slippage_ladder_bps
+        );
+        anyhow::ensure!(
+            self.min_exit_proceeds_sol >= 0.0,
+            "min_exit_proceeds_sol не может быть отрицательным"
+        );
+        anyhow::ensure!(
+            self.min_proceeds_sol >= 0.0,
+            "min_proceeds_sol не может быть отрицательным"
+        );
+        anyhow::ensure!(
+            self.priority_fee_multiplier >= 0.0 && self.compute_unit_limit > 0,
+            "priority_fee_multiplier не может быть отрицательным, compute_unit_limit — нулевым"
+        );
+        anyhow::ensure!(
+            self.max_hold_secs != Some(0),
+            "max_hold_secs должен быть больше 0"
+        );
+        anyhow::ensure!(
+            self.min_pool_sol >= 0.0,
+            "min_pool_sol не может быть отрицательным"
+        );
+        anyhow::ensure!(
+            (0.0..=100.0).contains(&self.creator_dump_pct),
+            "creator_dump_pct вне допустимого диапазона: {}",
+            self.creator_dump_pct
+        );
+        if self.whale_sell_pct > 0.0 {
+            in_range("whale_sell_pct", self.whale_sell_pct, true)?;
+            match self.whale_reaction {
+                WhaleReaction::Exit { fraction } => anyhow::ensure!(
+                    fraction > 0.0 && fraction <= 1.0,
+                    "whale_reaction: доля вне (0, 1]: {}",
+                    fraction
+                ),
+                WhaleReaction::Tighten {
+                    trailing_stop_pct,
+                    secs,
+                } => {
+                    in_range("whale_reaction.trailing_stop_pct", trailing_stop_pct, false)?;
+                    anyhow::ensure!(secs > 0, "whale_reaction: secs должен быть больше 0");
+                }
+            }
+        }
+        anyhow::ensure!(
+            self.tick_interval_ms > 0,
+            "tick_interval_ms должен быть больше 0"
+        );
+        if self.adaptive_interval {
+            anyhow::ensure!(
+                self.fast_interval_ms > 0 && self.fast_interval_ms <= self.slow_interval_ms,
+                "нужно 0 < fast_interval_ms <= slow_interval_ms: {} / {}",
+                self.fast_interval_ms,
+                self.slow_interval_ms
+            );
+            anyhow::ensure!(
+                self.fast_window_secs <= self.slow_after_secs,
+                "fast_window_secs больше slow_after_secs"
+            );
+        }
+        for &(multiple, fraction) in &self.take_profit_tiers {
+            anyhow::ensure!(
+                multiple > 1.0 && fraction > 0.0 && fraction <= 1.0,
+                "неверная ступень фиксации прибыли: {}x, доля {}",
+                multiple,
+                fraction
+            );
+        }
+        let total: f64 = self.take_profit_tiers.iter().map(|t| t.1).sum();
+        anyhow::ensure!(
+            total <= 1.0 + f64::EPSILON,
+            "ступени фиксации прибыли продают больше позиции: {}",
+            total
+        );
+        if let Some(multiple) = self.move_stop_to_breakeven_after {
+            anyhow::ensure!(
+                multiple > 1.0,
+                "move_stop_to_breakeven_after должен быть больше 1: {}",
+                multiple
+            );
+        }
+        anyhow::ensure!(
+            (0.0..100.0).contains(&self.breakeven_buffer_pct),
+            "breakeven_buffer_pct вне допустимого диапазона: {}",
+            self.breakeven_buffer_pct
+        );
+        anyhow::ensure!(self.history_len > 0, "history_len должен быть больше 0");
+        anyhow::ensure!(
+            self.drop_interval_secs > 0,
+            "drop_interval_secs должен быть больше 0"
+        );
+        in_range("drop_interval_pct", self.drop_interval_pct, true)?;
+        anyhow::ensure!(
+            self.drain_window_samples >= 2,
+            "drain_window_samples должен быть не меньше 2"
+        );
+        anyhow::ensure!(
+            self.max_drain_sol_per_sec >= 0.0 && self.max_drain_pct_per_sec >= 0.0,
+            "пороги оттока резерва не могут быть отрицательными"
+        );
+        Ok(())
+    }
+
+    /// Проскальзывание по попыткам продажи: базовое, затем ступени выше него.
+    /// Попыток не больше `sell_max_attempts`; последняя ступень повторяется, если их меньше.
+    pub fn slippage_ladder(&self, base_bps: u16) -> Vec<u16> {
+        let mut ladder = vec![base_bps];
+        if self.sell_slippage_ladder_bps.is_empty() {
+            ladder.push(base_bps.saturating_mul(2).min(10_000));
+        } else {
+            for &bps in &self.sell_slippage_ladder_bps {
+                if bps > *ladder.last().unwrap() {
+                    ladder.push(bps);
+                }
+            }
+        }
+        if self.sell_max_attempts > 0 {
+            let last = *ladder.last().unwrap();
+            ladder.resize(self.sell_max_attempts, last);
+        }
+        ladder
+    }
+
+    /// Симулировать ли продажу такой срочности перед отправкой
+    pub fn simulates(&self, urgency: Urgency) -> bool {
+        self.simulate_exits && (urgency != Urgency::Emergency || self.simulate_emergency_exits)
+    }
+
+    /// Комиссии продажи с запасом, lamports: базовая, потолок приоритетной и чаевые Jito
+    pub fn sell_fee_lamports(&self, urgency: Urgency) -> u64 {
+        let tip = match urgency {
+            Urgency::Emergency => self.jito_emergency_tip_lamports.max(self.jito_tip_lamports),
+            Urgency::Normal | Urgency::Forced => self.jito_tip_lamports,
+        };
+        // Чаевые уходят отдельной транзакцией бандла со своей базовой комиссией
+        let tip_fee = if tip > 0 { tip + BASE_FEE_LAMPORTS } else { 0 };
+        BASE_FEE_LAMPORTS + self.priority_fee_cap_lamports + tip_fee
+    }
+
+    /// Продажа `tokens` (сырые единицы) по `price` не стоит комиссий: выручка с учётом
+    /// проскальзывания за вычетом `sell_fee_lamports` ниже `min_proceeds_sol`.
+    /// Возвращает (выручку, комиссии), lamports; `None` — продавать.
+    pub fn dust_check(
+        &self,
+        tokens: u64,
+        price: f64,
+        slippage_bps: u16,
+        urgency: Urgency,
+    ) -> Option<(u64, u64)> {
+        if self.min_proceeds_sol <= 0.0 {
+            return None;
+        }
+        let proceeds = pump_sell::estimate_proceeds(tokens, TOKEN_DECIMALS, price, slippage_bps);
+        let fees = self.sell_fee_lamports(urgency);
+        let floor = (self.min_proceeds_sol * LAMPORTS_PER_SOL as f64) as u64;
+        (proceeds.saturating_sub(fees) < floor).then_some((proceeds, fees))
+    }
+
+    /// Интервал до следующего тика по возрасту позиции и изменению цены за последний тик, %.
+    /// Между `fast_window_secs` и `slow_after_secs` растёт линейно от быстрого к медленному.
+    pub fn tick_interval(&self, elapsed: Duration, last_change_pct: f64) -> Duration {
+        if !self.adaptive_interval {
+            return Duration::from_millis(self.tick_interval_ms);
+        }
+        let (fast, slow) = (self.fast_interval_ms, self.slow_interval_ms);
+        let secs = elapsed.as_secs_f64();
+        let ms = if last_change_pct.abs() >= self.volatility_pct
+            || secs <= self.fast_window_secs as f64
+        {
+            fast
+        } else if secs >= self.slow_after_secs as f64 {
+            slow
+        } else {
+            let ramp = (self.slow_after_secs - self.fast_window_secs) as f64;
+            let t = (secs - self.fast_window_secs as f64) / ramp;
+            fast + ((slow - fast) as f64 * t) as u64
+        };
+        Duration::from_millis(ms)
+    }
+
+    /// Жёсткий стоп: порог panic-sell, после переноса — вход плюс запас
+    pub(super) fn stop_price(&self, entry_price: f64, breakeven: bool) -> f64 {
+        if breakeven {
+            entry_price * (1.0 + self.breakeven_buffer_pct / 100.0)
+        } else {
+            entry_price * (1.0 - self.panic_drawdown_pct / 100.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_interval_by_age_and_volatility() {
+        let fixed = RiskConfig::default();
+        assert_eq!(
+            fixed.tick_interval(Duration::ZERO, 50.0),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            fixed.tick_interval(Duration::from_secs(3600), 0.0),
+            Duration::from_millis(500)
+        );
+
+        let config = RiskConfig {
+            adaptive_interval: true,
+            ..Default::default()
+        };
+        config.validate().unwrap();
+        let ms = |secs: u64, change: f64| {
+            config
+                .tick_interval(Duration::from_secs(secs), change)
+                .as_millis() as u64
+        };
+        // Быстро в первые 60 сек, медленно после 600, между ними — линейно
+        for (secs, expected) in [
+            (0, 100),
+            (60, 100),
+            (195, 575),
+            (330, 1050),
+            (599, 1996),
+            (600, 2000),
+            (3600, 2000),
+        ] {
+            assert_eq!(ms(secs, 0.0), expected, "{} сек", secs);
+        }
+        // Резкое движение в любую сторону возвращает быстрый интервал
+        assert_eq!(ms(3600, 2.0), 100);
+        assert_eq!(ms(330, -2.5), 100);
+        assert_eq!(ms(3600, 1.9), 2000);
+    }
+
+    #[test]
+    fn dust_check_boundary() {
+        let config = RiskConfig {
+            min_proceeds_sol: 0.001,
+            jito_emergency_tip_lamports: 100_000,
+            ..Default::default()
+        };
+        assert_eq!(config.sell_fee_lamports(Urgency::Normal), 5_000);
+        assert_eq!(config.sell_fee_lamports(Urgency::Emergency), 110_000);
+        // 1005 токенов по 1e-6 SOL: 1_005_000 lamports − 5_000 ровно на пороге
+        let price = 0.000_001;
+        assert_eq!(
+            config.dust_check(1_005_000_000, price, 0, Urgency::Normal),
+            None
+        );
+        assert_eq!(
+            config.dust_check(1_004_999_999, price, 0, Urgency::Normal),
+            Some((1_004_999, 5_000))
+        );
+        // Проскальзывание 1% и чаевые Jito считаются против той же выручки
+        assert_eq!(
+            config.dust_check(1_005_000_000, price, 100, Urgency::Normal),
+            Some((994_950, 5_000))
+        );
+        assert_eq!(
+            config.dust_check(1_005_000_000, price, 0, Urgency::Emergency),
+            Some((1_005_000, 110_000))
+        );
+        assert_eq!(
+            config.dust_check(1_110_000_000, price, 0, Urgency::Emergency),
+            None
+        );
+
+        // Порог выключен — продаём даже пыль
+        let off = RiskConfig::default();
+        assert_eq!(off.dust_check(1, price, 0, Urgency::Emergency), None);
+    }
+}
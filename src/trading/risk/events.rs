@@ -0,0 +1,139 @@
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::{ExitReason, ExitSummary, WhaleReaction};
+use crate::trading::{breaker::TripReason, history::ReserveDrain, pump_sell::SellReceipt};
+
+/// Событие монитора: решение о выходе или результат продажи
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum RiskEvent {
+    /// Мониторинг позиции запущен
+    Opened {
+        stake_sol: f64,
+        entry_price: f64,
+    },
+    /// Freeze authority не отозван на входе
+    FreezeAuthority,
+    /// Резерв SOL упал от входа или быстро утекает
+    RugPull {
+        reserve: u64,
+        drop_pct: f64,
+        drain: Option<ReserveDrain>,
+    },
+    /// Резерв SOL пула ниже `min_pool_sol` (lamports)
+    LiquidityFloor {
+        reserve: u64,
+        floor: u64,
+    },
+    /// Цена ниже порога panic-sell или слив лесенкой
+    PanicSell {
+        price: f64,
+        drawdown_pct: f64,
+    },
+    /// Стоп перенесён в безубыток
+    BreakevenArmed {
+        price: f64,
+        stop_price: f64,
+    },
+    /// Цена вернулась к стопу в безубытке
+    BreakevenStop {
+        price: f64,
+        stop_price: f64,
+    },
+    /// Нет роста за `timeout_secs` — частичная продажа
+    TimeoutPartial {
+        fraction: f64,
+        elapsed_secs: u64,
+    },
+    /// Пик дошёл до `trailing_activation_multiple` — trailing stop включён
+    TrailingArmed {
+        peak_price: f64,
+        multiple: f64,
+    },
+    TrailingStop {
+        price: f64,
+        peak_price: f64,
+        drawdown_pct: f64,
+    },
+    /// Продажа за тик вынула `reserve_drop_pct` % резерва SOL пула
+    WhaleDump {
+        reserve_drop_pct: f64,
+        reaction: WhaleReaction,
+    },
+    /// Создатель продал `amount_pct` % от максимума своего баланса — выход целиком
+    CreatorDump {
+        amount_pct: f64,
+    },
+    /// Общий supply mint-а вырос с `old` до `new` (сырые единицы) — выход целиком
+    SupplyInflated {
+        old: u64,
+        new: u64,
+    },
+    /// У mint-а снова есть mint authority — выпуск не закрыт, выход целиком
+    MintAuthority {
+        authority: Pubkey,
+    },
+    /// Истёк `max_hold_secs` — продаётся весь остаток
+    MaxHoldExit {
+        elapsed_secs: u64,
+    },
+    /// Продажа лунной доли по множителю или по таймеру
+    MoonExit {
+        price: f64,
+        multiple: f64,
+    },
+    /// Сработала ступень `take_profit_tiers`
+    TierHit {
+        tier: usize,
+        multiple: f64,
+        fraction: f64,
+    },
+    SellExecuted(SellReceipt),
+    /// Попытка продажи не прошла, следующая — с большим проскальзыванием
+    SellRetry {
+        reason: ExitReason,
+        attempt: usize,
+        slippage_bps: u16,
+        error: String,
+    },
+    /// Мониторинг сбоит `consecutive_errors` тиков подряд — защитный выход
+    Degraded {
+        consecutive_errors: u32,
+    },
+    /// Наш токен-аккаунт заморожен (honeypot): продажи прекращены, позиция списана
+    Frozen {
+        account: Pubkey,
+    },
+    /// Выручка за вычетом комиссий ниже `min_proceeds_sol`: продажа пропущена,
+    /// остаток списан, токен-аккаунт `account` ждёт закрытия (возврат ренты)
+    DustSkipped {
+        proceeds_sol: f64,
+        fees_sol: f64,
+        account: Pubkey,
+    },
+    /// Перед продажей на кошельке не оказалось токенов — позиция закрыта
+    /// с `ExitReason::External`; токены ушли мимо бота
+    ZeroBalance {
+        reason: ExitReason,
+    },
+    /// Продажа не прошла, доля вернулась в позицию
+    SellFailed {
+        reason: ExitReason,
+        error: String,
+    },
+    /// Позиция закрыта полностью
+    Closed(ExitSummary),
+    /// Сработал предохранитель `PositionManager`: все позиции закрываются,
+    /// новые не открываются (событие общее, не по mint-у)
+    CircuitBreaker {
+        reason: TripReason,
+    },
+}
+
+/// Событие с mint-ом позиции: один канал можно раздать нескольким мониторам
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionEvent {
+    pub mint: Pubkey,
+    pub event: RiskEvent,
+}
@@ -0,0 +1,1170 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::time::Duration;
+
+use super::{RiskConfig, RiskEvent, RiskState, WhaleReaction};
+use crate::trading::{fees::Urgency, history::PriceSample, pump_sell::TokenAmount};
+
+/// Относительный рост supply, который ещё не считается допечаткой
+pub(super) const SUPPLY_EPSILON: f64 = 1e-9;
+
+/// Почему продаётся позиция
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    /// Freeze authority не отозван на входе
+    FreezeAuthority,
+    RugPull,
+    /// Резерв SOL пула ниже `min_pool_sol`
+    LiquidityFloor,
+    PanicSell,
+    /// Нет роста за `timeout_secs` — частичная продажа
+    Timeout,
+    TrailingStop,
+    /// Продажа лунной доли
+    Moon,
+    /// Цена вернулась к стопу в безубытке
+    BreakevenStop,
+    /// Ступень `take_profit_tiers` с индексом `tier`
+    TakeProfit {
+        tier: usize,
+    },
+    /// Ручное закрытие (`PositionManager::close`)
+    Manual,
+    /// Токенов на кошельке не осталось — позиция закрыта вне монитора
+    External,
+    /// Истёк `max_hold_secs`
+    MaxHold,
+    /// Создатель продаёт свои токены
+    CreatorDump,
+    /// Supply mint-а вырос после входа или появился mint authority — нас размывают
+    SupplyInflated,
+    /// Крупная продажа вынула заметную часть резерва (`WhaleReaction::Exit`)
+    WhaleDump,
+    /// Защитный выход после `max_consecutive_errors` сбоев мониторинга подряд
+    Degraded,
+    /// Наш токен-аккаунт заморожен: продать нельзя, позиция списана в убыток
+    Frozen,
+    /// Остаток дешевле комиссий продажи (`min_proceeds_sol`): списан как пыль
+    Dust,
+    /// Мониторинг остановлен до закрытия позиции
+    Stopped,
+}
+
+impl ExitReason {
+    /// Срочность продажи: выходы из-под обвала платят максимальную комиссию
+    pub fn urgency(self) -> Urgency {
+        match self {
+            Self::RugPull
+            | Self::LiquidityFloor
+            | Self::PanicSell
+            | Self::FreezeAuthority
+            | Self::CreatorDump
+            | Self::SupplyInflated => Urgency::Emergency,
+            // Срок вышел или цены нет — продаём при любой цене
+            Self::MaxHold | Self::Degraded => Urgency::Forced,
+            _ => Urgency::Normal,
+        }
+    }
+}
+
+/// Решение продать часть позиции
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sale {
+    pub reason: ExitReason,
+    /// Доля исходной позиции
+    pub fraction: f64,
+    /// Доля того, что было на руках перед продажей
+    pub share: f64,
+    /// Точное количество токенов (сырые единицы) вместо `share`
+    pub tokens: Option<u64>,
+}
+
+impl Sale {
+    /// Сколько продавать от баланса кошелька
+    pub fn amount(&self) -> TokenAmount {
+        self.tokens
+            .map_or(TokenAmount::Share(self.share), TokenAmount::Raw)
+    }
+}
+
+/// Что монитор должен сделать по итогам тика
+#[derive(Debug, Clone)]
+pub enum RiskAction {
+    /// Только сообщить
+    Notify(RiskEvent),
+    /// Продать долю позиции
+    Sell { sale: Sale, event: RiskEvent },
+}
+
+impl RiskConfig {
+    /// Решения по одной точке цены: обновляет состояние позиции и возвращает действия.
+    /// Сети не трогает — продажи исполняет `RiskMonitor`.
+    pub fn evaluate(
+        &self,
+        state: &mut RiskState,
+        sample: &PriceSample,
+        elapsed: Duration,
+    ) -> Vec<RiskAction> {
+        let current_price = sample.price;
+        let quote_reserve = sample.sol_reserve;
+        let mut actions = Vec::new();
+
+        if state.entry_price <= 0.0 {
+            state.entry_price = current_price;
+        }
+        // Обновляем пик
+        if current_price > state.peak_price {
+            state.peak_price = current_price;
+        }
+        actions.extend(self.update_stop(state, current_price));
+        actions.extend(self.arm_trailing(state));
+        let previous_reserve = state.history.latest().map(|s| s.sol_reserve);
+        state.history.push(*sample);
+        // Пустая кривая не годится как база для rug-pull
+        if state.initial_reserve.is_none() && quote_reserve > 0 {
+            state.initial_reserve = Some(quote_reserve);
+        }
+
+        if let Some(previous) = previous_reserve {
+            actions.extend(self.check_whale_sell(state, previous, quote_reserve, elapsed));
+        }
+        // Продавать нечего: остальные условия дали бы пустые продажи
+        if state.is_closed() {
+            return actions;
+        }
+
+        // 2. Трёхуровневый стоп-лосс; первое сработавшее полное закрытие — последнее
+        let exit = self
+            .check_max_hold(state, elapsed)
+            .or_else(|| self.check_reserve_drain(state, quote_reserve))
+            .or_else(|| self.check_rug_pull(state, quote_reserve))
+            .or_else(|| self.check_liquidity_floor(state, quote_reserve))
+            .or_else(|| self.check_panic_sell(state, current_price, elapsed))
+            .or_else(|| self.check_trailing_stop(state, current_price, elapsed));
+        match exit {
+            Some(action) => actions.push(action),
+            // Фиксация прибыли по ступеням, если позиция не закрывается целиком
+            None => actions.extend(self.check_take_profit(state, current_price)),
+        }
+
+        // 3. Moon Mode: условия выхода
+        actions.extend(self.check_moon_exit(state, current_price, elapsed));
+        actions
+    }
+
+    /// Крупная продажа: резерв за тик упал от `previous_reserve` не меньше чем на
+    /// `whale_sell_pct` — реакция по `whale_reaction`
+    fn check_whale_sell(
+        &self,
+        state: &mut RiskState,
+        previous_reserve: u64,
+        current_reserve: u64,
+        elapsed: Duration,
+    ) -> Option<RiskAction> {
+        if self.whale_sell_pct <= 0.0 || previous_reserve == 0 || state.is_closed() {
+            return None;
+        }
+        let reserve_drop_pct = reserve_drop_pct(previous_reserve, current_reserve);
+        if reserve_drop_pct < self.whale_sell_pct {
+            return None;
+        }
+        let event = RiskEvent::WhaleDump {
+            reserve_drop_pct,
+            reaction: self.whale_reaction,
+        };
+        match self.whale_reaction {
+            WhaleReaction::Exit { fraction } => {
+                log::warn!(
+                    "🐋 Крупная продажа: −{:.1}% резерва за тик → продаём {:.0}% позиции",
+                    reserve_drop_pct,
+                    fraction * 100.0
+                );
+                Some(RiskAction::Sell {
+                    sale: state.take(fraction, ExitReason::WhaleDump),
+                    event,
+                })
+            }
+            WhaleReaction::Tighten {
+                trailing_stop_pct,
+                secs,
+            } => {
+                log::warn!(
+                    "🐋 Крупная продажа: −{:.1}% резерва за тик → trailing stop {}% на {} сек",
+                    reserve_drop_pct,
+                    trailing_stop_pct,
+                    secs
+                );
+                state.tight_trailing =
+                    Some((trailing_stop_pct, elapsed + Duration::from_secs(secs)));
+                Some(RiskAction::Notify(event))
+            }
+        }
+    }
+
+    /// Trailing stop по `trailing_schedule` для пикового множителя
+    pub fn trailing_pct_for(&self, peak_multiple: f64) -> f64 {
+        self.trailing_schedule
+            .iter()
+            .rev()
+            .find(|(multiple, _)| peak_multiple >= *multiple)
+            .map_or(self.trailing_stop_pct, |(_, pct)| *pct)
+    }
+
+    /// Trailing stop в момент `elapsed`: по расписанию от пика, а после крупной
+    /// продажи — суженный, пока не истёк. Запоминается в `RiskState::trailing_pct`.
+    pub fn trailing_stop_pct_at(&self, state: &mut RiskState, elapsed: Duration) -> f64 {
+        let scheduled = if state.entry_price > 0.0 {
+            self.trailing_pct_for(state.peak_price / state.entry_price)
+        } else {
+            self.trailing_stop_pct
+        };
+        let pct = match state.tight_trailing {
+            Some((pct, until)) if elapsed < until => pct.min(scheduled),
+            Some(_) => {
+                log::info!("🐋 Trailing stop снова {}%", scheduled);
+                state.tight_trailing = None;
+                scheduled
+            }
+            None => scheduled,
+        };
+        if pct != state.trailing_pct {
+            log::debug!("📉 Trailing stop: {}% → {}%", state.trailing_pct, pct);
+            state.trailing_pct = pct;
+        }
+        pct
+    }
+
+    /// Продажа создателя: баланс `creator_balance` (сырые единицы) упал от максимума
+    /// не меньше чем на `creator_dump_pct` — продаём весь остаток
+    pub fn check_creator_dump(
+        &self,
+        state: &mut RiskState,
+        creator_balance: u64,
+    ) -> Option<RiskAction> {
+        if self.creator_dump_pct <= 0.0 || state.creator_dump_triggered || state.is_closed() {
+            return None;
+        }
+        state.creator_peak_balance = state.creator_peak_balance.max(creator_balance);
+        let peak = state.creator_peak_balance;
+        if peak == 0 {
+            return None;
+        }
+        let amount_pct = (peak - creator_balance) as f64 / peak as f64 * 100.0;
+        if amount_pct < self.creator_dump_pct {
+            return None;
+        }
+        log::warn!(
+            "🚨 Создатель продал {:.1}% своих токенов → выходим целиком!",
+            amount_pct
+        );
+        state.creator_dump_triggered = true;
+        Some(RiskAction::Sell {
+            sale: state.take(1.0, ExitReason::CreatorDump),
+            event: RiskEvent::CreatorDump { amount_pct },
+        })
+    }
+
+    /// Общий supply mint-а `supply` (сырые единицы) против зафиксированного при входе:
+    /// рост больше `SUPPLY_EPSILON` — продаём весь остаток. Первое значение без входного
+    /// становится входным.
+    pub fn check_supply(&self, state: &mut RiskState, supply: u64) -> Option<RiskAction> {
+        if state.supply_triggered || state.is_closed() {
+            return None;
+        }
+        let Some(old) = state.entry_supply else {
+            state.entry_supply = Some(supply);
+            return None;
+        };
+        if supply as f64 <= old as f64 * (1.0 + SUPPLY_EPSILON) {
+            return None;
+        }
+        log::warn!(
+            "🚨 Supply вырос после входа: {} → {} → выходим целиком!",
+            old,
+            supply
+        );
+        state.supply_triggered = true;
+        Some(RiskAction::Sell {
+            sale: state.take(1.0, ExitReason::SupplyInflated),
+            event: RiskEvent::SupplyInflated { old, new: supply },
+        })
+    }
+
+    /// Предельный срок удержания: весь остаток, цена не важна
+    pub(super) fn check_max_hold(
+        &self,
+        state: &mut RiskState,
+        elapsed: Duration,
+    ) -> Option<RiskAction> {
+        let limit = self.max_hold_secs?;
+        if state.is_closed() || elapsed.as_secs() < limit {
+            return None;
+        }
+        log::warn!(
+            "⌛ Позиция держится {} сек (лимит {}) → продаём всё",
+            elapsed.as_secs(),
+            limit
+        );
+        Some(RiskAction::Sell {
+            sale: state.take(1.0, ExitReason::MaxHold),
+            event: RiskEvent::MaxHoldExit {
+                elapsed_secs: elapsed.as_secs(),
+            },
+        })
+    }
+
+    /// Уровень 0: скорость оттока ликвидности — сильнейший сигнал, проверяется первым
+    fn check_reserve_drain(
+        &self,
+        state: &mut RiskState,
+        current_reserve: u64,
+    ) -> Option<RiskAction> {
+        if state.rug_triggered {
+            return None;
+        }
+        let drain = state.history.reserve_drain(self.drain_window_samples)?;
+        let by_sol =
+            self.max_drain_sol_per_sec > 0.0 && drain.sol_per_sec >= self.max_drain_sol_per_sec;
+        let by_pct =
+            self.max_drain_pct_per_sec > 0.0 && drain.pct_per_sec >= self.max_drain_pct_per_sec;
+        if by_sol || by_pct {
+            log::error!(
+                "🚨 RUG-PULL DETECTED! Резерв утекает {:.2} SOL/сек ({:.1}%/сек)",
+                drain.sol_per_sec,
+                drain.pct_per_sec
+            );
+            state.rug_triggered = true;
+            let initial = state.initial_reserve.unwrap_or(0);
+            return Some(RiskAction::Sell {
+                sale: state.take(1.0, ExitReason::RugPull),
+                event: RiskEvent::RugPull {
+                    reserve: current_reserve,
+                    drop_pct: reserve_drop_pct(initial, current_reserve),
+                    drain: Some(drain),
+                },
+            });
+        }
+        None
+    }
+
+    /// Уровень 1: Rug-pull — резерв упал на `rug_reserve_drop_pct` от резерва при входе
+    fn check_rug_pull(&self, state: &mut RiskState, current_reserve: u64) -> Option<RiskAction> {
+        let initial_reserve = state.initial_reserve.unwrap_or(0);
+        if state.rug_triggered || initial_reserve == 0 {
+            return None;
+        }
+        let drop_pct = reserve_drop_pct(initial_reserve, current_reserve);
+        if drop_pct >= self.rug_reserve_drop_pct {
+            log::error!("🚨 RUG-PULL DETECTED! Резерв упал на {:.1}%", drop_pct);
+            state.rug_triggered = true;
+            return Some(RiskAction::Sell {
+                sale: state.take(1.0, ExitReason::RugPull), // продаём 100%
+                event: RiskEvent::RugPull {
+                    reserve: current_reserve,
+                    drop_pct,
+                    drain: None,
+                },
+            });
+        }
+        None
+    }
+
+    /// Резерв SOL пула ниже `min_pool_sol` — выход целиком, как бы он туда ни попал
+    fn check_liquidity_floor(
+        &self,
+        state: &mut RiskState,
+        current_reserve: u64,
+    ) -> Option<RiskAction> {
+        let floor = self.min_pool_lamports();
+        // 0 — резерв неизвестен
+        if state.floor_triggered || floor == 0 || current_reserve == 0 || current_reserve >= floor {
+            return None;
+        }
+        log::error!(
+            "🚨 Резерв пула {:.2} SOL ниже порога {} SOL → выходим целиком!",
+            current_reserve as f64 / LAMPORTS_PER_SOL as f64,
+            self.min_pool_sol
+        );
+        state.floor_triggered = true;
+        Some(RiskAction::Sell {
+            sale: state.take(1.0, ExitReason::LiquidityFloor),
+            event: RiskEvent::LiquidityFloor {
+                reserve: current_reserve,
+                floor,
+            },
+        })
+    }
+
+    fn min_pool_lamports(&self) -> u64 {
+        (self.min_pool_sol * LAMPORTS_PER_SOL as f64) as u64
+    }
+
+    /// Проверка пула на входе: резерв ниже `min_pool_sol` — ошибка
+    pub fn ensure_pool_floor(&self, reserve: u64) -> Result<()> {
+        let floor = self.min_pool_lamports();
+        anyhow::ensure!(
+            reserve >= floor,
+            "резерв пула {:.4} SOL ниже min_pool_sol {} SOL",
+            reserve as f64 / LAMPORTS_PER_SOL as f64,
+            self.min_pool_sol
+        );
+        Ok(())
+    }
+
+    /// Пересчёт жёсткого стопа; перенос в безубыток необратим
+    fn update_stop(&self, state: &mut RiskState, current_price: f64) -> Option<RiskAction> {
+        let mut armed = None;
+        if !state.breakeven_armed {
+            if let Some(multiple) = self.move_stop_to_breakeven_after {
+                if current_price >= state.entry_price * multiple {
+                    state.breakeven_armed = true;
+                    log::info!(
+                        "🛡️ Цена {:.1}x от входа → стоп перенесён в безубыток",
+                        current_price / state.entry_price
+                    );
+                    armed = Some(current_price);
+                }
+            }
+        }
+        state.stop_price = self.stop_price(state.entry_price, state.breakeven_armed);
+        armed.map(|price| {
+            RiskAction::Notify(RiskEvent::BreakevenArmed {
+                price,
+                stop_price: state.stop_price,
+            })
+        })
+    }
+
+    /// Уровень 2: Panic-sell — цена ниже жёсткого стопа (`panic_drawdown_pct` от входа
+    /// или безубыток) либо нет роста `timeout_secs`
+    fn check_panic_sell(
+        &self,
+        state: &mut RiskState,
+        current_price: f64,
+        elapsed: Duration,
+    ) -> Option<RiskAction> {
+        let drawdown_pct = (state.entry_price - current_price) / state.entry_price * 100.0;
+
+        // Если цена упала ниже стопа — экстренная продажа ВСЕГО
+        if current_price <= state.stop_price && !state.panic_triggered {
+            state.panic_triggered = true;
+            if state.breakeven_armed {
+                log::warn!(
+                    "🛡️ Стоп в безубытке: цена {} ниже {} → продажа остатка",
+                    current_price,
+                    state.stop_price
+                );
+                return Some(RiskAction::Sell {
+                    sale: state.take(1.0, ExitReason::BreakevenStop),
+                    event: RiskEvent::BreakevenStop {
+                        price: current_price,
+                        stop_price: state.stop_price,
+                    },
+                });
+            }
+            log::error!("🔥 PANIC SELL! Цена упала на {:.1}%", drawdown_pct);
+            return Some(RiskAction::Sell {
+                sale: state.take(1.0, ExitReason::PanicSell),
+                event: RiskEvent::PanicSell {
+                    price: current_price,
+                    drawdown_pct,
+                },
+            });
+        }
+        // Серия мелких свечей вниз — слив лесенкой, не дожидаемся общего порога
+        if self.drop_intervals > 0 && !state.panic_triggered {
+            let drops = state.history.consecutive_drops(
+                Duration::from_secs(self.drop_interval_secs),
+                self.drop_interval_pct,
+            );
+            if drops >= self.drop_intervals {
+                log::error!(
+                    "🔥 PANIC SELL! {} интервалов по {} сек подряд падение ≥ {:.1}% ({:?})",
+                    drops,
+                    self.drop_interval_secs,
+                    self.drop_interval_pct,
+                    state.history.rolling_returns()
+                );
+                state.panic_triggered = true;
+                return Some(RiskAction::Sell {
+                    sale: state.take(1.0, ExitReason::PanicSell),
+                    event: RiskEvent::PanicSell {
+                        price: current_price,
+                        drawdown_pct,
+                    },
+                });
+            }
+        }
+        // Если нет роста — продаём часть (один раз)
+        if elapsed.as_secs() > self.timeout_secs
+            && current_price < state.entry_price * (1.0 + self.timeout_min_gain_pct / 100.0)
+            && !state.timeout_triggered
+        {
+            log::warn!(
+                "⏳ Time-out: нет роста {} сек → частичная продажа",
+                self.timeout_secs
+            );
+            state.timeout_triggered = true;
+            let sale = state.take(self.timeout_sell_pct / 100.0, ExitReason::Timeout);
+            return Some(RiskAction::Sell {
+                sale,
+                event: RiskEvent::TimeoutPartial {
+                    fraction: sale.fraction,
+                    elapsed_secs: elapsed.as_secs(),
+                },
+            });
+        }
+        None
+    }
+
+    /// Уровень 3: Trailing stop — падение на `trailing_stop_pct` от максимума после роста
+    fn check_trailing_stop(
+        &self,
+        state: &mut RiskState,
+        current_price: f64,
+        elapsed: Duration,
+    ) -> Option<RiskAction> {
+        let trailing_stop_pct = self.trailing_stop_pct_at(state, elapsed);
+        if state.trailing_triggered || !state.trailing_armed {
+            return None;
+        }
+        let drawdown_pct = (state.peak_price - current_price) / state.peak_price * 100.0;
+        if drawdown_pct >= trailing_stop_pct {
+            log::info!(
+                "📉 Trailing stop: падение на {:.1}% от пика {} → продажа остатка",
+                drawdown_pct,
+                state.peak_price
+            );
+            state.trailing_triggered = true;
+            return Some(RiskAction::Sell {
+                sale: state.take(1.0, ExitReason::TrailingStop), // закрываем всё
+                event: RiskEvent::TrailingStop {
+                    price: current_price,
+                    peak_price: state.peak_price,
+                    drawdown_pct,
+                },
+            });
+        }
+        None
+    }
+
+    /// Включает trailing stop, когда пик дошёл до `trailing_activation_multiple`;
+    /// один раз за позицию
+    fn arm_trailing(&self, state: &mut RiskState) -> Option<RiskAction> {
+        if state.trailing_armed
+            || state.peak_price <= state.entry_price
+            || state.peak_price < state.entry_price * self.trailing_activation_multiple
+        {
+            return None;
+        }
+        state.trailing_armed = true;
+        let multiple = state.peak_price / state.entry_price;
+        log::info!("🎚️ Пик {:.2}x от входа → trailing stop включён", multiple);
+        Some(RiskAction::Notify(RiskEvent::TrailingArmed {
+            peak_price: state.peak_price,
+            multiple,
+        }))
+    }
+
+    /// Ступени фиксации прибыли; лунная доля в расчёт не входит
+    fn check_take_profit(&self, state: &mut RiskState, current_price: f64) -> Vec<RiskAction> {
+        let multiple = current_price / state.entry_price;
+        let moon_share = self.moon_allocation_pct / 100.0;
+        let mut actions = Vec::new();
+        for (tier, &(tier_multiple, fraction)) in self.take_profit_tiers.iter().enumerate() {
+            if multiple < tier_multiple {
+                continue;
+            }
+            match state.tiers_hit.get_mut(tier) {
+                Some(hit) if !*hit => *hit = true,
+                _ => continue,
+            }
+            // Лунную долю не трогаем, пока она не продана
+            let reserved = if state.moon_sold { 0.0 } else { moon_share };
+            let available = (state.remaining - reserved).max(0.0);
+            let amount = (fraction * (1.0 - moon_share)).min(available);
+            if amount <= f64::EPSILON {
+                continue;
+            }
+            log::info!(
+                "🎯 Take-profit {:.1}x: продаём {:.1}% позиции",
+                tier_multiple,
+                amount * 100.0
+            );
+            actions.push(RiskAction::Sell {
+                sale: state.take(amount, ExitReason::TakeProfit { tier }),
+                event: RiskEvent::TierHit {
+                    tier,
+                    multiple: tier_multiple,
+                    fraction: amount,
+                },
+            });
+        }
+        actions
+    }
+
+    /// Moon Mode: умный выход для лунной доли позиции
+    fn check_moon_exit(
+        &self,
+        state: &mut RiskState,
+        current_price: f64,
+        elapsed: Duration,
+    ) -> Option<RiskAction> {
+        if state.moon_sold || state.is_closed() {
+            return None;
+        }
+        let moon_multiplier = current_price / state.entry_price;
+
+        // Условие 1: множитель И объём за окно не меньше `moon_min_volume_sol`
+        let volume_ok = self.moon_min_volume_sol <= 0.0
+            || state
+                .volume_sol
+                .is_some_and(|v| v >= self.moon_min_volume_sol);
+        let by_multiple = moon_multiplier >= self.moon_multiplier && volume_ok;
+        if by_multiple {
+            log::info!(
+                "🌕 MOON MODE: +{:.0}x → фиксируем лунную долю!",
+                moon_multiplier
+            );
+        } else if moon_multiplier >= self.moon_multiplier {
+            log::debug!(
+                "🌕 +{:.0}x, но объём {:?} SOL ниже {} → держим лунную долю",
+                moon_multiplier,
+                state.volume_sol,
+                self.moon_min_volume_sol
+            );
+        }
+
+        // Условие 2: попадание в топ-N трендов DexScreener
+        let by_trending = !by_multiple && self.moon_top_n > 0 && state.in_top_n;
+        if by_trending {
+            log::info!(
+                "🌕 MOON MODE: токен в топ-{} DexScreener → фиксируем лунную долю!",
+                self.moon_top_n
+            );
+        }
+
+        // Условие 3: таймер (по умолчанию 24 часа)
+        let by_timer = !by_multiple && !by_trending && elapsed.as_secs() > self.moon_timer_secs;
+        if by_timer {
+            log::info!("🌕 MOON MODE: таймер истёк → auto-sell лунной доли");
+        }
+        if !by_multiple && !by_trending && !by_timer {
+            return None;
+        }
+        state.moon_sold = true;
+        let mut sale = state.take(self.moon_allocation_pct / 100.0, ExitReason::Moon);
+        sale.tokens = state.moon_tokens;
+        Some(RiskAction::Sell {
+            sale,
+            event: RiskEvent::MoonExit {
+                price: current_price,
+                multiple: moon_multiplier,
+            },
+        })
+    }
+}
+
+/// Падение резерва от входа, %
+pub(super) fn reserve_drop_pct(initial: u64, current: u64) -> f64 {
+    if initial == 0 {
+        return 0.0;
+    }
+    (1.0 - current as f64 / initial as f64) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START_MS: u64 = 1_760_500_000_000;
+    /// Резерв SOL пула, когда он не важен для проверки
+    const RESERVE: u64 = 30 * LAMPORTS_PER_SOL;
+
+    /// Прогоняет точки (сек с входа, цена, резерв) через `evaluate` при входе по 1.0
+    fn replay(
+        config: &RiskConfig,
+        ticks: impl IntoIterator<Item = (u64, f64, u64)>,
+    ) -> (RiskState, Vec<(u64, RiskAction)>) {
+        let mut state = RiskState::new(1.0, config);
+        let mut actions = Vec::new();
+        for (secs, price, sol_reserve) in ticks {
+            let sample = PriceSample {
+                timestamp_ms: START_MS + secs * 1000,
+                price,
+                sol_reserve,
+            };
+            let elapsed = Duration::from_secs(secs);
+            actions.extend(
+                config
+                    .evaluate(&mut state, &sample, elapsed)
+                    .into_iter()
+                    .map(|a| (secs, a)),
+            );
+        }
+        (state, actions)
+    }
+
+    /// Цены раз в секунду при неизменном резерве
+    fn run(config: &RiskConfig, prices: &[f64]) -> (RiskState, Vec<(u64, RiskAction)>) {
+        replay(
+            config,
+            prices
+                .iter()
+                .enumerate()
+                .map(|(i, &price)| (i as u64, price, RESERVE)),
+        )
+    }
+
+    /// Продажи: (секунда, причина, доля исходной позиции до 1e-6)
+    fn sales(actions: &[(u64, RiskAction)]) -> Vec<(u64, ExitReason, f64)> {
+        actions
+            .iter()
+            .filter_map(|(secs, action)| match action {
+                RiskAction::Sell { sale, .. } => {
+                    Some((*secs, sale.reason, (sale.fraction * 1e6).round() / 1e6))
+                }
+                RiskAction::Notify(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trailing_stop_follows_peak() {
+        let config = RiskConfig::default();
+        let (state, actions) = run(&config, &[1.0, 1.5, 2.0, 3.0, 2.5, 2.2, 2.0, 1.9]);
+        // 2.2 — −26.7% от пика 3.0, 2.0 — −33.3%
+        assert_eq!(sales(&actions), [(6, ExitReason::TrailingStop, 1.0)]);
+        assert_eq!(state.peak_price, 3.0);
+        assert!(state.trailing_triggered && state.is_closed());
+        match &actions.last().unwrap().1 {
+            RiskAction::Sell {
+                event:
+                    RiskEvent::TrailingStop {
+                        price, peak_price, ..
+                    },
+                ..
+            } => assert_eq!((*price, *peak_price), (2.0, 3.0)),
+            other => panic!("ожидали TrailingStop, получили {:?}", other),
+        }
+    }
+
+    #[test]
+    fn same_series_under_two_configs() {
+        let prices = [1.0, 2.0, 3.0, 2.0, 1.7, 0.7, 0.5];
+        let (_, actions) = run(&RiskConfig::default(), &prices);
+        assert_eq!(sales(&actions), [(3, ExitReason::TrailingStop, 1.0)]);
+
+        let loose = RiskConfig {
+            trailing_stop_pct: 40.0,
+            ..Default::default()
+        };
+        let (_, actions) = run(&loose, &prices);
+        assert_eq!(sales(&actions), [(4, ExitReason::TrailingStop, 1.0)]);
+
+        // Без trailing stop выход только по panic-sell
+        let panic_only = RiskConfig {
+            trailing_activation_multiple: 10.0,
+            panic_drawdown_pct: 40.0,
+            ..Default::default()
+        };
+        let (_, actions) = run(&panic_only, &prices);
+        assert_eq!(sales(&actions), [(6, ExitReason::PanicSell, 1.0)]);
+    }
+
+    #[test]
+    fn validate_rejects_nonsense() {
+        assert!(RiskConfig::default().validate().is_ok());
+        let bad = [
+            RiskConfig {
+                trailing_stop_pct: 100.0,
+                ..Default::default()
+            },
+            RiskConfig {
+                panic_drawdown_pct: 0.0,
+                ..Default::default()
+            },
+            RiskConfig {
+                rug_reserve_drop_pct: 120.0,
+                ..Default::default()
+            },
+            RiskConfig {
+                moon_multiplier: 1.0,
+                ..Default::default()
+            },
+        ];
+        for config in bad {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn trailing_stop_measures_drawdown_from_peak() {
+        let config = RiskConfig::default();
+        // 2x, затем −25% от пика — держим
+        let (_, actions) = run(&config, &[1.0, 2.0, 1.5, 1.5]);
+        assert!(sales(&actions).is_empty());
+        // −35% — продаём
+        let (_, actions) = run(&config, &[1.0, 2.0, 1.5, 1.3]);
+        assert_eq!(sales(&actions), [(3, ExitReason::TrailingStop, 1.0)]);
+        // Плоская цена на 2x после роста не продаётся никогда
+        let mut prices = vec![1.0];
+        prices.extend([2.0; 300]);
+        let (_, actions) = run(&config, &prices);
+        assert!(sales(&actions).is_empty());
+    }
+
+    #[test]
+    fn timeout_sells_half_once_then_exits_remainder() {
+        let config = RiskConfig::default();
+        let mut prices = vec![1.0; 300];
+        prices.push(0.3);
+        let (state, actions) = run(&config, &prices);
+        assert_eq!(
+            sales(&actions),
+            [
+                (91, ExitReason::Timeout, 0.5),
+                (300, ExitReason::PanicSell, 0.5)
+            ]
+        );
+        // Полный выход считается от остатка
+        match &actions.last().unwrap().1 {
+            RiskAction::Sell { sale, .. } => assert_eq!(sale.share, 1.0),
+            other => panic!("ожидали продажу, получили {:?}", other),
+        }
+        assert!(state.is_closed());
+    }
+
+    #[test]
+    fn take_profit_tiers_fire_once() {
+        let config = RiskConfig {
+            take_profit_tiers: vec![(2.0, 0.25), (3.0, 0.25), (5.0, 0.25)],
+            ..Default::default()
+        };
+        // 2x, откат, снова выше 2x, 3x; 5.5 сразу за последней ступенью
+        let (state, actions) = run(&config, &[1.0, 2.1, 1.8, 2.2, 3.1, 2.9, 5.5]);
+        // Лунные 20% в ступени не входят: 25% от 80% = 20% позиции
+        assert_eq!(
+            sales(&actions),
+            [
+                (1, ExitReason::TakeProfit { tier: 0 }, 0.2),
+                (4, ExitReason::TakeProfit { tier: 1 }, 0.2),
+                (6, ExitReason::TakeProfit { tier: 2 }, 0.2),
+            ]
+        );
+        assert_eq!(state.tiers_hit, [true, true, true]);
+        assert!((state.remaining - 0.4).abs() < 1e-9);
+        assert!(!state.moon_sold);
+
+        // Скачок через две ступени — обе за один тик
+        let (_, actions) = run(&config, &[1.0, 3.5]);
+        assert_eq!(
+            sales(&actions),
+            [
+                (1, ExitReason::TakeProfit { tier: 0 }, 0.2),
+                (1, ExitReason::TakeProfit { tier: 1 }, 0.2),
+            ]
+        );
+    }
+
+    #[test]
+    fn breakeven_stop_after_reaching_multiple() {
+        let config = RiskConfig {
+            move_stop_to_breakeven_after: Some(2.0),
+            ..Default::default()
+        };
+        let (state, actions) = run(&config, &[1.0, 2.5, 0.98]);
+        assert!(matches!(
+            actions[0].1,
+            RiskAction::Notify(RiskEvent::BreakevenArmed { price, .. }) if price == 2.5
+        ));
+        // Стоп — вход плюс 2% запаса
+        assert!((state.stop_price - 1.02).abs() < 1e-9);
+        assert_eq!(sales(&actions), [(2, ExitReason::BreakevenStop, 1.0)]);
+
+        // Без 2x тот же слив идёт по обычным правилам panic-sell
+        let (state, actions) = run(&config, &[1.0, 1.2, 0.98]);
+        assert!(sales(&actions).is_empty());
+        assert!(!state.breakeven_armed);
+        let (_, actions) = run(&config, &[1.0, 1.2, 0.98, 0.35]);
+        assert_eq!(sales(&actions), [(3, ExitReason::PanicSell, 1.0)]);
+    }
+
+    /// Лесенка вниз: каждые 5 сек цена падает на `step_pct`
+    fn stairs(step_pct: f64, secs: usize) -> Vec<f64> {
+        (0..secs)
+            .map(|t| (1.0 - step_pct / 100.0).powi((t / 5) as i32))
+            .collect()
+    }
+
+    #[test]
+    fn stair_step_dump_panics_before_drawdown() {
+        let config = RiskConfig {
+            drop_intervals: 3,
+            drop_interval_secs: 5,
+            drop_interval_pct: 5.0,
+            ..Default::default()
+        };
+        // Три ступени по 6%: всего −17%, до panic_drawdown_pct далеко
+        let (state, actions) = run(&config, &stairs(6.0, 30));
+        assert_eq!(sales(&actions), [(15, ExitReason::PanicSell, 1.0)]);
+        assert!(state.panic_triggered && state.is_closed());
+
+        // Ступени мельче порога не считаются
+        let (_, actions) = run(&config, &stairs(4.0, 60));
+        assert!(sales(&actions).is_empty());
+
+        // Пауза между ступенями сбрасывает счёт
+        let mut prices = stairs(6.0, 15);
+        let base = prices[14];
+        prices.extend([base; 5]);
+        prices.extend(stairs(6.0, 15).iter().map(|p| p * base));
+        let (_, actions) = run(&config, &prices);
+        assert!(sales(&actions).is_empty());
+
+        // Выключено по умолчанию
+        let (_, actions) = run(&RiskConfig::default(), &stairs(6.0, 30));
+        assert!(sales(&actions).is_empty());
+    }
+
+    /// Цена 1.0, резерв по секундам, SOL
+    fn reserves(config: &RiskConfig, sol: &[f64]) -> (RiskState, Vec<(u64, RiskAction)>) {
+        replay(
+            config,
+            sol.iter()
+                .enumerate()
+                .map(|(i, &s)| (i as u64, 1.0, (s * LAMPORTS_PER_SOL as f64) as u64)),
+        )
+    }
+
+    #[test]
+    fn reserve_drain_velocity() {
+        let config = RiskConfig {
+            drain_window_samples: 4,
+            max_drain_sol_per_sec: 2.0,
+            ..Default::default()
+        };
+        // Быстрый слив по 3 SOL/сек: выход на −30%, раньше порога rug_reserve_drop_pct
+        let fast = [30.0, 30.0, 30.0, 30.0, 30.0, 27.0, 24.0, 21.0, 18.0, 15.0];
+        let (_, actions) = reserves(&config, &fast);
+        assert_eq!(sales(&actions), [(7, ExitReason::RugPull, 1.0)]);
+        match &actions[0].1 {
+            RiskAction::Sell {
+                event: RiskEvent::RugPull { drain, .. },
+                ..
+            } => assert!(drain.is_some_and(|d| d.sol_per_sec >= 2.0)),
+            other => panic!("ожидали RugPull, получили {:?}", other),
+        }
+
+        // Медленное истекание: 0.1 SOL/сек, за минуту −20%
+        let slow: Vec<f64> = (0..60).map(|t| 30.0 - t as f64 * 0.1).collect();
+        let (_, actions) = reserves(&config, &slow);
+        assert!(sales(&actions).is_empty());
+
+        // Один шумный снимок не срабатывает
+        let mut noisy = [30.0; 12];
+        noisy[5] = 20.0;
+        let (_, actions) = reserves(&config, &noisy);
+        assert!(sales(&actions).is_empty());
+
+        // Без порога тот же слив ловит только падение на 40%
+        let (_, actions) = reserves(&RiskConfig::default(), &fast);
+        assert_eq!(sales(&actions), [(8, ExitReason::RugPull, 1.0)]);
+    }
+
+    #[test]
+    fn max_hold_wins_over_trailing_stop() {
+        let config = RiskConfig {
+            max_hold_secs: Some(10),
+            ..Default::default()
+        };
+        // На 10-й секунде одновременно истёк срок и цена −50% от пика
+        let mut prices = vec![1.0];
+        prices.extend([2.0; 9]);
+        prices.extend([1.0; 6]);
+        let (state, actions) = run(&config, &prices);
+        assert_eq!(sales(&actions), [(10, ExitReason::MaxHold, 1.0)]);
+        assert!(!state.trailing_triggered);
+        assert_eq!(ExitReason::MaxHold.urgency(), Urgency::Forced);
+
+        // До срока работает trailing stop
+        let (_, actions) = run(&config, &[1.0, 2.0, 1.0]);
+        assert_eq!(sales(&actions), [(2, ExitReason::TrailingStop, 1.0)]);
+    }
+
+    #[test]
+    fn trailing_schedule_tightens_with_peak() {
+        let config = RiskConfig {
+            trailing_stop_pct: 40.0,
+            trailing_schedule: vec![(2.0, 30.0), (5.0, 20.0), (20.0, 12.0)],
+            ..Default::default()
+        };
+        let mut state = RiskState::new(1.0, &config);
+        // (цена, действующий trailing stop после тика)
+        let path = [
+            (1.5, 40.0),
+            (1.2, 40.0),
+            (3.0, 30.0),
+            (6.0, 20.0),
+            (5.0, 20.0),
+            (25.0, 12.0),
+            (22.5, 12.0),
+        ];
+        for (i, (price, pct)) in path.into_iter().enumerate() {
+            let sample = PriceSample {
+                timestamp_ms: START_MS + i as u64 * 1000,
+                price,
+                sol_reserve: RESERVE,
+            };
+            let actions = config.evaluate(&mut state, &sample, Duration::from_secs(i as u64));
+            assert!(actions.iter().all(|a| matches!(a, RiskAction::Notify(_))));
+            assert_eq!(state.trailing_pct, pct, "цена {}", price);
+        }
+        // −12.4% от пика 25
+        let sample = PriceSample {
+            timestamp_ms: START_MS + 10_000,
+            price: 21.9,
+            sol_reserve: RESERVE,
+        };
+        let actions = config.evaluate(&mut state, &sample, Duration::from_secs(10));
+        assert!(matches!(
+            actions.as_slice(),
+            [RiskAction::Sell { sale, .. }] if sale.reason == ExitReason::TrailingStop
+        ));
+    }
+
+    #[test]
+    fn timeout_and_moon_latch() {
+        let config = RiskConfig {
+            moon_timer_secs: 120,
+            ..Default::default()
+        };
+        // 600 тиков после time-out без роста
+        let (state, actions) = run(&config, &[1.0; 91 + 600]);
+        assert_eq!(
+            sales(&actions),
+            [(91, ExitReason::Timeout, 0.5), (121, ExitReason::Moon, 0.2)]
+        );
+        assert!(state.timeout_triggered && state.moon_sold);
+        assert!((state.remaining - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn trailing_arms_at_activation_multiple() {
+        let config = RiskConfig::default();
+        // Пик ровно 1.3x — trailing stop включается
+        let (state, actions) = run(&config, &[1.0, 1.3, 0.84]);
+        assert!(matches!(
+            actions[0],
+            (1, RiskAction::Notify(RiskEvent::TrailingArmed { multiple, .. })) if multiple == 1.3
+        ));
+        assert_eq!(sales(&actions), [(2, ExitReason::TrailingStop, 1.0)]);
+        assert!(state.trailing_armed);
+
+        // Чуть ниже — только panic-sell и rug-pull
+        let (state, actions) = run(&config, &[1.0, 1.29, 0.84]);
+        assert!(actions.is_empty());
+        assert!(!state.trailing_armed);
+
+        // Всплеск +5% и обычный откат
+        let (_, actions) = run(&config, &[1.0, 1.05, 0.9, 0.95]);
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn creator_dump_from_peak_balance() {
+        let config = RiskConfig {
+            creator_dump_pct: 50.0,
+            ..Default::default()
+        };
+        let mut state = RiskState::new(1.0, &config);
+        // Докупка поднимает максимум, продажа меньше порога не в счёт
+        for balance in [0, 1_000, 2_000, 1_100] {
+            assert!(config.check_creator_dump(&mut state, balance).is_none());
+        }
+        assert_eq!(state.creator_peak_balance, 2_000);
+        match config.check_creator_dump(&mut state, 1_000) {
+            Some(RiskAction::Sell {
+                sale,
+                event: RiskEvent::CreatorDump { amount_pct },
+            }) => {
+                assert_eq!(sale.reason, ExitReason::CreatorDump);
+                assert_eq!(sale.fraction, 1.0);
+                assert_eq!(amount_pct, 50.0);
+            }
+            other => panic!("ожидали CreatorDump, получили {:?}", other),
+        }
+        assert!(state.creator_dump_triggered && state.is_closed());
+        assert!(config.check_creator_dump(&mut state, 0).is_none());
+
+        // Выключено — не реагируем даже на полную продажу
+        let off = RiskConfig::default();
+        let mut state = RiskState::new(1.0, &off);
+        assert!(off.check_creator_dump(&mut state, 1_000).is_none());
+        assert!(off.check_creator_dump(&mut state, 0).is_none());
+        assert!(!state.is_closed());
+    }
+
+    #[test]
+    fn whale_dump_reactions() {
+        const SOL: u64 = LAMPORTS_PER_SOL;
+        let exit = RiskConfig {
+            whale_sell_pct: 20.0,
+            whale_reaction: WhaleReaction::Exit { fraction: 0.5 },
+            ..Default::default()
+        };
+        // −16.7% за тик мало, −24% и −21% — по половине позиции
+        let reserves = [30, 30, 25, 19, 19, 15].map(|sol| sol * SOL);
+        let (state, actions) = replay(
+            &exit,
+            reserves
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| (i as u64, 1.0, r)),
+        );
+        assert_eq!(
+            sales(&actions),
+            [
+                (3, ExitReason::WhaleDump, 0.5),
+                (5, ExitReason::WhaleDump, 0.5)
+            ]
+        );
+        assert!(state.is_closed());
+
+        // Сужение trailing stop до 10% на минуту после падения резерва на 25%
+        let tighten = RiskConfig {
+            whale_sell_pct: 20.0,
+            ..Default::default()
+        };
+        let whale = |later: u64| {
+            let ticks = [
+                (0, 1.0, 30 * SOL),
+                (1, 2.0, 30 * SOL),
+                (2, 1.9, 30 * SOL * 3 / 4),
+                (later, 1.75, 30 * SOL * 3 / 4),
+            ];
+            replay(&tighten, ticks)
+        };
+        let (state, actions) = whale(3);
+        assert!(actions.iter().any(|action| matches!(
+            action,
+            (2, RiskAction::Notify(RiskEvent::WhaleDump { reaction, .. }))
+                if *reaction == tighten.whale_reaction
+        )));
+        assert_eq!(sales(&actions), [(3, ExitReason::TrailingStop, 1.0)]);
+        assert_eq!(state.trailing_pct, 10.0);
+        // Через минуту снова 30%: −12.5% от пика не продаём
+        let (state, actions) = whale(63);
+        assert!(sales(&actions).is_empty());
+        assert_eq!(state.trailing_pct, 30.0);
+        assert!(state.tight_trailing.is_none());
+
+        // Без порога то же падение резерва не замечаем
+        let (_, actions) = replay(
+            &RiskConfig::default(),
+            [(0, 1.0, 30 * SOL), (1, 1.0, 30 * SOL * 3 / 4)],
+        );
+        assert!(actions.is_empty());
+    }
+}
@@ -0,0 +1,1390 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+    time,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    exit::reserve_drop_pct, ExitReason, ExitSummary, PositionEvent, PositionStatus, RiskAction,
+    RiskConfig, RiskEvent, RiskState,
+};
+use crate::pricing::SolPriceFeed;
+use crate::scanner::{
+    onchain::bonding_curve_pda, raydium::WSOL_MINT, verify_authorities, Candle, PumpToken,
+};
+use crate::trading::{
+    clock::{Clock, SystemClock},
+    curve::{fetch_curve, PoolSnapshot},
+    dexscreener::DexScreenerClient,
+    errors::{ErrorStreak, MigrationPending},
+    executor::ExitExecutor,
+    feed::{self, FeedUpdate, PriceFeed},
+    history::{PriceSample, RollingReturns},
+    jito::JitoClient,
+    journal::{Journal, JournalEntry, TickRecord},
+    jupiter::JupiterClient,
+    pool::{find_raydium_pool, PriceSource},
+    pump_buy::BuyReceipt,
+    pump_sell::{self, BASE_FEE_LAMPORTS, DEFAULT_SELL_SLIPPAGE_BPS},
+    rpc_pool::{EndpointHealth, RpcPool},
+    slippage::DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS,
+    store::{PersistedPosition, PositionStore},
+    volume::VolumeTracker,
+};
+
+/// Управление запущенным мониторингом
+#[derive(Debug)]
+pub struct MonitorHandle {
+    monitor: Arc<RiskMonitor>,
+    cancel: CancellationToken,
+    task: JoinHandle<ExitSummary>,
+}
+
+impl MonitorHandle {
+    /// Останавливает мониторинг; позиция остаётся как есть
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    pub fn monitor(&self) -> &Arc<RiskMonitor> {
+        &self.monitor
+    }
+
+    pub fn status(&self) -> PositionStatus {
+        PositionStatus {
+            running: self.is_running(),
+            ..self.monitor.status()
+        }
+    }
+
+    /// Условия выхода перестают продавать; цена и флаги обновляются дальше
+    pub fn pause(&self) {
+        self.monitor.pause();
+    }
+
+    pub fn resume(&self) {
+        self.monitor.resume();
+    }
+
+    /// Ручная продажа доли `share` текущего остатка, см. `RiskMonitor::sell_now`
+    pub async fn sell_now(&self, share: f64) -> bool {
+        self.monitor.sell_now(share).await
+    }
+
+    /// Новые пороги для работающего мониторинга
+    pub fn set_config(&self, config: RiskConfig) -> Result<()> {
+        self.monitor.set_config(config)
+    }
+
+    /// Ждёт завершения мониторинга
+    pub async fn await_exit(self) -> Result<ExitSummary> {
+        Ok(self.task.await?)
+    }
+}
+
+/// Как исполняются продажи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExecutionMode {
+    /// Транзакции отправляются в сеть
+    #[default]
+    Live,
+    /// Транзакция подписывается и прогоняется через `simulateTransaction`
+    Simulate,
+    /// Без транзакций: продажа по последней цене минус проскальзывание
+    Paper,
+}
+
+impl ExecutionMode {
+    /// `Config::dry_run` — бумажная торговля
+    pub fn from_dry_run(dry_run: bool) -> Self {
+        if dry_run {
+            Self::Paper
+        } else {
+            Self::Live
+        }
+    }
+
+    pub fn is_live(self) -> bool {
+        self == Self::Live
+    }
+}
+
+pub struct RiskMonitor {
+    pub(super) client: Arc<RpcClient>,
+    /// Чтение цены с переключением между endpoints
+    pub(super) rpc: Arc<RpcPool>,
+    pub(super) wallet: Arc<Keypair>,
+    pub(super) token_mint: Pubkey,
+    /// Создатель токена (`PumpToken.creator_address`)
+    pub(super) creator: Option<Pubkey>,
+    pub(super) stake_sol: f64,
+    pub(super) entry_fee_lamports: u64,
+    /// Меняется на лету через `set_config`
+    pub(super) config: Mutex<Arc<RiskConfig>>,
+    /// На паузе условия выхода считаются, но не продают
+    pub(super) paused: AtomicBool,
+    /// Идёт фоновый мониторинг (`start_monitoring`)
+    pub(super) running: AtomicBool,
+    /// Продажи (автоматические и ручные) идут по одной
+    pub(super) act_lock: tokio::sync::Mutex<()>,
+    /// Время удержания и отметки в истории цены — по этим часам
+    pub(super) clock: Arc<dyn Clock>,
+    pub(super) started_ms: u64, // unix, мс — время входа
+    pub(super) sell_slippage_bps: u16,
+    pub(super) emergency_sell_slippage_bps: u16,
+    pub(super) mode: ExecutionMode,
+    pub(super) jupiter: JupiterClient,
+    pub(super) jito: Option<Arc<JitoClient>>,
+    pub(super) executor: Option<Arc<dyn ExitExecutor>>,
+    pub(super) price_feed: PriceFeed,
+    pub(super) tick_interval_ms: AtomicU64, // текущий интервал, для статуса
+    pub(super) ticks: AtomicU64,
+    pub(super) dexscreener: Option<Arc<DexScreenerClient>>,
+    pub(super) sol_price: Option<Arc<SolPriceFeed>>,
+    pub(super) volume: tokio::sync::Mutex<VolumeTracker>,
+    pub(super) last_volume_poll: Mutex<Option<u64>>, // unix, мс
+    pub(super) events: Option<mpsc::Sender<PositionEvent>>,
+    pub(super) journal: Option<Journal>,
+    pub(super) store: Option<Arc<PositionStore>>,
+    /// Кошелёк суб-позиции в хранилище; `None` — основной кошелёк трейдера
+    pub(super) sub_wallet: Option<Pubkey>,
+    pub(super) last_saved: Mutex<Option<PersistedPosition>>,
+    pub(super) state: Mutex<RiskState>,
+}
+
+impl fmt::Debug for RiskMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RiskMonitor")
+            .field("wallet", &self.wallet.pubkey())
+            .field("token_mint", &self.token_mint)
+            .field("stake_sol", &self.stake_sol)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl RiskMonitor {
+    pub fn new(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        token: &PumpToken,
+        stake_sol: f64,
+        config: RiskConfig,
+    ) -> Result<Self> {
+        config.validate()?;
+        let mint = Pubkey::from_str(&token.mint).unwrap_or_default();
+        let state = RiskState::new(token.price, &config);
+        let volume = VolumeTracker::new(Duration::from_secs(config.volume_window_secs));
+        let tick_interval_ms = config.tick_interval(Duration::ZERO, 0.0).as_millis() as u64;
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock::new());
+        Ok(Self {
+            rpc: Arc::new(RpcPool::single(client.clone())),
+            client,
+            wallet,
+            token_mint: mint,
+            creator: Pubkey::from_str(&token.creator_address).ok(),
+            stake_sol,
+            entry_fee_lamports: BASE_FEE_LAMPORTS,
+            config: Mutex::new(Arc::new(config)),
+            paused: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            act_lock: tokio::sync::Mutex::new(()),
+            started_ms: clock.now_ms(),
+            clock,
+            sell_slippage_bps: DEFAULT_SELL_SLIPPAGE_BPS,
+            emergency_sell_slippage_bps: DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS,
+            mode: ExecutionMode::Live,
+            jupiter: JupiterClient::default(),
+            jito: None,
+            executor: None,
+            price_feed: PriceFeed::Polling,
+            tick_interval_ms: AtomicU64::new(tick_interval_ms),
+            ticks: AtomicU64::new(0),
+            volume: tokio::sync::Mutex::new(volume),
+            last_volume_poll: Mutex::new(None),
+            dexscreener: None,
+            sol_price: None,
+            events: None,
+            journal: None,
+            store: None,
+            sub_wallet: None,
+            last_saved: Mutex::new(None),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Монитор по сохранённой позиции; время входа и флаги условий — как до перезапуска
+    pub fn restore(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        saved: &PersistedPosition,
+        config: RiskConfig,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            Pubkey::from_str(&saved.mint).is_ok(),
+            "неверный mint сохранённой позиции: {}",
+            saved.mint
+        );
+        let token = PumpToken {
+            mint: saved.mint.clone(),
+            creator_address: saved.creator.clone().unwrap_or_default(),
+            price: saved.entry_price,
+            ..Default::default()
+        };
+        let mut monitor = Self::new(client, wallet, &token, saved.stake_sol, config)?;
+        monitor.entry_fee_lamports = saved.entry_fee_lamports;
+        monitor.started_ms = saved.entry_time_ms;
+        {
+            let mut state = monitor.state.lock().unwrap();
+            let config = monitor.config();
+            state.peak_price = saved.peak_price.max(saved.entry_price);
+            state.breakeven_armed = saved.breakeven_armed;
+            state.stop_price = config.stop_price(saved.entry_price, saved.breakeven_armed);
+            state.initial_reserve = saved.initial_reserve;
+            state.entry_slot = saved.entry_slot;
+            state.entry_signature = saved
+                .entry_signature
+                .as_deref()
+                .and_then(|s| Signature::from_str(s).ok());
+            state.remaining = saved.remaining;
+            state.rug_triggered = saved.rug_triggered;
+            state.floor_triggered = saved.floor_triggered;
+            state.panic_triggered = saved.panic_triggered;
+            state.timeout_triggered = saved.timeout_triggered;
+            state.trailing_triggered = saved.trailing_triggered;
+            // До версии с порогом trailing работал с любого роста
+            state.trailing_armed = saved
+                .trailing_armed
+                .unwrap_or(saved.peak_price > saved.entry_price);
+            state.moon_sold = saved.moon_sold;
+            state.creator_dump_triggered = saved.creator_dump_triggered;
+            state.creator_peak_balance = saved.creator_peak_balance;
+            state.supply_triggered = saved.supply_triggered;
+            state.tight_trailing = saved
+                .tight_trailing
+                .map(|(pct, until_ms)| (pct, Duration::from_millis(until_ms)));
+            state.moon_tokens = saved.moon_tokens;
+            state.entry_supply = saved.entry_supply;
+            state.entry_sol_usd = saved.entry_sol_usd;
+            // Ступени могли поменяться в конфиге — берём совпадающие по индексу
+            for (hit, saved_hit) in state.tiers_hit.iter_mut().zip(&saved.tiers_hit) {
+                *hit = *saved_hit;
+            }
+            state.sol_recovered = saved.sol_recovered;
+            state.fees_paid = saved.fees_paid;
+            state.last_exit = saved.last_exit;
+        }
+        Ok(monitor)
+    }
+
+    /// Монитор с резервом и ценой входа из уже полученного снимка пула
+    pub fn from_snapshot(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        token: &PumpToken,
+        stake_sol: f64,
+        config: RiskConfig,
+        snapshot: &PoolSnapshot,
+    ) -> Result<Self> {
+        let monitor = Self::new(client, wallet, token, stake_sol, config)?;
+        monitor.set_entry(snapshot);
+        Ok(monitor)
+    }
+
+    /// Монитор с резервом и ценой входа, прочитанными из пула сейчас;
+    /// пул с резервом ниже `min_pool_sol` — ошибка
+    pub async fn init(
+        client: Arc<RpcClient>,
+        wallet: Arc<Keypair>,
+        token: &PumpToken,
+        stake_sol: f64,
+        config: RiskConfig,
+    ) -> Result<Self> {
+        let monitor = Self::new(client, wallet, token, stake_sol, config)?;
+        let snapshot = monitor.get_price_and_liquidity().await?;
+        monitor.config().ensure_pool_floor(snapshot.sol_reserve)?;
+        monitor.set_entry(&snapshot);
+        if monitor.config().supply_check_ticks > 0 {
+            match monitor.token_supply().await {
+                Ok(supply) => monitor.state.lock().unwrap().entry_supply = Some(supply),
+                Err(e) => log::debug!("Supply на входе не получен: {}", e),
+            }
+        }
+        Ok(monitor)
+    }
+
+    fn set_entry(&self, snapshot: &PoolSnapshot) {
+        let mut state = self.state.lock().unwrap();
+        if snapshot.price > 0.0 {
+            state.entry_price = snapshot.price;
+            state.peak_price = snapshot.price;
+            state.stop_price = self.config().stop_price(snapshot.price, false);
+        }
+        state.initial_reserve = Some(snapshot.sol_reserve).filter(|r| *r > 0);
+        state.entry_slot = Some(snapshot.slot);
+    }
+
+    /// Подпись транзакции покупки
+    pub fn with_entry_signature(self, signature: Signature) -> Self {
+        self.state.lock().unwrap().entry_signature = Some(signature);
+        self
+    }
+
+    /// Куплено токенов (сырые единицы): от этого количества фиксируется лунная доля.
+    /// Без него в `ExecutionMode::Live` она считается по балансу кошелька на старте.
+    pub fn with_entry_tokens(self, raw: u64) -> Self {
+        self.state.lock().unwrap().moon_tokens = Some(pump_sell::fraction_of(
+            raw,
+            self.config().moon_allocation_pct / 100.0,
+        ));
+        self
+    }
+
+    /// Комиссия транзакции покупки, lamports (по умолчанию — базовая за одну подпись)
+    pub fn with_entry_fee(mut self, lamports: u64) -> Self {
+        self.entry_fee_lamports = lamports;
+        self
+    }
+
+    /// Вход по фактической покупке: цена входа — её эффективная цена, ставка —
+    /// потраченное на токены, плюс подпись, количество и комиссия
+    pub fn with_buy(mut self, receipt: &BuyReceipt) -> Self {
+        if receipt.sol_spent > 0 {
+            self.stake_sol = receipt.sol_spent_sol();
+        }
+        if receipt.effective_price > 0.0 && receipt.effective_price.is_finite() {
+            let mut state = self.state.lock().unwrap();
+            state.entry_price = receipt.effective_price;
+            state.peak_price = receipt.effective_price;
+            state.stop_price = self.config().stop_price(receipt.effective_price, false);
+        }
+        self.with_entry_signature(receipt.signature)
+            .with_entry_tokens(receipt.tokens_received)
+            .with_entry_fee(receipt.fee_lamports)
+    }
+
+    /// История цены до входа (свечи из `PumpFunScanner::get_candles`)
+    pub fn with_history(self, candles: &[Candle]) -> Self {
+        {
+            let mut state = self.state.lock().unwrap();
+            for c in candles {
+                state.history.push(PriceSample {
+                    timestamp_ms: c.timestamp * 1000,
+                    price: c.close,
+                    sol_reserve: 0,
+                });
+            }
+        }
+        self
+    }
+
+    /// Базовое проскальзывание продаж, б.п.; при неудаче — `RiskConfig::slippage_ladder`
+    pub fn with_sell_slippage(mut self, bps: u16) -> Self {
+        self.sell_slippage_bps = bps;
+        self
+    }
+
+    /// Базовое проскальзывание срочных продаж (rug-pull, panic), б.п.
+    pub fn with_emergency_sell_slippage(mut self, bps: u16) -> Self {
+        self.emergency_sell_slippage_bps = bps;
+        self
+    }
+
+    /// Продажи не отправляются: `true` — бумажная торговля (`ExecutionMode::Paper`)
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        self.with_execution_mode(ExecutionMode::from_dry_run(dry_run))
+    }
+
+    pub fn with_execution_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Клиент Jupiter для продаж мимо bonding curve
+    pub fn with_jupiter(mut self, jupiter: JupiterClient) -> Self {
+        self.jupiter = jupiter;
+        self
+    }
+
+    /// Продажи на bonding curve уходят бандлом Jito, если в конфиге заданы чаевые
+    pub fn with_jito(mut self, jito: Arc<JitoClient>) -> Self {
+        self.jito = Some(jito);
+        self
+    }
+
+    /// Несколько RPC: цена читается с переключением, срочные продажи уходят во все;
+    /// остальные запросы — через лучший endpoint на момент вызова
+    pub fn with_rpc_pool(mut self, rpc: Arc<RpcPool>) -> Self {
+        self.client = rpc.primary();
+        self.rpc = rpc;
+        self
+    }
+
+    /// Задержка и сбои RPC endpoint-ов
+    pub fn rpc_health(&self) -> Vec<EndpointHealth> {
+        self.rpc.health()
+    }
+
+    /// Свой исполнитель продаж вместо встроенного (bonding curve → Jupiter);
+    /// режим исполнения и настройки продаж монитора на него не действуют
+    pub fn with_executor(mut self, executor: Arc<dyn ExitExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Свои часы вместо системных (бэктест); уже прошедшее время удержания сохраняется
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        let held = self.elapsed().as_millis() as u64;
+        self.started_ms = clock.now_ms().saturating_sub(held);
+        self.clock = clock;
+        self
+    }
+
+    /// Источник обновлений цены; по умолчанию — опрос по HTTP
+    pub fn with_price_feed(mut self, feed: PriceFeed) -> Self {
+        self.price_feed = feed;
+        self
+    }
+
+    /// Клиент DexScreener для условия `moon_top_n`; общий на все мониторы,
+    /// чтобы кэш трендов не дублировался
+    pub fn with_dexscreener(mut self, dexscreener: Arc<DexScreenerClient>) -> Self {
+        self.dexscreener = Some(dexscreener);
+        self
+    }
+
+    /// Хранилище, куда позиция пишется при каждом существенном изменении
+    pub fn with_store(mut self, store: Arc<PositionStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Позиция — доля покупки с дополнительного кошелька: в хранилище
+    /// она пишется отдельно, под своим кошельком
+    pub fn with_sub_wallet(mut self) -> Self {
+        self.sub_wallet = Some(self.wallet.pubkey());
+        self
+    }
+
+    /// Цена SOL для PnL в USD; цена на входе — из кэша на момент вызова
+    /// (или первая полученная после него)
+    pub fn with_sol_price(mut self, feed: Arc<SolPriceFeed>) -> Self {
+        if let Some(price) = feed.cached() {
+            let mut state = self.state.lock().unwrap();
+            state.entry_sol_usd = state.entry_sol_usd.or(Some(price.usd));
+        }
+        self.sol_price = Some(feed);
+        self
+    }
+
+    /// Канал, куда публикуются все `RiskEvent` монитора
+    pub fn with_events(mut self, events: mpsc::Sender<PositionEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Журнал тиков и продаж для разбора и бэктеста (`journal::samples`)
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// История цены позиции, старые точки первыми
+    pub fn price_history(&self) -> Vec<PriceSample> {
+        let state = self.state.lock().unwrap();
+        state.history.samples().copied().collect()
+    }
+
+    /// Доходность за последние 5/15/30 сек
+    pub fn rolling_returns(&self) -> RollingReturns {
+        self.state.lock().unwrap().history.rolling_returns()
+    }
+
+    /// Снимок текущего состояния позиции
+    pub fn state(&self) -> RiskState {
+        self.state.lock().unwrap().clone()
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        self.token_mint
+    }
+
+    /// Кошелёк, с которого продаётся позиция
+    pub fn wallet(&self) -> Pubkey {
+        self.wallet.pubkey()
+    }
+
+    /// Ставка в позиции, SOL
+    pub fn stake_sol(&self) -> f64 {
+        self.stake_sol
+    }
+
+    /// Продаёт весь остаток позиции вне условий выхода; `true` — продано
+    pub async fn sell_all(&self) -> bool {
+        self.sell_now(1.0).await
+    }
+
+    /// Ручная продажа доли `share` (0–1) того, что на руках, с `ExitReason::Manual`;
+    /// работает и на паузе. Автоматическая продажа в процессе дожидается — не наоборот.
+    /// `true` — продано (или продавать уже нечего).
+    pub async fn sell_now(&self, share: f64) -> bool {
+        let _act = self.act_lock.lock().await;
+        let sale = {
+            let mut state = self.state.lock().unwrap();
+            if state.is_closed() {
+                return true;
+            }
+            let fraction = state.remaining * share.clamp(0.0, 1.0);
+            state.take(fraction, ExitReason::Manual)
+        };
+        log::info!(
+            "✋ Ручная продажа {:.0}% остатка по {}",
+            share * 100.0,
+            self.token_mint
+        );
+        let sold = self.execute(sale).await;
+        self.persist();
+        sold
+    }
+
+    /// Текущие пороги
+    pub fn config(&self) -> Arc<RiskConfig> {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Новые пороги на лету; действуют со следующего тика.
+    /// Ступени фиксации прибыли сопоставляются по индексу. Состояние и пороги меняются
+    /// под блокировкой состояния, как и в `evaluate`: тик не увидит одно без другого.
+    pub fn set_config(&self, config: RiskConfig) -> Result<()> {
+        config.validate()?;
+        let mut state = self.state.lock().unwrap();
+        state
+            .tiers_hit
+            .resize(config.take_profit_tiers.len(), false);
+        if state.entry_price > 0.0 {
+            state.stop_price = config.stop_price(state.entry_price, state.breakeven_armed);
+        }
+        *self.config.lock().unwrap() = Arc::new(config);
+        drop(state);
+        log::info!("⚙️ Пороги риска {} обновлены", self.token_mint);
+        Ok(())
+    }
+
+    /// Пауза: состояние и история обновляются, но условия выхода не продают
+    pub fn pause(&self) {
+        if !self.paused.swap(true, Ordering::Relaxed) {
+            log::info!("⏸️ Автопродажи {} на паузе", self.token_mint);
+        }
+    }
+
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::Relaxed) {
+            log::info!("▶️ Автопродажи {} снова включены", self.token_mint);
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Сверяет позицию с балансом кошелька: нет токенов — позиция закрыта
+    /// с `ExitReason::External`. `true` — токены на месте.
+    /// Вне `ExecutionMode::Live` баланс не проверяется.
+    pub async fn reconcile_balance(&self) -> Result<bool> {
+        if !self.mode.is_live() {
+            return Ok(true);
+        }
+        let balance =
+            pump_sell::token_balance(&self.client, &self.wallet.pubkey(), &self.token_mint).await?;
+        if balance > 0 {
+            log::info!(
+                "♻️ Позиция {} восстановлена: {} токенов на кошельке, остаток {:.1}%",
+                self.token_mint,
+                balance,
+                self.state().remaining * 100.0
+            );
+            return Ok(true);
+        }
+        log::warn!(
+            "♻️ Токенов {} на кошельке нет — позиция закрыта вне бота",
+            self.token_mint
+        );
+        {
+            let mut state = self.state.lock().unwrap();
+            state.remaining = 0.0;
+            state.last_exit = Some(ExitReason::External);
+        }
+        self.persist();
+        self.finish();
+        Ok(false)
+    }
+
+    /// Данные для `PositionStore`
+    pub fn persisted(&self) -> PersistedPosition {
+        let state = self.state.lock().unwrap();
+        PersistedPosition {
+            mint: self.token_mint.to_string(),
+            wallet: self.sub_wallet.map(|w| w.to_string()),
+            creator: self.creator.map(|c| c.to_string()),
+            stake_sol: self.stake_sol,
+            entry_fee_lamports: self.entry_fee_lamports,
+            entry_time_ms: self.started_ms,
+            entry_price: state.entry_price,
+            peak_price: state.peak_price,
+            initial_reserve: state.initial_reserve,
+            entry_slot: state.entry_slot,
+            entry_signature: state.entry_signature.map(|s| s.to_string()),
+            remaining: state.remaining,
+            breakeven_armed: state.breakeven_armed,
+            rug_triggered: state.rug_triggered,
+            floor_triggered: state.floor_triggered,
+            panic_triggered: state.panic_triggered,
+            timeout_triggered: state.timeout_triggered,
+            trailing_triggered: state.trailing_triggered,
+            trailing_armed: Some(state.trailing_armed),
+            moon_sold: state.moon_sold,
+            creator_dump_triggered: state.creator_dump_triggered,
+            creator_peak_balance: state.creator_peak_balance,
+            supply_triggered: state.supply_triggered,
+            tight_trailing: state
+                .tight_trailing
+                .map(|(pct, until)| (pct, until.as_millis() as u64)),
+            moon_tokens: state.moon_tokens,
+            entry_supply: state.entry_supply,
+            entry_sol_usd: state.entry_sol_usd,
+            tiers_hit: state.tiers_hit.clone(),
+            sol_recovered: state.sol_recovered,
+            fees_paid: state.fees_paid,
+            last_exit: state.last_exit,
+        }
+    }
+
+    /// Пишет позицию в хранилище, если она изменилась; закрытую — удаляет
+    pub(super) fn persist(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let position = self.persisted();
+        let mut last = self.last_saved.lock().unwrap();
+        if last.as_ref() == Some(&position) {
+            return;
+        }
+        let result = if position.remaining <= f64::EPSILON {
+            store.remove(&position.mint, position.wallet.as_deref())
+        } else {
+            store.save(&position)
+        };
+        match result {
+            Ok(()) => *last = Some(position),
+            Err(e) => log::warn!("Не удалось сохранить позицию {}: {}", self.token_mint, e),
+        }
+    }
+
+    /// Запуск фонового мониторинга; задача завершается, когда позиция закрыта
+    /// или вызван `MonitorHandle::stop`. Нужен tokio runtime.
+    pub fn start_monitoring(self: Arc<Self>) -> MonitorHandle {
+        self.persist();
+        {
+            let state = self.state.lock().unwrap();
+            self.publish(RiskEvent::Opened {
+                stake_sol: self.stake_sol,
+                entry_price: state.entry_price,
+            });
+        }
+        let cancel = CancellationToken::new();
+        self.running.store(true, Ordering::Relaxed);
+        let task = tokio::spawn({
+            let monitor = self.clone();
+            let cancel = cancel.clone();
+            async move {
+                let summary = monitor.clone().run(cancel).await;
+                monitor.running.store(false, Ordering::Relaxed);
+                summary
+            }
+        });
+        MonitorHandle {
+            monitor: self,
+            cancel,
+            task,
+        }
+    }
+
+    async fn run(self: Arc<Self>, cancel: CancellationToken) -> ExitSummary {
+        // Не доверяем API: проверяем полномочия mint-а on-chain при входе
+        match verify_authorities(&self.client, &self.token_mint).await {
+            Ok(status) if !status.freeze_revoked() => {
+                log::error!("🧊 Freeze authority не отозван — выходим из позиции");
+                let sale = self
+                    .state
+                    .lock()
+                    .unwrap()
+                    .take(1.0, ExitReason::FreezeAuthority);
+                let sold = self.act(sale, RiskEvent::FreezeAuthority).await;
+                self.persist();
+                if sold {
+                    return self.finish();
+                }
+            }
+            Ok(status) if !status.mint_revoked() => {
+                log::warn!("⚠️ Mint authority не отозван у {}", self.token_mint);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Не удалось проверить полномочия mint-а: {}", e),
+        }
+        self.fix_moon_tokens().await;
+
+        let (feed_tx, mut updates) = watch::channel(None);
+        let feed_cancel = cancel.child_token();
+        match &self.price_feed {
+            PriceFeed::Polling => drop(feed_tx),
+            PriceFeed::AccountSubscribe { ws_url } => {
+                let monitor = self.clone();
+                tokio::spawn(feed::account_updates(
+                    ws_url.clone(),
+                    move || monitor.pool_account(),
+                    feed_tx,
+                    feed_cancel.clone(),
+                ));
+            }
+        }
+        tokio::spawn(self.clone().refresh_market(feed_cancel.clone()));
+
+        // Первый тик сразу, дальше — по `next_interval`
+        let mut tick = Duration::ZERO;
+        let watchdog = time::sleep(tick);
+        tokio::pin!(watchdog);
+        let mut last_update: Option<Instant> = None;
+        let mut errors = ErrorStreak::default();
+        loop {
+            let result = tokio::select! {
+                _ = cancel.cancelled() => {
+                    log::info!("⏹️ Мониторинг {} остановлен вручную", self.token_mint);
+                    break;
+                }
+                Ok(()) = updates.changed() => {
+                    last_update = Some(Instant::now());
+                    let update = *updates.borrow_and_update();
+                    match update {
+                        Some(FeedUpdate::Curve(snapshot)) => self.process_snapshot(&snapshot).await,
+                        _ => self.check_risk_conditions().await,
+                    }
+                }
+                _ = &mut watchdog => {
+                    // Сторож: по HTTP, только если websocket молчит дольше тика
+                    if let Some(at) = last_update.filter(|at| at.elapsed() < tick) {
+                        watchdog.as_mut().reset((at + tick).into());
+                        continue;
+                    }
+                    self.check_risk_conditions().await
+                }
+            };
+            tick = self.next_interval();
+            match result {
+                Ok(true) => {
+                    log::info!(
+                        "✅ Позиция по {} закрыта, мониторинг остановлен",
+                        self.token_mint
+                    );
+                    break;
+                }
+                Ok(false) => {
+                    let failed = errors.success();
+                    if failed > 0 {
+                        log::info!(
+                            "🔌 Мониторинг {} восстановлен после {} сбоев",
+                            self.token_mint,
+                            failed
+                        );
+                    }
+                }
+                // Сбой RPC или миграция на Raydium ещё идёт — позицию не бросаем
+                Err(e) => {
+                    let (pause, escalate) =
+                        errors.failure(&e, tick, self.config().max_consecutive_errors);
+                    log::warn!(
+                        "Ошибка мониторинга рисков ({} подряд, повтор через {:?}): {}",
+                        errors.consecutive(),
+                        pause,
+                        e
+                    );
+                    tick = pause;
+                    if self.force_max_hold().await
+                        || (escalate && self.protective_exit(errors.consecutive()).await)
+                    {
+                        break;
+                    }
+                }
+            }
+            watchdog.as_mut().reset(time::Instant::now() + tick);
+        }
+        feed_cancel.cancel();
+        self.finish()
+    }
+
+    /// Время с входа по часам монитора
+    pub(super) fn elapsed(&self) -> Duration {
+        Duration::from_millis(self.clock.now_ms().saturating_sub(self.started_ms))
+    }
+
+    /// Проверка всех условий выхода; `true` — позиция закрыта полностью
+    pub async fn check_risk_conditions(&self) -> Result<bool> {
+        // 1. Получаем текущую цену и данные пула
+        let snapshot = self.get_price_and_liquidity().await?;
+        self.process_snapshot(&snapshot).await
+    }
+
+    /// Тик по уже полученному снимку (из опроса или подписки)
+    /// Снимок уже получен: решения по нему (rug-pull, отток, цена) — первыми,
+    /// проверки со своими запросами к RPC — после и параллельно.
+    async fn process_snapshot(&self, snapshot: &PoolSnapshot) -> Result<bool> {
+        self.refresh_sol_price();
+        if self.on_tick(snapshot, self.elapsed()).await? {
+            return Ok(true);
+        }
+        let tick = self.ticks.fetch_add(1, Ordering::Relaxed);
+        let (frozen, supply, creator) = tokio::join!(
+            self.check_frozen(tick),
+            self.check_supply(tick),
+            self.check_creator()
+        );
+        Ok(frozen || supply || creator)
+    }
+
+    /// Один тик мониторинга по снимку пула и времени с входа.
+    /// Решения принимаются под блокировкой состояния, продажи — после неё.
+    /// `true` — позиция закрыта полностью.
+    pub async fn on_tick(&self, snapshot: &PoolSnapshot, elapsed: Duration) -> Result<bool> {
+        let sample = PriceSample {
+            timestamp_ms: self.started_ms + elapsed.as_millis() as u64,
+            price: snapshot.price,
+            sol_reserve: snapshot.sol_reserve,
+        };
+        let actions = {
+            let mut state = self.state.lock().unwrap();
+            let actions = self.config().evaluate(&mut state, &sample, elapsed);
+            if let Some(journal) = &self.journal {
+                let tick = self.tick_record(&state, &sample, &actions);
+                journal.record(self.token_mint, JournalEntry::Tick(tick));
+            }
+            actions
+        };
+
+        for action in actions {
+            match action {
+                RiskAction::Notify(event) => self.publish(event),
+                RiskAction::Sell { sale, event } => {
+                    self.act(sale, event).await;
+                }
+            }
+        }
+        self.persist();
+        Ok(self.state().is_closed())
+    }
+
+    /// Мониторинг сбоит слишком долго: уведомление и продажа всего вслепую.
+    /// На паузе только уведомление. `true` — позиция закрыта.
+    async fn protective_exit(&self, consecutive_errors: u32) -> bool {
+        log::error!(
+            "🆘 {} сбоев мониторинга {} подряд → защитный выход",
+            consecutive_errors,
+            self.token_mint
+        );
+        self.publish(RiskEvent::Degraded { consecutive_errors });
+        let _act = self.act_lock.lock().await;
+        if self.is_paused() {
+            return false;
+        }
+        let sale = {
+            let mut state = self.state.lock().unwrap();
+            if state.is_closed() {
+                return true;
+            }
+            state.take(1.0, ExitReason::Degraded)
+        };
+        self.execute(sale).await;
+        self.persist();
+        self.state().is_closed()
+    }
+
+    /// Срок удержания истёк, а цены нет: продаём вслепую; `true` — позиция закрыта
+    async fn force_max_hold(&self) -> bool {
+        let elapsed = self.elapsed();
+        let (sale, event) = {
+            let mut state = self.state.lock().unwrap();
+            match self.config().check_max_hold(&mut state, elapsed) {
+                Some(RiskAction::Sell { sale, event }) => (sale, event),
+                _ => return false,
+            }
+        };
+        log::warn!("⌛ Цена недоступна, продажа по max_hold_secs вслепую");
+        self.act(sale, event).await;
+        self.persist();
+        self.state().is_closed()
+    }
+
+    /// Интервал до следующего тика; запоминается для статуса
+    fn next_interval(&self) -> Duration {
+        let change = self
+            .state
+            .lock()
+            .unwrap()
+            .history
+            .last_change_pct()
+            .unwrap_or(0.0);
+        let tick = self.config().tick_interval(self.elapsed(), change);
+        self.tick_interval_ms
+            .store(tick.as_millis() as u64, Ordering::Relaxed);
+        tick
+    }
+
+    /// Аккаунт, где лежит SOL пула: bonding curve (`true`) или SOL-хранилище Raydium
+    pub(super) fn pool_account(&self) -> (Pubkey, bool) {
+        let state = self.state.lock().unwrap();
+        match (state.price_source, &state.raydium_pool) {
+            (PriceSource::Raydium, Some(pool)) if pool.pc_mint == WSOL_MINT => {
+                (pool.pc_vault, false)
+            }
+            (PriceSource::Raydium, Some(pool)) => (pool.coin_vault, false),
+            _ => (bonding_curve_pda(&self.token_mint), true),
+        }
+    }
+
+    /// Цена и ликвидность: bonding curve, после миграции — пул Raydium
+    pub(super) async fn get_price_and_liquidity(&self) -> Result<PoolSnapshot> {
+        let source = self.state.lock().unwrap().price_source;
+        if source == PriceSource::BondingCurve {
+            let mint = self.token_mint;
+            let (curve, slot) = self
+                .rpc
+                .fetch(|client| async move { fetch_curve(&client, &mint).await })
+                .await?;
+            if !curve.complete {
+                return Ok(PoolSnapshot::from_curve(&curve, slot));
+            }
+            // Кривая завершена: её резервы больше не отражают рынок
+            let pool = find_raydium_pool(&self.client, &self.token_mint)
+                .await
+                .context(MigrationPending)?;
+            log::info!(
+                "🎓 {} мигрировал на Raydium (пул {}) → цена из пула",
+                self.token_mint,
+                pool.address
+            );
+            let mut state = self.state.lock().unwrap();
+            state.price_source = PriceSource::Raydium;
+            state.raydium_pool = Some(pool);
+        }
+
+        let pool = self
+            .state
+            .lock()
+            .unwrap()
+            .raydium_pool
+            .context("пул Raydium не сохранён")?;
+        self.rpc
+            .fetch(|client| async move { pool.fetch_snapshot(&client).await })
+            .await
+    }
+
+    /// Строка журнала по состоянию после решений тика
+    fn tick_record(
+        &self,
+        state: &RiskState,
+        sample: &PriceSample,
+        actions: &[RiskAction],
+    ) -> TickRecord {
+        let pct_below = |base: f64| {
+            if base > 0.0 {
+                (1.0 - sample.price / base) * 100.0
+            } else {
+                0.0
+            }
+        };
+        TickRecord {
+            timestamp_ms: sample.timestamp_ms,
+            price: sample.price,
+            sol_reserve: sample.sol_reserve,
+            multiple: if state.entry_price > 0.0 {
+                sample.price / state.entry_price
+            } else {
+                1.0
+            },
+            drawdown_from_peak_pct: pct_below(state.peak_price),
+            drawdown_from_entry_pct: pct_below(state.entry_price),
+            reserve_drop_pct: state
+                .initial_reserve
+                .map_or(0.0, |initial| reserve_drop_pct(initial, sample.sol_reserve)),
+            peak_price: state.peak_price,
+            stop_price: state.stop_price,
+            breakeven_armed: state.breakeven_armed,
+            trailing_armed: state.trailing_armed,
+            trailing_stop_price: (state.trailing_armed && !state.trailing_triggered)
+                .then(|| state.peak_price * (1.0 - state.trailing_pct / 100.0)),
+            remaining: state.remaining,
+            paused: self.is_paused(),
+            actions: actions
+                .iter()
+                .filter_map(|action| {
+                    let (RiskAction::Notify(event) | RiskAction::Sell { event, .. }) = action;
+                    serde_json::to_value(event).ok()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use solana_client::{
+        client_error::{ClientErrorKind, Result as ClientResult},
+        rpc_client::RpcClientConfig,
+        rpc_request::RpcRequest,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+    use crate::scanner::onchain::PUMP_PROGRAM;
+    use crate::trading::{
+        curve::{BondingCurve, BONDING_CURVE_DISCRIMINATOR},
+        fees::Urgency,
+        pump_sell::{SellReceipt, TokenAmount},
+    };
+
+    /// Свежая кривая pump.fun
+    const CURVE: BondingCurve = BondingCurve {
+        virtual_token_reserves: 1_073_000_000_000_000,
+        virtual_sol_reserves: 30_000_000_000,
+        real_token_reserves: 793_100_000_000_000,
+        real_sol_reserves: 0,
+        token_total_supply: 1_000_000_000_000_000,
+        complete: false,
+        creator: None,
+    };
+
+    /// RPC, у которого первые `failures` чтений bonding curve обрываются соединением;
+    /// остальные запросы — фатальная ошибка
+    struct FlakyCurve {
+        curve: String,
+        failures: Mutex<u32>,
+    }
+
+    impl FlakyCurve {
+        fn account(&self) -> serde_json::Value {
+            let mut data = BONDING_CURVE_DISCRIMINATOR.to_vec();
+            for v in [
+                CURVE.virtual_token_reserves,
+                CURVE.virtual_sol_reserves,
+                CURVE.real_token_reserves,
+                CURVE.real_sol_reserves,
+                CURVE.token_total_supply,
+            ] {
+                data.extend(v.to_le_bytes());
+            }
+            data.push(0);
+            serde_json::json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "data": [STANDARD.encode(&data), "base64"],
+                    "executable": false,
+                    "lamports": LAMPORTS_PER_SOL,
+                    "owner": PUMP_PROGRAM.to_string(),
+                    "rentEpoch": 0,
+                    "space": data.len(),
+                }
+            })
+        }
+    }
+
+    #[async_trait]
+    impl RpcSender for FlakyCurve {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+        ) -> ClientResult<serde_json::Value> {
+            if request == RpcRequest::GetAccountInfo && params[0] == self.curve.as_str() {
+                let mut failures = self.failures.lock().unwrap();
+                if *failures == 0 {
+                    return Ok(self.account());
+                }
+                *failures -= 1;
+                let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+                return Err(ClientErrorKind::Io(reset).into());
+            }
+            Err(ClientErrorKind::Custom(format!("{} не поддержан в тесте", request)).into())
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "flaky".to_string()
+        }
+    }
+
+    /// Монитор по цене свежей кривой; RPC сбоит `failures` раз подряд
+    fn flaky_monitor(
+        failures: u32,
+        config: RiskConfig,
+    ) -> (Arc<RiskMonitor>, mpsc::Receiver<PositionEvent>) {
+        let mint = Pubkey::new_unique();
+        let token = PumpToken {
+            mint: mint.to_string(),
+            price: PoolSnapshot::from_curve(&CURVE, 0).price,
+            ..Default::default()
+        };
+        let sender = FlakyCurve {
+            curve: bonding_curve_pda(&mint).to_string(),
+            failures: Mutex::new(failures),
+        };
+        let client = Arc::new(RpcClient::new_sender(sender, RpcClientConfig::default()));
+        let (tx, rx) = mpsc::channel(64);
+        let monitor = RiskMonitor::new(client, Arc::new(Keypair::new()), &token, 1.0, config)
+            .unwrap()
+            .with_dry_run(true)
+            .with_events(tx);
+        (Arc::new(monitor), rx)
+    }
+
+    fn degraded(rx: &mut mpsc::Receiver<PositionEvent>) -> Vec<u32> {
+        let mut found = Vec::new();
+        while let Ok(PositionEvent { event, .. }) = rx.try_recv() {
+            if let RiskEvent::Degraded { consecutive_errors } = event {
+                found.push(consecutive_errors);
+            }
+        }
+        found
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_rpc_errors_keep_position() {
+        let config = RiskConfig {
+            max_consecutive_errors: 5,
+            ..Default::default()
+        };
+        let (monitor, mut rx) = flaky_monitor(4, config);
+        let handle = monitor.clone().start_monitoring();
+        time::sleep(Duration::from_secs(30)).await;
+
+        // Четыре обрыва подряд — меньше порога: позиция на месте, тики идут
+        assert!(handle.is_running());
+        assert_eq!(monitor.state().remaining, 1.0);
+        assert!(monitor.state().history.latest().is_some());
+        assert!(degraded(&mut rx).is_empty());
+
+        handle.stop();
+        let summary = handle.await_exit().await.unwrap();
+        assert_eq!(summary.exit_reason, ExitReason::Stopped);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn persistent_rpc_errors_trigger_protective_exit() {
+        let config = RiskConfig {
+            max_consecutive_errors: 3,
+            ..Default::default()
+        };
+        let (monitor, mut rx) = flaky_monitor(u32::MAX, config);
+        let handle = monitor.clone().start_monitoring();
+        let summary = time::timeout(Duration::from_secs(60), handle.await_exit())
+            .await
+            .expect("монитор не вышел после сбоев")
+            .unwrap();
+
+        // Позицию не бросаем молча: уведомление и продажа всего остатка
+        assert_eq!(degraded(&mut rx), vec![3]);
+        assert_eq!(summary.exit_reason, ExitReason::Degraded);
+        assert!(monitor.state().is_closed());
+    }
+
+    async fn tick(monitor: &RiskMonitor, secs: u64, price: f64) -> bool {
+        let snapshot = PoolSnapshot {
+            price,
+            sol_reserve: 30 * LAMPORTS_PER_SOL,
+            token_reserve: 0,
+            slot: secs,
+        };
+        monitor
+            .on_tick(&snapshot, Duration::from_secs(secs))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn persisted_position_survives_restart() {
+        let config = RiskConfig {
+            take_profit_tiers: vec![(2.0, 0.5), (4.0, 0.25)],
+            moon_allocation_pct: 0.0,
+            whale_sell_pct: 20.0,
+            creator_dump_pct: 50.0,
+            ..Default::default()
+        };
+        let token = PumpToken {
+            mint: Pubkey::new_unique().to_string(),
+            price: 1.0,
+            ..Default::default()
+        };
+        let client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        let wallet = Arc::new(Keypair::new());
+        let store = Arc::new(PositionStore::open_in_memory().unwrap());
+        let monitor = RiskMonitor::new(client.clone(), wallet.clone(), &token, 1.0, config.clone())
+            .unwrap()
+            .with_entry_fee(5_000)
+            .with_dry_run(true)
+            .with_store(store.clone());
+
+        // Половина продана на 2x, пик 2.5x, затем крупная продажа сузила trailing stop
+        assert!(!tick(&monitor, 0, 1.0).await);
+        assert!(!tick(&monitor, 1, 2.0).await);
+        assert!(!tick(&monitor, 2, 2.5).await);
+        let whale = PoolSnapshot {
+            price: 2.4,
+            sol_reserve: 30 * LAMPORTS_PER_SOL * 3 / 4,
+            token_reserve: 0,
+            slot: 3,
+        };
+        assert!(!monitor
+            .on_tick(&whale, Duration::from_secs(3))
+            .await
+            .unwrap());
+        // Разовые условия, сработавшие по данным RPC, которых в тесте нет
+        {
+            let mut state = monitor.state.lock().unwrap();
+            state.floor_triggered = true;
+            state.creator_dump_triggered = true;
+            state.creator_peak_balance = 1_000_000;
+            state.supply_triggered = true;
+        }
+        monitor.persist();
+        let before = monitor.state();
+        assert!((before.remaining - 0.5).abs() < 1e-9);
+        assert!(before.sol_recovered > 0);
+        assert_eq!(before.tight_trailing, Some((10.0, Duration::from_secs(63))));
+
+        let saved = store.load_all().unwrap();
+        assert_eq!(saved, vec![monitor.persisted()]);
+        drop(monitor);
+
+        // «Перезапуск»: тот же конфиг, монитор из базы
+        let restored = RiskMonitor::restore(client, wallet, &saved[0], config)
+            .unwrap()
+            .with_dry_run(true)
+            .with_store(store.clone());
+        assert_eq!(restored.persisted(), saved[0]);
+        let state = restored.state();
+        assert_eq!(state.remaining, before.remaining);
+        assert_eq!(state.peak_price, before.peak_price);
+        assert_eq!(state.tiers_hit, vec![true, false]);
+        assert!(state.trailing_armed);
+        assert_eq!(state.sol_recovered, before.sol_recovered);
+        assert_eq!(state.fees_paid, before.fees_paid);
+        assert!(state.floor_triggered && state.creator_dump_triggered && state.supply_triggered);
+        assert_eq!(state.creator_peak_balance, 1_000_000);
+        assert_eq!(state.tight_trailing, before.tight_trailing);
+        // Продажа создателя уже обработана — повторно не сработает
+        let config = restored.config();
+        assert!(config
+            .check_creator_dump(&mut restored.state.lock().unwrap(), 0)
+            .is_none());
+
+        // Сработавшая ступень не повторяется, trailing суженный и от сохранённого пика:
+        // −4% держим, −12% уже продаём (без сужения порог 30%)
+        assert!(!tick(&restored, 4, 2.4).await);
+        assert_eq!(restored.state().remaining, before.remaining);
+        assert!(tick(&restored, 5, 2.2).await);
+        assert_eq!(
+            restored.exit_summary().exit_reason,
+            ExitReason::TrailingStop
+        );
+        assert!(store.load_all().unwrap().is_empty());
+    }
+
+    /// Исполнитель, чьи продажи ждут разрешения и не проходят
+    struct StalledFail {
+        entered: tokio::sync::Notify,
+        release: tokio::sync::Semaphore,
+    }
+
+    #[async_trait]
+    impl ExitExecutor for StalledFail {
+        async fn sell(&self, _: Pubkey, _: TokenAmount, _: Urgency) -> Result<SellReceipt> {
+            self.entered.notify_one();
+            self.release.acquire().await?.forget();
+            anyhow::bail!("продажа не прошла")
+        }
+    }
+
+    #[tokio::test]
+    async fn shrinking_tiers_during_failed_tier_sale() {
+        let config = RiskConfig {
+            take_profit_tiers: vec![(1.5, 0.3), (2.0, 0.3)],
+            moon_allocation_pct: 0.0,
+            ..Default::default()
+        };
+        let token = PumpToken {
+            mint: Pubkey::new_unique().to_string(),
+            price: 1.0,
+            ..Default::default()
+        };
+        let executor = Arc::new(StalledFail {
+            entered: tokio::sync::Notify::new(),
+            release: tokio::sync::Semaphore::new(0),
+        });
+        let client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        let monitor = RiskMonitor::new(client, Arc::new(Keypair::new()), &token, 1.0, config)
+            .unwrap()
+            .with_executor(executor.clone());
+        let monitor = Arc::new(monitor);
+        assert!(!tick(&monitor, 0, 1.0).await);
+
+        // Обе ступени сработали; пока первая продаётся, ступень остаётся одна
+        let ticking = tokio::spawn({
+            let monitor = monitor.clone();
+            async move { tick(&monitor, 1, 2.0).await }
+        });
+        executor.entered.notified().await;
+        monitor
+            .set_config(RiskConfig {
+                take_profit_tiers: vec![(1.5, 0.3)],
+                moon_allocation_pct: 0.0,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(monitor.state().tiers_hit, [true]);
+        executor.release.add_permits(2);
+        assert!(!ticking.await.unwrap());
+
+        // Обе доли вернулись, оставшаяся ступень снова взведена
+        let state = monitor.state();
+        assert_eq!(state.remaining, 1.0);
+        assert_eq!(state.tiers_hit, [false]);
+
+        executor.release.add_permits(1);
+        assert!(!tick(&monitor, 2, 2.0).await);
+        assert_eq!(monitor.state().tiers_hit, [false]);
+    }
+}
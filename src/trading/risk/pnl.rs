@@ -0,0 +1,350 @@
+use serde::Serialize;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use std::{sync::atomic::Ordering, time::Duration};
+
+use super::{ExitReason, RiskEvent, RiskMonitor};
+use crate::trading::pool::PriceSource;
+
+/// Итог позиции после остановки мониторинга
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitSummary {
+    pub mint: Pubkey,
+    /// Создатель токена, если известен
+    pub creator: Option<Pubkey>,
+    pub entry_price: f64,
+    pub exit_reason: ExitReason,
+    /// Получено от всех продаж, SOL
+    pub sol_recovered: f64,
+    /// Выручка за вычетом комиссий и стоимости проданной доли, SOL
+    pub realized_pnl_sol: f64,
+    /// `realized_pnl_sol` к стоимости проданной доли, %
+    pub realized_pnl_pct: f64,
+    /// Пик цены к цене входа
+    pub peak_multiple: f64,
+    pub hold_duration: Duration,
+    /// Стоимость входа в USD по цене SOL на входе
+    pub entry_cost_usd: Option<f64>,
+    /// `sol_recovered` в USD по цене SOL на выходе
+    pub sol_recovered_usd: Option<f64>,
+    /// Выручка (USD на выходе) минус стоимость проданной доли (USD на входе);
+    /// `None` — цены SOL на входе или выходе нет
+    pub realized_pnl_usd: Option<f64>,
+}
+
+/// Открытая позиция для списков и UI
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionStatus {
+    #[serde(serialize_with = "pubkey_str")]
+    pub mint: Pubkey,
+    pub stake_sol: f64,
+    pub entry_price: f64,
+    /// Цена последнего тика; до первого тика — цена входа
+    pub last_price: f64,
+    /// `last_price` к цене входа
+    pub multiple: f64,
+    pub peak_price: f64,
+    /// Жёсткий стоп (panic-sell или безубыток)
+    pub stop_price: f64,
+    /// Жёсткий стоп перенесён в безубыток
+    pub breakeven_armed: bool,
+    /// Действующий trailing stop от пика, %
+    pub trailing_stop_pct: f64,
+    /// Trailing stop включён (`trailing_activation_multiple` достигнут)
+    pub trailing_armed: bool,
+    /// Уровень trailing stop; `None` — не включён или уже сработал
+    pub trailing_stop_price: Option<f64>,
+    /// Непроданная доля позиции
+    pub remaining: f64,
+    /// Непроданная лунная доля (доля исходной позиции)
+    pub moon_remaining: f64,
+    /// Получено от продаж, SOL
+    pub sol_recovered: f64,
+    /// PnL остатка по цене последнего тика, SOL
+    pub unrealized_pnl_sol: f64,
+    /// Цена SOL на входе, USD
+    pub entry_sol_usd: Option<f64>,
+    /// Последняя известная цена SOL, USD; `None` — источник недоступен
+    pub sol_usd: Option<f64>,
+    /// Сколько мс назад получена `sol_usd`
+    pub sol_usd_age_ms: Option<u64>,
+    /// Ставка в USD по цене SOL на входе
+    pub stake_usd: Option<f64>,
+    /// `unrealized_pnl_sol` в USD по текущей цене SOL
+    pub unrealized_pnl_usd: Option<f64>,
+    /// Объём торгов за `volume_window_secs`, SOL; `None` — не измеряется
+    pub volume_sol: Option<f64>,
+    /// Текущий интервал мониторинга, мс
+    pub tick_interval_ms: u64,
+    /// Частичная продажа по time-out уже была
+    pub timeout_triggered: bool,
+    pub moon_sold: bool,
+    /// Сработавшие ступени `take_profit_tiers` по индексу
+    pub tiers_hit: Vec<bool>,
+    /// Откуда берётся цена
+    pub price_source: PriceSource,
+    /// Время с входа, сек
+    pub elapsed_secs: u64,
+    /// Время последней цены, unix, мс; `None` — цен ещё не было
+    pub last_update_ms: Option<u64>,
+    /// Автопродажи на паузе
+    pub paused: bool,
+    pub running: bool,
+}
+
+/// Для `#[serde(serialize_with)]`: pubkey строкой base58, а не массивом байт
+pub(super) fn pubkey_str<S: serde::Serializer>(
+    key: &Pubkey,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    s.collect_str(key)
+}
+
+impl RiskMonitor {
+    /// Стоимость входа: ставка плюс комиссия покупки, SOL
+    pub fn entry_cost_sol(&self) -> f64 {
+        self.stake_sol + self.entry_fee_lamports as f64 / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Нереализованный PnL остатка позиции при цене `current_price`, SOL
+    pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
+        let state = self.state.lock().unwrap();
+        state.unrealized_pnl_sol(self.entry_cost_sol(), current_price)
+    }
+
+    /// Сводка по позиции из состояния последнего тика, без запросов в сеть
+    pub fn status(&self) -> PositionStatus {
+        let config = self.config();
+        let sol_usd = self.sol_price.as_ref().and_then(|feed| feed.cached());
+        let state = self.state.lock().unwrap();
+        let latest = state.history.latest();
+        let last_price = latest.map_or(state.entry_price, |s| s.price);
+        PositionStatus {
+            mint: self.token_mint,
+            stake_sol: self.stake_sol,
+            entry_price: state.entry_price,
+            last_price,
+            multiple: if state.entry_price > 0.0 {
+                last_price / state.entry_price
+            } else {
+                1.0
+            },
+            peak_price: state.peak_price,
+            stop_price: state.stop_price,
+            breakeven_armed: state.breakeven_armed,
+            trailing_stop_pct: state.trailing_pct,
+            trailing_armed: state.trailing_armed,
+            trailing_stop_price: (state.trailing_armed && !state.trailing_triggered)
+                .then(|| state.peak_price * (1.0 - state.trailing_pct / 100.0)),
+            remaining: state.remaining,
+            moon_remaining: if state.moon_sold {
+                0.0
+            } else {
+                (config.moon_allocation_pct / 100.0).min(state.remaining)
+            },
+            sol_recovered: state.sol_recovered as f64 / LAMPORTS_PER_SOL as f64,
+            unrealized_pnl_sol: state.unrealized_pnl_sol(self.entry_cost_sol(), last_price),
+            entry_sol_usd: state.entry_sol_usd,
+            sol_usd: sol_usd.map(|p| p.usd),
+            sol_usd_age_ms: sol_usd.map(|p| p.age_ms),
+            stake_usd: state.entry_sol_usd.map(|usd| self.stake_sol * usd),
+            unrealized_pnl_usd: sol_usd
+                .map(|p| state.unrealized_pnl_sol(self.entry_cost_sol(), last_price) * p.usd),
+            volume_sol: state.volume_sol,
+            tick_interval_ms: self.tick_interval_ms.load(Ordering::Relaxed),
+            timeout_triggered: state.timeout_triggered,
+            moon_sold: state.moon_sold,
+            tiers_hit: state.tiers_hit.clone(),
+            price_source: state.price_source,
+            elapsed_secs: self.elapsed().as_secs(),
+            last_update_ms: latest.map(|s| s.timestamp_ms),
+            paused: self.is_paused(),
+            running: self.running.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Итог позиции; закрытая целиком публикуется как `RiskEvent::Closed`
+    pub(crate) fn finish(&self) -> ExitSummary {
+        let summary = self.exit_summary();
+        if self.state().is_closed() {
+            self.publish(RiskEvent::Closed(summary.clone()));
+        }
+        summary
+    }
+
+    /// Итог позиции на текущий момент
+    pub fn exit_summary(&self) -> ExitSummary {
+        let state = self.state();
+        let cost = self.entry_cost_sol();
+        let realized_pnl_sol = state.realized_pnl_sol(cost);
+        let sold_cost = cost * (1.0 - state.remaining);
+        let exit_sol_usd = self
+            .sol_price
+            .as_ref()
+            .and_then(|feed| feed.cached())
+            .map(|p| p.usd);
+        let proceeds_sol =
+            state.sol_recovered.saturating_sub(state.fees_paid) as f64 / LAMPORTS_PER_SOL as f64;
+        ExitSummary {
+            mint: self.token_mint,
+            creator: self.creator,
+            entry_price: state.entry_price,
+            exit_reason: match state.last_exit {
+                Some(reason) if state.is_closed() => reason,
+                _ => ExitReason::Stopped,
+            },
+            sol_recovered: state.sol_recovered as f64 / LAMPORTS_PER_SOL as f64,
+            realized_pnl_sol,
+            realized_pnl_pct: if sold_cost > 0.0 {
+                realized_pnl_sol / sold_cost * 100.0
+            } else {
+                0.0
+            },
+            peak_multiple: if state.entry_price > 0.0 {
+                state.peak_price / state.entry_price
+            } else {
+                1.0
+            },
+            hold_duration: self.elapsed(),
+            entry_cost_usd: state.entry_sol_usd.map(|usd| cost * usd),
+            sol_recovered_usd: exit_sol_usd
+                .map(|usd| state.sol_recovered as f64 / LAMPORTS_PER_SOL as f64 * usd),
+            realized_pnl_usd: state
+                .entry_sol_usd
+                .zip(exit_sol_usd)
+                .map(|(entry_usd, exit_usd)| proceeds_sol * exit_usd - sold_cost * entry_usd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_sdk::signature::{Keypair, Signature};
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::scanner::PumpToken;
+    use crate::trading::{
+        curve::PoolSnapshot,
+        executor::ExitExecutor,
+        fees::Urgency,
+        pump_sell::{SellReceipt, SellRoute, TokenAmount},
+        risk::RiskConfig,
+    };
+
+    const SOL: u64 = LAMPORTS_PER_SOL;
+
+    /// Исполнитель с заранее заданными (выручкой, комиссией) продаж по порядку, lamports
+    struct Fills(Mutex<VecDeque<(u64, u64)>>);
+
+    #[async_trait]
+    impl ExitExecutor for Fills {
+        async fn sell(&self, _: Pubkey, _: TokenAmount, _: Urgency) -> Result<SellReceipt> {
+            let (sol_received, fee_lamports) = self
+                .0
+                .lock()
+                .unwrap()
+                .pop_front()
+                .context("лишняя продажа")?;
+            Ok(SellReceipt {
+                signature: Signature::default(),
+                route: SellRoute::BondingCurve,
+                tokens_sold: 0,
+                sol_received,
+                expected_sol: sol_received,
+                realized_slippage_bps: None,
+                fee_lamports,
+                simulated: false,
+            })
+        }
+    }
+
+    /// Ставка 1 SOL по цене 1.0, покупка стоила 5000 lamports комиссии
+    fn monitor(config: RiskConfig, fills: &[(u64, u64)]) -> RiskMonitor {
+        let token = PumpToken {
+            mint: Pubkey::new_unique().to_string(),
+            price: 1.0,
+            ..Default::default()
+        };
+        let client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        RiskMonitor::new(client, Arc::new(Keypair::new()), &token, 1.0, config)
+            .unwrap()
+            .with_entry_fee(5_000)
+            .with_executor(Arc::new(Fills(Mutex::new(fills.iter().copied().collect()))))
+    }
+
+    async fn tick(monitor: &RiskMonitor, secs: u64, price: f64) -> bool {
+        let snapshot = PoolSnapshot {
+            price,
+            sol_reserve: 30 * SOL,
+            token_reserve: 0,
+            slot: secs,
+        };
+        monitor
+            .on_tick(&snapshot, Duration::from_secs(secs))
+            .await
+            .unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[tokio::test]
+    async fn partial_exits_at_different_prices() {
+        let config = RiskConfig {
+            take_profit_tiers: vec![(2.0, 0.5)],
+            moon_allocation_pct: 0.0,
+            ..Default::default()
+        };
+        // Половина на 2x, остаток по trailing stop с 2.5x до 1.6x
+        let monitor = monitor(config, &[(990_000_000, 10_000), (790_000_000, 15_000)]);
+        let cost = 1.000_005;
+        assert!(close(monitor.entry_cost_sol(), cost));
+
+        assert!(!tick(&monitor, 0, 1.0).await);
+        assert!(!tick(&monitor, 1, 2.0).await);
+        let state = monitor.state();
+        assert!(close(state.remaining, 0.5));
+        // 0.99 − 0.00001 − половина входа
+        assert!(close(state.realized_pnl_sol(cost), 0.98999 - cost / 2.0));
+        assert!(close(monitor.unrealized_pnl(2.0), cost / 2.0));
+        let summary = monitor.exit_summary();
+        assert_eq!(summary.exit_reason, ExitReason::Stopped);
+        assert!(close(
+            summary.realized_pnl_pct,
+            (0.98999 - cost / 2.0) / (cost / 2.0) * 100.0
+        ));
+
+        assert!(!tick(&monitor, 2, 2.5).await);
+        assert!(tick(&monitor, 3, 1.6).await);
+        let summary = monitor.exit_summary();
+        assert_eq!(summary.exit_reason, ExitReason::TrailingStop);
+        assert!(close(summary.sol_recovered, 1.78));
+        // 1.78 − 0.000025 комиссий продаж − 1.000005 входа
+        assert!(close(summary.realized_pnl_sol, 1.78 - 0.000_025 - cost));
+        assert!(close(
+            summary.realized_pnl_pct,
+            summary.realized_pnl_sol / cost * 100.0
+        ));
+        assert!(close(summary.peak_multiple, 2.5));
+        assert!(close(monitor.unrealized_pnl(3.0), 0.0));
+        assert_eq!(summary.realized_pnl_usd, None);
+    }
+
+    #[tokio::test]
+    async fn loss_counts_fees() {
+        let monitor = monitor(RiskConfig::default(), &[(300_000_000, 20_000)]);
+        assert!(!tick(&monitor, 0, 1.0).await);
+        assert!(tick(&monitor, 1, 0.3).await);
+        let summary = monitor.exit_summary();
+        assert_eq!(summary.exit_reason, ExitReason::PanicSell);
+        assert!(close(summary.realized_pnl_sol, 0.29998 - 1.000_005));
+        assert!(summary.realized_pnl_pct < -70.0);
+    }
+}
@@ -0,0 +1,523 @@
+use anyhow::{Context, Result};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+use std::sync::Arc;
+use tokio::{sync::mpsc, time};
+
+use super::{ExecutionMode, ExitReason, PositionEvent, RiskAction, RiskEvent, RiskMonitor, Sale};
+use crate::scanner::onchain::associated_token_address;
+use crate::trading::{
+    curve::TOKEN_DECIMALS,
+    executor::{
+        self, CurveExecutor, DryRunExecutor, ExitExecutor, ExitStyle, FallbackExecutor,
+        JupiterExecutor, RetryHook, SellSettings,
+    },
+    fees::Urgency,
+    history::PriceSample,
+    journal::{ActionOutcome, ActionRecord, JournalEntry},
+    pool::PriceSource,
+    pump_sell::{self, SellReceipt, SellRoute, TokenAmount},
+    slippage,
+};
+
+impl RiskMonitor {
+    /// Автоматическая продажа по условию выхода: на паузе доля возвращается
+    /// в позицию и условие снова взводится, иначе — событие и `execute`
+    pub(super) async fn act(&self, sale: Sale, event: RiskEvent) -> bool {
+        let _act = self.act_lock.lock().await;
+        if self.is_paused() {
+            log::debug!("⏸️ Пауза: продажа ({:?}) пропущена", sale.reason);
+            self.state.lock().unwrap().restore(sale);
+            self.journal_action(sale, ActionOutcome::Paused);
+            return false;
+        }
+        self.publish(event);
+        self.execute(sale).await
+    }
+
+    /// Продажа; если не удалась — доля возвращается в позицию,
+    /// а условие снова взводится и сработает на следующем тике
+    pub(super) async fn execute(&self, sale: Sale) -> bool {
+        if self.skip_dust(sale) {
+            self.journal_action(sale, ActionOutcome::Dust);
+            return true;
+        }
+        match self.emergency_sell(sale).await {
+            Ok((receipt, filled)) => {
+                {
+                    let mut state = self.state.lock().unwrap();
+                    // Отменённые части остаются в позиции
+                    state.remaining = (state.remaining + sale.fraction * (1.0 - filled)).min(1.0);
+                    state.sol_recovered += receipt.sol_received;
+                    state.fees_paid += receipt.fee_lamports;
+                    state.last_exit = Some(sale.reason);
+                }
+                self.journal_action(
+                    sale,
+                    ActionOutcome::Sold {
+                        signature: receipt.signature.to_string(),
+                        sol_received: receipt.sol_received,
+                        fee_lamports: receipt.fee_lamports,
+                        filled,
+                        realized_slippage_bps: receipt.realized_slippage_bps,
+                        simulated: receipt.simulated,
+                    },
+                );
+                self.publish(RiskEvent::SellExecuted(receipt));
+                true
+            }
+            Err(e) if pump_sell::is_no_tokens(&e) => {
+                log::warn!(
+                    "🚩 Продажа ({:?}): токенов {} на кошельке нет — закрываем позицию",
+                    sale.reason,
+                    self.token_mint
+                );
+                {
+                    let mut state = self.state.lock().unwrap();
+                    state.remaining = 0.0;
+                    state.last_exit = Some(ExitReason::External);
+                }
+                self.journal_action(sale, ActionOutcome::NoTokens);
+                self.publish(RiskEvent::ZeroBalance {
+                    reason: sale.reason,
+                });
+                true
+            }
+            Err(e) => {
+                log::error!("Ошибка экстренной продажи ({:?}): {}", sale.reason, e);
+                self.state.lock().unwrap().restore(sale);
+                self.journal_action(
+                    sale,
+                    ActionOutcome::Failed {
+                        error: e.to_string(),
+                    },
+                );
+                self.publish(RiskEvent::SellFailed {
+                    reason: sale.reason,
+                    error: e.to_string(),
+                });
+                false
+            }
+        }
+    }
+
+    /// Продажа не окупает комиссии (`RiskConfig::dust_check`): остаток списывается,
+    /// ATA встаёт в очередь на закрытие в `PositionStore`. `true` — позиция закрыта.
+    fn skip_dust(&self, sale: Sale) -> bool {
+        let price = {
+            let state = self.state.lock().unwrap();
+            state
+                .history
+                .latest()
+                .map_or(state.entry_price, |s| s.price)
+        };
+        let urgency = sale.reason.urgency();
+        let slippage_bps = match urgency {
+            Urgency::Emergency => self.emergency_sell_slippage_bps,
+            _ => self.sell_slippage_bps,
+        };
+        let Some((proceeds, fees)) =
+            self.config()
+                .dust_check(self.sale_tokens(sale), price, slippage_bps, urgency)
+        else {
+            return false;
+        };
+        let account = associated_token_address(&self.wallet.pubkey(), &self.token_mint);
+        let proceeds_sol = proceeds as f64 / LAMPORTS_PER_SOL as f64;
+        let fees_sol = fees as f64 / LAMPORTS_PER_SOL as f64;
+        log::warn!(
+            "🧹 Продажа ({:?}) {} пропущена: выручка ~{:.6} SOL, комиссии ~{:.6} SOL — остаток списан как пыль",
+            sale.reason,
+            self.token_mint,
+            proceeds_sol,
+            fees_sol
+        );
+        {
+            let mut state = self.state.lock().unwrap();
+            state.remaining = 0.0;
+            state.last_exit = Some(ExitReason::Dust);
+        }
+        if let (Some(store), true) = (&self.store, self.mode.is_live()) {
+            if let Err(e) = store.queue_dust(&account.to_string(), &self.token_mint.to_string()) {
+                log::warn!("Счёт {} не поставлен на закрытие: {}", account, e);
+            }
+        }
+        self.publish(RiskEvent::DustSkipped {
+            proceeds_sol,
+            fees_sol,
+            account,
+        });
+        true
+    }
+
+    /// Токены продажи, сырые единицы: точное количество или доля токенов входа
+    /// (по ставке и цене входа)
+    fn sale_tokens(&self, sale: Sale) -> u64 {
+        if let Some(tokens) = sale.tokens {
+            return tokens;
+        }
+        let entry_price = self.state.lock().unwrap().entry_price;
+        if entry_price <= 0.0 {
+            return 0;
+        }
+        pump_sell::ui_to_raw(self.stake_sol * sale.fraction / entry_price, TOKEN_DECIMALS)
+    }
+
+    /// Итог продажи в журнал
+    fn journal_action(&self, sale: Sale, outcome: ActionOutcome) {
+        if let Some(journal) = &self.journal {
+            journal.record(
+                self.token_mint,
+                JournalEntry::Action(ActionRecord {
+                    timestamp_ms: self.clock.now_ms(),
+                    reason: sale.reason,
+                    fraction: sale.fraction,
+                    outcome,
+                }),
+            );
+        }
+    }
+
+    /// Отправка события без ожидания: медленный получатель не тормозит выход
+    pub(super) fn publish(&self, event: RiskEvent) {
+        let Some(events) = &self.events else {
+            return;
+        };
+        let event = PositionEvent {
+            mint: self.token_mint,
+            event,
+        };
+        if let Err(mpsc::error::TrySendError::Full(event)) = events.try_send(event) {
+            log::warn!("Очередь событий риска переполнена, пропущено: {:?}", event);
+        }
+    }
+
+    /// Продажа доли позиции через исполнитель (`with_executor`, иначе встроенный);
+    /// плановые — по `exit_style`. Возвращает квитанцию и проданную долю продажи (0–1].
+    async fn emergency_sell(&self, sale: Sale) -> Result<(SellReceipt, f64)> {
+        anyhow::ensure!(sale.share > 0.0, "пустая продажа");
+        log::info!(
+            "📤 Экстренная продажа {:.1}% позиции (~{} SOL) с {}",
+            sale.fraction * 100.0,
+            self.stake_sol * sale.fraction,
+            self.wallet.pubkey()
+        );
+        let amount = if self.mode == ExecutionMode::Paper && self.executor.is_none() {
+            // Бумажной позиции на кошельке нет — токены по ставке и цене входа
+            TokenAmount::Raw(self.sale_tokens(Sale {
+                tokens: None,
+                ..sale
+            }))
+        } else {
+            sale.amount()
+        };
+        let on_retry = |attempt: usize, bps: u16, e: &anyhow::Error| {
+            log::warn!(
+                "⚠️ Продажа не прошла (попытка {}, {} б.п.): {}",
+                attempt,
+                bps,
+                e
+            );
+            self.publish(RiskEvent::SellRetry {
+                reason: sale.reason,
+                attempt,
+                slippage_bps: bps,
+                error: e.to_string(),
+            });
+        };
+        let urgency = sale.reason.urgency();
+        let style = self.config().exit_style;
+        let executor = self.executor();
+        let (receipt, filled) = match style.parts(urgency) {
+            1 => (
+                executor
+                    .sell_reporting(self.token_mint, amount, urgency, &on_retry)
+                    .await?,
+                1.0,
+            ),
+            parts => {
+                self.sell_in_tranches(executor.as_ref(), amount, urgency, parts, style, &on_retry)
+                    .await?
+            }
+        };
+        log::info!(
+            "💰 Продано {} токенов за {} lamports через {:?}{}{}: {}",
+            receipt.tokens_sold,
+            receipt.sol_received,
+            receipt.route,
+            slippage::describe(receipt.realized_slippage_bps),
+            if receipt.simulated {
+                " (симуляция)"
+            } else {
+                ""
+            },
+            receipt.signature
+        );
+        Ok((receipt, filled))
+    }
+
+    /// Плановый выход частями. Перед каждой следующей частью — свежая цена: если по ней
+    /// сработал бы срочный выход (rug-pull, panic-sell, порог ликвидности), оставшиеся
+    /// части отменяются. Возвращает сводную квитанцию и проданную долю продажи.
+    async fn sell_in_tranches(
+        &self,
+        executor: &dyn ExitExecutor,
+        amount: TokenAmount,
+        urgency: Urgency,
+        parts: u8,
+        style: ExitStyle,
+        on_retry: &RetryHook<'_>,
+    ) -> Result<(SellReceipt, f64)> {
+        let mut receipts = Vec::new();
+        for k in 0..parts {
+            if k > 0 {
+                time::sleep(style.interval()).await;
+                if self.emergency_pending().await {
+                    log::warn!(
+                        "🚨 Срочный выход по свежей цене → оставшиеся {} из {} частей отменены",
+                        parts - k,
+                        parts
+                    );
+                    break;
+                }
+            }
+            let part = executor::tranche_amount(amount, k, parts);
+            match executor
+                .sell_reporting(self.token_mint, part, urgency, on_retry)
+                .await
+            {
+                Ok(receipt) => {
+                    log::info!(
+                        "🧩 Часть {} из {}: {} lamports",
+                        k + 1,
+                        parts,
+                        receipt.sol_received
+                    );
+                    receipts.push(receipt);
+                }
+                Err(e) if receipts.is_empty() => return Err(e),
+                Err(e) => {
+                    log::warn!("⚠️ Часть {} из {} не продана: {}", k + 1, parts, e);
+                    break;
+                }
+            }
+        }
+        let filled = receipts.len() as f64 / parts as f64;
+        let receipt = executor::combine_receipts(&receipts).context("ни одной части не продано")?;
+        Ok((receipt, filled))
+    }
+
+    /// Сработал бы по свежей цене срочный выход; цена недоступна — нет
+    async fn emergency_pending(&self) -> bool {
+        let snapshot = match self.get_price_and_liquidity().await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::debug!("Цена между частями продажи не получена: {}", e);
+                return false;
+            }
+        };
+        let elapsed = self.elapsed();
+        let sample = PriceSample {
+            timestamp_ms: self.started_ms + elapsed.as_millis() as u64,
+            price: snapshot.price,
+            sol_reserve: snapshot.sol_reserve,
+        };
+        // На копии: решения по-настоящему примет следующий тик
+        let mut probe = self.state();
+        self.config()
+            .evaluate(&mut probe, &sample, elapsed)
+            .iter()
+            .any(|action| {
+                matches!(action, RiskAction::Sell { sale, .. }
+                    if sale.reason.urgency() == Urgency::Emergency)
+            })
+    }
+
+    /// Исполнитель продаж: заданный через `with_executor`; в `Paper` — синтетическая
+    /// продажа по цене последнего тика; иначе bonding curve с запасным Jupiter
+    /// (после миграции — сразу Jupiter)
+    fn executor(&self) -> Arc<dyn ExitExecutor> {
+        if let Some(executor) = &self.executor {
+            return executor.clone();
+        }
+        let (price, graduated) = {
+            let state = self.state.lock().unwrap();
+            let price = state
+                .history
+                .latest()
+                .map_or(state.entry_price, |s| s.price);
+            (price, state.price_source == PriceSource::Raydium)
+        };
+        if self.mode == ExecutionMode::Paper {
+            let route = if graduated {
+                SellRoute::Jupiter
+            } else {
+                SellRoute::BondingCurve
+            };
+            return Arc::new(DryRunExecutor::paper(price, self.sell_slippage_bps, route));
+        }
+        let settings = SellSettings {
+            config: self.config(),
+            slippage_bps: self.sell_slippage_bps,
+            emergency_slippage_bps: self.emergency_sell_slippage_bps,
+            jito: self.jito.clone(),
+            rpc: Some(self.rpc.clone()),
+            dry_run: !self.mode.is_live(),
+        };
+        let jupiter = Arc::new(JupiterExecutor::new(
+            self.client.clone(),
+            self.wallet.clone(),
+            self.jupiter.clone(),
+            settings.clone(),
+        ));
+        if graduated {
+            return jupiter;
+        }
+        Arc::new(FallbackExecutor::new(
+            Arc::new(CurveExecutor::new(
+                self.client.clone(),
+                self.wallet.clone(),
+                settings,
+            )),
+            jupiter,
+        ))
+    }
+
+    /// Фиксирует лунную долю в токенах по балансу кошелька, если она ещё не известна.
+    /// Только `ExecutionMode::Live`; после частичных продаж баланс уже не «входной» — пропуск.
+    pub(super) async fn fix_moon_tokens(&self) {
+        {
+            let state = self.state.lock().unwrap();
+            if !self.mode.is_live()
+                || state.moon_tokens.is_some()
+                || state.moon_sold
+                || state.remaining < 1.0
+            {
+                return;
+            }
+        }
+        match pump_sell::token_balance(&self.client, &self.wallet.pubkey(), &self.token_mint).await
+        {
+            Ok(0) => log::warn!("🚩 На кошельке нет токенов {} на входе", self.token_mint),
+            Ok(balance) => {
+                let moon =
+                    pump_sell::fraction_of(balance, self.config().moon_allocation_pct / 100.0);
+                log::info!(
+                    "🌕 Лунная доля: {} из {} токенов",
+                    pump_sell::raw_to_ui(moon, TOKEN_DECIMALS),
+                    pump_sell::raw_to_ui(balance, TOKEN_DECIMALS)
+                );
+                self.state.lock().unwrap().moon_tokens = Some(moon);
+            }
+            Err(e) => log::warn!("Баланс для лунной доли не получен: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use solana_client::{
+        client_error::{ClientErrorKind, Result as ClientResult},
+        nonblocking::rpc_client::RpcClient,
+        rpc_client::RpcClientConfig,
+        rpc_request::RpcRequest,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+    use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+    use std::{sync::Mutex, time::Duration};
+
+    use crate::scanner::PumpToken;
+    use crate::trading::{curve::PoolSnapshot, risk::RiskConfig};
+
+    /// RPC, который запоминает запросы и на все отвечает ошибкой
+    #[derive(Clone, Default)]
+    struct RecordingSender(Arc<Mutex<Vec<RpcRequest>>>);
+
+    #[async_trait]
+    impl RpcSender for RecordingSender {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            _: serde_json::Value,
+        ) -> ClientResult<serde_json::Value> {
+            self.0.lock().unwrap().push(request);
+            Err(ClientErrorKind::Custom("RPC недоступен в тесте".to_string()).into())
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "stub".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn paper_mode_never_touches_rpc() {
+        let sender = RecordingSender::default();
+        let client = Arc::new(RpcClient::new_sender(
+            sender.clone(),
+            RpcClientConfig::default(),
+        ));
+        let token = PumpToken {
+            mint: Pubkey::new_unique().to_string(),
+            price: 1.0,
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::channel(64);
+        let monitor = RiskMonitor::new(
+            client.clone(),
+            Arc::new(Keypair::new()),
+            &token,
+            1.0,
+            RiskConfig::default(),
+        )
+        .unwrap()
+        .with_dry_run(true)
+        .with_sell_slippage(100)
+        .with_events(tx);
+
+        // Рост до 2x и trailing stop на 1.3
+        for (secs, price) in [(0, 1.0), (1, 2.0), (2, 1.3)] {
+            let snapshot = PoolSnapshot {
+                price,
+                sol_reserve: 30 * LAMPORTS_PER_SOL,
+                token_reserve: 0,
+                slot: secs,
+            };
+            monitor
+                .on_tick(&snapshot, Duration::from_secs(secs))
+                .await
+                .unwrap();
+        }
+        assert!(monitor.state().is_closed());
+        assert!(
+            sender.0.lock().unwrap().is_empty(),
+            "dry-run обратился к RPC"
+        );
+
+        // Те же события, что и вживую; выручка — по цене тика минус 1%
+        let mut receipts = Vec::new();
+        let mut trailing = false;
+        while let Ok(PositionEvent { event, .. }) = rx.try_recv() {
+            match event {
+                RiskEvent::TrailingStop { .. } => trailing = true,
+                RiskEvent::SellExecuted(receipt) => receipts.push(receipt),
+                _ => {}
+            }
+        }
+        assert!(trailing);
+        assert_eq!(receipts.len(), 1);
+        assert!(receipts[0].simulated);
+        assert_eq!(receipts[0].tokens_sold, 1_000_000);
+        let expected = 1.3 * 0.99 * LAMPORTS_PER_SOL as f64;
+        assert!((receipts[0].sol_received as f64 - expected).abs() <= 1.0);
+        assert_eq!(monitor.exit_summary().exit_reason, ExitReason::TrailingStop);
+
+        // Заглушка действительно стоит за клиентом монитора
+        assert!(client.get_slot().await.is_err());
+        assert_eq!(*sender.0.lock().unwrap(), [RpcRequest::GetSlot]);
+    }
+}
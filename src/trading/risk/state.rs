@@ -0,0 +1,172 @@
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signature};
+use std::time::Duration;
+
+use super::{ExitReason, RiskConfig, Sale};
+use crate::trading::{
+    history::PriceHistory,
+    pool::{PriceSource, RaydiumPool},
+};
+
+/// Изменяемое состояние позиции, общее для всех тиков мониторинга
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskState {
+    /// Цена входа, SOL за токен; без цены из API — цена первого тика
+    pub entry_price: f64,
+    /// Максимальная цена с момента входа
+    pub peak_price: f64,
+    /// Уровень жёсткого стопа, SOL за токен: ниже него продаётся весь остаток
+    pub stop_price: f64,
+    /// Стоп перенесён в безубыток (`move_stop_to_breakeven_after`)
+    pub breakeven_armed: bool,
+    /// Цены с момента входа (и свечи до него, если переданы)
+    pub history: PriceHistory,
+    /// Непроданная доля исходной позиции (1.0 — вся)
+    pub remaining: f64,
+    /// Резерв SOL пула при входе (или на первом тике) — база для детекта rug-pull
+    pub initial_reserve: Option<u64>,
+    /// Слот снимка пула при входе
+    pub entry_slot: Option<u64>,
+    /// Подпись транзакции покупки
+    pub entry_signature: Option<Signature>,
+    pub rug_triggered: bool,
+    pub floor_triggered: bool,
+    pub panic_triggered: bool,
+    pub timeout_triggered: bool,
+    pub trailing_triggered: bool,
+    /// Пик дошёл до `trailing_activation_multiple` — trailing stop работает
+    pub trailing_armed: bool,
+    pub moon_sold: bool,
+    pub creator_dump_triggered: bool,
+    /// Общий supply mint-а при входе (сырые единицы); `None` — ещё не получен
+    pub entry_supply: Option<u64>,
+    pub supply_triggered: bool,
+    /// Действующий trailing stop, % (пересчитывается каждый тик)
+    pub trailing_pct: f64,
+    /// Суженный после крупной продажи trailing stop, % и до какого момента (время с входа)
+    pub tight_trailing: Option<(f64, Duration)>,
+    /// Максимальный баланс токена у создателя за время мониторинга (сырые единицы)
+    pub creator_peak_balance: u64,
+    /// Лунная доля в сырых единицах токена, зафиксированная при входе;
+    /// `None` — продаётся долей `moon_allocation_pct`
+    pub moon_tokens: Option<u64>,
+    /// Цена SOL в USD на входе; `None` — не была известна
+    pub entry_sol_usd: Option<f64>,
+    /// Токен в топ-`moon_top_n` трендов DexScreener (обновляет монитор)
+    pub in_top_n: bool,
+    /// Объём торгов за `volume_window_secs`, SOL; `None` — ещё не измерен (обновляет монитор)
+    pub volume_sol: Option<f64>,
+    /// Сработавшие ступени `take_profit_tiers` (по индексу)
+    pub tiers_hit: Vec<bool>,
+    /// Получено от продаж, lamports
+    pub sol_recovered: u64,
+    /// Комиссии сети за продажи, lamports
+    pub fees_paid: u64,
+    /// Причина последней удачной продажи
+    pub last_exit: Option<ExitReason>,
+    /// Откуда сейчас берётся цена
+    pub price_source: PriceSource,
+    /// Пул Raydium после миграции с bonding curve
+    pub raydium_pool: Option<RaydiumPool>,
+}
+
+impl RiskState {
+    /// Состояние новой позиции; `entry_price` 0 — цена возьмётся с первого тика
+    pub fn new(entry_price: f64, config: &RiskConfig) -> Self {
+        Self {
+            entry_price,
+            peak_price: entry_price,
+            stop_price: config.stop_price(entry_price, false),
+            breakeven_armed: false,
+            history: PriceHistory::new(config.history_len),
+            remaining: 1.0,
+            initial_reserve: None,
+            entry_slot: None,
+            entry_signature: None,
+            rug_triggered: false,
+            floor_triggered: false,
+            panic_triggered: false,
+            timeout_triggered: false,
+            trailing_triggered: false,
+            trailing_armed: false,
+            moon_sold: false,
+            creator_dump_triggered: false,
+            entry_supply: None,
+            supply_triggered: false,
+            creator_peak_balance: 0,
+            trailing_pct: config.trailing_stop_pct,
+            tight_trailing: None,
+            moon_tokens: None,
+            entry_sol_usd: None,
+            in_top_n: false,
+            volume_sol: None,
+            tiers_hit: vec![false; config.take_profit_tiers.len()],
+            sol_recovered: 0,
+            fees_paid: 0,
+            last_exit: None,
+            price_source: PriceSource::BondingCurve,
+            raydium_pool: None,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.remaining <= f64::EPSILON
+    }
+
+    /// Возвращает долю неудавшейся продажи и снимает флаг её условия
+    pub(super) fn restore(&mut self, sale: Sale) {
+        self.remaining = (self.remaining + sale.fraction).min(1.0);
+        match sale.reason {
+            ExitReason::FreezeAuthority
+            | ExitReason::Manual
+            | ExitReason::External
+            | ExitReason::MaxHold
+            | ExitReason::Frozen
+            | ExitReason::WhaleDump
+            | ExitReason::Degraded
+            | ExitReason::Dust
+            | ExitReason::Stopped => {}
+            ExitReason::RugPull => self.rug_triggered = false,
+            ExitReason::LiquidityFloor => self.floor_triggered = false,
+            ExitReason::PanicSell | ExitReason::BreakevenStop => self.panic_triggered = false,
+            ExitReason::Timeout => self.timeout_triggered = false,
+            ExitReason::TrailingStop => self.trailing_triggered = false,
+            ExitReason::Moon => self.moon_sold = false,
+            ExitReason::CreatorDump => self.creator_dump_triggered = false,
+            ExitReason::SupplyInflated => self.supply_triggered = false,
+            // Ступени могли убрать из конфига, пока шла продажа
+            ExitReason::TakeProfit { tier } => {
+                if let Some(hit) = self.tiers_hit.get_mut(tier) {
+                    *hit = false;
+                }
+            }
+        }
+    }
+
+    /// Реализованный PnL, SOL: выручка минус комиссии продаж
+    /// минус стоимость проданной доли (`cost_sol` — стоимость всей позиции)
+    pub fn realized_pnl_sol(&self, cost_sol: f64) -> f64 {
+        let proceeds = self.sol_recovered.saturating_sub(self.fees_paid) as f64;
+        proceeds / LAMPORTS_PER_SOL as f64 - cost_sol * (1.0 - self.remaining)
+    }
+
+    /// Нереализованный PnL остатка при цене `price`, SOL
+    pub fn unrealized_pnl_sol(&self, cost_sol: f64, price: f64) -> f64 {
+        if self.entry_price <= 0.0 {
+            return 0.0;
+        }
+        cost_sol * self.remaining * (price / self.entry_price - 1.0)
+    }
+
+    /// Списывает долю позиции; не больше, чем осталось
+    pub(super) fn take(&mut self, fraction: f64, reason: ExitReason) -> Sale {
+        let before = self.remaining;
+        let sold = fraction.min(before).max(0.0);
+        self.remaining -= sold;
+        Sale {
+            reason,
+            fraction: sold,
+            share: if before > 0.0 { sold / before } else { 0.0 },
+            tokens: None,
+        }
+    }
+}
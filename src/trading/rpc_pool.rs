@@ -0,0 +1,220 @@
+use anyhow::Result;
+use futures_util::future::{select_ok, BoxFuture, FutureExt};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::errors::is_transient;
+
+/// Таймаут одного запроса цены, когда есть запасные endpoints
+pub const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// Вес нового замера в скользящей средней задержки
+const LATENCY_ALPHA: f64 = 0.3;
+
+/// Endpoint не ответил за отведённое время
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcTimeout(pub Duration);
+
+impl fmt::Display for RpcTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RPC не ответил за {:?}", self.0)
+    }
+}
+
+impl std::error::Error for RpcTimeout {}
+
+/// Состояние endpoint-а для метрик
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    /// Скользящая средняя задержки удачных запросов, мс; `None` — замеров ещё нет
+    pub latency_ms: Option<f64>,
+    /// Сбоев подряд; 0 — endpoint здоров
+    pub consecutive_failures: u32,
+    pub requests: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl EndpointHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+struct Endpoint {
+    client: Arc<RpcClient>,
+    health: Mutex<EndpointHealth>,
+}
+
+impl Endpoint {
+    fn new(client: Arc<RpcClient>) -> Self {
+        let health = EndpointHealth {
+            url: client.url(),
+            ..Default::default()
+        };
+        Self {
+            client,
+            health: Mutex::new(health),
+        }
+    }
+
+    fn success(&self, latency: Duration) {
+        let mut health = self.health.lock().unwrap();
+        let ms = latency.as_secs_f64() * 1000.0;
+        health.latency_ms = Some(match health.latency_ms {
+            Some(avg) => avg + LATENCY_ALPHA * (ms - avg),
+            None => ms,
+        });
+        health.requests += 1;
+        health.consecutive_failures = 0;
+    }
+
+    fn failure(&self, e: &anyhow::Error) {
+        let mut health = self.health.lock().unwrap();
+        health.requests += 1;
+        health.failures += 1;
+        health.consecutive_failures += 1;
+        health.last_error = Some(e.to_string());
+    }
+
+    /// Ключ выбора: сначала здоровые, среди них — быстрые; без замеров — в конце
+    fn rank(&self) -> (u32, f64) {
+        let health = self.health.lock().unwrap();
+        (
+            health.consecutive_failures,
+            health.latency_ms.unwrap_or(f64::MAX),
+        )
+    }
+}
+
+/// Упорядоченный список RPC endpoint-ов (`Config::rpc_endpoints`).
+/// Чтения идут в самый быстрый здоровый endpoint, при сбое или таймауте — в следующий;
+/// срочные отправки уходят во все сразу.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    call_timeout: Duration,
+}
+
+impl fmt::Debug for RpcPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcPool")
+            .field("endpoints", &self.health())
+            .field("call_timeout", &self.call_timeout)
+            .finish()
+    }
+}
+
+impl RpcPool {
+    pub fn new(urls: &[String]) -> Result<Self> {
+        anyhow::ensure!(!urls.is_empty(), "не задано ни одного RPC endpoint-а");
+        Ok(Self::from_clients(
+            urls.iter()
+                .map(|url| Arc::new(RpcClient::new(url.clone())))
+                .collect(),
+        ))
+    }
+
+    /// Пул из одного клиента: без таймаутов и переключений
+    pub fn single(client: Arc<RpcClient>) -> Self {
+        Self::from_clients(vec![client])
+    }
+
+    fn from_clients(clients: Vec<Arc<RpcClient>>) -> Self {
+        Self {
+            endpoints: clients.into_iter().map(Endpoint::new).collect(),
+            call_timeout: DEFAULT_CALL_TIMEOUT,
+        }
+    }
+
+    /// Таймаут одного чтения перед переходом к следующему endpoint-у
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = timeout;
+        self
+    }
+
+    /// Endpoints по предпочтению
+    fn ranked(&self) -> Vec<&Endpoint> {
+        let mut ranked: Vec<&Endpoint> = self.endpoints.iter().collect();
+        // Стабильная сортировка: при равенстве — порядок из конфига
+        ranked.sort_by(|a, b| a.rank().partial_cmp(&b.rank()).unwrap());
+        ranked
+    }
+
+    /// Клиент лучшего endpoint-а сейчас
+    pub fn primary(&self) -> Arc<RpcClient> {
+        self.ranked()[0].client.clone()
+    }
+
+    /// Чтение с переключением: endpoints по очереди, каждому — `call_timeout`
+    /// (если endpoint один — без таймаута). Ошибки данных (не сетевые) возвращаются сразу.
+    pub async fn fetch<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let failover = self.endpoints.len() > 1;
+        let mut last_err = None;
+        for endpoint in self.ranked() {
+            let started = Instant::now();
+            let call = op(endpoint.client.clone());
+            let result = if failover {
+                match tokio::time::timeout(self.call_timeout, call).await {
+                    Ok(result) => result,
+                    Err(_) => Err(RpcTimeout(self.call_timeout).into()),
+                }
+            } else {
+                call.await
+            };
+            match result {
+                Ok(value) => {
+                    endpoint.success(started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) if is_transient(&e) => {
+                    log::debug!("RPC {} сбоит: {}", endpoint.client.url(), e);
+                    endpoint.failure(&e);
+                    last_err = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_err.expect("в пуле есть хотя бы один endpoint"))
+    }
+
+    /// Один и тот же запрос во все endpoints сразу; первый удачный ответ выигрывает,
+    /// остальные отменяются. Для отправки срочных продаж.
+    pub async fn broadcast<'a, T, F>(&'a self, op: F) -> Result<T>
+    where
+        T: Send + 'a,
+        F: Fn(Arc<RpcClient>) -> BoxFuture<'a, Result<T>>,
+    {
+        let calls = self.endpoints.iter().map(|endpoint| {
+            let started = Instant::now();
+            op(endpoint.client.clone())
+                .map(move |result| {
+                    match &result {
+                        Ok(_) => endpoint.success(started.elapsed()),
+                        Err(e) => endpoint.failure(e),
+                    }
+                    result
+                })
+                .boxed()
+        });
+        select_ok(calls).await.map(|(value, _)| value)
+    }
+
+    /// Задержка и сбои по каждому endpoint-у, в порядке конфига
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.health.lock().unwrap().clone())
+            .collect()
+    }
+}
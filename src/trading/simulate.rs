@@ -0,0 +1,344 @@
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig,
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{
+    instruction::InstructionError, pubkey, pubkey::Pubkey, transaction::TransactionError,
+    transaction::VersionedTransaction,
+};
+use std::fmt;
+
+use super::pump_sell::SYSTEM_PROGRAM;
+use crate::scanner::onchain::{PUMP_PROGRAM, TOKEN_PROGRAM};
+
+/// Агрегатор Jupiter v6
+pub const JUPITER_PROGRAM: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// pump.fun: `TooMuchSolRequired`, `TooLittleSolReceived`
+const PUMP_SLIPPAGE_CODES: [u32; 2] = [6002, 6003];
+/// pump.fun: `MintDoesNotMatchBondingCurve`
+const PUMP_MINT_MISMATCH: u32 = 6004;
+/// pump.fun: `BondingCurveComplete`
+const PUMP_CURVE_COMPLETE: u32 = 6005;
+/// Jupiter: `SlippageToleranceExceeded`
+const JUPITER_SLIPPAGE: u32 = 6001;
+/// Ошибки ограничений и аккаунтов Anchor (2000–3999): аккаунт не тот, что ждёт программа
+const ANCHOR_ACCOUNT_CODES: std::ops::Range<u32> = 2000..4000;
+/// Коды SPL Token меньше этого; у Anchor-программ свои коды начинаются со 100,
+/// поэтому малый код в инструкции pump.fun — ошибка токен-программы из CPI
+const TOKEN_CODES_END: u32 = 100;
+/// SPL Token: `InsufficientFunds`
+const TOKEN_INSUFFICIENT_FUNDS: u32 = 1;
+/// SPL Token: `AccountFrozen`
+const TOKEN_ACCOUNT_FROZEN: u32 = 17;
+/// System program: `ResultWithNegativeLamports` (не хватает SOL на перевод чаевых)
+const SYSTEM_NEGATIVE_LAMPORTS: u32 = 1;
+
+/// Почему продажа не прошла бы; приходит внутри `anyhow::Error`, см. `sim_error`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExitSimError {
+    /// Выручка ниже минимальной — поможет большее проскальзывание
+    Slippage,
+    /// Не хватает SOL на комиссию, ренту или чаевые
+    InsufficientFunds,
+    /// На счёте меньше токенов, чем продаём
+    InsufficientTokens,
+    /// Токен-аккаунт заморожен
+    Frozen,
+    /// Кривая завершена — продавать через Jupiter
+    CurveComplete,
+    /// Аккаунты транзакции не сходятся с кривой (mint, создатель, получатель комиссий)
+    CurveMismatch { code: u32 },
+    /// blockhash устарел — собрать транзакцию заново
+    Expired,
+    /// Прочий отказ программы
+    Program { program: Pubkey, code: u32 },
+    /// Прочая ошибка транзакции
+    Transaction(String),
+    /// Симуляция не выполнилась (RPC)
+    Rpc(String),
+}
+
+impl ExitSimError {
+    /// Повтор с большим проскальзыванием не поможет
+    pub fn stops_escalation(&self) -> bool {
+        matches!(
+            self,
+            Self::InsufficientFunds
+                | Self::InsufficientTokens
+                | Self::Frozen
+                | Self::CurveComplete
+                | Self::CurveMismatch { .. }
+        )
+    }
+
+    /// Другим маршрутом тоже не продать: дело в кошельке, а не в пуле
+    pub fn is_wallet_problem(&self) -> bool {
+        matches!(
+            self,
+            Self::InsufficientFunds | Self::InsufficientTokens | Self::Frozen
+        )
+    }
+}
+
+impl fmt::Display for ExitSimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slippage => f.write_str("симуляция: выручка ниже минимальной (проскальзывание)"),
+            Self::InsufficientFunds => f.write_str("симуляция: не хватает SOL на комиссии"),
+            Self::InsufficientTokens => f.write_str("симуляция: не хватает токенов на счёте"),
+            Self::Frozen => f.write_str("симуляция: токен-аккаунт заморожен"),
+            Self::CurveComplete => f.write_str("симуляция: кривая завершена"),
+            Self::CurveMismatch { code } => {
+                write!(f, "симуляция: аккаунты не сходятся с кривой (код {})", code)
+            }
+            Self::Expired => f.write_str("симуляция: blockhash устарел"),
+            Self::Program { program, code } => {
+                write!(f, "симуляция: программа {} вернула код {}", program, code)
+            }
+            Self::Transaction(err) => write!(f, "симуляция: {}", err),
+            Self::Rpc(err) => write!(f, "симуляция не выполнена: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExitSimError {}
+
+/// Ошибка симуляции из цепочки `anyhow::Error`
+pub fn sim_error(e: &anyhow::Error) -> Option<&ExitSimError> {
+    e.downcast_ref::<ExitSimError>()
+}
+
+/// Код ошибки `code` программы `program` в терминах выхода
+fn classify_custom(program: Option<Pubkey>, code: u32) -> ExitSimError {
+    let token_error = |code| match code {
+        TOKEN_INSUFFICIENT_FUNDS => ExitSimError::InsufficientTokens,
+        TOKEN_ACCOUNT_FROZEN => ExitSimError::Frozen,
+        code => ExitSimError::Program {
+            program: TOKEN_PROGRAM,
+            code,
+        },
+    };
+    match program {
+        Some(TOKEN_PROGRAM) => token_error(code),
+        Some(PUMP_PROGRAM) if code < TOKEN_CODES_END => token_error(code),
+        Some(PUMP_PROGRAM) if PUMP_SLIPPAGE_CODES.contains(&code) => ExitSimError::Slippage,
+        Some(PUMP_PROGRAM) if code == PUMP_CURVE_COMPLETE => ExitSimError::CurveComplete,
+        Some(PUMP_PROGRAM)
+            if code == PUMP_MINT_MISMATCH || ANCHOR_ACCOUNT_CODES.contains(&code) =>
+        {
+            ExitSimError::CurveMismatch { code }
+        }
+        Some(JUPITER_PROGRAM) if code == JUPITER_SLIPPAGE => ExitSimError::Slippage,
+        Some(SYSTEM_PROGRAM) if code == SYSTEM_NEGATIVE_LAMPORTS => ExitSimError::InsufficientFunds,
+        program => ExitSimError::Program {
+            program: program.unwrap_or_default(),
+            code,
+        },
+    }
+}
+
+/// Ошибка транзакции `tx` в терминах выхода; программа определяется по индексу инструкции
+pub fn classify_error(tx: &VersionedTransaction, err: &TransactionError) -> ExitSimError {
+    match err {
+        TransactionError::InsufficientFundsForFee
+        | TransactionError::InsufficientFundsForRent { .. }
+        | TransactionError::AccountNotFound => ExitSimError::InsufficientFunds,
+        TransactionError::BlockhashNotFound => ExitSimError::Expired,
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+            let program = tx
+                .message
+                .instructions()
+                .get(*index as usize)
+                .and_then(|ix| {
+                    tx.message
+                        .static_account_keys()
+                        .get(ix.program_id_index as usize)
+                })
+                .copied();
+            classify_custom(program, *code)
+        }
+        TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => {
+            ExitSimError::InsufficientFunds
+        }
+        err => ExitSimError::Transaction(err.to_string()),
+    }
+}
+
+/// Результат `simulateTransaction` для `tx`: `Ok` — прошла бы
+pub fn classify(
+    tx: &VersionedTransaction,
+    result: &RpcSimulateTransactionResult,
+) -> Result<(), ExitSimError> {
+    let Some(err) = &result.err else {
+        return Ok(());
+    };
+    let classified = classify_error(tx, err);
+    if let Some(logs) = &result.logs {
+        log::debug!("Симуляция не прошла ({}): {:?}", classified, logs);
+    }
+    Err(classified)
+}
+
+/// Прогоняет `tx` через `simulateTransaction` без проверки подписей
+pub async fn simulate_and_classify(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+) -> Result<(), ExitSimError> {
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        commitment: Some(client.commitment()),
+        ..Default::default()
+    };
+    let response = client
+        .simulate_transaction_with_config(tx, config)
+        .await
+        .map_err(|e| ExitSimError::Rpc(e.to_string()))?;
+    classify(tx, &response.value)
+}
+
+/// Симуляция перед отправкой: отказ — ошибка, а недоступная симуляция
+/// продажу не задерживает
+pub async fn presimulate(client: &RpcClient, tx: &VersionedTransaction) -> anyhow::Result<()> {
+    match simulate_and_classify(client, tx).await {
+        Ok(()) => Ok(()),
+        Err(ExitSimError::Rpc(e)) => {
+            log::warn!("Симуляция продажи недоступна ({}) — отправляем без неё", e);
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction, instruction::Instruction, message::Message,
+        transaction::Transaction,
+    };
+
+    /// Продажа с чаевыми: бюджет, pump.fun, SPL Token, Jupiter, перевод чаевых Jito
+    fn exit_tx() -> VersionedTransaction {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(120_000)];
+        for program in [PUMP_PROGRAM, TOKEN_PROGRAM, JUPITER_PROGRAM, SYSTEM_PROGRAM] {
+            instructions.push(Instruction::new_with_bytes(program, &[], Vec::new()));
+        }
+        let message = Message::new(&instructions, Some(&Pubkey::new_unique()));
+        Transaction::new_unsigned(message).into()
+    }
+
+    #[test]
+    fn classifies_simulation_errors() {
+        let tx = exit_tx();
+        let pump = |code| ExitSimError::Program {
+            program: PUMP_PROGRAM,
+            code,
+        };
+        // `err` из ответа simulateTransaction как есть
+        for (err, expected) in [
+            (
+                r#"{"InstructionError":[1,{"Custom":6003}]}"#,
+                ExitSimError::Slippage,
+            ),
+            (
+                r#"{"InstructionError":[1,{"Custom":6002}]}"#,
+                ExitSimError::Slippage,
+            ),
+            (
+                r#"{"InstructionError":[1,{"Custom":6005}]}"#,
+                ExitSimError::CurveComplete,
+            ),
+            (
+                r#"{"InstructionError":[1,{"Custom":6004}]}"#,
+                ExitSimError::CurveMismatch { code: 6004 },
+            ),
+            (
+                r#"{"InstructionError":[1,{"Custom":2006}]}"#,
+                ExitSimError::CurveMismatch { code: 2006 },
+            ),
+            (r#"{"InstructionError":[1,{"Custom":6010}]}"#, pump(6010)),
+            // Ошибки SPL Token из CPI pump.fun и напрямую
+            (
+                r#"{"InstructionError":[1,{"Custom":1}]}"#,
+                ExitSimError::InsufficientTokens,
+            ),
+            (
+                r#"{"InstructionError":[1,{"Custom":17}]}"#,
+                ExitSimError::Frozen,
+            ),
+            (
+                r#"{"InstructionError":[2,{"Custom":17}]}"#,
+                ExitSimError::Frozen,
+            ),
+            (
+                r#"{"InstructionError":[2,{"Custom":4}]}"#,
+                ExitSimError::Program {
+                    program: TOKEN_PROGRAM,
+                    code: 4,
+                },
+            ),
+            (
+                r#"{"InstructionError":[3,{"Custom":6001}]}"#,
+                ExitSimError::Slippage,
+            ),
+            // Чаевые Jito: не хватило SOL на перевод
+            (
+                r#"{"InstructionError":[4,{"Custom":1}]}"#,
+                ExitSimError::InsufficientFunds,
+            ),
+            (
+                r#"{"InstructionError":[4,"InsufficientFunds"]}"#,
+                ExitSimError::InsufficientFunds,
+            ),
+            (
+                r#""InsufficientFundsForFee""#,
+                ExitSimError::InsufficientFunds,
+            ),
+            (
+                r#"{"InsufficientFundsForRent":{"account_index":0}}"#,
+                ExitSimError::InsufficientFunds,
+            ),
+            (r#""AccountNotFound""#, ExitSimError::InsufficientFunds),
+            (r#""BlockhashNotFound""#, ExitSimError::Expired),
+            // Индекс вне транзакции — программа неизвестна
+            (
+                r#"{"InstructionError":[9,{"Custom":6003}]}"#,
+                ExitSimError::Program {
+                    program: Pubkey::default(),
+                    code: 6003,
+                },
+            ),
+        ] {
+            let err: TransactionError = serde_json::from_str(err).unwrap();
+            assert_eq!(classify_error(&tx, &err), expected, "{}", err);
+        }
+
+        let err: TransactionError = serde_json::from_str(r#""AlreadyProcessed""#).unwrap();
+        assert!(matches!(
+            classify_error(&tx, &err),
+            ExitSimError::Transaction(_)
+        ));
+    }
+
+    #[test]
+    fn escalation_stops_on_wallet_and_curve_errors() {
+        for (err, stops) in [
+            (ExitSimError::Slippage, false),
+            (ExitSimError::Expired, false),
+            (ExitSimError::Rpc("timeout".to_string()), false),
+            (ExitSimError::InsufficientFunds, true),
+            (ExitSimError::Frozen, true),
+            (ExitSimError::CurveComplete, true),
+            (ExitSimError::CurveMismatch { code: 6004 }, true),
+        ] {
+            assert_eq!(err.stops_escalation(), stops, "{}", err);
+        }
+        assert!(ExitSimError::Frozen.is_wallet_problem());
+        assert!(!ExitSimError::CurveComplete.is_wallet_problem());
+
+        // Тип сохраняется внутри `anyhow::Error` для лестницы проскальзывания
+        let e = anyhow::Error::from(ExitSimError::Frozen).context("продажа");
+        assert_eq!(sim_error(&e), Some(&ExitSimError::Frozen));
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use serde::Deserialize;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::time::Duration;
+
+use super::{pump_buy::DEFAULT_BUY_SLIPPAGE_BPS, pump_sell::DEFAULT_SELL_SLIPPAGE_BPS};
+
+/// Проскальзывание выше этого (50%) — почти наверняка ошибка в конфиге
+pub const MAX_SLIPPAGE_BPS: u16 = 5_000;
+
+/// Проскальзывание срочных продаж (rug-pull, panic) по умолчанию, б.п.
+pub const DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS: u16 = 1_500;
+
+/// Сколько раз спрашивать подтверждённую транзакцию, пока RPC её не отдаёт
+const FILL_ATTEMPTS: usize = 5;
+const FILL_RETRY_DELAY: Duration = Duration::from_millis(400);
+
+/// Допустимое проскальзывание сделок, б.п. От него считается минимум токенов
+/// покупки и минимум выручки продажи (и `slippageBps` у Jupiter)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SlippageConfig {
+    pub buy_bps: u16,
+    /// Плановые продажи (ступени, trailing stop, time-out); при неудаче —
+    /// `RiskConfig::slippage_ladder`
+    pub sell_bps: u16,
+    /// Срочные продажи (rug-pull, panic, деградация мониторинга)
+    pub emergency_sell_bps: u16,
+    /// Разрешить значения выше `MAX_SLIPPAGE_BPS`
+    pub allow_above_cap: bool,
+}
+
+impl Default for SlippageConfig {
+    fn default() -> Self {
+        Self {
+            buy_bps: DEFAULT_BUY_SLIPPAGE_BPS,
+            sell_bps: DEFAULT_SELL_SLIPPAGE_BPS,
+            emergency_sell_bps: DEFAULT_EMERGENCY_SELL_SLIPPAGE_BPS,
+            allow_above_cap: false,
+        }
+    }
+}
+
+impl SlippageConfig {
+    /// Значения в 1..=10000 б.п.; выше `MAX_SLIPPAGE_BPS` — только с `allow_above_cap`.
+    /// Ступени лестницы продаж (`ladder_bps`) проверяются так же.
+    pub fn validate(&self, ladder_bps: &[u16]) -> Result<()> {
+        let named = [
+            ("slippage.buy_bps", self.buy_bps),
+            ("slippage.sell_bps", self.sell_bps),
+            ("slippage.emergency_sell_bps", self.emergency_sell_bps),
+        ];
+        let steps = ladder_bps
+            .iter()
+            .map(|&bps| ("risk.sell_slippage_ladder_bps", bps));
+        for (name, bps) in named.into_iter().chain(steps) {
+            anyhow::ensure!(
+                (1..=10_000).contains(&bps),
+                "{}: {} б.п. вне 1..=10000",
+                name,
+                bps
+            );
+            anyhow::ensure!(
+                bps <= MAX_SLIPPAGE_BPS || self.allow_above_cap,
+                "{}: {} б.п. больше предела {} б.п. — нужен slippage.allow_above_cap",
+                name,
+                bps,
+                MAX_SLIPPAGE_BPS
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Реализованное проскальзывание продажи, б.п.: насколько выручка `actual`
+/// меньше ожидаемой `expected`; отрицательное — получено больше
+pub fn sell_slippage_bps(expected: u64, actual: u64) -> Option<i32> {
+    (expected > 0).then(|| ((expected as f64 - actual as f64) / expected as f64 * 10_000.0) as i32)
+}
+
+/// Реализованное проскальзывание покупки, б.п.: насколько заплачено `actual`
+/// больше ожидаемого `expected`; отрицательное — дешевле
+pub fn buy_slippage_bps(expected: u64, actual: u64) -> Option<i32> {
+    (expected > 0).then(|| ((actual as f64 - expected as f64) / expected as f64 * 10_000.0) as i32)
+}
+
+/// Движение SOL кошелька `owner` по `meta` ответа `getTransaction` (encoding json),
+/// lamports: больше нуля — получено. Комиссия сети и рента созданных в транзакции
+/// токен-аккаунтов `owner` не считаются — остаётся чистая цена сделки.
+/// Кошелёк должен быть плательщиком (первый аккаунт).
+pub fn sol_fill(meta: &serde_json::Value, owner: &Pubkey) -> Option<i64> {
+    let balance = |key: &str, i: usize| meta[key].get(i).and_then(|b| b.as_i64());
+    let fee = meta["fee"].as_i64()?;
+    let mut delta = balance("postBalances", 0)? - balance("preBalances", 0)? + fee;
+    let owner = owner.to_string();
+    for token in meta["postTokenBalances"].as_array().into_iter().flatten() {
+        if token["owner"].as_str() != Some(owner.as_str()) {
+            continue;
+        }
+        let Some(i) = token["accountIndex"].as_u64().map(|i| i as usize) else {
+            continue;
+        };
+        if let (Some(0), Some(rent)) = (balance("preBalances", i), balance("postBalances", i)) {
+            delta += rent;
+        }
+    }
+    Some(delta)
+}
+
+/// Как показать проскальзывание в логе: `, проскальзывание +12 б.п.` или пусто
+pub fn describe(bps: Option<i32>) -> String {
+    bps.map_or(String::new(), |bps| {
+        format!(", проскальзывание {:+} б.п.", bps)
+    })
+}
+
+/// Фактическая сумма сделки `signature`, lamports (модуль `sol_fill`);
+/// `None` — транзакция не прочиталась, сделка учитывается по ожиданию
+pub async fn realized_fill(
+    client: &RpcClient,
+    signature: &Signature,
+    owner: &Pubkey,
+) -> Option<u64> {
+    match fetch_sol_fill(client, signature, owner).await {
+        Ok(delta) => Some(delta.unsigned_abs()),
+        Err(e) => {
+            log::warn!("Фактическое исполнение {} не получено: {}", signature, e);
+            None
+        }
+    }
+}
+
+/// `sol_fill` подтверждённой транзакции `signature`; пока RPC её не отдаёт — повторы
+pub async fn fetch_sol_fill(
+    client: &RpcClient,
+    signature: &Signature,
+    owner: &Pubkey,
+) -> Result<i64> {
+    let params = serde_json::json!([
+        signature.to_string(),
+        {
+            "encoding": "json",
+            "commitment": "confirmed",
+            "maxSupportedTransactionVersion": 0,
+        },
+    ]);
+    for attempt in 1..=FILL_ATTEMPTS {
+        let tx: serde_json::Value = client
+            .send(RpcRequest::GetTransaction, params.clone())
+            .await?;
+        if !tx.is_null() {
+            return sol_fill(&tx["meta"], owner)
+                .ok_or_else(|| anyhow::anyhow!("в транзакции {} нет балансов", signature));
+        }
+        if attempt < FILL_ATTEMPTS {
+            tokio::time::sleep(FILL_RETRY_DELAY).await;
+        }
+    }
+    anyhow::bail!("транзакция {} пока не видна RPC", signature)
+}
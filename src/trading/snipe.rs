@@ -0,0 +1,332 @@
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use std::{fmt, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    positions::{EntryBlock, PositionManager},
+    pump_buy::{BuyReceipt, EntryExecutor},
+};
+use crate::config::Config;
+use crate::scanner::{
+    monitor_tokens, pump_fun::unix_now, PumpToken, ScannerEvent, ScannerFilter, ScoreWeights,
+    TokenScanner,
+};
+
+/// Сколько событий сканера ждёт обработки
+const EVENT_CAPACITY: usize = 256;
+
+/// Как часто убирать завершившиеся позиции между событиями
+const REAP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Почему токен не куплен
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// Не прошёл фильтр движка (`ScannerFilter::rejection_reason`)
+    Filter(&'static str),
+    Score {
+        score: f64,
+        min: f64,
+    },
+    /// Лимиты, предохранитель или пауза после выхода
+    Entry(EntryBlock),
+    /// Размер позиции не определился
+    Sizing(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Filter(reason) => write!(f, "фильтр {}", reason),
+            Self::Score { score, min } => write!(f, "оценка {:.2} < {:.2}", score, min),
+            Self::Entry(block) => write!(f, "{}", block),
+            Self::Sizing(e) => write!(f, "размер позиции: {}", e),
+        }
+    }
+}
+
+/// Решение движка по токену
+#[derive(Debug, Clone)]
+pub enum SnipeDecision {
+    /// Куплен и поставлен на мониторинг; квитанция на каждый кошелёк покупки
+    Bought(Vec<BuyReceipt>),
+    Skipped(SkipReason),
+    /// Покупка или запуск мониторинга не удались
+    Failed(String),
+}
+
+impl fmt::Display for SnipeDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bought(receipts) => {
+                let spent: f64 = receipts.iter().map(|r| r.sol_spent_sol()).sum();
+                let price = if spent > 0.0 {
+                    receipts
+                        .iter()
+                        .map(|r| r.effective_price * r.sol_spent_sol() / spent)
+                        .sum()
+                } else {
+                    receipts.first().map_or(0.0, |r| r.effective_price)
+                };
+                write!(f, "куплено на {:.4} SOL по {:.3e}", spent, price)?;
+                if receipts.len() > 1 {
+                    write!(f, " с {} кошельков", receipts.len())?;
+                }
+                if receipts.iter().any(|r| r.simulated) {
+                    f.write_str(" (dry-run)")?;
+                }
+                Ok(())
+            }
+            Self::Skipped(reason) => write!(f, "пропуск — {}", reason),
+            Self::Failed(e) => write!(f, "ошибка — {}", e),
+        }
+    }
+}
+
+/// Конвейер снайпера: события сканера → фильтр, оценка, лимиты → покупка →
+/// `RiskMonitor` по фактической покупке в `PositionManager`.
+/// Dry-run (`Config::dry_run`) передаётся исполнителю покупки; продажи — по режиму трейдера.
+pub struct SnipeEngine {
+    config: Config,
+    scanner: Arc<dyn TokenScanner>,
+    executor: Arc<dyn EntryExecutor>,
+    positions: Arc<Mutex<PositionManager>>,
+    filter: Option<ScannerFilter>,
+    weights: ScoreWeights,
+    min_score: f64,
+    capital_sol: Option<f64>,
+    poll_interval: Duration,
+}
+
+impl fmt::Debug for SnipeEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnipeEngine")
+            .field("scanner", &self.scanner.name())
+            .field("dry_run", &self.config.dry_run)
+            .field("min_score", &self.min_score)
+            .finish()
+    }
+}
+
+impl SnipeEngine {
+    pub fn new(
+        config: Config,
+        scanner: Arc<dyn TokenScanner>,
+        executor: Arc<dyn EntryExecutor>,
+        positions: Arc<Mutex<PositionManager>>,
+    ) -> Self {
+        Self {
+            config,
+            scanner,
+            executor,
+            positions,
+            filter: None,
+            weights: ScoreWeights::default(),
+            min_score: 0.0,
+            capital_sol: None,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Собственный фильтр поверх фильтров сканера (дешёвые проверки, без запросов)
+    pub fn with_filter(mut self, filter: ScannerFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Покупать только токены с оценкой не ниже `min_score`
+    pub fn with_min_score(mut self, weights: ScoreWeights, min_score: f64) -> Self {
+        self.weights = weights;
+        self.min_score = min_score;
+        self
+    }
+
+    /// Капитал для расчёта ставки, SOL; по умолчанию — баланс кошельков трейдера
+    pub fn with_capital(mut self, capital_sol: f64) -> Self {
+        self.capital_sol = Some(capital_sol);
+        self
+    }
+
+    /// Интервал опроса сканера в `run`
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn positions(&self) -> &Arc<Mutex<PositionManager>> {
+        &self.positions
+    }
+
+    /// Ставка: `Config::buy_amount_sol` процентов капитала
+    async fn stake_sol(&self) -> Result<f64> {
+        let capital = match self.capital_sol {
+            Some(capital) => capital,
+            None => self.positions.lock().await.trader().balance_sol().await?,
+        };
+        let stake = capital * self.config.buy_amount_sol / 100.0;
+        anyhow::ensure!(
+            stake > 0.0,
+            "ставка {:.4} SOL от капитала {:.4} SOL",
+            stake,
+            capital
+        );
+        Ok(stake)
+    }
+
+    /// Решение по одному токену: фильтр, оценка, размер, лимиты, покупка, мониторинг
+    pub async fn on_token(&self, token: &PumpToken) -> SnipeDecision {
+        let decision = self.decide(token).await;
+        match &decision {
+            SnipeDecision::Bought(_) => {
+                log::info!("🎯 {} ({}): {}", token.symbol, token.mint, decision)
+            }
+            SnipeDecision::Skipped(_) => {
+                log::info!("⏭️ {} ({}): {}", token.symbol, token.mint, decision)
+            }
+            SnipeDecision::Failed(_) => {
+                log::error!("❌ {} ({}): {}", token.symbol, token.mint, decision)
+            }
+        }
+        decision
+    }
+
+    async fn decide(&self, token: &PumpToken) -> SnipeDecision {
+        let mint = match Pubkey::from_str(&token.mint) {
+            Ok(mint) => mint,
+            Err(e) => return SnipeDecision::Failed(format!("неверный mint: {}", e)),
+        };
+        let now = unix_now();
+        if let Some(reason) = self
+            .filter
+            .as_ref()
+            .and_then(|f| f.rejection_reason(token, now))
+        {
+            return SnipeDecision::Skipped(SkipReason::Filter(reason));
+        }
+        let score = self.weights.score(token, now);
+        if score < self.min_score {
+            return SnipeDecision::Skipped(SkipReason::Score {
+                score,
+                min: self.min_score,
+            });
+        }
+        let stake_sol = match self.stake_sol().await {
+            Ok(stake) => stake,
+            Err(e) => return SnipeDecision::Skipped(SkipReason::Sizing(e.to_string())),
+        };
+        let creator = Pubkey::from_str(&token.creator_address).ok();
+        {
+            let mut positions = self.positions.lock().await;
+            positions.reap().await;
+            if let Err(block) = positions.check_entry(&mint, creator.as_ref(), stake_sol) {
+                return SnipeDecision::Skipped(SkipReason::Entry(block));
+            }
+        }
+        log::info!(
+            "🛒 {} ({}): оценка {:.2}, покупка на {:.4} SOL{}",
+            token.symbol,
+            mint,
+            score,
+            stake_sol,
+            if self.config.dry_run {
+                " (dry-run)"
+            } else {
+                ""
+            }
+        );
+        let receipts = match self
+            .executor
+            .buy_split(token, stake_sol, self.config.dry_run)
+            .await
+        {
+            Ok(receipts) if !receipts.is_empty() => receipts,
+            Ok(_) => return SnipeDecision::Failed("покупка: нет квитанций".to_string()),
+            Err(e) => return SnipeDecision::Failed(format!("покупка: {}", e)),
+        };
+        match self
+            .positions
+            .lock()
+            .await
+            .open_split(token, &receipts)
+            .await
+        {
+            Ok(()) => SnipeDecision::Bought(receipts),
+            Err(e) => SnipeDecision::Failed(format!(
+                "куплено ({}), но мониторинг не запущен: {}",
+                receipts[0].signature, e
+            )),
+        }
+    }
+
+    /// Событие сканера: новые токены идут в `on_token`, остальное — в лог
+    pub async fn handle_event(&self, event: ScannerEvent) -> Option<SnipeDecision> {
+        match event {
+            ScannerEvent::NewToken(token) => return Some(self.on_token(&token).await),
+            ScannerEvent::TokenUpdated { new, .. } => {
+                log::debug!("Обновление {} — не вход", new.mint)
+            }
+            ScannerEvent::Graduated(mint) => log::debug!("Кривая {} завершена", mint),
+            ScannerEvent::ScanError(e) => log::warn!("Ошибка сканера: {}", e),
+            ScannerEvent::RateLimited { retry_after } => {
+                log::warn!("Сканер упёрся в лимит, пауза {:?}", retry_after)
+            }
+            ScannerEvent::Dropped(n) => {
+                log::warn!("Движок не успевал: пропущено событий сканера: {}", n)
+            }
+        }
+        None
+    }
+
+    /// Обрабатывает события из канала (например, `PumpFunScanner::run`)
+    /// до закрытия канала или отмены `cancel`; между событиями убирает закрытые позиции
+    pub async fn run_events(
+        &self,
+        mut events: mpsc::Receiver<ScannerEvent>,
+        cancel: CancellationToken,
+    ) {
+        let mut reap = tokio::time::interval(REAP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = reap.tick() => {
+                    self.positions.lock().await.reap().await;
+                }
+                event = events.recv() => match event {
+                    Some(event) => {
+                        self.handle_event(event).await;
+                    }
+                    None => break,
+                },
+            }
+        }
+    }
+
+    /// Опрашивает сканер (`monitor_tokens`) и покупает подходящие токены до отмены `cancel`
+    pub async fn run(&self, cancel: CancellationToken) -> Result<()> {
+        log::info!(
+            "🚀 Снайпер запущен: сканер {}, ставка {}% капитала{}",
+            self.scanner.name(),
+            self.config.buy_amount_sol,
+            if self.config.dry_run { ", dry-run" } else { "" }
+        );
+        let (tx, rx) = mpsc::channel(EVENT_CAPACITY);
+        let poller = {
+            let scanner = self.scanner.clone();
+            let cancel = cancel.clone();
+            let interval = self.poll_interval;
+            tokio::spawn(async move {
+                monitor_tokens(scanner.as_ref(), interval, cancel, |tokens| {
+                    for token in tokens {
+                        if tx.try_send(ScannerEvent::NewToken(token)).is_err() {
+                            log::warn!("Очередь снайпера переполнена, токен пропущен");
+                        }
+                    }
+                })
+                .await
+            })
+        };
+        self.run_events(rx, cancel).await;
+        poller.await?
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{path::Path, str::FromStr, sync::Mutex};
+
+use super::{
+    breaker::BreakerState,
+    cooldown::{Cooldown, CooldownKind},
+    journal::JournalEntry,
+    risk::ExitReason,
+};
+use crate::scanner::pump_fun::unix_now;
+
+/// Текущая версия схемы (`PRAGMA user_version`)
+const SCHEMA_VERSION: i32 = 6;
+
+const MIGRATIONS: &[&str] = &[
+    // v1
+    "CREATE TABLE positions (
+        mint       TEXT PRIMARY KEY,
+        updated_at INTEGER NOT NULL,
+        data       TEXT NOT NULL
+    );",
+    // v2
+    "CREATE TABLE dust_accounts (
+        account    TEXT PRIMARY KEY,
+        mint       TEXT NOT NULL,
+        queued_at  INTEGER NOT NULL
+    );",
+    // v3
+    "CREATE TABLE cooldowns (
+        kind       TEXT NOT NULL,
+        key        TEXT NOT NULL,
+        reason     TEXT NOT NULL,
+        until      INTEGER NOT NULL,
+        PRIMARY KEY (kind, key)
+    );",
+    // v4
+    "CREATE TABLE journal (
+        id           INTEGER PRIMARY KEY AUTOINCREMENT,
+        mint         TEXT NOT NULL,
+        timestamp_ms INTEGER NOT NULL,
+        data         TEXT NOT NULL
+    );
+    CREATE INDEX journal_mint ON journal (mint, id);",
+    // v5
+    "CREATE TABLE settings (
+        key        TEXT PRIMARY KEY,
+        updated_at INTEGER NOT NULL,
+        data       TEXT NOT NULL
+    );",
+    // v6: позиция — строка на (mint, кошелёк); '' — основной кошелёк
+    "CREATE TABLE positions_v6 (
+        mint       TEXT NOT NULL,
+        wallet     TEXT NOT NULL DEFAULT '',
+        updated_at INTEGER NOT NULL,
+        data       TEXT NOT NULL,
+        PRIMARY KEY (mint, wallet)
+    );
+    INSERT INTO positions_v6 (mint, updated_at, data)
+        SELECT mint, updated_at, data FROM positions;
+    DROP TABLE positions;
+    ALTER TABLE positions_v6 RENAME TO positions;",
+];
+
+/// Ключ состояния предохранителя в `settings`
+const BREAKER_KEY: &str = "circuit_breaker";
+
+/// Состояние позиции, достаточное, чтобы продолжить мониторинг после перезапуска
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedPosition {
+    pub mint: String,
+    /// Кошелёк доли покупки; `None` — основной кошелёк трейдера
+    #[serde(default)]
+    pub wallet: Option<String>,
+    /// Создатель токена — для детекта его продаж после перезапуска
+    #[serde(default)]
+    pub creator: Option<String>,
+    pub stake_sol: f64,
+    pub entry_fee_lamports: u64,
+    /// Начало мониторинга, unix, мс
+    pub entry_time_ms: u64,
+    pub entry_price: f64,
+    pub peak_price: f64,
+    pub initial_reserve: Option<u64>,
+    pub entry_slot: Option<u64>,
+    pub entry_signature: Option<String>,
+    pub remaining: f64,
+    pub breakeven_armed: bool,
+    pub rug_triggered: bool,
+    #[serde(default)]
+    pub floor_triggered: bool,
+    pub panic_triggered: bool,
+    pub timeout_triggered: bool,
+    pub trailing_triggered: bool,
+    /// Trailing stop включён; `None` — позиция сохранена до появления порога
+    #[serde(default)]
+    pub trailing_armed: Option<bool>,
+    pub moon_sold: bool,
+    #[serde(default)]
+    pub creator_dump_triggered: bool,
+    /// Максимальный баланс токена у создателя (сырые единицы)
+    #[serde(default)]
+    pub creator_peak_balance: u64,
+    #[serde(default)]
+    pub supply_triggered: bool,
+    /// Суженный после крупной продажи trailing stop: %, до какого момента с входа, мс
+    #[serde(default)]
+    pub tight_trailing: Option<(f64, u64)>,
+    /// Лунная доля в сырых единицах токена (с версии, где она фиксируется при входе)
+    #[serde(default)]
+    pub moon_tokens: Option<u64>,
+    /// Общий supply mint-а при входе (с версии, где он сверяется)
+    #[serde(default)]
+    pub entry_supply: Option<u64>,
+    /// Цена SOL в USD на входе
+    #[serde(default)]
+    pub entry_sol_usd: Option<f64>,
+    pub tiers_hit: Vec<bool>,
+    pub sol_recovered: u64,
+    pub fees_paid: u64,
+    pub last_exit: Option<ExitReason>,
+}
+
+/// Токен-аккаунт с пылью, ждущий закрытия (возврат ренты)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DustAccount {
+    pub account: String,
+    pub mint: String,
+    /// Когда поставлен в очередь, unix, сек
+    pub queued_at: u64,
+}
+
+/// Открытые позиции в SQLite: строка на mint и кошелёк, закрытые удаляются
+pub struct PositionStore {
+    conn: Mutex<Connection>,
+}
+
+impl std::fmt::Debug for PositionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PositionStore").finish_non_exhaustive()
+    }
+}
+
+impl PositionStore {
+    /// Открывает (или создаёт) базу и применяет миграции
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// База в памяти — для тестов и разовых прогонов
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "схема базы позиций v{} новее поддерживаемой v{}",
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        let tx = conn.transaction()?;
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            log::info!("Миграция базы позиций до v{}", i + 1);
+            tx.execute_batch(sql)?;
+        }
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Записывает позицию поверх прежней
+    pub fn save(&self, position: &PersistedPosition) -> Result<()> {
+        let data = serde_json::to_string(position)?;
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO positions (mint, wallet, updated_at, data) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (mint, wallet) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                data = excluded.data",
+            params![
+                position.mint,
+                position.wallet.as_deref().unwrap_or_default(),
+                unix_now() as i64,
+                data
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Убирает закрытую позицию кошелька `wallet` (`None` — основного)
+    pub fn remove(&self, mint: &str, wallet: Option<&str>) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM positions WHERE mint = ?1 AND wallet = ?2",
+            params![mint, wallet.unwrap_or_default()],
+        )?;
+        Ok(())
+    }
+
+    /// Все сохранённые позиции
+    pub fn load_all(&self) -> Result<Vec<PersistedPosition>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT data FROM positions ORDER BY mint, wallet")?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    /// Ставит токен-аккаунт с пылью в очередь на закрытие; повтор — без изменений
+    pub fn queue_dust(&self, account: &str, mint: &str) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR IGNORE INTO dust_accounts (account, mint, queued_at) VALUES (?1, ?2, ?3)",
+            params![account, mint, unix_now() as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Очередь на закрытие, старые первыми
+    pub fn dust_accounts(&self) -> Result<Vec<DustAccount>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT account, mint, queued_at FROM dust_accounts ORDER BY queued_at, account",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok(DustAccount {
+                account: r.get(0)?,
+                mint: r.get(1)?,
+                queued_at: r.get::<_, i64>(2)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Записывает запрет повторного входа поверх прежнего
+    pub fn save_cooldown(&self, cooldown: &Cooldown) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO cooldowns (kind, key, reason, until) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (kind, key) DO UPDATE SET
+                reason = excluded.reason,
+                until = excluded.until",
+            params![
+                serde_json::to_string(&cooldown.kind)?,
+                cooldown.key.to_string(),
+                serde_json::to_string(&cooldown.reason)?,
+                cooldown.until as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Запреты, действующие на `now` (unix, сек); истёкшие удаляются
+    pub fn load_cooldowns(&self, now: u64) -> Result<Vec<Cooldown>> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM cooldowns WHERE until <= ?1",
+            params![now as i64],
+        )?;
+        let mut stmt = conn.prepare("SELECT kind, key, reason, until FROM cooldowns")?;
+        let rows = stmt.query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, i64>(3)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (kind, key, reason, until) = row?;
+            Ok(Cooldown {
+                kind: serde_json::from_str::<CooldownKind>(&kind)?,
+                key: Pubkey::from_str(&key)?,
+                reason: serde_json::from_str(&reason)?,
+                until: until as u64,
+            })
+        })
+        .collect()
+    }
+
+    /// Дописывает запись журнала позиции
+    pub fn append_journal(&self, mint: &str, entry: &JournalEntry) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO journal (mint, timestamp_ms, data) VALUES (?1, ?2, ?3)",
+            params![
+                mint,
+                entry.timestamp_ms() as i64,
+                serde_json::to_string(entry)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Журнал позиции в порядке записи
+    pub fn load_journal(&self, mint: &str) -> Result<Vec<JournalEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT data FROM journal WHERE mint = ?1 ORDER BY id")?;
+        let rows = stmt.query_map(params![mint], |r| r.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    /// Записывает состояние предохранителя
+    pub fn save_breaker(&self, state: &BreakerState) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO settings (key, updated_at, data) VALUES (?1, ?2, ?3)
+             ON CONFLICT (key) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                data = excluded.data",
+            params![
+                BREAKER_KEY,
+                unix_now() as i64,
+                serde_json::to_string(state)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Сохранённое состояние предохранителя; `None` — ещё не записывалось
+    pub fn load_breaker(&self) -> Result<Option<BreakerState>> {
+        let conn = self.conn.lock().unwrap();
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM settings WHERE key = ?1",
+                params![BREAKER_KEY],
+                |r| r.get(0),
+            )
+            .optional()?;
+        data.map(|data| Ok(serde_json::from_str(&data)?))
+            .transpose()
+    }
+
+    /// Убирает закрытый токен-аккаунт из очереди
+    pub fn remove_dust(&self, account: &str) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM dust_accounts WHERE account = ?1",
+            params![account],
+        )?;
+        Ok(())
+    }
+}
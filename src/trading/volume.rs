@@ -0,0 +1,134 @@
+use anyhow::Result;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature};
+use std::{collections::VecDeque, str::FromStr, time::Duration};
+
+/// Сколько транзакций разбирать за один опрос; остальные оцениваются по среднему
+pub const MAX_TRANSACTIONS_PER_POLL: usize = 50;
+
+/// Скользящий объём торгов по аккаунту, где лежит SOL пула
+/// (bonding curve или WSOL-хранилище Raydium).
+/// Каждый опрос берёт только подписи новее последней учтённой.
+#[derive(Debug, Clone)]
+pub struct VolumeTracker {
+    window: Duration,
+    /// (unix мс, lamports) по сделкам, старые первыми
+    trades: VecDeque<(u64, u64)>,
+    account: Option<Pubkey>,
+    last_signature: Option<Signature>,
+}
+
+impl VolumeTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            trades: VecDeque::new(),
+            account: None,
+            last_signature: None,
+        }
+    }
+
+    /// Сделка на `lamports` в момент `timestamp_ms`
+    pub fn record(&mut self, timestamp_ms: u64, lamports: u64) {
+        let at = self.trades.partition_point(|(ts, _)| *ts <= timestamp_ms);
+        self.trades.insert(at, (timestamp_ms, lamports));
+    }
+
+    /// Объём за окно, заканчивающееся в `now_ms`, SOL; старые сделки выбрасываются
+    pub fn volume_sol(&mut self, now_ms: u64) -> f64 {
+        let from = now_ms.saturating_sub(self.window.as_millis() as u64);
+        while self.trades.front().is_some_and(|(ts, _)| *ts < from) {
+            self.trades.pop_front();
+        }
+        self.trades.iter().map(|(_, l)| *l as f64).sum::<f64>() / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Подтягивает сделки по `account` новее последней учтённой и возвращает объём, SOL.
+    /// Смена аккаунта (миграция на Raydium) начинает счёт подписей заново,
+    /// накопленный объём при этом сохраняется.
+    pub async fn poll(&mut self, client: &RpcClient, account: &Pubkey, now_ms: u64) -> Result<f64> {
+        if self.account != Some(*account) {
+            self.account = Some(*account);
+            self.last_signature = None;
+        }
+        let from_secs = now_ms.saturating_sub(self.window.as_millis() as u64) / 1000;
+        let page = client
+            .get_signatures_for_address_with_config(
+                account,
+                GetConfirmedSignaturesForAddress2Config {
+                    until: self.last_signature,
+                    limit: Some(1000),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        // Новые первыми; старше окна — не нужны
+        let fresh: Vec<_> = page
+            .iter()
+            .filter(|s| s.err.is_none())
+            .filter(|s| s.block_time.is_none_or(|t| t as u64 >= from_secs))
+            .collect();
+        if let Some(newest) = page.first() {
+            self.last_signature = Signature::from_str(&newest.signature).ok();
+        }
+
+        let mut parsed = Vec::new();
+        for sig in fresh.iter().take(MAX_TRANSACTIONS_PER_POLL) {
+            let timestamp_ms = sig.block_time.map_or(now_ms, |t| t as u64 * 1000);
+            match transaction_lamports(client, &sig.signature, account).await {
+                Ok(lamports) => parsed.push((timestamp_ms, lamports)),
+                Err(e) => log::debug!("Транзакция {} не разобрана: {}", sig.signature, e),
+            }
+        }
+        // Неразобранные сверх лимита — по среднему размеру разобранных
+        if !parsed.is_empty() && fresh.len() > parsed.len() {
+            let avg = parsed.iter().map(|(_, l)| l).sum::<u64>() / parsed.len() as u64;
+            for sig in fresh.iter().skip(MAX_TRANSACTIONS_PER_POLL) {
+                self.record(sig.block_time.map_or(now_ms, |t| t as u64 * 1000), avg);
+            }
+        }
+        for (timestamp_ms, lamports) in parsed {
+            self.record(timestamp_ms, lamports);
+        }
+        Ok(self.volume_sol(now_ms))
+    }
+}
+
+/// Изменение баланса `account` в транзакции, lamports (по модулю)
+async fn transaction_lamports(
+    client: &RpcClient,
+    signature: &str,
+    account: &Pubkey,
+) -> Result<u64> {
+    let tx: serde_json::Value = client
+        .send(
+            RpcRequest::GetTransaction,
+            serde_json::json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )
+        .await?;
+    let meta = &tx["meta"];
+    let account = account.to_string();
+    // Статические ключи, затем подгруженные из lookup-таблиц (writable, readonly)
+    let mut keys = tx["transaction"]["message"]["accountKeys"]
+        .as_array()
+        .into_iter()
+        .chain(meta["loadedAddresses"]["writable"].as_array())
+        .chain(meta["loadedAddresses"]["readonly"].as_array())
+        .flatten();
+    let Some(index) = keys.position(|k| k.as_str() == Some(&account)) else {
+        return Ok(0);
+    };
+    let balance = |field: &str| meta[field][index].as_u64();
+    match (balance("preBalances"), balance("postBalances")) {
+        (Some(pre), Some(post)) => Ok(pre.abs_diff(post)),
+        _ => anyhow::bail!("в транзакции нет балансов аккаунта"),
+    }
+}
@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    bs58,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::config::Config;
+
+/// Сколько аккаунтов за один `getMultipleAccounts`
+const ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Как делить покупку между кошельками
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMode {
+    /// Поровну
+    #[default]
+    Equal,
+    /// Пропорционально свободному балансу (сверх `min_balance_sol`)
+    Balance,
+    /// Веса по порядку `Config.wallets`; кошелёк без веса или с нулевым не покупает
+    Weights(Vec<f64>),
+}
+
+/// Покупка частями с нескольких кошельков, чтобы не оставлять один крупный след
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WalletSplitConfig {
+    /// На сколько кошельков делить покупку (1 — один кошелёк)
+    pub wallets: usize,
+    pub mode: SplitMode,
+    /// Кошельки с балансом ниже этого пропускаются, SOL
+    pub min_balance_sol: f64,
+}
+
+impl Default for WalletSplitConfig {
+    fn default() -> Self {
+        Self {
+            wallets: 1,
+            mode: SplitMode::Equal,
+            min_balance_sol: 0.01,
+        }
+    }
+}
+
+/// Секретный ключ кошелька: base58 или JSON-массив байт (формат `solana-keygen`).
+/// В ошибках сам ключ не показывается.
+pub fn parse_keypair(secret: &str) -> Result<Keypair> {
+    let secret = secret.trim();
+    let bytes = if secret.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(secret).context("неверный JSON-массив ключа")?
+    } else {
+        bs58::decode(secret)
+            .into_vec()
+            .context("неверный base58 ключа")?
+    };
+    anyhow::ensure!(
+        bytes.len() == 64,
+        "ключ должен быть 64 байта, а не {}",
+        bytes.len()
+    );
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow::anyhow!("неверный ключ: {}", e))
+}
+
+/// Делит `total` по весам: доли округляются вниз, остаток — первой доле с ненулевым весом
+pub fn split_amount(total: u64, weights: &[f64]) -> Vec<u64> {
+    let weights: Vec<f64> = weights
+        .iter()
+        .map(|w| if w.is_finite() { w.max(0.0) } else { 0.0 })
+        .collect();
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        return vec![0; weights.len()];
+    }
+    let mut amounts: Vec<u64> = weights
+        .iter()
+        .map(|w| (total as f64 * w / sum).floor() as u64)
+        .collect();
+    let rest = total.saturating_sub(amounts.iter().sum());
+    if let Some(first) = weights.iter().position(|w| *w > 0.0) {
+        amounts[first] += rest;
+    }
+    amounts
+}
+
+/// Кошельки из `Config.wallets` с последними известными балансами.
+/// Первый кошелёк — основной: на нём работает трейдер.
+pub struct WalletManager {
+    client: Arc<RpcClient>,
+    wallets: Vec<Arc<Keypair>>,
+    split: WalletSplitConfig,
+    /// Последние известные балансы, lamports
+    balances: Mutex<HashMap<Pubkey, u64>>,
+}
+
+impl fmt::Debug for WalletManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalletManager")
+            .field(
+                "wallets",
+                &self.wallets.iter().map(|w| w.pubkey()).collect::<Vec<_>>(),
+            )
+            .field("split", &self.split)
+            .finish()
+    }
+}
+
+impl WalletManager {
+    pub fn new(client: Arc<RpcClient>, wallets: Vec<Arc<Keypair>>) -> Result<Self> {
+        anyhow::ensure!(!wallets.is_empty(), "не задано ни одного кошелька");
+        for (i, wallet) in wallets.iter().enumerate() {
+            anyhow::ensure!(
+                !wallets[..i].iter().any(|w| w.pubkey() == wallet.pubkey()),
+                "кошелёк {} указан дважды",
+                wallet.pubkey()
+            );
+        }
+        Ok(Self {
+            client,
+            wallets,
+            split: WalletSplitConfig::default(),
+            balances: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Кошельки из `Config.wallets` и правила деления из `Config.wallet_split`
+    pub fn from_config(client: Arc<RpcClient>, config: &Config) -> Result<Self> {
+        let wallets = config
+            .wallets
+            .iter()
+            .enumerate()
+            .map(|(i, secret)| {
+                parse_keypair(secret)
+                    .map(Arc::new)
+                    .with_context(|| format!("кошелёк {} в Config.wallets", i + 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(client, wallets)?.with_split(config.wallet_split.clone()))
+    }
+
+    /// Правила деления покупки
+    pub fn with_split(mut self, split: WalletSplitConfig) -> Self {
+        self.split = split;
+        self
+    }
+
+    pub fn split(&self) -> &WalletSplitConfig {
+        &self.split
+    }
+
+    /// Основной кошелёк (первый в `Config.wallets`)
+    pub fn primary(&self) -> &Arc<Keypair> {
+        &self.wallets[0]
+    }
+
+    pub fn wallets(&self) -> &[Arc<Keypair>] {
+        &self.wallets
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&Arc<Keypair>> {
+        self.wallets.iter().find(|w| w.pubkey() == *pubkey)
+    }
+
+    /// Последний известный баланс, lamports; `None` — ещё не запрашивался
+    pub fn balance(&self, pubkey: &Pubkey) -> Option<u64> {
+        self.balances.lock().unwrap().get(pubkey).copied()
+    }
+
+    /// Сумма последних известных балансов, SOL
+    pub fn total_balance_sol(&self) -> f64 {
+        self.balances.lock().unwrap().values().sum::<u64>() as f64 / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Запрашивает балансы всех кошельков; несуществующий аккаунт — ноль
+    pub async fn refresh(&self) -> Result<()> {
+        let pubkeys: Vec<Pubkey> = self.wallets.iter().map(|w| w.pubkey()).collect();
+        let mut fresh = HashMap::with_capacity(pubkeys.len());
+        for chunk in pubkeys.chunks(ACCOUNTS_PER_REQUEST) {
+            let accounts = self.client.get_multiple_accounts(chunk).await?;
+            for (pubkey, account) in chunk.iter().zip(accounts) {
+                fresh.insert(*pubkey, account.map_or(0, |a| a.lamports));
+            }
+        }
+        *self.balances.lock().unwrap() = fresh;
+        Ok(())
+    }
+
+    /// Доли покупки на `total` lamports по последним балансам: не больше
+    /// `max_wallets` кошельков с балансом от `min_balance_sol`, по порядку `Config.wallets`
+    pub fn plan_split(&self, total: u64, max_wallets: usize) -> Result<Vec<(Arc<Keypair>, u64)>> {
+        let min_balance = (self.split.min_balance_sol * LAMPORTS_PER_SOL as f64) as u64;
+        let balances = self.balances.lock().unwrap().clone();
+        let mut chosen = Vec::new();
+        for (i, wallet) in self.wallets.iter().enumerate() {
+            if chosen.len() >= max_wallets.max(1) {
+                break;
+            }
+            let balance = balances.get(&wallet.pubkey()).copied().unwrap_or_default();
+            if balance < min_balance {
+                log::debug!(
+                    "👛 {} пропущен: баланс {:.4} SOL ниже порога",
+                    wallet.pubkey(),
+                    balance as f64 / LAMPORTS_PER_SOL as f64
+                );
+                continue;
+            }
+            let weight = match &self.split.mode {
+                SplitMode::Equal => 1.0,
+                SplitMode::Balance => (balance - min_balance) as f64,
+                SplitMode::Weights(weights) => weights.get(i).copied().unwrap_or_default(),
+            };
+            if weight > 0.0 {
+                chosen.push((wallet.clone(), weight));
+            }
+        }
+        anyhow::ensure!(
+            !chosen.is_empty(),
+            "нет кошельков с балансом от {} SOL",
+            self.split.min_balance_sol
+        );
+        let weights: Vec<f64> = chosen.iter().map(|(_, w)| *w).collect();
+        Ok(chosen
+            .into_iter()
+            .zip(split_amount(total, &weights))
+            .filter(|(_, amount)| *amount > 0)
+            .map(|((wallet, _), amount)| (wallet, amount))
+            .collect())
+    }
+
+    /// Доли покупки на `total` lamports по свежим балансам (`WalletSplitConfig.wallets`
+    /// кошельков); балансы не обновились — по последним известным
+    pub async fn plan(&self, total: u64) -> Result<Vec<(Arc<Keypair>, u64)>> {
+        if let Err(e) = self.refresh().await {
+            anyhow::ensure!(
+                !self.balances.lock().unwrap().is_empty(),
+                "балансы кошельков не получены: {}",
+                e
+            );
+            log::warn!("Балансы кошельков не обновлены ({}) — по последним", e);
+        }
+        let legs = self.plan_split(total, self.split.wallets)?;
+        if legs.len() > 1 {
+            log::info!(
+                "👛 Покупка на {:.4} SOL делится на {} кошельков",
+                total as f64 / LAMPORTS_PER_SOL as f64,
+                legs.len()
+            );
+        }
+        Ok(legs)
+    }
+}
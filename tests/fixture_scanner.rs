@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use solana_sniper_core::scanner::{FixtureScanner, ScannerFilter, TokenScanner};
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+async fn symbols(scanner: &FixtureScanner) -> Vec<String> {
+    scanner
+        .eligible_tokens()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.symbol)
+        .collect()
+}
+
+#[tokio::test]
+async fn single_file_repeats() {
+    let scanner = FixtureScanner::from_fixture(&fixtures().join("coins_01.json")).unwrap();
+    // RUGL — мало ликвидности, ZZZ — слабый рост, MINT — mint не отозван, OLD — старый
+    assert_eq!(symbols(&scanner).await, ["MCAT"]);
+    assert_eq!(symbols(&scanner).await, ["MCAT"]);
+}
+
+#[tokio::test]
+async fn directory_plays_files_in_order() {
+    let scanner = FixtureScanner::from_fixture(&fixtures())
+        .unwrap()
+        .now(1_760_500_000);
+    assert_eq!(symbols(&scanner).await, ["MCAT"]);
+    // Во втором ответе появился FDOG, битая запись пропущена
+    assert_eq!(symbols(&scanner).await, ["MCAT", "FDOG"]);
+    // Файлы кончились — повторяется последний
+    assert_eq!(symbols(&scanner).await, ["MCAT", "FDOG"]);
+}
+
+#[tokio::test]
+async fn custom_filter() {
+    let scanner = FixtureScanner::from_fixture(&fixtures().join("coins_02.json"))
+        .unwrap()
+        .filter(ScannerFilter {
+            min_liquidity_sol: 10.0,
+            require_mint_revoked: false,
+            ..Default::default()
+        });
+    assert_eq!(symbols(&scanner).await, ["MCAT", "MINT"]);
+}
+
+#[tokio::test]
+async fn missing_fixtures() {
+    assert!(FixtureScanner::from_fixture(&fixtures().join("replay")).is_err());
+    let scanner = FixtureScanner::from_fixture(&fixtures().join("missing.json")).unwrap();
+    assert!(scanner.eligible_tokens().await.is_err());
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use solana_sniper_core::trading::{
+    risk::backtest::{run_backtest, ReplayFeed},
+    ExitReason, RiskConfig,
+};
+
+/// Запись запуска: рост до ~2.65x, боковик, слив
+fn launch() -> ReplayFeed {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay/launch.csv");
+    ReplayFeed::from_file(&path).unwrap()
+}
+
+#[tokio::test]
+async fn example_config_exits_on_trailing_stop() {
+    // Конфиг из examples/backtest.rs
+    let config = RiskConfig {
+        take_profit_tiers: vec![(2.0, 0.3)],
+        ..Default::default()
+    };
+    let summary = run_backtest(config, launch(), 1.0, 500).await.unwrap();
+    assert_eq!(summary.exit_reason, ExitReason::TrailingStop);
+    assert!((summary.realized_pnl_sol - 0.7433).abs() < 1e-4);
+    assert!((summary.realized_pnl_pct - 74.33).abs() < 0.01);
+    assert!((summary.peak_multiple - 2.652).abs() < 1e-3);
+    assert_eq!(summary.hold_duration.as_secs(), 141);
+}
+
+#[tokio::test]
+async fn looser_trailing_stop_exits_later() {
+    let config = RiskConfig {
+        trailing_stop_pct: 50.0,
+        ..Default::default()
+    };
+    let summary = run_backtest(config, launch(), 1.0, 500).await.unwrap();
+    // Та же запись: выход на 12 сек позже и глубже в сливе
+    assert_eq!(summary.exit_reason, ExitReason::TrailingStop);
+    assert!((summary.realized_pnl_sol - 0.2473).abs() < 1e-4);
+    assert_eq!(summary.hold_duration.as_secs(), 153);
+}